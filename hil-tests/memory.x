@@ -0,0 +1,6 @@
+/* Memory layout of the soft core. Adapt this to your hardware design. */
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 64K
+  RAM : ORIGIN = 0x20000000, LENGTH = 64K
+}
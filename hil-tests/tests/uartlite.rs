@@ -0,0 +1,62 @@
+//! On-target tests for the AXI UART Lite driver.
+//!
+//! The base address and the interrupt number of the UART Lite have to match the hardware design.
+#![no_std]
+#![no_main]
+
+use panic_probe as _;
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use axi_uartlite::{AxiUartlite, hil};
+    use cortex_m::peripheral::NVIC;
+
+    const BASE_ADDR: u32 = 0x4060_0000;
+    const MAX_POLLS: u32 = 100_000;
+
+    #[derive(Copy, Clone)]
+    struct UartliteIrq;
+
+    // SAFETY: The interrupt number of the UART Lite in the hardware design.
+    unsafe impl cortex_m::interrupt::InterruptNumber for UartliteIrq {
+        fn number(self) -> u16 {
+            0
+        }
+    }
+
+    #[init]
+    fn init() -> AxiUartlite {
+        // SAFETY: The UART Lite is only used by the test.
+        unsafe { AxiUartlite::new(BASE_ADDR) }
+    }
+
+    #[test]
+    fn fifo_reset(mut uart: AxiUartlite) {
+        hil::check_fifo_reset(&mut uart).unwrap();
+    }
+
+    #[test]
+    fn tx_drain(mut uart: AxiUartlite) {
+        hil::check_tx_drain(&mut uart, MAX_POLLS).unwrap();
+    }
+
+    #[test]
+    fn interrupt_enable(mut uart: AxiUartlite) {
+        hil::check_interrupt_enable(&mut uart).unwrap();
+    }
+
+    #[test]
+    fn tx_interrupt(mut uart: AxiUartlite) {
+        // The interrupt stays masked, so only the pending bit is checked.
+        NVIC::unpend(UartliteIrq);
+        hil::check_tx_interrupt(&mut uart, MAX_POLLS, || NVIC::is_pending(UartliteIrq)).unwrap();
+        NVIC::unpend(UartliteIrq);
+    }
+
+    #[cfg(feature = "loopback")]
+    #[test]
+    fn loopback(mut uart: AxiUartlite) {
+        hil::check_loopback(&mut uart, b"The quick brown fox", MAX_POLLS).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+/*
+ * C API of the axi-uartlite Rust driver, which is available with the `ffi` feature.
+ */
+#ifndef AXI_UARTLITE_H
+#define AXI_UARTLITE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Function completed successfully. */
+#define AXI_UARTLITE_OK 0
+/* A null pointer was passed. */
+#define AXI_UARTLITE_ERR_NULL -1
+
+/* Register offsets relative to the base address of the UART Lite. */
+#define AXI_UARTLITE_RX_FIFO_OFFSET 0x0
+#define AXI_UARTLITE_TX_FIFO_OFFSET 0x4
+#define AXI_UARTLITE_STAT_REG_OFFSET 0x8
+#define AXI_UARTLITE_CTRL_REG_OFFSET 0xC
+
+/* Opaque driver handle. The storage is provided by the user.
+ *
+ * The Rust library can not be built with the `ffi` feature together with the `stats`, `hooks`
+ * or `debug-audit` features, because the additional driver state does not fit into this
+ * handle. */
+typedef struct {
+    uintptr_t opaque[16];
+} axi_uartlite_t;
+
+/* RX error counters. */
+typedef struct {
+    uint8_t parity;
+    uint8_t frame;
+    uint8_t overrun;
+} axi_uartlite_errors_t;
+
+/* Initialize the driver handle for the UART Lite at the given base address. */
+int32_t axi_uartlite_init(axi_uartlite_t *handle, uintptr_t base_addr);
+
+/* Write as many bytes as fit into the TX FIFO. Returns the number of written bytes. */
+size_t axi_uartlite_write(axi_uartlite_t *handle, const uint8_t *data, size_t len);
+
+/* Write all bytes, blocking until they were written into the TX FIFO. */
+int32_t axi_uartlite_write_blocking(axi_uartlite_t *handle, const uint8_t *data, size_t len);
+
+/* Read the available bytes from the RX FIFO. Returns the number of read bytes. */
+size_t axi_uartlite_read(axi_uartlite_t *handle, uint8_t *buf, size_t len);
+
+/* Block until the TX FIFO is empty. */
+int32_t axi_uartlite_flush(axi_uartlite_t *handle);
+
+/* Reset the TX and RX FIFO. */
+int32_t axi_uartlite_reset_fifos(axi_uartlite_t *handle);
+
+/* Read and clear the RX error counters. */
+int32_t axi_uartlite_read_and_clear_errors(axi_uartlite_t *handle, axi_uartlite_errors_t *errors);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AXI_UARTLITE_H */
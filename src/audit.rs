@@ -0,0 +1,142 @@
+//! # Control register write audit
+//!
+//! This module is only available with the `debug-audit` feature. The [Tx] driver records the
+//! last [CONTROL_AUDIT_DEPTH] control register writes in a [ControlAudit] ring, which is part
+//! of the [crate::Diagnostics] snapshot. This helps to find out which code path disabled the
+//! interrupt or reset a FIFO.
+//!
+//! The writes are recorded per driver instance, so writes through other handles for the same
+//! peripheral, for example stolen handles, are recorded in the ring of that handle.
+//!
+//! # Example
+//!
+//! ```ignore
+//! uart.set_audit_clock(|| TIMER.now_ticks());
+//! // ...
+//! for write in uart.control_audit().iter() {
+//!     log::info!("ctrl {:#04x} at {:?}", write.value, write.timestamp);
+//! }
+//! ```
+use crate::{AxiUartlite, Tx, registers::RegisterAccess};
+
+/// Number of recorded control register writes.
+pub const CONTROL_AUDIT_DEPTH: usize = 8;
+
+/// Recorded control register write.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlWrite {
+    /// Raw value written into the control register.
+    pub value: u32,
+    /// Timestamp of the write if an audit clock was set.
+    pub timestamp: Option<u64>,
+}
+
+/// Ring of the last [CONTROL_AUDIT_DEPTH] control register writes.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlAudit {
+    entries: [ControlWrite; CONTROL_AUDIT_DEPTH],
+    next: u8,
+    len: u8,
+}
+
+impl Default for ControlAudit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControlAudit {
+    /// Create an empty audit ring.
+    pub const fn new() -> Self {
+        Self {
+            entries: [ControlWrite {
+                value: 0,
+                timestamp: None,
+            }; CONTROL_AUDIT_DEPTH],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Record a control register write. The oldest write is overwritten if the ring is full.
+    pub fn record(&mut self, write: ControlWrite) {
+        self.entries[self.next as usize] = write;
+        self.next = ((self.next as usize + 1) % CONTROL_AUDIT_DEPTH) as u8;
+        if (self.len as usize) < CONTROL_AUDIT_DEPTH {
+            self.len += 1;
+        }
+    }
+
+    /// Number of recorded writes.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// No write was recorded yet.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Most recent write.
+    pub fn last(&self) -> Option<ControlWrite> {
+        self.iter().last()
+    }
+
+    /// Recorded writes, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = ControlWrite> + '_ {
+        let start = self.next as usize + CONTROL_AUDIT_DEPTH - self.len as usize;
+        (0..self.len as usize).map(move |idx| self.entries[(start + idx) % CONTROL_AUDIT_DEPTH])
+    }
+
+    /// Remove all recorded writes.
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+}
+
+impl core::fmt::Debug for ControlAudit {
+    /// Shows the recorded writes, oldest first.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<R: RegisterAccess> Tx<R> {
+    /// Recorded control register writes of this handle.
+    #[inline]
+    pub const fn control_audit(&self) -> &ControlAudit {
+        &self.audit
+    }
+
+    /// Set the clock which is used to timestamp the recorded control register writes.
+    #[inline]
+    pub fn set_audit_clock(&mut self, clock: fn() -> u64) {
+        self.audit_clock = Some(clock);
+    }
+
+    pub(crate) fn record_control_write(&mut self, value: u32) {
+        self.audit.record(ControlWrite {
+            value,
+            timestamp: self.audit_clock.map(|clock| clock()),
+        });
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Recorded control register writes, see [Tx::control_audit].
+    #[inline]
+    pub const fn control_audit(&self) -> &ControlAudit {
+        &self.tx.audit
+    }
+
+    /// Set the clock which is used to timestamp the recorded control register writes.
+    #[inline]
+    pub fn set_audit_clock(&mut self, clock: fn() -> u64) {
+        self.tx.set_audit_clock(clock);
+    }
+}
@@ -0,0 +1,145 @@
+//! # Baud rate mismatch heuristic
+//!
+//! The baud rate of the UART Lite is fixed at synthesis time, and a remote end running at a
+//! different rate is a very common integration mistake. The symptoms are frame errors and
+//! garbage bytes instead of an obvious failure.
+//!
+//! The [BaudCheck] analyzer is fed with the received bytes and the RX error counters and
+//! flags a probable mismatch if the frame error ratio is high, or if it is elevated and many
+//! received bytes show the stretched bit patterns of a remote end which is slower than the UART
+//! Lite. Such a remote bit spans multiple local bit times, so the start bit and the following
+//! data bits turn into a run of zeros followed by ones, for example `0x00`, `0x80`, `0xF0` or
+//! `0xFE`.
+//!
+//! This is a heuristic: binary protocols with many `0x00` and `0xFF` bytes need a higher frame
+//! error ratio before a mismatch is flagged, and a noisy line can look like a mismatch.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut check = BaudCheck::new();
+//! let read = uart.read_nonblocking(&mut buf);
+//! check.record_bytes(&buf[0..read]);
+//! check.record_errors(&uart.read_and_clear_errors());
+//! if check.verdict() == BaudVerdict::ProbableMismatch {
+//!     log::warn!("UART Lite baud rate mismatch? {check:?}");
+//! }
+//! ```
+use crate::RxErrorsCounted;
+
+/// Minimum number of received bytes and frame errors before a verdict is given.
+pub const BAUD_CHECK_MIN_SAMPLES: u32 = 16;
+
+/// Result of the baud rate mismatch heuristic.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BaudVerdict {
+    /// Less than [BAUD_CHECK_MIN_SAMPLES] bytes and frame errors were recorded.
+    Insufficient,
+    /// The received data does not indicate a baud rate mismatch.
+    Plausible,
+    /// The received data indicates a baud rate mismatch.
+    ProbableMismatch,
+}
+
+/// Does the byte consist of a run of zeros followed by a run of ones, LSB first?
+///
+/// This is the typical pattern of a byte sent by a remote end which is slower than the
+/// receiver.
+#[inline]
+pub const fn is_stretched_pattern(byte: u8) -> bool {
+    byte == 0 || byte.wrapping_neg().is_power_of_two()
+}
+
+/// Baud rate mismatch analyzer.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BaudCheck {
+    bytes: u32,
+    stretched: u32,
+    frame_errors: u32,
+    parity_errors: u32,
+}
+
+impl BaudCheck {
+    /// Create an empty analyzer.
+    pub const fn new() -> Self {
+        Self {
+            bytes: 0,
+            stretched: 0,
+            frame_errors: 0,
+            parity_errors: 0,
+        }
+    }
+
+    /// Record a received byte.
+    #[inline]
+    pub fn record_byte(&mut self, byte: u8) {
+        self.bytes = self.bytes.saturating_add(1);
+        if is_stretched_pattern(byte) {
+            self.stretched = self.stretched.saturating_add(1);
+        }
+    }
+
+    /// Record received bytes.
+    pub fn record_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.record_byte(byte);
+        }
+    }
+
+    /// Record the RX error counters of the driver. The counters should be reset after
+    /// recording them, for example with [crate::AxiUartlite::read_and_clear_errors].
+    pub fn record_errors(&mut self, errors: &RxErrorsCounted) {
+        self.frame_errors = self.frame_errors.saturating_add(errors.frame() as u32);
+        self.parity_errors = self.parity_errors.saturating_add(errors.parity() as u32);
+    }
+
+    /// Number of recorded bytes.
+    #[inline]
+    pub const fn bytes(&self) -> u32 {
+        self.bytes
+    }
+
+    /// Number of recorded bytes with a stretched bit pattern, see [is_stretched_pattern].
+    #[inline]
+    pub const fn stretched_bytes(&self) -> u32 {
+        self.stretched
+    }
+
+    /// Number of recorded frame errors.
+    #[inline]
+    pub const fn frame_errors(&self) -> u32 {
+        self.frame_errors
+    }
+
+    /// Number of recorded parity errors.
+    #[inline]
+    pub const fn parity_errors(&self) -> u32 {
+        self.parity_errors
+    }
+
+    /// Evaluate the heuristic.
+    ///
+    /// A mismatch is flagged if at least a quarter of the recorded bytes had a frame error, or
+    /// if at least one in sixteen bytes had a frame error and half of the bytes show a
+    /// stretched bit pattern.
+    pub const fn verdict(&self) -> BaudVerdict {
+        let bytes = self.bytes as u64;
+        let frame_errors = self.frame_errors as u64;
+        if bytes + frame_errors < BAUD_CHECK_MIN_SAMPLES as u64 {
+            return BaudVerdict::Insufficient;
+        }
+        if frame_errors * 4 >= bytes
+            || (frame_errors * 16 >= bytes && self.stretched as u64 * 2 >= bytes)
+        {
+            return BaudVerdict::ProbableMismatch;
+        }
+        BaudVerdict::Plausible
+    }
+
+    /// Remove all recorded data.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
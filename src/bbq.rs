@@ -0,0 +1,97 @@
+//! # `bbqueue` RX integration
+//!
+//! This module is only available with the `bbqueue` feature. It provides interrupt handler
+//! functions which move the bytes from the RX FIFO directly into a
+//! [bbqueue](https://docs.rs/bbqueue) grant. This allows zero-copy handoff of the RX data from
+//! the interrupt handler to the task context, where the data can be consumed using the
+//! regular [bbqueue::Consumer] or [bbqueue::framed::FrameConsumer] API.
+use bbqueue::{Producer, framed::FrameProducer};
+
+use crate::{FIFO_DEPTH, Rx, registers::RegisterAccess};
+
+/// RX data was dropped because no grant could be acquired.
+///
+/// The RX FIFO is still drained in that case to avoid hardware overruns.
+#[derive(Debug)]
+pub struct RxDropped {
+    /// Number of dropped bytes.
+    pub dropped: usize,
+    /// Error returned when requesting the grant.
+    pub cause: bbqueue::Error,
+}
+
+impl core::fmt::Display for RxDropped {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "dropped {} RX bytes, grant error: {:?}",
+            self.dropped, self.cause
+        )
+    }
+}
+
+impl core::error::Error for RxDropped {}
+
+/// Read the whole RX FIFO into a [bbqueue] producer grant.
+///
+/// This can be called in the interrupt handler for the UART Lite. Returns the number of bytes
+/// which were committed.
+pub fn on_interrupt_rx_bbqueue<const N: usize, R: RegisterAccess>(
+    rx: &mut Rx<R>,
+    producer: &mut Producer<'_, N>,
+) -> Result<usize, RxDropped> {
+    match producer.grant_max_remaining(FIFO_DEPTH) {
+        Ok(mut grant) => {
+            let read = read_into(rx, grant.buf());
+            grant.commit(read);
+            Ok(read)
+        }
+        Err(cause) => Err(RxDropped {
+            dropped: drain_fifo(rx),
+            cause,
+        }),
+    }
+}
+
+/// Read the whole RX FIFO into a framed [bbqueue] producer grant.
+///
+/// This can be called in the interrupt handler for the UART Lite. All bytes which are read
+/// inside one call are committed as one frame. Returns the size of the committed frame,
+/// and no frame is committed if the RX FIFO was empty.
+pub fn on_interrupt_rx_bbqueue_framed<const N: usize, R: RegisterAccess>(
+    rx: &mut Rx<R>,
+    producer: &mut FrameProducer<'_, N>,
+) -> Result<usize, RxDropped> {
+    match producer.grant(FIFO_DEPTH) {
+        Ok(mut grant) => {
+            let read = read_into(rx, &mut grant);
+            if read > 0 {
+                grant.commit(read);
+            }
+            Ok(read)
+        }
+        Err(cause) => Err(RxDropped {
+            dropped: drain_fifo(rx),
+            cause,
+        }),
+    }
+}
+
+fn read_into<R: RegisterAccess>(rx: &mut Rx<R>, buf: &mut [u8]) -> usize {
+    let mut read = 0;
+    while read < buf.len() {
+        match rx.read_fifo() {
+            Ok(byte) => {
+                buf[read] = byte;
+                read += 1;
+            }
+            Err(nb::Error::WouldBlock) => break,
+        }
+    }
+    read
+}
+
+fn drain_fifo<R: RegisterAccess>(rx: &mut Rx<R>) -> usize {
+    let mut buf = [0; FIFO_DEPTH];
+    rx.read_whole_fifo(&mut buf)
+}
@@ -0,0 +1,339 @@
+//! # Throughput and latency benchmarks
+//!
+//! This module is only available with the `bench` feature. It contains routines which measure
+//! the sustained TX throughput, the interrupt service latency, the number of bytes serviced
+//! per interrupt and the round-trip time through a loopback or a remote echo, which helps when
+//! tuning baud rates and FIFO strategies.
+//!
+//! All measurements use a user-provided [CycleCounter], for example the cycle counter of the
+//! CPU or a free running AXI timer. The counter may wrap, but a single measured interval must
+//! be shorter than one counter period.
+//!
+//! The results are collected in a [BenchReport], which is printed as a structured report with
+//! one `key=value` line for each measurement:
+//!
+//! ```text
+//! bench tx_throughput bytes=1024 cycles=8888889 bytes_per_sec=11520
+//! bench isr_latency count=64 min=112 max=430 avg=158
+//! bench interrupts count=64 bytes=1024 bytes_per_interrupt=16
+//! bench round_trip count=32 min=9120 max=9410 avg=9188 lost=0
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut counter = || timer.counter_value();
+//! let mut report = BenchReport::new(TIMER_FREQ_HZ);
+//! report.tx_throughput = Some(measure_tx_throughput(&mut tx, &mut counter, 1024));
+//! write!(tx, "{report}").unwrap();
+//! ```
+use crate::{AxiUartlite, Tx, registers::RegisterAccess};
+
+/// Free running cycle counter used for the measurements.
+pub trait CycleCounter {
+    /// Current counter value.
+    fn cycles(&mut self) -> u32;
+}
+
+impl<F: FnMut() -> u32> CycleCounter for F {
+    fn cycles(&mut self) -> u32 {
+        self()
+    }
+}
+
+/// Result of a TX throughput measurement.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Throughput {
+    /// Number of transmitted bytes.
+    pub bytes: u32,
+    /// Counter cycles until the last byte left the TX FIFO.
+    pub cycles: u32,
+}
+
+impl Throughput {
+    /// Throughput in bytes per second for the given counter frequency.
+    ///
+    /// Returns 0 if no cycles were measured.
+    pub const fn bytes_per_sec(&self, counter_hz: u32) -> u32 {
+        if self.cycles == 0 {
+            return 0;
+        }
+        (self.bytes as u64 * counter_hz as u64 / self.cycles as u64) as u32
+    }
+}
+
+/// Measure the sustained TX throughput by sending `len` bytes with the blocking API.
+///
+/// The measurement ends when the TX FIFO is empty, so the last byte might still be shifted out
+/// by the transmitter. This error becomes negligible for large `len` values.
+pub fn measure_tx_throughput<R: RegisterAccess>(
+    tx: &mut Tx<R>,
+    counter: &mut impl CycleCounter,
+    len: u32,
+) -> Throughput {
+    let start = counter.cycles();
+    for idx in 0..len {
+        let Ok(()) = nb::block!(tx.write_fifo(b'A' + (idx % 26) as u8));
+    }
+    while !tx.fifo_empty() {}
+    Throughput {
+        bytes: len,
+        cycles: counter.cycles().wrapping_sub(start),
+    }
+}
+
+/// Minimum, maximum and average of latency samples in counter cycles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LatencyStats {
+    count: u32,
+    min: u32,
+    max: u32,
+    sum: u64,
+}
+
+impl Default for LatencyStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyStats {
+    /// Create empty latency statistics.
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            min: u32::MAX,
+            max: 0,
+            sum: 0,
+        }
+    }
+
+    /// Add a sample.
+    pub const fn record(&mut self, cycles: u32) {
+        self.count += 1;
+        if cycles < self.min {
+            self.min = cycles;
+        }
+        if cycles > self.max {
+            self.max = cycles;
+        }
+        self.sum += cycles as u64;
+    }
+
+    /// Number of samples.
+    pub const fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Minimum latency, or [None] if no samples were recorded.
+    pub const fn min(&self) -> Option<u32> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.min)
+    }
+
+    /// Maximum latency, or [None] if no samples were recorded.
+    pub const fn max(&self) -> Option<u32> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.max)
+    }
+
+    /// Average latency, or [None] if no samples were recorded.
+    pub const fn avg(&self) -> Option<u32> {
+        if self.count == 0 {
+            return None;
+        }
+        Some((self.sum / self.count as u64) as u32)
+    }
+}
+
+/// Result of a round-trip measurement.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct RoundTrip {
+    /// Round-trip times of the echoed probes.
+    pub latency: LatencyStats,
+    /// Number of probes which were not echoed within the timeout.
+    pub lost: u32,
+    /// Number of received bytes which did not match the pending probe, for example late
+    /// echoes of lost probes.
+    pub unexpected: u32,
+}
+
+/// Measure the round-trip time by sending `probes` probe bytes and waiting for their echo.
+///
+/// The probe bytes contain the low byte of the probe index, and the send time of each probe is
+/// taken from the counter. The remote end, for example a wire between the TX and the RX pin or
+/// an echo task, has to send back every byte. A probe is lost if its echo was not received
+/// within `timeout_cycles`. Stale bytes inside the RX FIFO are discarded before the first
+/// probe.
+pub fn measure_round_trip<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+    counter: &mut impl CycleCounter,
+    probes: u32,
+    timeout_cycles: u32,
+) -> RoundTrip {
+    let mut result = RoundTrip::default();
+    while uart.read_fifo().is_ok() {}
+    for idx in 0..probes {
+        let probe = idx as u8;
+        let Ok(()) = nb::block!(uart.write_fifo(probe));
+        let sent_at = counter.cycles();
+        loop {
+            let elapsed = counter.cycles().wrapping_sub(sent_at);
+            match uart.read_fifo() {
+                Ok(byte) if byte == probe => {
+                    result.latency.record(elapsed);
+                    break;
+                }
+                Ok(_) => result.unexpected += 1,
+                Err(nb::Error::WouldBlock) if elapsed > timeout_cycles => {
+                    result.lost += 1;
+                    break;
+                }
+                Err(nb::Error::WouldBlock) => (),
+            }
+        }
+    }
+    result
+}
+
+/// Interrupt service benchmark.
+///
+/// [Self::arm] is called with the counter value when the interrupt condition is triggered,
+/// for example when the TX FIFO is filled while the transmitter is idle, and
+/// [Self::on_interrupt] is called with the counter value at the entry of the interrupt
+/// handler. The difference is recorded as the interrupt service latency. Interrupts which
+/// occur while the benchmark is not armed only count towards the bytes per interrupt.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct InterruptBench {
+    armed_at: Option<u32>,
+    latency: LatencyStats,
+    interrupts: u32,
+    bytes: u32,
+}
+
+impl InterruptBench {
+    /// Create a new benchmark.
+    pub const fn new() -> Self {
+        Self {
+            armed_at: None,
+            latency: LatencyStats::new(),
+            interrupts: 0,
+            bytes: 0,
+        }
+    }
+
+    /// Arm the latency measurement for the next interrupt.
+    pub const fn arm(&mut self, now: u32) {
+        self.armed_at = Some(now);
+    }
+
+    /// Record an interrupt which serviced `bytes` bytes, with `entry` being the counter value
+    /// at the entry of the interrupt handler.
+    pub const fn on_interrupt(&mut self, entry: u32, bytes: usize) {
+        if let Some(armed_at) = self.armed_at.take() {
+            self.latency.record(entry.wrapping_sub(armed_at));
+        }
+        self.interrupts += 1;
+        self.bytes += bytes as u32;
+    }
+
+    /// Interrupt service latency.
+    pub const fn latency(&self) -> LatencyStats {
+        self.latency
+    }
+
+    /// Number of recorded interrupts.
+    pub const fn interrupts(&self) -> u32 {
+        self.interrupts
+    }
+
+    /// Number of bytes serviced by all recorded interrupts.
+    pub const fn bytes(&self) -> u32 {
+        self.bytes
+    }
+
+    /// Average number of bytes serviced per interrupt, or [None] if no interrupts were
+    /// recorded.
+    pub const fn bytes_per_interrupt(&self) -> Option<u32> {
+        if self.interrupts == 0 {
+            return None;
+        }
+        Some(self.bytes / self.interrupts)
+    }
+}
+
+/// Structured benchmark report.
+///
+/// The [core::fmt::Display] implementation prints one line for each available measurement.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BenchReport {
+    /// Frequency of the cycle counter.
+    pub counter_hz: u32,
+    /// TX throughput measurement.
+    pub tx_throughput: Option<Throughput>,
+    /// Interrupt service measurement.
+    pub interrupts: Option<InterruptBench>,
+    /// Round-trip measurement.
+    pub round_trip: Option<RoundTrip>,
+}
+
+impl BenchReport {
+    /// Create an empty report for a cycle counter running at `counter_hz`.
+    pub const fn new(counter_hz: u32) -> Self {
+        Self {
+            counter_hz,
+            tx_throughput: None,
+            interrupts: None,
+            round_trip: None,
+        }
+    }
+}
+
+impl core::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(throughput) = self.tx_throughput {
+            writeln!(
+                f,
+                "bench tx_throughput bytes={} cycles={} bytes_per_sec={}",
+                throughput.bytes,
+                throughput.cycles,
+                throughput.bytes_per_sec(self.counter_hz)
+            )?;
+        }
+        if let Some(interrupts) = self.interrupts {
+            let latency = interrupts.latency();
+            if let (Some(min), Some(max), Some(avg)) = (latency.min(), latency.max(), latency.avg())
+            {
+                writeln!(
+                    f,
+                    "bench isr_latency count={} min={min} max={max} avg={avg}",
+                    latency.count()
+                )?;
+            }
+            writeln!(
+                f,
+                "bench interrupts count={} bytes={} bytes_per_interrupt={}",
+                interrupts.interrupts(),
+                interrupts.bytes(),
+                interrupts.bytes_per_interrupt().unwrap_or(0)
+            )?;
+        }
+        if let Some(round_trip) = self.round_trip {
+            let latency = round_trip.latency;
+            writeln!(
+                f,
+                "bench round_trip count={} min={} max={} avg={} lost={}",
+                latency.count(),
+                latency.min().unwrap_or(0),
+                latency.max().unwrap_or(0),
+                latency.avg().unwrap_or(0),
+                round_trip.lost
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! # Software bridge
+//!
+//! This module forwards bytes in both directions between two UART Lite instances, which is
+//! useful for protocol sniffing and for tunneling a console through a second link.
+//!
+//! The [Bridge] buffers the data of each direction in a ring buffer with a size of `N`. Its
+//! [Bridge::service] method can be called from a superloop or from the interrupt handler of
+//! both instances, see [Bridge::enable_interrupts]. The [bridge] function is a blocking
+//! variant which services both directions until a user-provided stop condition is met.
+//!
+//! Received bytes which do not fit into the ring buffer are dropped and counted, so a slow
+//! link never causes a hardware RX overrun on the other link.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Tunnel the console of uart_a through uart_b forever.
+//! bridge(&mut uart_a, &mut uart_b, |_| false);
+//! ```
+use crate::{AxiUartlite, FIFO_DEPTH, registers::RegisterAccess, ring::Ring};
+
+/// Counters of a [Bridge].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BridgeStats {
+    /// Number of bytes forwarded from A to B.
+    pub a_to_b: u32,
+    /// Number of bytes forwarded from B to A.
+    pub b_to_a: u32,
+    /// Number of bytes received on A which were dropped because the buffer was full.
+    pub a_dropped: u32,
+    /// Number of bytes received on B which were dropped because the buffer was full.
+    pub b_dropped: u32,
+}
+
+struct Direction<const N: usize> {
+    ring: Ring<N>,
+    forwarded: u32,
+    dropped: u32,
+}
+
+impl<const N: usize> Direction<N> {
+    const fn new() -> Self {
+        Self {
+            ring: Ring::new(),
+            forwarded: 0,
+            dropped: 0,
+        }
+    }
+
+    fn service<R: RegisterAccess>(
+        &mut self,
+        from: &mut AxiUartlite<R>,
+        to: &mut AxiUartlite<R>,
+    ) -> usize {
+        while let Ok(byte) = from.read_fifo() {
+            if !self.ring.push(byte) {
+                self.dropped = self.dropped.wrapping_add(1);
+            }
+        }
+        let mut burst = [0; FIFO_DEPTH];
+        let len = self.ring.peek(&mut burst);
+        let written = to.write_burst(&burst[0..len]);
+        self.ring.consume(written);
+        self.forwarded = self.forwarded.wrapping_add(written as u32);
+        written
+    }
+}
+
+/// Bidirectional software bridge between two UART Lite instances A and B with a buffer size of
+/// `N` for each direction.
+pub struct Bridge<const N: usize = 64> {
+    a_to_b: Direction<N>,
+    b_to_a: Direction<N>,
+}
+
+impl<const N: usize> Bridge<N> {
+    /// Create a new bridge with empty buffers.
+    pub const fn new() -> Self {
+        Self {
+            a_to_b: Direction::new(),
+            b_to_a: Direction::new(),
+        }
+    }
+
+    /// Enable the interrupts of both instances for interrupt-driven forwarding.
+    ///
+    /// [Self::service] should then be called from the interrupt handlers of both instances.
+    pub fn enable_interrupts<R: RegisterAccess>(
+        &self,
+        a: &mut AxiUartlite<R>,
+        b: &mut AxiUartlite<R>,
+    ) {
+        a.enable_interrupt();
+        b.enable_interrupt();
+    }
+
+    /// Forward pending bytes in both directions.
+    ///
+    /// This drains both RX FIFOs into the buffers and tops up both TX FIFOs. Returns the number
+    /// of bytes written into the TX FIFOs.
+    pub fn service<R: RegisterAccess>(
+        &mut self,
+        a: &mut AxiUartlite<R>,
+        b: &mut AxiUartlite<R>,
+    ) -> usize {
+        self.a_to_b.service(a, b) + self.b_to_a.service(b, a)
+    }
+
+    /// Number of buffered bytes which still need to be forwarded from A to B.
+    #[inline]
+    pub const fn pending_a_to_b(&self) -> usize {
+        self.a_to_b.ring.len()
+    }
+
+    /// Number of buffered bytes which still need to be forwarded from B to A.
+    #[inline]
+    pub const fn pending_b_to_a(&self) -> usize {
+        self.b_to_a.ring.len()
+    }
+
+    /// Snapshot of the bridge counters.
+    pub const fn stats(&self) -> BridgeStats {
+        BridgeStats {
+            a_to_b: self.a_to_b.forwarded,
+            b_to_a: self.b_to_a.forwarded,
+            a_dropped: self.a_to_b.dropped,
+            b_dropped: self.b_to_a.dropped,
+        }
+    }
+}
+
+impl<const N: usize> Default for Bridge<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Blocking bridge between two UART Lite instances.
+///
+/// Both directions are serviced by polling until `stop` returns true. The `stop` closure is
+/// called once per service round with the current counters. Returns the final counters.
+pub fn bridge<R: RegisterAccess>(
+    a: &mut AxiUartlite<R>,
+    b: &mut AxiUartlite<R>,
+    mut stop: impl FnMut(&BridgeStats) -> bool,
+) -> BridgeStats {
+    let mut bridge = Bridge::<64>::new();
+    loop {
+        bridge.service(a, b);
+        let stats = bridge.stats();
+        if stop(&stats) {
+            return stats;
+        }
+    }
+}
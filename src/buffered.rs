@@ -0,0 +1,1069 @@
+//! # Interrupt-driven buffered driver
+//!
+//! This module provides the [BufferedUartlite] driver. The TX and RX data is buffered inside
+//! the software ring buffers of a [BufferedState] structure, which is usually placed inside a
+//! `static`. The interrupt handler services both directions by calling
+//! [BufferedState::on_interrupt]: received bytes are moved from the RX FIFO into the RX ring
+//! buffer and the TX FIFO is refilled from the TX ring buffer.
+//!
+//! The [BufferedUartlite] driver can then be used from thread context with the non-blocking
+//! inherent API or the [embedded_io] traits. The [embedded_io_async] traits are implemented
+//! as well, which makes the driver suitable as the I/O backend for asynchronous interactive
+//! shells or line editors.
+//!
+//! Optional XON/XOFF software flow control can be enabled with
+//! [BufferedState::enable_xon_xoff].
+//!
+//! Writes only access the hardware if no transfer is active. While the interrupt handler is
+//! transmitting, small writes are coalesced inside the TX ring buffer and sent with the next
+//! TX FIFO refill, which avoids a status register read for every `write!` call.
+//!
+//! Urgent frames like alarms or acknowledgements can jump ahead of bulk output through an
+//! optional urgent TX lane, see [BufferedUartlite::try_write_urgent].
+//!
+//! The behavior for a full RX ring buffer can be configured with
+//! [BufferedState::set_rx_overflow_config]. By default, newly received bytes are dropped.
+//!
+//! The driver can carry any [crate::framing::FrameCodec] with
+//! [BufferedUartlite::into_framed] to send and receive complete frames.
+//!
+//! A clock can be registered with [BufferedState::set_rx_clock] to timestamp the first byte
+//! which is received into the empty RX ring buffer inside the interrupt handler.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static BUFFERS: BufferedState<256, 256> = BufferedState::new();
+//!
+//! // Thread context.
+//! let uart = unsafe { AxiUartlite::new(UARTLITE_BASE) };
+//! let mut buffered = BufferedUartlite::new(uart, &BUFFERS);
+//!
+//! // Interrupt handler.
+//! let mut tx = unsafe { Tx::steal(UARTLITE_BASE as usize) };
+//! let mut rx = unsafe { Rx::steal(UARTLITE_BASE as usize) };
+//! BUFFERS.on_interrupt(&mut tx, &mut rx);
+//! ```
+use core::cell::{Cell, RefCell};
+#[cfg(any(feature = "embedded-hal-nb", feature = "embedded-io"))]
+use core::convert::Infallible;
+use core::{future::poll_fn, task::Poll};
+
+use critical_section::Mutex;
+use embassy_sync::{
+    blocking_mutex::raw::CriticalSectionRawMutex, pipe::Pipe, signal::Signal,
+    waitqueue::AtomicWaker,
+};
+
+#[cfg(feature = "embedded-io")]
+use crate::framing::{FrameCodec, Framed};
+use crate::{
+    AxiUartlite, FIFO_DEPTH, Rx, RxErrors, RxErrorsCounted, Tx, XonXoff, XonXoffConfig,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    handle_status_reg_errors,
+    mpsc_ring::MpscRing,
+    registers::{MmioRegisters, RegisterAccess, Status},
+};
+
+/// Bytes which are dropped if the RX ring buffer is full.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RxOverflowPolicy {
+    /// Drop the newly received bytes.
+    #[default]
+    DropNewest,
+    /// Drop the oldest bytes in the ring buffer to make room for the newly received bytes.
+    DropOldest,
+}
+
+/// RX overflow behavior of the buffered driver.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxOverflowConfig {
+    /// Bytes which are dropped.
+    pub policy: RxOverflowPolicy,
+    /// Reset the hardware RX FIFO after an overflow, discarding all bytes which were received
+    /// while the interrupt was serviced.
+    pub reset_rx_fifo: bool,
+}
+
+/// Clock which is used to timestamp received data.
+pub type RxClock = fn() -> u64;
+
+/// RX error event which is published by the interrupt handler, see
+/// [BufferedState::wait_rx_error].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxErrorEvent {
+    /// Errors reported by the interrupt.
+    pub errors: RxErrors,
+    /// Number of interrupts which reported RX errors so far. The counter wraps around.
+    ///
+    /// Only the latest event is kept, so a gap in the counter shows that a slow subscriber
+    /// missed events.
+    pub count: u32,
+}
+
+/// The urgent frame can never fit into the urgent TX lane, see
+/// [BufferedUartlite::write_urgent].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UrgentFrameTooLarge {
+    /// Length of the frame.
+    pub len: usize,
+    /// Size of the urgent TX lane.
+    pub capacity: usize,
+}
+
+impl core::fmt::Display for UrgentFrameTooLarge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "urgent frame of {} bytes exceeds the urgent TX lane of {} bytes",
+            self.len, self.capacity
+        )
+    }
+}
+
+impl core::error::Error for UrgentFrameTooLarge {}
+
+/// Handler which is called by the interrupt handler when the RX ring buffer fill level reached
+/// the high watermark, see [BufferedState::set_rx_high_watermark]. The fill level is passed.
+pub type RxHighWatermarkHandler = fn(usize);
+
+/// Shared state of a [BufferedUartlite] driver.
+///
+/// Contains the TX ring buffer with a size of `TXN`, the RX ring buffer with a size of `RXN`
+/// and the urgent TX lane with a size of `URGENT`, which is disabled by default. This
+/// structure is shared between the interrupt handler and the thread context and is usually
+/// placed inside a `static`.
+pub struct BufferedState<const TXN: usize, const RXN: usize, const URGENT: usize = 0> {
+    tx: MpscRing<TXN>,
+    tx_space_waker: AtomicWaker,
+    tx_urgent: Pipe<CriticalSectionRawMutex, URGENT>,
+    tx_urgent_waker: AtomicWaker,
+    rx: Pipe<CriticalSectionRawMutex, RXN>,
+    rx_overflows: Mutex<Cell<usize>>,
+    rx_overflow_config: Mutex<Cell<RxOverflowConfig>>,
+    rx_clock: Mutex<Cell<Option<RxClock>>>,
+    rx_timestamp: Mutex<Cell<Option<u64>>>,
+    rx_watermark_waker: AtomicWaker,
+    rx_high_watermark: Mutex<Cell<Option<(usize, RxHighWatermarkHandler)>>>,
+    rx_above_high_watermark: Mutex<Cell<bool>>,
+    errors: Mutex<Cell<RxErrorsCounted>>,
+    rx_error_count: Mutex<Cell<u32>>,
+    rx_error_signal: Signal<CriticalSectionRawMutex, RxErrorEvent>,
+    flow_control: Mutex<Cell<Option<XonXoff>>>,
+    producer_tx: Mutex<RefCell<Option<Tx>>>,
+    // A refill of the TX FIFO is pending, so the TX FIFO empty interrupt will follow.
+    tx_active: AtomicBool,
+    tx_dropped_chunks: AtomicUsize,
+}
+
+impl<const TXN: usize, const RXN: usize, const URGENT: usize> BufferedState<TXN, RXN, URGENT> {
+    /// Create a new buffered state structure with empty ring buffers.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            tx: MpscRing::new(),
+            tx_space_waker: AtomicWaker::new(),
+            tx_urgent: Pipe::new(),
+            tx_urgent_waker: AtomicWaker::new(),
+            rx: Pipe::new(),
+            rx_overflows: Mutex::new(Cell::new(0)),
+            rx_overflow_config: Mutex::new(Cell::new(RxOverflowConfig {
+                policy: RxOverflowPolicy::DropNewest,
+                reset_rx_fifo: false,
+            })),
+            rx_clock: Mutex::new(Cell::new(None)),
+            rx_timestamp: Mutex::new(Cell::new(None)),
+            rx_watermark_waker: AtomicWaker::new(),
+            rx_high_watermark: Mutex::new(Cell::new(None)),
+            rx_above_high_watermark: Mutex::new(Cell::new(false)),
+            errors: Mutex::new(Cell::new(RxErrorsCounted::new())),
+            rx_error_count: Mutex::new(Cell::new(0)),
+            rx_error_signal: Signal::new(),
+            flow_control: Mutex::new(Cell::new(None)),
+            producer_tx: Mutex::new(RefCell::new(None)),
+            tx_active: AtomicBool::new(false),
+            tx_dropped_chunks: AtomicUsize::new(0),
+        }
+    }
+
+    /// Enable XON/XOFF software flow control.
+    ///
+    /// The thresholds are applied to the fill level of the RX ring buffer.
+    pub fn enable_xon_xoff(&self, config: XonXoffConfig) {
+        critical_section::with(|cs| self.flow_control.borrow(cs).set(Some(XonXoff::new(config))));
+    }
+
+    /// Disable XON/XOFF software flow control.
+    pub fn disable_xon_xoff(&self) {
+        critical_section::with(|cs| self.flow_control.borrow(cs).set(None));
+    }
+
+    /// Current XON/XOFF flow control state, [None] if flow control is disabled.
+    pub fn xon_xoff(&self) -> Option<XonXoff> {
+        critical_section::with(|cs| self.flow_control.borrow(cs).get())
+    }
+
+    /// Configure the behavior for a full RX ring buffer.
+    pub fn set_rx_overflow_config(&self, config: RxOverflowConfig) {
+        critical_section::with(|cs| self.rx_overflow_config.borrow(cs).set(config));
+    }
+
+    /// Current RX overflow behavior.
+    pub fn rx_overflow_config(&self) -> RxOverflowConfig {
+        critical_section::with(|cs| self.rx_overflow_config.borrow(cs).get())
+    }
+
+    /// Register the clock which is used to timestamp received data, see
+    /// [Self::take_rx_timestamp].
+    pub fn set_rx_clock(&self, now: RxClock) {
+        critical_section::with(|cs| self.rx_clock.borrow(cs).set(Some(now)));
+    }
+
+    /// Read and clear the timestamp of the first byte which was received into the empty RX
+    /// ring buffer.
+    ///
+    /// For frame-gap based protocols, where the application drains the RX ring buffer for each
+    /// frame, this is the timestamp of the first byte of the frame. Returns [None] if no clock
+    /// was registered or no data was received since the last call.
+    pub fn take_rx_timestamp(&self) -> Option<u64> {
+        critical_section::with(|cs| self.rx_timestamp.borrow(cs).take())
+    }
+
+    /// Wait until at least `len` bytes are buffered inside the RX ring buffer.
+    ///
+    /// `len` is clamped to the size of the RX ring buffer. Only one task can wait for a
+    /// watermark at a time.
+    pub async fn wait_rx_watermark(&self, len: usize) {
+        let len = len.min(RXN);
+        poll_fn(|cx| {
+            if self.rx.len() >= len {
+                return Poll::Ready(());
+            }
+            self.rx_watermark_waker.register(cx.waker());
+            // Data might have been received before the waker was registered.
+            if self.rx.len() >= len {
+                return Poll::Ready(());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Number of bytes currently buffered in the RX ring buffer.
+    pub fn rx_len(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// Register a handler which is called by the interrupt handler once the RX ring buffer fill
+    /// level reaches `level`.
+    ///
+    /// This allows throttling the remote end, for example with a protocol NACK, before data is
+    /// dropped. The handler is called once per crossing and is armed again after the fill
+    /// level dropped below `level`. Tasks can wait for the same condition with
+    /// [Self::wait_rx_watermark].
+    pub fn set_rx_high_watermark(&self, level: usize, handler: RxHighWatermarkHandler) {
+        critical_section::with(|cs| {
+            self.rx_high_watermark
+                .borrow(cs)
+                .set(Some((level, handler)));
+            self.rx_above_high_watermark.borrow(cs).set(false);
+        });
+    }
+
+    /// Remove the RX high watermark handler.
+    pub fn clear_rx_high_watermark(&self) {
+        critical_section::with(|cs| self.rx_high_watermark.borrow(cs).set(None));
+    }
+
+    /// The RX fill level reached the high watermark and did not drop below it yet, as seen by
+    /// the interrupt handler.
+    pub fn rx_above_high_watermark(&self) -> bool {
+        critical_section::with(|cs| self.rx_above_high_watermark.borrow(cs).get())
+    }
+
+    /// Wait for the next RX error reported by the interrupt handler.
+    ///
+    /// This allows a supervisory task to react to a degrading line quality without polling
+    /// the error counters. Only one task can wait for errors at a time. An event which was
+    /// published before the call is returned immediately.
+    pub async fn wait_rx_error(&self) -> RxErrorEvent {
+        self.rx_error_signal.wait().await
+    }
+
+    /// Take the latest RX error event if one was published since the last call.
+    pub fn try_take_rx_error(&self) -> Option<RxErrorEvent> {
+        self.rx_error_signal.try_take()
+    }
+
+    /// Interrupt handler for the buffered driver.
+    ///
+    /// This has to be called in the interrupt handler of the UART Lite. It moves all received
+    /// bytes into the RX ring buffer and refills the TX FIFO from the TX ring buffer if it is
+    /// empty. [Tx::steal] and [Rx::steal] can be used to create the required handles.
+    pub fn on_interrupt<R: RegisterAccess>(&self, tx: &mut Tx<R>, rx: &mut Rx<R>) {
+        rx.record_interrupt();
+        let mut buf = [0; FIFO_DEPTH];
+        let mut read = rx.read_whole_fifo(&mut buf);
+        if read > 0 {
+            read = self.filter_flow_control(&mut buf[0..read]);
+        }
+        // The application might have drained the RX ring buffer since the last interrupt.
+        self.update_rx_high_watermark();
+        if read > 0 {
+            if self.rx.is_empty() {
+                self.stamp_rx();
+            }
+            let config = self.rx_overflow_config();
+            let dropped = self.push_rx(&buf[0..read], config.policy);
+            if dropped > 0 {
+                critical_section::with(|cs| {
+                    let overflows = self.rx_overflows.borrow(cs);
+                    overflows.set(overflows.get().saturating_add(dropped));
+                });
+                if config.reset_rx_fifo {
+                    tx.reset_rx_fifo();
+                }
+            }
+            self.rx_watermark_waker.wake();
+            self.update_rx_high_watermark();
+        }
+        // Reading the status register clears the RX error bits, so it is read once for the TX
+        // refill and its errors are reported together with the errors of the RX FIFO reads and
+        // of a RX FIFO reset.
+        let status_reg = tx.regs.read_stat_reg();
+        let errors = [
+            rx.read_and_clear_last_error(),
+            tx.read_and_clear_last_error(),
+            handle_status_reg_errors(&status_reg),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(RxErrors::union);
+        if let Some(errors) = errors {
+            self.record_rx_errors(errors);
+        }
+        self.update_flow_control_level();
+        critical_section::with(|_| self.refill_tx_fifo(tx, &status_reg));
+    }
+
+    /// Count the RX errors and publish them as an [RxErrorEvent].
+    fn record_rx_errors(&self, errors: RxErrors) {
+        #[cfg(feature = "hooks")]
+        crate::hooks::call(|hooks| hooks.on_error(errors));
+        let count = critical_section::with(|cs| {
+            let counted = self.errors.borrow(cs);
+            let mut current = counted.get();
+            current.add(errors);
+            counted.set(current);
+            let count = self.rx_error_count.borrow(cs);
+            count.set(count.get().wrapping_add(1));
+            count.get()
+        });
+        self.rx_error_signal.signal(RxErrorEvent { errors, count });
+    }
+
+    /// Read the status register from thread context and record the RX errors it reports,
+    /// which would otherwise be lost because the read clears the error bits.
+    fn read_status<R: RegisterAccess>(&self, tx: &Tx<R>) -> Status {
+        let status_reg = tx.regs.read_stat_reg();
+        if let Some(errors) = handle_status_reg_errors(&status_reg) {
+            self.record_rx_errors(errors);
+        }
+        status_reg
+    }
+
+    /// Update the high watermark state with the current RX fill level and call the handler if
+    /// the watermark was reached.
+    fn update_rx_high_watermark(&self) {
+        let fill = self.rx.len();
+        let handler = critical_section::with(|cs| {
+            let (level, handler) = self.rx_high_watermark.borrow(cs).get()?;
+            let above = self.rx_above_high_watermark.borrow(cs);
+            if fill < level {
+                above.set(false);
+                return None;
+            }
+            if above.replace(true) {
+                return None;
+            }
+            Some(handler)
+        });
+        if let Some(handler) = handler {
+            handler(fill);
+        }
+    }
+
+    /// Timestamp the first byte received into the empty RX ring buffer.
+    fn stamp_rx(&self) {
+        critical_section::with(|cs| {
+            if let Some(now) = self.rx_clock.borrow(cs).get() {
+                self.rx_timestamp.borrow(cs).set(Some(now()));
+            }
+        });
+    }
+
+    /// Move the received bytes into the RX ring buffer according to the overflow policy.
+    ///
+    /// Returns the number of dropped bytes.
+    fn push_rx(&self, data: &[u8], policy: RxOverflowPolicy) -> usize {
+        let mut written = 0;
+        let mut dropped = 0;
+        while written < data.len() {
+            if let Ok(n) = self.rx.try_write(&data[written..]) {
+                written += n;
+                continue;
+            }
+            let remaining = data.len() - written;
+            if policy == RxOverflowPolicy::DropOldest {
+                let mut discard = [0; FIFO_DEPTH];
+                if let Ok(n) = self.rx.try_read(&mut discard[0..remaining]) {
+                    dropped += n;
+                    continue;
+                }
+            }
+            return dropped + remaining;
+        }
+        dropped
+    }
+
+    /// Consume the XON/XOFF characters in the passed buffer if flow control is enabled.
+    ///
+    /// Returns the number of remaining data bytes, which are moved to the start of the buffer.
+    fn filter_flow_control(&self, buf: &mut [u8]) -> usize {
+        critical_section::with(|cs| {
+            let flow_control = self.flow_control.borrow(cs);
+            let Some(mut state) = flow_control.get() else {
+                return buf.len();
+            };
+            let mut data_len = 0;
+            for idx in 0..buf.len() {
+                if let Some(byte) = state.on_rx_byte(buf[idx]) {
+                    buf[data_len] = byte;
+                    data_len += 1;
+                }
+            }
+            flow_control.set(Some(state));
+            data_len
+        })
+    }
+
+    /// Update the flow control state with the current RX ring buffer fill level.
+    fn update_flow_control_level(&self) {
+        critical_section::with(|cs| {
+            let flow_control = self.flow_control.borrow(cs);
+            if let Some(mut state) = flow_control.get() {
+                state.on_rx_fill_level(self.rx.len());
+                flow_control.set(Some(state));
+            }
+        });
+    }
+
+    /// Refill the TX FIFO from the TX ring buffer if the FIFO is empty according to the passed
+    /// status register value.
+    ///
+    /// Pending flow control characters are written first and only require the FIFO to not
+    /// be full. No data is written while TX is paused by the remote end.
+    ///
+    /// Returns the number of bytes written to the FIFO.
+    fn refill_tx_fifo<R: RegisterAccess>(&self, tx: &mut Tx<R>, status_reg: &Status) -> usize {
+        let mut written = self.refill_tx_fifo_inner(tx, status_reg);
+        self.tx_active.store(written.is_some(), Ordering::SeqCst);
+        if written.is_none() && !self.tx.is_empty() {
+            // A producer on another core might have published its data while the transfer
+            // was still marked active, so it did not start a transfer.
+            written = self.refill_tx_fifo_inner(tx, status_reg);
+            self.tx_active.store(written.is_some(), Ordering::SeqCst);
+        }
+        written.unwrap_or(0)
+    }
+
+    /// Refill the TX FIFO if no transfer is active. Small writes are coalesced in the TX ring
+    /// buffer while a transfer is active, because the TX FIFO empty interrupt refills the
+    /// FIFO with all buffered data anyway. This avoids status register reads for every write.
+    fn start_tx_if_idle<R: RegisterAccess>(&self, tx: &mut Tx<R>) {
+        critical_section::with(|_| {
+            if !self.tx_active.load(Ordering::SeqCst) {
+                let status_reg = self.read_status(tx);
+                self.refill_tx_fifo(tx, &status_reg);
+            }
+        });
+    }
+
+    /// Returns [None] if the transfer is finished or paused, so no TX FIFO empty interrupt
+    /// follows.
+    fn refill_tx_fifo_inner<R: RegisterAccess>(
+        &self,
+        tx: &mut Tx<R>,
+        status_reg: &Status,
+    ) -> Option<usize> {
+        let mut fifo_empty = status_reg.tx_fifo_empty();
+        let tx_paused = critical_section::with(|cs| {
+            let flow_control = self.flow_control.borrow(cs);
+            let Some(mut state) = flow_control.get() else {
+                return false;
+            };
+            if !status_reg.tx_fifo_full() {
+                if let Some(control) = state.take_pending() {
+                    tx.write_fifo_unchecked(control);
+                    flow_control.set(Some(state));
+                    fifo_empty = false;
+                }
+            }
+            state.tx_paused()
+        });
+        if tx_paused {
+            return None;
+        }
+        if !fifo_empty {
+            return Some(0);
+        }
+        let mut buf = [0; FIFO_DEPTH];
+        let mut filled = 0;
+        // The urgent lane is always drained first.
+        while filled < FIFO_DEPTH {
+            match self.tx_urgent.try_read(&mut buf[filled..]) {
+                Ok(n) => filled += n,
+                Err(_) => break,
+            }
+        }
+        if filled > 0 {
+            self.tx_urgent_waker.wake();
+        }
+        let regular = self.tx.try_read(&mut buf[filled..]);
+        if regular > 0 {
+            self.tx_space_waker.wake();
+        }
+        filled += regular;
+        if filled == 0 {
+            return None;
+        }
+        Some(tx.write_fifo_unchecked_slice(&buf[0..filled]))
+    }
+
+    /// Number of bytes which were dropped because the RX ring buffer was full.
+    pub fn rx_overflows(&self) -> usize {
+        critical_section::with(|cs| self.rx_overflows.borrow(cs).get())
+    }
+
+    /// Handle for enqueuing TX data from multiple tasks and interrupt handlers without
+    /// locking, see [TxProducer].
+    #[inline]
+    pub const fn producer(&self) -> TxProducer<'_, TXN, RXN, URGENT> {
+        TxProducer { state: self }
+    }
+
+    /// Set the TX handle which is used by [TxProducer]s to start a transfer if the TX FIFO is
+    /// idle. Returns the previous handle. [Tx::steal] can be used to create the handle.
+    ///
+    /// Data enqueued while no TX handle is set is only sent after the next interrupt, for
+    /// example caused by the driver itself.
+    pub fn set_producer_tx(&self, tx: Tx) -> Option<Tx> {
+        critical_section::with(|cs| self.producer_tx.borrow(cs).replace(Some(tx)))
+    }
+
+    /// Number of chunks which were dropped by [TxProducer::try_enqueue] because the TX ring
+    /// buffer was full. The counter wraps around.
+    pub fn tx_dropped_chunks(&self) -> usize {
+        self.tx_dropped_chunks.load(Ordering::Relaxed)
+    }
+
+    /// Both TX lanes are empty.
+    fn tx_lanes_empty(&self) -> bool {
+        self.tx.is_empty() && self.tx_urgent.is_empty()
+    }
+}
+
+/// Handle for enqueuing TX data into the ring buffer of a [BufferedState].
+///
+/// The handle is [Copy] and only needs a shared reference to the state, so multiple tasks and
+/// interrupt handlers can enqueue log data concurrently without access to the TX driver.
+/// The enqueue is lock-free: space in the TX ring buffer is reserved with a compare-and-swap,
+/// so a producer which is preempted while copying its chunk never blocks other producers and
+/// interrupts are never disabled for copying. Only starting a transfer on an idle line takes
+/// a short critical section to access the TX handle. Every chunk is enqueued completely or
+/// not at all, so chunks of different producers are never interleaved.
+#[derive(Copy, Clone)]
+pub struct TxProducer<'state, const TXN: usize, const RXN: usize, const URGENT: usize = 0> {
+    state: &'state BufferedState<TXN, RXN, URGENT>,
+}
+
+impl<const TXN: usize, const RXN: usize, const URGENT: usize> TxProducer<'_, TXN, RXN, URGENT> {
+    /// Enqueue a chunk without blocking and start a transfer if the TX FIFO is idle.
+    ///
+    /// Returns false and counts the chunk as dropped if it does not fit into the TX ring buffer
+    /// completely, see [BufferedState::tx_dropped_chunks].
+    pub fn try_enqueue(&self, chunk: &[u8]) -> bool {
+        if !self.state.tx.try_write_all(chunk) {
+            self.state.tx_dropped_chunks.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        // An active transfer picks up the chunk with the next TX FIFO refill.
+        if !self.state.tx_active.load(Ordering::SeqCst) {
+            critical_section::with(|cs| {
+                if let Some(tx) = self.state.producer_tx.borrow(cs).borrow_mut().as_mut() {
+                    self.state.start_tx_if_idle(tx);
+                }
+            });
+        }
+        true
+    }
+}
+
+impl<const TXN: usize, const RXN: usize, const URGENT: usize> core::fmt::Write
+    for TxProducer<'_, TXN, RXN, URGENT>
+{
+    /// Every string piece is enqueued as a separate chunk, so formatted output of different
+    /// producers might be interleaved. Format into a buffer first and use
+    /// [TxProducer::try_enqueue] if lines must stay intact.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.try_enqueue(s.as_bytes()) {
+            Ok(())
+        } else {
+            Err(core::fmt::Error)
+        }
+    }
+}
+
+/// Interrupt-driven buffered AXI UART Lite driver.
+///
+/// Data is buffered inside the ring buffers of the [BufferedState] structure, and the
+/// interrupt handler is responsible for moving the data from and to the hardware FIFOs.
+/// The UART Lite interrupt is enabled when creating the driver. The register backend `R`
+/// defaults to memory-mapped access.
+pub struct BufferedUartlite<
+    'state,
+    const TXN: usize,
+    const RXN: usize,
+    const URGENT: usize = 0,
+    R = MmioRegisters<'static>,
+> {
+    tx: Tx<R>,
+    rx: Rx<R>,
+    state: &'state BufferedState<TXN, RXN, URGENT>,
+}
+
+impl<'state, const TXN: usize, const RXN: usize, const URGENT: usize>
+    BufferedUartlite<'state, TXN, RXN, URGENT>
+{
+    /// Create a new buffered driver in a `const` context without accessing the hardware.
+    ///
+    /// This allows declaring the driver inside a `static`, for example wrapped in a blocking
+    /// mutex, or as an RTIC resource without lazy initialization. [Self::init] has to be
+    /// called once before the driver is used.
+    ///
+    /// ```ignore
+    /// static BUFFERS: BufferedState<256, 256> = BufferedState::new();
+    /// static UART: Mutex<CriticalSectionRawMutex, RefCell<BufferedUartlite<'static, 256, 256>>> =
+    ///     Mutex::new(RefCell::new(BufferedUartlite::new_const(
+    ///         unsafe { AxiUartlite::new(UARTLITE_BASE) },
+    ///         &BUFFERS,
+    ///     )));
+    /// ```
+    pub const fn new_const(
+        uart: AxiUartlite,
+        state: &'state BufferedState<TXN, RXN, URGENT>,
+    ) -> Self {
+        let AxiUartlite { tx, rx, .. } = uart;
+        Self { tx, rx, state }
+    }
+}
+
+impl<'state, const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    BufferedUartlite<'state, TXN, RXN, URGENT, R>
+{
+    /// Create a new buffered driver and enable the UART Lite interrupt.
+    ///
+    /// Both hardware FIFOs are reset.
+    pub fn new(mut uart: AxiUartlite<R>, state: &'state BufferedState<TXN, RXN, URGENT>) -> Self {
+        uart.reset_rx_fifo();
+        uart.reset_tx_fifo();
+        state.tx_active.store(false, Ordering::SeqCst);
+        uart.enable_interrupt();
+        let (tx, rx) = uart.split();
+        Self { tx, rx, state }
+    }
+
+    /// Reset both hardware FIFOs and enable the UART Lite interrupt.
+    ///
+    /// This has to be called once for a driver created with [Self::new_const] and is done
+    /// by [Self::new] automatically.
+    pub fn init(&mut self) {
+        self.tx.reset_rx_fifo();
+        self.tx.reset_fifo();
+        self.state.tx_active.store(false, Ordering::SeqCst);
+        let control = self.tx.control_state();
+        self.tx.apply_control(control.with_interrupt_enabled(true));
+    }
+
+    /// Write as many bytes as possible into the TX ring buffer without blocking.
+    ///
+    /// Returns the number of bytes written, which might be 0 if the ring buffer is full.
+    pub fn try_write(&mut self, buf: &[u8]) -> usize {
+        let written = self.state.tx.try_write(buf);
+        self.start_tx_if_idle();
+        written
+    }
+
+    /// Write an urgent frame into the urgent TX lane without blocking.
+    ///
+    /// The urgent lane is drained before the regular TX ring buffer on every TX FIFO refill, so
+    /// urgent frames like alarms or acknowledgements jump ahead of bulk output. Regular output
+    /// may be interrupted between any two bytes. The frame is only written if it fits
+    /// completely, and it is copied inside a critical section, so the interrupt handler never
+    /// sees a partial frame and urgent frames are never split.
+    ///
+    /// Returns whether the frame was written.
+    pub fn try_write_urgent(&mut self, frame: &[u8]) -> bool {
+        let written = critical_section::with(|_| {
+            if frame.len() > self.state.tx_urgent.free_capacity() {
+                return false;
+            }
+            // The copy might wrap around the end of the ring buffer.
+            let mut written = 0;
+            while written < frame.len() {
+                match self.state.tx_urgent.try_write(&frame[written..]) {
+                    Ok(n) => written += n,
+                    Err(_) => break,
+                }
+            }
+            true
+        });
+        if written {
+            self.start_tx_if_idle();
+        }
+        written
+    }
+
+    /// Write an urgent frame into the urgent TX lane, waiting until the urgent lane has space
+    /// for the whole frame, see [Self::try_write_urgent].
+    ///
+    /// Returns [UrgentFrameTooLarge] without waiting if the frame can never fit, which is
+    /// always the case if the urgent lane is disabled.
+    pub async fn write_urgent(&mut self, frame: &[u8]) -> Result<(), UrgentFrameTooLarge> {
+        if frame.len() > URGENT {
+            return Err(UrgentFrameTooLarge {
+                len: frame.len(),
+                capacity: URGENT,
+            });
+        }
+        poll_fn(|cx| {
+            self.state.tx_urgent_waker.register(cx.waker());
+            if self.try_write_urgent(frame) {
+                return Poll::Ready(());
+            }
+            Poll::Pending
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Read as many bytes as possible from the RX ring buffer without blocking.
+    ///
+    /// Returns the number of bytes read, which might be 0 if no data was received.
+    pub fn try_read(&mut self, buf: &mut [u8]) -> usize {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.state.rx.try_read(&mut buf[read..]) {
+                Ok(n) => read += n,
+                Err(_) => break,
+            }
+        }
+        if read > 0 {
+            // Emit XON if required.
+            self.state.update_flow_control_level();
+            self.start_tx_if_idle();
+        }
+        read
+    }
+
+    /// Wait until at least `len` bytes are buffered inside the RX ring buffer, see
+    /// [BufferedState::wait_rx_watermark].
+    pub async fn wait_rx_watermark(&self, len: usize) {
+        self.state.wait_rx_watermark(len).await
+    }
+
+    /// Wait until enough bytes are buffered to fill the whole buffer and read them in one shot.
+    ///
+    /// This is useful to await fixed-size headers. The buffer length should not exceed the size
+    /// of the RX ring buffer, otherwise only the size of the RX ring buffer is awaited.
+    /// Returns the number of read bytes.
+    pub async fn read_watermark(&mut self, buf: &mut [u8]) -> usize {
+        self.state.wait_rx_watermark(buf.len()).await;
+        self.try_read(buf)
+    }
+
+    /// Read and clear the timestamp of the first byte which was received into the empty RX
+    /// ring buffer, see [BufferedState::take_rx_timestamp].
+    pub fn take_rx_timestamp(&self) -> Option<u64> {
+        self.state.take_rx_timestamp()
+    }
+
+    /// Number of bytes currently buffered in the RX ring buffer.
+    pub fn rx_len(&self) -> usize {
+        self.state.rx.len()
+    }
+
+    /// Free space in the TX ring buffer.
+    pub fn tx_free_capacity(&self) -> usize {
+        self.state.tx.free_capacity()
+    }
+
+    /// Number of bytes which were dropped because the RX ring buffer was full.
+    pub fn rx_overflows(&self) -> usize {
+        self.state.rx_overflows()
+    }
+
+    /// Read the error counters which were captured by the interrupt handler and also reset
+    /// them.
+    pub fn read_and_clear_errors(&mut self) -> RxErrorsCounted {
+        critical_section::with(|cs| self.state.errors.borrow(cs).replace(RxErrorsCounted::new()))
+    }
+
+    /// Release the TX and RX halves.
+    ///
+    /// The UART Lite interrupt remains enabled.
+    pub fn release(self) -> (Tx<R>, Rx<R>) {
+        (self.tx, self.rx)
+    }
+
+    /// Kick off a transfer if no transfer is active. Otherwise, the TX FIFO empty interrupt
+    /// will take care of refilling the FIFO.
+    fn start_tx_if_idle(&mut self) {
+        self.state.start_tx_if_idle(&mut self.tx);
+    }
+
+    /// Refill the TX FIFO if it is empty, even if a transfer is active.
+    fn refill_tx(&mut self) {
+        critical_section::with(|_| {
+            let status_reg = self.state.read_status(&self.tx);
+            self.state.refill_tx_fifo(&mut self.tx, &status_reg)
+        });
+    }
+
+    /// Is the TX FIFO empty? RX errors reported by the status register read are recorded.
+    fn tx_fifo_empty(&self) -> bool {
+        self.state.read_status(&self.tx).tx_fifo_empty()
+    }
+
+    /// Block until both TX lanes and the TX FIFO are empty.
+    pub fn flush_blocking(&mut self) {
+        while !self.state.tx_lanes_empty() {
+            // The interrupt might not be serviced, for example if interrupts are disabled.
+            self.refill_tx();
+        }
+        while !self.tx_fifo_empty() {}
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<'state, const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    BufferedUartlite<'state, TXN, RXN, URGENT, R>
+{
+    /// Wrap the driver together with a frame codec, which sends and receives complete frames
+    /// through the ring buffers.
+    pub fn into_framed<C: FrameCodec>(self, codec: C) -> Framed<Self, C> {
+        Framed::new(self, codec)
+    }
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_hal_nb::serial::ErrorType for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    type Error = Infallible;
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_hal_nb::serial::Write for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Writes the byte into the TX ring buffer, [nb::Error::WouldBlock] if it is full.
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        if self.try_write(&[word]) == 0 {
+            // The interrupt might not be serviced, for example if interrupts are disabled.
+            self.refill_tx();
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(())
+    }
+
+    /// [nb::Error::WouldBlock] until both TX lanes and the TX FIFO are empty.
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        if self.state.tx_lanes_empty() && self.tx_fifo_empty() {
+            return Ok(());
+        }
+        // The interrupt might not be serviced, for example if interrupts are disabled.
+        self.refill_tx();
+        Err(nb::Error::WouldBlock)
+    }
+}
+
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_hal_nb::serial::Read for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Reads one byte from the RX ring buffer, [nb::Error::WouldBlock] if it is empty.
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        let mut byte = [0; 1];
+        if self.try_read(&mut byte) == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(byte[0])
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_io::ErrorType for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    type Error = Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess> embedded_io::Write
+    for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Blocks until at least one byte could be written into the TX ring buffer.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let written = self.try_write(buf);
+            if written > 0 {
+                return Ok(written);
+            }
+            // The interrupt might not be serviced, for example if interrupts are disabled.
+            self.refill_tx();
+        }
+    }
+
+    /// Blocks until the TX ring buffer and the TX FIFO are empty.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flush_blocking();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_io::WriteReady for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.state.tx.is_full())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess> embedded_io::Read
+    for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Blocks until at least one byte is available inside the RX ring buffer.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = self.try_read(buf);
+            if read > 0 {
+                return Ok(read);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_io::ReadReady for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.state.rx.is_empty())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_io_async::Write for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Waits until at least one byte could be written into the TX ring buffer.
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let written = poll_fn(|cx| {
+            self.state.tx_space_waker.register(cx.waker());
+            match self.try_write(buf) {
+                0 => Poll::Pending,
+                written => Poll::Ready(written),
+            }
+        })
+        .await;
+        Ok(written)
+    }
+
+    /// Waits until both TX lanes and the TX FIFO are empty.
+    ///
+    /// There is no interrupt for the transition of the TX FIFO to empty which could be used
+    /// for waking, so this yields until the transfer is complete.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        poll_fn(|cx| {
+            if self.state.tx_lanes_empty() && self.tx_fifo_empty() {
+                return Poll::Ready(());
+            }
+            self.refill_tx();
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess>
+    embedded_io_async::Read for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Waits until at least one byte is available inside the RX ring buffer.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let read = self.state.rx.read(buf).await;
+        // Emit XON if required.
+        self.state.update_flow_control_level();
+        self.start_tx_if_idle();
+        Ok(read)
+    }
+}
+
+impl<const TXN: usize, const RXN: usize, const URGENT: usize, R: RegisterAccess> core::fmt::Write
+    for BufferedUartlite<'_, TXN, RXN, URGENT, R>
+{
+    /// Blocking write of the string into the TX ring buffer.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let mut remaining = s.as_bytes();
+        while !remaining.is_empty() {
+            let written = self.try_write(remaining);
+            if written == 0 {
+                // The interrupt might not be serviced, for example if interrupts are disabled.
+                self.refill_tx();
+            }
+            remaining = &remaining[written..];
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+//! # Build script helper
+//!
+//! This module is only available with the `build-helper` feature and is intended to be used from
+//! the build script of an application crate, with this crate added as a build dependency. It
+//! parses the `xparameters.h` header generated by Vitis and emits Rust constants for all
+//! UART Lite instances of the design, which avoids hand-copied base addresses drifting from the
+//! hardware design.
+//!
+//! For every instance, the generated source contains a `<NAME>_BASEADDR` constant and, if the
+//! header contains the synthesis parameters, a `<NAME>_HW_CONFIG` constant. A
+//! [crate::uartlite_instances] declaration named `Peripherals` is generated for all instances.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     axi_uartlite::build_helper::generate_from_xparameters(
+//!         "bsp/include/xparameters.h",
+//!         std::path::Path::new(&out_dir).join("uartlite.rs"),
+//!     )
+//!     .unwrap();
+//! }
+//!
+//! // main.rs
+//! include!(concat!(env!("OUT_DIR"), "/uartlite.rs"));
+//! ```
+use std::{
+    fmt::Write as _,
+    format,
+    path::Path,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{DataBits, HwConfig, Parity};
+
+/// UART Lite instance parsed from the hardware description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UartliteInstance {
+    /// Instance name, for example `AXI_UARTLITE_0`.
+    pub name: String,
+    /// Base address.
+    pub base_addr: u64,
+    /// Synthesis parameters, if all of them were specified.
+    pub hw_config: Option<HwConfig>,
+}
+
+/// Error when parsing `xparameters.h`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XparametersError {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Name of the definition with the invalid value.
+    pub define: String,
+}
+
+impl core::fmt::Display for XparametersError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid value for {} in line {}", self.define, self.line)
+    }
+}
+
+impl core::error::Error for XparametersError {}
+
+#[derive(Default)]
+struct RawInstance {
+    name: String,
+    base_addr: Option<u64>,
+    baud_rate: Option<u32>,
+    data_bits: Option<DataBits>,
+    use_parity: Option<bool>,
+    odd_parity: Option<bool>,
+}
+
+fn parse_int(value: &str) -> Option<u64> {
+    let value = value.trim_end_matches(['U', 'u', 'L', 'l']);
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Parse all UART Lite instances from the content of a `xparameters.h` header.
+///
+/// All `XPAR_<NAME>_BASEADDR` definitions with an instance name containing `UARTLITE` are
+/// recognized. The optional `_BAUDRATE`, `_DATA_BITS`, `_USE_PARITY` and `_ODD_PARITY`
+/// definitions of the same instance are used for the [HwConfig]. Vitis also emits canonical
+/// aliases like `XPAR_UARTLITE_0_BASEADDR`, so only the first instance for each base address is
+/// returned.
+pub fn parse_xparameters(content: &str) -> Result<Vec<UartliteInstance>, XparametersError> {
+    const SUFFIXES: [&str; 5] = [
+        "_BASEADDR",
+        "_BAUDRATE",
+        "_DATA_BITS",
+        "_USE_PARITY",
+        "_ODD_PARITY",
+    ];
+    let mut raw_instances: Vec<RawInstance> = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some("#define") {
+            continue;
+        }
+        let (Some(define), Some(value)) = (tokens.next(), tokens.next()) else {
+            continue;
+        };
+        let Some(define_name) = define.strip_prefix("XPAR_") else {
+            continue;
+        };
+        let Some((name, suffix)) = SUFFIXES
+            .iter()
+            .find_map(|suffix| Some((define_name.strip_suffix(suffix)?, *suffix)))
+        else {
+            continue;
+        };
+        if !name.contains("UARTLITE") {
+            continue;
+        }
+        let error = || XparametersError {
+            line: idx + 1,
+            define: define.to_string(),
+        };
+        let value = parse_int(value).ok_or_else(error)?;
+        let instance = match raw_instances.iter().position(|i| i.name == name) {
+            Some(pos) => &mut raw_instances[pos],
+            None => {
+                raw_instances.push(RawInstance {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                raw_instances.last_mut().unwrap()
+            }
+        };
+        match suffix {
+            "_BASEADDR" => instance.base_addr = Some(value),
+            "_BAUDRATE" => instance.baud_rate = Some(u32::try_from(value).map_err(|_| error())?),
+            "_DATA_BITS" => {
+                instance.data_bits = Some(match value {
+                    5 => DataBits::Five,
+                    6 => DataBits::Six,
+                    7 => DataBits::Seven,
+                    8 => DataBits::Eight,
+                    _ => return Err(error()),
+                })
+            }
+            "_USE_PARITY" => instance.use_parity = Some(value != 0),
+            _ => instance.odd_parity = Some(value != 0),
+        }
+    }
+    let mut instances: Vec<UartliteInstance> = Vec::new();
+    for raw in raw_instances {
+        let Some(base_addr) = raw.base_addr else {
+            continue;
+        };
+        if instances.iter().any(|i| i.base_addr == base_addr) {
+            continue;
+        }
+        let hw_config = match (raw.baud_rate, raw.data_bits, raw.use_parity) {
+            (Some(baud_rate), Some(data_bits), Some(use_parity)) => {
+                let parity = match (use_parity, raw.odd_parity.unwrap_or(false)) {
+                    (false, _) => Parity::None,
+                    (true, false) => Parity::Even,
+                    (true, true) => Parity::Odd,
+                };
+                Some(HwConfig::new(baud_rate, data_bits, parity))
+            }
+            _ => None,
+        };
+        instances.push(UartliteInstance {
+            name: raw.name,
+            base_addr,
+            hw_config,
+        });
+    }
+    Ok(instances)
+}
+
+/// Generate the Rust source for the passed instances.
+///
+/// The generated source refers to this crate as `axi_uartlite`. No `Peripherals` declaration is
+/// generated if no instances are passed.
+pub fn generate(instances: &[UartliteInstance]) -> String {
+    let mut out = String::from("// Generated by axi_uartlite::build_helper. Do not edit.\n");
+    for instance in instances {
+        let name = &instance.name;
+        // Writing into a string can not fail.
+        let _ = writeln!(
+            out,
+            "\n/// Base address of the `{name}` UART Lite instance.\n\
+             pub const {name}_BASEADDR: usize = {:#x};",
+            instance.base_addr
+        );
+        if let Some(hw_config) = instance.hw_config {
+            let _ = writeln!(
+                out,
+                "\n/// Synthesis parameters of the `{name}` UART Lite instance.\n\
+                 pub const {name}_HW_CONFIG: axi_uartlite::HwConfig = axi_uartlite::HwConfig::new(\
+                 {}, axi_uartlite::DataBits::{:?}, axi_uartlite::Parity::{:?});",
+                hw_config.baud_rate, hw_config.data_bits, hw_config.parity
+            );
+        }
+    }
+    if !instances.is_empty() {
+        out.push_str(
+            "\naxi_uartlite::uartlite_instances! {\n    \
+             /// UART Lite instances of the hardware design.\n    \
+             pub struct Peripherals {\n",
+        );
+        for instance in instances {
+            let _ = writeln!(out, "        {0}: {0}_BASEADDR,", instance.name);
+        }
+        out.push_str("    }\n}\n");
+    }
+    out
+}
+
+/// Parse the `xparameters.h` header at `xparameters` and write the generated Rust source to
+/// `out_file`.
+///
+/// This also instructs Cargo to re-run the build script if the header changes.
+pub fn generate_from_xparameters(
+    xparameters: impl AsRef<Path>,
+    out_file: impl AsRef<Path>,
+) -> std::io::Result<()> {
+    let xparameters = xparameters.as_ref();
+    std::println!("cargo:rerun-if-changed={}", xparameters.display());
+    let content = std::fs::read_to_string(xparameters)?;
+    let instances = parse_xparameters(&content).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{}: {e}", xparameters.display()),
+        )
+    })?;
+    std::fs::write(out_file, generate(&instances))
+}
@@ -0,0 +1,225 @@
+//! # CCSDS space packet RX support
+//!
+//! This module provides the [SpacePacketReceiver] which assembles CCSDS space packets, for
+//! example PUS telecommands, from a raw byte stream. The length of each packet is extracted
+//! from the primary header, so no additional framing is required. Complete packets are passed
+//! to a user handler and can then be processed further, for example with the
+//! [`spacepackets`](https://docs.rs/spacepackets) crate.
+//!
+//! Bytes which can not be the start of a valid primary header are discarded to resynchronize
+//! with the byte stream.
+
+/// Length of the CCSDS space packet primary header.
+pub const PRIMARY_HEADER_LEN: usize = 6;
+/// Maximum length of a CCSDS space packet.
+pub const MAX_PACKET_LEN: usize = PRIMARY_HEADER_LEN + u16::MAX as usize + 1;
+
+/// CCSDS packet type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PacketType {
+    /// Telemetry packet.
+    Tm = 0,
+    /// Telecommand packet.
+    Tc = 1,
+}
+
+/// Parsed CCSDS space packet primary header.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PrimaryHeader {
+    raw: [u8; PRIMARY_HEADER_LEN],
+}
+
+impl PrimaryHeader {
+    /// Parse the primary header from the start of the passed buffer.
+    ///
+    /// Returns [None] if the buffer is too short or the packet version number is not 0.
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        let raw: [u8; PRIMARY_HEADER_LEN] = buf.get(0..PRIMARY_HEADER_LEN)?.try_into().ok()?;
+        if raw[0] >> 5 != 0 {
+            return None;
+        }
+        Some(Self { raw })
+    }
+
+    /// Packet type.
+    pub const fn packet_type(&self) -> PacketType {
+        if (self.raw[0] >> 4) & 0b1 == 1 {
+            PacketType::Tc
+        } else {
+            PacketType::Tm
+        }
+    }
+
+    /// Secondary header flag.
+    pub const fn sec_header_flag(&self) -> bool {
+        (self.raw[0] >> 3) & 0b1 == 1
+    }
+
+    /// Application process identifier (11 bits).
+    pub const fn apid(&self) -> u16 {
+        u16::from_be_bytes([self.raw[0] & 0b111, self.raw[1]])
+    }
+
+    /// Sequence flags (2 bits).
+    pub const fn seq_flags(&self) -> u8 {
+        self.raw[2] >> 6
+    }
+
+    /// Packet sequence count (14 bits).
+    pub const fn seq_count(&self) -> u16 {
+        u16::from_be_bytes([self.raw[2] & 0x3F, self.raw[3]])
+    }
+
+    /// Raw packet data length field, which is the length of the packet data field minus 1.
+    pub const fn data_len(&self) -> u16 {
+        u16::from_be_bytes([self.raw[4], self.raw[5]])
+    }
+
+    /// Total packet length including the primary header.
+    pub const fn packet_len(&self) -> usize {
+        PRIMARY_HEADER_LEN + self.data_len() as usize + 1
+    }
+}
+
+/// Space packet reception error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpacePacketError {
+    /// The packet length specified in the primary header exceeds the receiver buffer. The
+    /// packet is skipped.
+    TooLarge(usize),
+}
+
+impl core::fmt::Display for SpacePacketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooLarge(len) => write!(f, "packet length {len} exceeds receiver buffer"),
+        }
+    }
+}
+
+impl core::error::Error for SpacePacketError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    Header,
+    Data { packet_len: usize },
+    Skip { remaining: usize },
+}
+
+/// CCSDS space packet receiver with an internal buffer of size `N`.
+#[derive(Debug)]
+pub struct SpacePacketReceiver<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    state: State,
+    discarded: usize,
+}
+
+impl<const N: usize> Default for SpacePacketReceiver<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SpacePacketReceiver<N> {
+    /// Create a new receiver.
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                N >= PRIMARY_HEADER_LEN,
+                "buffer too small for primary header"
+            )
+        };
+        Self {
+            buf: [0; N],
+            len: 0,
+            state: State::Header,
+            discarded: 0,
+        }
+    }
+
+    /// Reset the receiver, discarding the packet which is currently being received.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.state = State::Header;
+    }
+
+    /// Number of bytes which were discarded to resynchronize with the byte stream.
+    pub const fn discarded(&self) -> usize {
+        self.discarded
+    }
+
+    /// Feed one received byte into the receiver.
+    ///
+    /// Returns the complete packet including the primary header once it was received.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, SpacePacketError> {
+        Ok(self.push(byte)?.map(|len| &self.buf[0..len]))
+    }
+
+    /// Feed all passed bytes into the receiver and call the handler for each complete packet.
+    ///
+    /// This can be called with the bytes read in the RX interrupt handler, for example with
+    /// [crate::Rx::on_interrupt_rx]. Errors are passed to the handler as well and reception
+    /// continues with the next packet.
+    pub fn feed_all(
+        &mut self,
+        bytes: &[u8],
+        mut handler: impl FnMut(Result<&[u8], SpacePacketError>),
+    ) {
+        for &byte in bytes {
+            match self.push(byte) {
+                Ok(Some(len)) => handler(Ok(&self.buf[0..len])),
+                Ok(None) => (),
+                Err(e) => handler(Err(e)),
+            }
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> Result<Option<usize>, SpacePacketError> {
+        match self.state {
+            State::Header => {
+                // The packet version number must be 0.
+                if self.len == 0 && byte >> 5 != 0 {
+                    self.discarded += 1;
+                    return Ok(None);
+                }
+                self.buf[self.len] = byte;
+                self.len += 1;
+                if self.len < PRIMARY_HEADER_LEN {
+                    return Ok(None);
+                }
+                let packet_len = PrimaryHeader::from_bytes(&self.buf)
+                    .map(|header| header.packet_len())
+                    .unwrap_or(0);
+                if packet_len > N {
+                    self.len = 0;
+                    self.state = State::Skip {
+                        remaining: packet_len - PRIMARY_HEADER_LEN,
+                    };
+                    return Err(SpacePacketError::TooLarge(packet_len));
+                }
+                self.state = State::Data { packet_len };
+                Ok(None)
+            }
+            State::Data { packet_len } => {
+                self.buf[self.len] = byte;
+                self.len += 1;
+                if self.len < packet_len {
+                    return Ok(None);
+                }
+                self.reset();
+                Ok(Some(packet_len))
+            }
+            State::Skip { remaining } => {
+                if remaining <= 1 {
+                    self.reset();
+                } else {
+                    self.state = State::Skip {
+                        remaining: remaining - 1,
+                    };
+                }
+                Ok(None)
+            }
+        }
+    }
+}
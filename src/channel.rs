@@ -0,0 +1,161 @@
+//! # Typed message channel
+//!
+//! This module turns the UART Lite into a typed message pipe, for example between a softcore
+//! on the FPGA and a host. Messages are serialized with [postcard] and framed with the COBS
+//! codec of the [crate::framing::cobs] module, so the receiver can resynchronize on the next
+//! zero delimiter after a corrupted frame.
+//!
+//! The host side can use the `postcard` crate with its COBS flavor, for example
+//! `postcard::to_stdvec_cobs` and `postcard::from_bytes_cobs`, to talk to the [MessageChannel].
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! enum Request {
+//!     Ping,
+//!     ReadRegister(u32),
+//! }
+//!
+//! let (tx, rx) = uart.split();
+//! let mut channel = MessageChannel::<_, _, 128>::new(tx, rx);
+//! match channel.recv::<Request>()? {
+//!     Request::Ping => channel.send(&Response::Pong)?,
+//!     Request::ReadRegister(addr) => channel.send(&Response::Value(read(addr)))?,
+//! }
+//! ```
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::framing::{
+    FrameReadError,
+    cobs::{self, CobsDecodeError, CobsDecoder},
+};
+
+/// Message channel error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelError<IoError> {
+    /// Reader or writer I/O error.
+    Io(IoError),
+    /// The reader reached end of file.
+    Eof,
+    /// COBS decoding error of a received frame.
+    Frame(CobsDecodeError),
+    /// The message could not be serialized into or deserialized from the message buffer.
+    Postcard(postcard::Error),
+}
+
+impl<IoError> From<FrameReadError<IoError, CobsDecodeError>> for ChannelError<IoError> {
+    fn from(error: FrameReadError<IoError, CobsDecodeError>) -> Self {
+        match error {
+            FrameReadError::Io(e) => Self::Io(e),
+            FrameReadError::Eof => Self::Eof,
+            FrameReadError::Decode(e) => Self::Frame(e),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug> core::fmt::Display for ChannelError<IoError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+            Self::Eof => write!(f, "unexpected end of file"),
+            Self::Frame(e) => write!(f, "frame error: {e}"),
+            Self::Postcard(e) => write!(f, "postcard error: {e}"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug> core::error::Error for ChannelError<IoError> {}
+
+/// Typed message channel over a writer and a reader, for example the [crate::Tx] and
+/// [crate::Rx] halves of a UART Lite.
+///
+/// `N` is the maximum serialized message size. The channel contains one serialization buffer
+/// and one frame buffer of that size.
+pub struct MessageChannel<W, R, const N: usize = 256> {
+    writer: W,
+    reader: R,
+    tx_buf: [u8; N],
+    decoder: CobsDecoder<N>,
+}
+
+impl<W, R, const N: usize> MessageChannel<W, R, N> {
+    /// Create a new message channel.
+    pub const fn new(writer: W, reader: R) -> Self {
+        Self {
+            writer,
+            reader,
+            tx_buf: [0; N],
+            decoder: CobsDecoder::new(),
+        }
+    }
+
+    /// Access the wrapped writer.
+    #[inline]
+    pub fn writer(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Access the wrapped reader.
+    ///
+    /// Bytes read directly are not passed to the frame decoder, so the decoder should be
+    /// resynchronized with [Self::resync] afterwards.
+    #[inline]
+    pub fn reader(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Discard the partially received frame. The next complete frame after a zero delimiter is
+    /// received normally.
+    pub fn resync(&mut self) {
+        self.decoder.reset();
+    }
+
+    /// Release the writer and the reader.
+    pub fn release(self) -> (W, R) {
+        (self.writer, self.reader)
+    }
+}
+
+impl<W: embedded_io::Write, R, const N: usize> MessageChannel<W, R, N> {
+    /// Serialize the message and send it as one COBS frame.
+    pub fn send<T: Serialize>(&mut self, msg: &T) -> Result<(), ChannelError<W::Error>> {
+        let serialized =
+            postcard::to_slice(msg, &mut self.tx_buf).map_err(ChannelError::Postcard)?;
+        cobs::encode_to_writer(&mut self.writer, serialized).map_err(ChannelError::Io)
+    }
+}
+
+impl<W: embedded_io_async::Write, R, const N: usize> MessageChannel<W, R, N> {
+    /// Serialize the message and send it asynchronously as one COBS frame.
+    pub async fn send_async<T: Serialize>(
+        &mut self,
+        msg: &T,
+    ) -> Result<(), ChannelError<W::Error>> {
+        let serialized =
+            postcard::to_slice(msg, &mut self.tx_buf).map_err(ChannelError::Postcard)?;
+        cobs::encode_to_writer_async(&mut self.writer, serialized)
+            .await
+            .map_err(ChannelError::Io)
+    }
+}
+
+impl<W, R: embedded_io::Read, const N: usize> MessageChannel<W, R, N> {
+    /// Blocking receive of the next message.
+    ///
+    /// On errors, the channel is ready to receive the next frame.
+    pub fn recv<T: DeserializeOwned>(&mut self) -> Result<T, ChannelError<R::Error>> {
+        let frame = self.decoder.read_frame(&mut self.reader)?;
+        postcard::from_bytes(frame).map_err(ChannelError::Postcard)
+    }
+}
+
+impl<W, R: embedded_io_async::Read, const N: usize> MessageChannel<W, R, N> {
+    /// Asynchronously receive the next message.
+    ///
+    /// On errors, the channel is ready to receive the next frame.
+    pub async fn recv_async<T: DeserializeOwned>(&mut self) -> Result<T, ChannelError<R::Error>> {
+        let frame = self.decoder.read_frame_async(&mut self.reader).await?;
+        postcard::from_bytes(frame).map_err(ChannelError::Postcard)
+    }
+}
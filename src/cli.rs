@@ -0,0 +1,117 @@
+//! # `embedded-cli` byte I/O adapter
+//!
+//! This module is only available with the `embedded-cli` feature. The
+//! [`embedded-cli`](https://docs.rs/embedded-cli) crate writes its output through the
+//! `embedded-io` 0.6 [embedded_io_06::Write] trait and is fed with received bytes by the
+//! application. The [CliWriter] implements this trait on top of any TX driver, and the
+//! [CliReader] reads the bytes for the CLI from any RX driver, either blocking or
+//! asynchronously.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let (writer, mut reader) = uart.into_cli_io();
+//! let mut cli = CliBuilder::default()
+//!     .writer(writer)
+//!     .command_buffer([0; 64])
+//!     .history_buffer([0; 128])
+//!     .build()?;
+//! loop {
+//!     let byte = reader.read_byte();
+//!     cli.process_byte::<Command, _>(byte, &mut Command::processor(handle_command))?;
+//! }
+//! ```
+use core::convert::Infallible;
+
+use crate::{AxiUartlite, Rx, Tx, UartLiteRx, UartLiteTx, registers::RegisterAccess};
+
+/// Writer for the `embedded-cli` crate on top of a TX driver.
+pub struct CliWriter<W> {
+    tx: W,
+}
+
+impl<W: UartLiteTx> CliWriter<W> {
+    /// Create a new writer.
+    pub const fn new(tx: W) -> Self {
+        Self { tx }
+    }
+
+    /// Release the TX driver.
+    pub fn release(self) -> W {
+        self.tx
+    }
+}
+
+impl<W: UartLiteTx> embedded_io_06::ErrorType for CliWriter<W> {
+    type Error = Infallible;
+}
+
+impl<W: UartLiteTx> embedded_io_06::Write for CliWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let written = self.tx.write_burst(buf);
+            if written > 0 {
+                return Ok(written);
+            }
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        while !self.tx.tx_fifo_empty() {}
+        Ok(())
+    }
+}
+
+/// Byte source for the `embedded-cli` crate on top of an RX driver.
+///
+/// The blocking methods are available for the RX drivers of this crate, and the asynchronous
+/// method for all [embedded_io_async::Read] implementations, for example the buffered driver.
+pub struct CliReader<R> {
+    rx: R,
+}
+
+impl<R> CliReader<R> {
+    /// Create a new reader.
+    pub const fn new(rx: R) -> Self {
+        Self { rx }
+    }
+
+    /// Release the RX driver.
+    pub fn release(self) -> R {
+        self.rx
+    }
+
+    /// Asynchronously read the next byte.
+    pub async fn read_byte_async(&mut self) -> Result<u8, embedded_io::ReadExactError<R::Error>>
+    where
+        R: embedded_io_async::Read,
+    {
+        let mut byte = [0; 1];
+        self.rx.read_exact(&mut byte).await?;
+        Ok(byte[0])
+    }
+}
+
+impl<R: UartLiteRx> CliReader<R> {
+    /// Blocking read of the next byte.
+    pub fn read_byte(&mut self) -> u8 {
+        let Ok(byte) = nb::block!(self.rx.read_fifo());
+        byte
+    }
+
+    /// Read the next byte if one is available.
+    pub fn try_read_byte(&mut self) -> Option<u8> {
+        self.rx.read_fifo().ok()
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Split the driver into the writer and the reader for the `embedded-cli` crate.
+    pub fn into_cli_io(self) -> (CliWriter<Tx<R>>, CliReader<Rx<R>>) {
+        let (tx, rx) = self.split();
+        (CliWriter::new(tx), CliReader::new(rx))
+    }
+}
@@ -0,0 +1,181 @@
+//! # Console input modes
+//!
+//! The [Console] reads input from a UART Lite in one of two [ConsoleMode]s, which can be
+//! switched at runtime, so the same UART can serve both an interactive shell and a binary
+//! protocol:
+//!
+//! - [ConsoleMode::Raw] passes every received byte through without echo.
+//! - [ConsoleMode::Cooked] echoes the input, supports line editing with backspace and `Ctrl-U`,
+//!   and returns complete lines. Lines can be terminated by CR, LF or CRLF, and the echo is
+//!   normalized to CRLF.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut console = Console::<64>::new(ConsoleMode::Cooked);
+//! loop {
+//!     match console.read(&mut uart) {
+//!         ConsoleInput::Line(b"upload") => console.set_mode(ConsoleMode::Raw),
+//!         ConsoleInput::Line(line) => handle_command(line),
+//!         ConsoleInput::Byte(byte) => handle_binary(byte),
+//!     }
+//! }
+//! ```
+use crate::{UartLiteIo, UartLiteTx};
+
+const BACKSPACE: u8 = 0x08;
+const DELETE: u8 = 0x7F;
+const KILL_LINE: u8 = 0x15;
+const BELL: u8 = 0x07;
+
+/// Console input mode.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConsoleMode {
+    /// Byte-for-byte input without echo.
+    Raw,
+    /// Echoed line input with line editing and CRLF normalization.
+    #[default]
+    Cooked,
+}
+
+/// Console input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConsoleInput<'line> {
+    /// Byte received in [ConsoleMode::Raw].
+    Byte(u8),
+    /// Line received in [ConsoleMode::Cooked], without the line terminator.
+    Line(&'line [u8]),
+}
+
+enum Event {
+    Byte(u8),
+    Line(usize),
+}
+
+/// Console with a runtime selectable input mode and a line buffer of size `N`.
+#[derive(Debug)]
+pub struct Console<const N: usize> {
+    mode: ConsoleMode,
+    buf: [u8; N],
+    len: usize,
+    skip_lf: bool,
+}
+
+impl<const N: usize> Default for Console<N> {
+    fn default() -> Self {
+        Self::new(ConsoleMode::default())
+    }
+}
+
+impl<const N: usize> Console<N> {
+    /// Create a new console.
+    pub const fn new(mode: ConsoleMode) -> Self {
+        Self {
+            mode,
+            buf: [0; N],
+            len: 0,
+            skip_lf: false,
+        }
+    }
+
+    /// Current input mode.
+    #[inline]
+    pub const fn mode(&self) -> ConsoleMode {
+        self.mode
+    }
+
+    /// Switch the input mode. A partially received line is discarded.
+    pub fn set_mode(&mut self, mode: ConsoleMode) {
+        self.mode = mode;
+        self.len = 0;
+        self.skip_lf = false;
+    }
+
+    /// Partially received line in [ConsoleMode::Cooked].
+    #[inline]
+    pub fn pending_line(&self) -> &[u8] {
+        &self.buf[0..self.len]
+    }
+
+    /// Process one received byte. The echo is written through the passed TX driver.
+    ///
+    /// In [ConsoleMode::Cooked], input exceeding the line buffer is dropped and answered with
+    /// a bell character.
+    pub fn feed(&mut self, byte: u8, echo: &mut impl UartLiteTx) -> Option<ConsoleInput<'_>> {
+        self.push(byte, echo).map(|event| self.input(event))
+    }
+
+    /// Process the next received byte if one is available, see [Self::feed].
+    pub fn poll(&mut self, uart: &mut impl UartLiteIo) -> Option<ConsoleInput<'_>> {
+        let byte = uart.read_fifo().ok()?;
+        self.feed(byte, uart)
+    }
+
+    /// Blocking read of the next byte in [ConsoleMode::Raw] or the next line in
+    /// [ConsoleMode::Cooked].
+    pub fn read(&mut self, uart: &mut impl UartLiteIo) -> ConsoleInput<'_> {
+        loop {
+            let Ok(byte) = nb::block!(uart.read_fifo());
+            if let Some(event) = self.push(byte, uart) {
+                return self.input(event);
+            }
+        }
+    }
+
+    fn input(&self, event: Event) -> ConsoleInput<'_> {
+        match event {
+            Event::Byte(byte) => ConsoleInput::Byte(byte),
+            Event::Line(len) => ConsoleInput::Line(&self.buf[0..len]),
+        }
+    }
+
+    fn push(&mut self, byte: u8, echo: &mut impl UartLiteTx) -> Option<Event> {
+        if self.mode == ConsoleMode::Raw {
+            return Some(Event::Byte(byte));
+        }
+        let skip_lf = core::mem::take(&mut self.skip_lf);
+        match byte {
+            b'\n' if skip_lf => None,
+            b'\r' | b'\n' => {
+                self.skip_lf = byte == b'\r';
+                write_echo(echo, b"\r\n");
+                let len = self.len;
+                self.len = 0;
+                Some(Event::Line(len))
+            }
+            BACKSPACE | DELETE => {
+                if self.len > 0 {
+                    self.len -= 1;
+                    write_echo(echo, b"\x08 \x08");
+                }
+                None
+            }
+            KILL_LINE => {
+                for _ in 0..self.len {
+                    write_echo(echo, b"\x08 \x08");
+                }
+                self.len = 0;
+                None
+            }
+            b'\t' | 0x20.. => {
+                if self.len < N {
+                    self.buf[self.len] = byte;
+                    self.len += 1;
+                    write_echo(echo, &[byte]);
+                } else {
+                    write_echo(echo, &[BELL]);
+                }
+                None
+            }
+            // Other control characters are ignored.
+            _ => None,
+        }
+    }
+}
+
+fn write_echo(echo: &mut impl UartLiteTx, data: &[u8]) {
+    for &byte in data {
+        let Ok(()) = nb::block!(echo.write_fifo(byte));
+    }
+}
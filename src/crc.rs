@@ -0,0 +1,339 @@
+//! # CRC support
+//!
+//! Small bitwise CRC implementations which are used by the framing layers.
+//!
+//! The [CrcWriter] and [CrcReader] adapters compute a [RollingCrc] over all data which passes
+//! through a wrapped [embedded_io] writer or reader, so framed protocols can checksum without
+//! a second pass over the data.
+
+/// CRC calculator which can be updated incrementally.
+pub trait RollingCrc {
+    /// CRC value type.
+    type Output: Copy;
+
+    /// Update the CRC with a slice of bytes.
+    fn update(&mut self, data: &[u8]);
+
+    /// Current CRC value.
+    fn finalize(&self) -> Self::Output;
+}
+
+/// CRC-16/CCITT-FALSE calculator (polynomial 0x1021, initial value 0xFFFF).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc16Ccitt {
+    crc: u16,
+}
+
+impl Default for Crc16Ccitt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc16Ccitt {
+    /// Polynomial.
+    pub const POLY: u16 = 0x1021;
+    /// Initial value.
+    pub const INIT: u16 = 0xFFFF;
+
+    /// Create a new CRC calculator.
+    pub const fn new() -> Self {
+        Self { crc: Self::INIT }
+    }
+
+    /// Create a new CRC calculator with a custom initial value.
+    pub const fn new_with_init(init: u16) -> Self {
+        Self { crc: init }
+    }
+
+    /// Update the CRC with one byte.
+    #[inline]
+    pub const fn update_byte(&mut self, byte: u8) {
+        self.crc ^= (byte as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            if self.crc & 0x8000 != 0 {
+                self.crc = (self.crc << 1) ^ Self::POLY;
+            } else {
+                self.crc <<= 1;
+            }
+            bit += 1;
+        }
+    }
+
+    /// Update the CRC with a slice of bytes.
+    #[inline]
+    pub const fn update(&mut self, data: &[u8]) {
+        let mut idx = 0;
+        while idx < data.len() {
+            self.update_byte(data[idx]);
+            idx += 1;
+        }
+    }
+
+    /// Current CRC value.
+    pub const fn finalize(&self) -> u16 {
+        self.crc
+    }
+}
+
+impl RollingCrc for Crc16Ccitt {
+    type Output = u16;
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        Crc16Ccitt::update(self, data);
+    }
+
+    #[inline]
+    fn finalize(&self) -> u16 {
+        Crc16Ccitt::finalize(self)
+    }
+}
+
+/// CRC-32/ISO-HDLC calculator as used by Ethernet and zlib (reflected polynomial 0xEDB88320,
+/// initial value and final XOR 0xFFFFFFFF).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    /// Reflected polynomial.
+    pub const POLY: u32 = 0xEDB8_8320;
+    /// Initial value.
+    pub const INIT: u32 = 0xFFFF_FFFF;
+
+    /// Create a new CRC calculator.
+    pub const fn new() -> Self {
+        Self { crc: Self::INIT }
+    }
+
+    /// Update the CRC with one byte.
+    #[inline]
+    pub const fn update_byte(&mut self, byte: u8) {
+        self.crc ^= byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            if self.crc & 0x0001 != 0 {
+                self.crc = (self.crc >> 1) ^ Self::POLY;
+            } else {
+                self.crc >>= 1;
+            }
+            bit += 1;
+        }
+    }
+
+    /// Update the CRC with a slice of bytes.
+    #[inline]
+    pub const fn update(&mut self, data: &[u8]) {
+        let mut idx = 0;
+        while idx < data.len() {
+            self.update_byte(data[idx]);
+            idx += 1;
+        }
+    }
+
+    /// Current CRC value with the final XOR applied.
+    pub const fn finalize(&self) -> u32 {
+        !self.crc
+    }
+}
+
+impl RollingCrc for Crc32 {
+    type Output = u32;
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        Crc32::update(self, data);
+    }
+
+    #[inline]
+    fn finalize(&self) -> u32 {
+        Crc32::finalize(self)
+    }
+}
+
+/// Calculate the CRC-32/ISO-HDLC checksum of the passed data.
+pub const fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finalize()
+}
+
+/// Calculate the CRC-16/CCITT-FALSE checksum of the passed data.
+pub const fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc = Crc16Ccitt::new();
+    crc.update(data);
+    crc.finalize()
+}
+
+/// Calculate the CRC-16/XMODEM checksum of the passed data. This is the same polynomial as
+/// CRC-16/CCITT-FALSE, but with an initial value of 0.
+pub const fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = Crc16Ccitt::new_with_init(0);
+    crc.update(data);
+    crc.finalize()
+}
+
+/// Calculate the CRC-16/MODBUS checksum of the passed data (reflected polynomial 0xA001,
+/// initial value 0xFFFF). The checksum is transmitted in little endian byte order.
+pub const fn crc16_modbus(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    let mut idx = 0;
+    while idx < data.len() {
+        crc ^= data[idx] as u16;
+        let mut bit = 0;
+        while bit < 8 {
+            if crc & 0x0001 != 0 {
+                crc = (crc >> 1) ^ 0xA001;
+            } else {
+                crc >>= 1;
+            }
+            bit += 1;
+        }
+        idx += 1;
+    }
+    crc
+}
+
+/// Writer adapter which computes a CRC over all written data.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+#[derive(Debug)]
+pub struct CrcWriter<W, C> {
+    writer: W,
+    crc: C,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W, C: RollingCrc> CrcWriter<W, C> {
+    /// Create a new CRC writer adapter with the passed CRC calculator.
+    pub const fn new(writer: W, crc: C) -> Self {
+        Self { writer, crc }
+    }
+
+    /// CRC over all data written so far.
+    #[inline]
+    pub fn finalize(&self) -> C::Output {
+        self.crc.finalize()
+    }
+
+    /// Replace the CRC calculator, for example to start a new frame.
+    pub fn reset(&mut self, crc: C) {
+        self.crc = crc;
+    }
+
+    /// Access the wrapped writer. Data written directly is not part of the CRC.
+    #[inline]
+    pub fn writer(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Release the wrapped writer and the CRC calculator.
+    pub fn release(self) -> (W, C) {
+        (self.writer, self.crc)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::ErrorType, C> embedded_io::ErrorType for CrcWriter<W, C> {
+    type Error = W::Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::Write, C: RollingCrc> embedded_io::Write for CrcWriter<W, C> {
+    /// Only the bytes accepted by the wrapped writer are part of the CRC.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = self.writer.write(buf)?;
+        self.crc.update(&buf[0..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io_async::Write, C: RollingCrc> embedded_io_async::Write for CrcWriter<W, C> {
+    /// Only the bytes accepted by the wrapped writer are part of the CRC.
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = self.writer.write(buf).await?;
+        self.crc.update(&buf[0..written]);
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush().await
+    }
+}
+
+/// Reader adapter which computes a CRC over all read data.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+#[derive(Debug)]
+pub struct CrcReader<R, C> {
+    reader: R,
+    crc: C,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R, C: RollingCrc> CrcReader<R, C> {
+    /// Create a new CRC reader adapter with the passed CRC calculator.
+    pub const fn new(reader: R, crc: C) -> Self {
+        Self { reader, crc }
+    }
+
+    /// CRC over all data read so far.
+    #[inline]
+    pub fn finalize(&self) -> C::Output {
+        self.crc.finalize()
+    }
+
+    /// Replace the CRC calculator, for example to start a new frame.
+    pub fn reset(&mut self, crc: C) {
+        self.crc = crc;
+    }
+
+    /// Access the wrapped reader. Data read directly is not part of the CRC, which can be used
+    /// to read the transmitted checksum.
+    #[inline]
+    pub fn reader(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Release the wrapped reader and the CRC calculator.
+    pub fn release(self) -> (R, C) {
+        (self.reader, self.crc)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io::ErrorType, C> embedded_io::ErrorType for CrcReader<R, C> {
+    type Error = R::Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io::Read, C: RollingCrc> embedded_io::Read for CrcReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let read = self.reader.read(buf)?;
+        self.crc.update(&buf[0..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io_async::Read, C: RollingCrc> embedded_io_async::Read for CrcReader<R, C> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let read = self.reader.read(buf).await?;
+        self.crc.update(&buf[0..read]);
+        Ok(read)
+    }
+}
@@ -0,0 +1,212 @@
+//! # Deferred logging
+//!
+//! This module provides the [DeferredLog] sink. Formatted bytes are copied into a ring buffer
+//! and the TX FIFO is drained in the background by the UART Lite TX FIFO empty interrupt, so
+//! log calls never block on the 16 byte hardware FIFO. If the ring buffer is full, the
+//! remaining bytes are dropped and counted.
+//!
+//! The interrupt handler drains the ring buffer without locking. Writers are serialized with
+//! a short critical section which only covers copying the data into the ring buffer and
+//! starting a transfer if the TX FIFO is empty.
+//!
+//! With the `log` feature, the [DeferredLogger] can be used as a [log] crate backend.
+use core::cell::{Cell, RefCell, UnsafeCell};
+
+use critical_section::Mutex;
+
+use crate::{
+    FIFO_DEPTH, Tx,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// Deferred logging sink with a ring buffer of size `N`.
+///
+/// It is usually placed inside a `static`. The interrupt handler of the UART Lite has to call
+/// [Self::on_interrupt_tx] and the interrupt must be enabled.
+pub struct DeferredLog<const N: usize> {
+    buf: UnsafeCell<[u8; N]>,
+    // Monotonic wrapping counters. The buffer index is the counter modulo N.
+    read_idx: AtomicUsize,
+    write_idx: AtomicUsize,
+    dropped: Mutex<Cell<usize>>,
+    tx: Mutex<RefCell<Option<Tx>>>,
+}
+
+// Safety: The buffer is only written by writers inside a critical section and only read by
+// the single consumer between the read and write indices.
+unsafe impl<const N: usize> Sync for DeferredLog<N> {}
+
+impl<const N: usize> DeferredLog<N> {
+    /// Create a new deferred logging sink.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            buf: UnsafeCell::new([0; N]),
+            read_idx: AtomicUsize::new(0),
+            write_idx: AtomicUsize::new(0),
+            dropped: Mutex::new(Cell::new(0)),
+            tx: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Set the TX handle which is used by writers to start a transfer if the TX FIFO is
+    /// empty. Returns the previous handle.
+    ///
+    /// Data written before a TX handle was set is only sent after the next TX interrupt.
+    pub fn set_tx(&self, tx: Tx) -> Option<Tx> {
+        critical_section::with(|cs| self.tx.borrow(cs).replace(Some(tx)))
+    }
+
+    /// Number of bytes currently buffered.
+    pub fn len(&self) -> usize {
+        self.write_idx
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.read_idx.load(Ordering::Acquire))
+    }
+
+    /// No bytes are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of bytes which were dropped because the ring buffer was full.
+    pub fn dropped(&self) -> usize {
+        critical_section::with(|cs| self.dropped.borrow(cs).get())
+    }
+
+    /// Copy the passed data into the ring buffer and start a transfer if the TX FIFO is
+    /// empty. This function never blocks on the hardware.
+    ///
+    /// Returns the number of bytes which were buffered. The remaining bytes are dropped.
+    pub fn write(&self, data: &[u8]) -> usize {
+        critical_section::with(|cs| {
+            let write_idx = self.write_idx.load(Ordering::Relaxed);
+            let free = N - write_idx.wrapping_sub(self.read_idx.load(Ordering::Acquire));
+            let len = core::cmp::min(free, data.len());
+            let buf = self.buf.get() as *mut u8;
+            for (offset, &byte) in data[0..len].iter().enumerate() {
+                // Safety: The slot is outside of the region owned by the consumer.
+                unsafe { buf.add(write_idx.wrapping_add(offset) % N).write(byte) };
+            }
+            self.write_idx
+                .store(write_idx.wrapping_add(len), Ordering::Release);
+            if len < data.len() {
+                let dropped = self.dropped.borrow(cs);
+                dropped.set(dropped.get().saturating_add(data.len() - len));
+            }
+            if let Some(tx) = self.tx.borrow(cs).borrow_mut().as_mut() {
+                self.drain_into_fifo(tx);
+            }
+            len
+        })
+    }
+
+    /// This has to be called in the interrupt handler of the UART Lite. It refills the TX
+    /// FIFO from the ring buffer if the FIFO is empty. [Tx::steal] can be used to create the
+    /// required handle.
+    pub fn on_interrupt_tx(&self, tx: &mut Tx) {
+        self.drain_into_fifo(tx);
+    }
+
+    /// Block until all buffered data was moved into the TX FIFO and the FIFO is empty.
+    ///
+    /// This can be useful before a reset or when interrupts are disabled, for example inside
+    /// a panic handler.
+    pub fn flush_blocking(&self, tx: &mut Tx) {
+        while !self.is_empty() {
+            critical_section::with(|_| self.drain_into_fifo(tx));
+        }
+        while !tx.fifo_empty() {}
+    }
+
+    fn drain_into_fifo(&self, tx: &mut Tx) -> usize {
+        if !tx.fifo_empty() {
+            return 0;
+        }
+        let read_idx = self.read_idx.load(Ordering::Relaxed);
+        let available = self
+            .write_idx
+            .load(Ordering::Acquire)
+            .wrapping_sub(read_idx);
+        let len = core::cmp::min(available, FIFO_DEPTH);
+        let buf = self.buf.get() as *const u8;
+        for offset in 0..len {
+            // Safety: The slot is inside the region published by the writers.
+            tx.write_fifo_unchecked(unsafe { buf.add(read_idx.wrapping_add(offset) % N).read() });
+        }
+        self.read_idx
+            .store(read_idx.wrapping_add(len), Ordering::Release);
+        len
+    }
+}
+
+impl<const N: usize> core::fmt::Write for &DeferredLog<N> {
+    /// Buffer the string. Never fails, bytes which do not fit are dropped.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        DeferredLog::write(self, s.as_bytes());
+        Ok(())
+    }
+}
+
+/// [log] crate backend which uses a [DeferredLog] sink.
+#[cfg(feature = "log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+pub struct DeferredLogger<const N: usize> {
+    sink: DeferredLog<N>,
+    level: log::LevelFilter,
+}
+
+#[cfg(feature = "log")]
+impl<const N: usize> DeferredLogger<N> {
+    /// Create a new deferred logger with the given level filter.
+    pub const fn new(level: log::LevelFilter) -> Self {
+        Self {
+            sink: DeferredLog::new(),
+            level,
+        }
+    }
+
+    /// Deferred logging sink, which can be used to call [DeferredLog::on_interrupt_tx].
+    pub const fn sink(&self) -> &DeferredLog<N> {
+        &self.sink
+    }
+}
+
+#[cfg(feature = "log")]
+impl<const N: usize> log::Log for DeferredLogger<N> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &log::Record) {
+        use core::fmt::Write;
+
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        write!(
+            &self.sink,
+            "{:<5} [{}] {}\r\n",
+            record.level(),
+            record.target(),
+            record.args()
+        )
+        .ok();
+    }
+
+    fn flush(&self) {}
+}
+
+/// Set the TX handle of the passed deferred logger, install it as the global logger and set
+/// the maximum log level.
+#[cfg(feature = "log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+pub fn init_deferred_logger<const N: usize>(
+    logger: &'static DeferredLogger<N>,
+    tx: Tx,
+) -> Result<(), log::SetLoggerError> {
+    logger.sink.set_tx(tx);
+    log::set_logger(logger)?;
+    log::set_max_level(logger.level);
+    Ok(())
+}
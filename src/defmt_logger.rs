@@ -0,0 +1,91 @@
+//! # defmt global logger
+//!
+//! This module is only available with the `defmt-logger` feature. It provides a
+//! [defmt global logger](https://defmt.ferrous-systems.com/global-logger) which sends the
+//! defmt frames over a UART Lite [Tx] handle. This allows using the defmt tooling on boards
+//! without RTT or JTAG access, for example by piping the UART output into `defmt-print`.
+//!
+//! The logger has to be initialized with [init_defmt_logger]. All log calls before the
+//! initialization are discarded. Logging is blocking and performed inside a critical section.
+use core::cell::{Cell, RefCell};
+
+use critical_section::{CriticalSection, Mutex, RestoreState};
+
+use crate::{
+    Tx,
+    atomic::{AtomicBool, Ordering},
+};
+
+static TX: Mutex<RefCell<Option<Tx>>> = Mutex::new(RefCell::new(None));
+static ENCODER: Mutex<RefCell<defmt::Encoder>> = Mutex::new(RefCell::new(defmt::Encoder::new()));
+static RESTORE_STATE: Mutex<Cell<RestoreState>> = Mutex::new(Cell::new(RestoreState::invalid()));
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Initialize the defmt logger with the passed TX handle.
+///
+/// Returns the previous TX handle if the logger was already initialized.
+pub fn init_defmt_logger(tx: Tx) -> Option<Tx> {
+    critical_section::with(|cs| TX.borrow(cs).replace(Some(tx)))
+}
+
+/// Release the TX handle used by the defmt logger. Subsequent log calls are discarded.
+pub fn release_defmt_logger() -> Option<Tx> {
+    critical_section::with(|cs| TX.borrow(cs).take())
+}
+
+fn write_blocking(cs: CriticalSection<'_>, bytes: &[u8]) {
+    if let Some(tx) = TX.borrow(cs).borrow_mut().as_mut() {
+        for &byte in bytes {
+            nb::block!(tx.write_fifo(byte)).unwrap();
+        }
+    }
+}
+
+#[defmt::global_logger]
+struct Logger;
+
+unsafe impl defmt::Logger for Logger {
+    fn acquire() {
+        let restore_state = unsafe { critical_section::acquire() };
+        if TAKEN.load(Ordering::Relaxed) {
+            panic!("defmt logger taken reentrantly");
+        }
+        TAKEN.store(true, Ordering::Relaxed);
+        // Safety: We are inside the critical section acquired above.
+        let cs = unsafe { CriticalSection::new() };
+        RESTORE_STATE.borrow(cs).set(restore_state);
+        ENCODER
+            .borrow(cs)
+            .borrow_mut()
+            .start_frame(|bytes| write_blocking(cs, bytes));
+    }
+
+    unsafe fn flush() {
+        // Safety: Only called between acquire and release.
+        let cs = unsafe { CriticalSection::new() };
+        if let Some(tx) = TX.borrow(cs).borrow().as_ref() {
+            while !tx.fifo_empty() {}
+        }
+    }
+
+    unsafe fn release() {
+        // Safety: Only called after acquire.
+        let cs = unsafe { CriticalSection::new() };
+        ENCODER
+            .borrow(cs)
+            .borrow_mut()
+            .end_frame(|bytes| write_blocking(cs, bytes));
+        TAKEN.store(false, Ordering::Relaxed);
+        let restore_state = RESTORE_STATE.borrow(cs).get();
+        unsafe { critical_section::release(restore_state) };
+    }
+
+    unsafe fn write(bytes: &[u8]) {
+        // Safety: Only called between acquire and release.
+        let cs = unsafe { CriticalSection::new() };
+        ENCODER
+            .borrow(cs)
+            .borrow_mut()
+            .write(bytes, |bytes| write_blocking(cs, bytes));
+    }
+}
@@ -0,0 +1,87 @@
+//! # Diagnostics snapshot
+//!
+//! [AxiUartlite::diagnostics] and [crate::TxAsync::diagnostics] return a [Diagnostics]
+//! snapshot of the complete driver state, which can be dumped with its [Debug] implementation
+//! or with `defmt` when things go wrong.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let diagnostics = uart.diagnostics();
+//! log::error!("UART Lite state: {diagnostics:?}");
+//! ```
+use crate::{
+    AxiUartlite, RxErrorsCounted, handle_status_reg_errors,
+    registers::{RegisterAccess, Status},
+};
+
+/// Snapshot of the driver state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Diagnostics {
+    /// The RX FIFO contains data.
+    pub rx_valid_data: bool,
+    /// The RX FIFO is full.
+    pub rx_full: bool,
+    /// The TX FIFO is empty.
+    pub tx_empty: bool,
+    /// The TX FIFO is full.
+    pub tx_full: bool,
+    /// Interrupts are enabled.
+    pub intr_enabled: bool,
+    /// Accumulated RX errors.
+    pub errors: RxErrorsCounted,
+    /// Driver statistics.
+    #[cfg(feature = "stats")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    pub stats: crate::Stats,
+    /// Progress of the active asynchronous TX transfer.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub tx_transfer: Option<crate::TxTransferProgress>,
+    /// Recorded control register writes.
+    #[cfg(feature = "debug-audit")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-audit")))]
+    pub control_writes: crate::ControlAudit,
+}
+
+impl Diagnostics {
+    pub(crate) const fn new(status: &Status, errors: RxErrorsCounted) -> Self {
+        Self {
+            rx_valid_data: status.rx_fifo_valid_data(),
+            rx_full: status.rx_fifo_full(),
+            tx_empty: status.tx_fifo_empty(),
+            tx_full: status.tx_fifo_full(),
+            intr_enabled: status.intr_enabled(),
+            errors,
+            #[cfg(feature = "stats")]
+            stats: crate::Stats::new(),
+            #[cfg(feature = "async")]
+            tx_transfer: None,
+            #[cfg(feature = "debug-audit")]
+            control_writes: crate::ControlAudit::new(),
+        }
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Snapshot of the driver state.
+    ///
+    /// RX errors reported by the status register read are added to the error counters, which
+    /// are not reset. The driver does not know the waker slot of asynchronous transfers, so the
+    /// transfer progress is not included. [crate::TxAsync::diagnostics] or
+    /// [crate::tx_transfer_progress] can be used for this.
+    pub fn diagnostics(&mut self) -> Diagnostics {
+        let status = self.regs().read_stat_reg();
+        if let Some(errors) = handle_status_reg_errors(&status) {
+            self.errors.add(errors);
+        }
+        Diagnostics {
+            #[cfg(feature = "stats")]
+            stats: self.stats(),
+            #[cfg(feature = "debug-audit")]
+            control_writes: self.tx.audit,
+            ..Diagnostics::new(&status, self.errors)
+        }
+    }
+}
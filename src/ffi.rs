@@ -0,0 +1,218 @@
+//! # C FFI wrapper layer
+//!
+//! This module is only available with the `ffi` feature. It exports a small `extern "C"` API
+//! over the [AxiUartlite] driver, which allows mixed C and Rust projects to use this driver from
+//! C code instead of maintaining the Xilinx C driver in parallel. The matching C header can be
+//! found in `include/axi_uartlite.h`.
+//!
+//! The driver state is stored inside the opaque [AxiUartliteHandle] structure, which is
+//! allocated by the C code. No heap allocation is required.
+//!
+//! The functions are exported from the crate which links this crate into a `staticlib`, so a
+//! small wrapper crate with `crate-type = ["staticlib"]` which depends on this crate with the
+//! `ffi` feature is sufficient to build a library for the Vitis project.
+use core::mem::MaybeUninit;
+
+use crate::{AxiUartlite, registers::Registers};
+
+/// Function completed successfully.
+pub const AXI_UARTLITE_OK: i32 = 0;
+/// A null pointer was passed.
+pub const AXI_UARTLITE_ERR_NULL: i32 = -1;
+
+/// Opaque driver handle which is allocated by the C code.
+///
+/// The `stats`, `hooks` and `debug-audit` features can not be enabled together with this module,
+/// because the additional driver state does not fit into the handle.
+#[repr(C)]
+pub struct AxiUartliteHandle {
+    opaque: [MaybeUninit<usize>; 16],
+}
+
+const _: () = assert!(size_of::<AxiUartlite>() <= size_of::<AxiUartliteHandle>());
+const _: () = assert!(align_of::<AxiUartlite>() <= align_of::<AxiUartliteHandle>());
+
+/// RX error counters.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AxiUartliteErrors {
+    /// Parity error count.
+    pub parity: u8,
+    /// Frame error count.
+    pub frame: u8,
+    /// Overrun error count.
+    pub overrun: u8,
+}
+
+/// Convert the handle pointer into a driver reference.
+///
+/// # Safety
+///
+/// The handle must be null or initialized with [axi_uartlite_init].
+unsafe fn driver<'a>(handle: *mut AxiUartliteHandle) -> Option<&'a mut AxiUartlite> {
+    unsafe { handle.cast::<AxiUartlite>().as_mut() }
+}
+
+/// Initialize the driver handle for the UART Lite at the given base address.
+///
+/// The hardware is not accessed.
+///
+/// # Safety
+///
+/// - The handle must be null or point to writable memory for a [AxiUartliteHandle].
+/// - The same safety rules specified in [AxiUartlite::new] apply.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_init(
+    handle: *mut AxiUartliteHandle,
+    base_addr: usize,
+) -> i32 {
+    if handle.is_null() {
+        return AXI_UARTLITE_ERR_NULL;
+    }
+    let regs = unsafe { Registers::new_mmio_at(base_addr) };
+    let uart = AxiUartlite::new_with_regs(unsafe { regs.clone() }, regs);
+    unsafe { handle.cast::<AxiUartlite>().write(uart) };
+    AXI_UARTLITE_OK
+}
+
+/// Write as many bytes as fit into the TX FIFO without blocking.
+///
+/// Returns the number of written bytes.
+///
+/// # Safety
+///
+/// - The handle must be null or initialized with [axi_uartlite_init].
+/// - `data` must be valid for reads of `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_write(
+    handle: *mut AxiUartliteHandle,
+    data: *const u8,
+    len: usize,
+) -> usize {
+    let Some(uart) = (unsafe { driver(handle) }) else {
+        return 0;
+    };
+    if data.is_null() {
+        return 0;
+    }
+    let data = unsafe { core::slice::from_raw_parts(data, len) };
+    let mut written = 0;
+    for &byte in data {
+        if uart.write_fifo(byte).is_err() {
+            break;
+        }
+        written += 1;
+    }
+    written
+}
+
+/// Write all bytes, blocking until they were written into the TX FIFO.
+///
+/// # Safety
+///
+/// - The handle must be null or initialized with [axi_uartlite_init].
+/// - `data` must be valid for reads of `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_write_blocking(
+    handle: *mut AxiUartliteHandle,
+    data: *const u8,
+    len: usize,
+) -> i32 {
+    let Some(uart) = (unsafe { driver(handle) }) else {
+        return AXI_UARTLITE_ERR_NULL;
+    };
+    if data.is_null() {
+        return AXI_UARTLITE_ERR_NULL;
+    }
+    for &byte in unsafe { core::slice::from_raw_parts(data, len) } {
+        let Ok(()) = nb::block!(uart.write_fifo(byte));
+    }
+    AXI_UARTLITE_OK
+}
+
+/// Read the available bytes from the RX FIFO without blocking.
+///
+/// Returns the number of read bytes.
+///
+/// # Safety
+///
+/// - The handle must be null or initialized with [axi_uartlite_init].
+/// - `buf` must be valid for writes of `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_read(
+    handle: *mut AxiUartliteHandle,
+    buf: *mut u8,
+    len: usize,
+) -> usize {
+    let Some(uart) = (unsafe { driver(handle) }) else {
+        return 0;
+    };
+    if buf.is_null() {
+        return 0;
+    }
+    let buf = unsafe { core::slice::from_raw_parts_mut(buf, len) };
+    let mut read = 0;
+    for byte in buf.iter_mut() {
+        match uart.read_fifo() {
+            Ok(data) => *byte = data,
+            Err(nb::Error::WouldBlock) => break,
+        }
+        read += 1;
+    }
+    read
+}
+
+/// Block until the TX FIFO is empty.
+///
+/// # Safety
+///
+/// The handle must be null or initialized with [axi_uartlite_init].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_flush(handle: *mut AxiUartliteHandle) -> i32 {
+    let Some(uart) = (unsafe { driver(handle) }) else {
+        return AXI_UARTLITE_ERR_NULL;
+    };
+    while !uart.tx_fifo_empty() {}
+    AXI_UARTLITE_OK
+}
+
+/// Reset the TX and RX FIFO.
+///
+/// # Safety
+///
+/// The handle must be null or initialized with [axi_uartlite_init].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_reset_fifos(handle: *mut AxiUartliteHandle) -> i32 {
+    let Some(uart) = (unsafe { driver(handle) }) else {
+        return AXI_UARTLITE_ERR_NULL;
+    };
+    uart.reset_rx_fifo();
+    uart.reset_tx_fifo();
+    AXI_UARTLITE_OK
+}
+
+/// Read and clear the RX error counters.
+///
+/// # Safety
+///
+/// - The handle must be null or initialized with [axi_uartlite_init].
+/// - `errors` must be null or point to writable memory for a [AxiUartliteErrors].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn axi_uartlite_read_and_clear_errors(
+    handle: *mut AxiUartliteHandle,
+    errors: *mut AxiUartliteErrors,
+) -> i32 {
+    let Some(uart) = (unsafe { driver(handle) }) else {
+        return AXI_UARTLITE_ERR_NULL;
+    };
+    let Some(errors) = (unsafe { errors.as_mut() }) else {
+        return AXI_UARTLITE_ERR_NULL;
+    };
+    let counted = uart.read_and_clear_errors();
+    *errors = AxiUartliteErrors {
+        parity: counted.parity(),
+        frame: counted.frame(),
+        overrun: counted.overrun(),
+    };
+    AXI_UARTLITE_OK
+}
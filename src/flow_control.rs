@@ -0,0 +1,114 @@
+//! # XON/XOFF software flow control
+//!
+//! The UART Lite IP core does not support hardware flow control. This module provides the
+//! [XonXoff] state machine which implements software flow control:
+//!
+//! - TX is paused when [XOFF] is received and resumed when [XON] is received.
+//! - [XOFF] is emitted when the RX buffer fill level reaches the configured high threshold and
+//!   [XON] is emitted once the fill level drops to the configured low threshold.
+//!
+//! The state machine can be used standalone, or it can be enabled for the [crate::BufferedUartlite]
+//! driver using [crate::BufferedState::enable_xon_xoff].
+
+/// XON (DC1) control character which resumes transmission.
+pub const XON: u8 = 0x11;
+/// XOFF (DC3) control character which pauses transmission.
+pub const XOFF: u8 = 0x13;
+
+/// XON/XOFF thresholds configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct XonXoffConfig {
+    /// [XOFF] is sent when the RX buffer fill level reaches this value.
+    pub xoff_threshold: usize,
+    /// [XON] is sent when the RX buffer fill level drops to this value after [XOFF] was sent.
+    pub xon_threshold: usize,
+}
+
+impl XonXoffConfig {
+    /// Create a new configuration.
+    ///
+    /// The XON threshold should be smaller than the XOFF threshold.
+    pub const fn new(xoff_threshold: usize, xon_threshold: usize) -> Self {
+        Self {
+            xoff_threshold,
+            xon_threshold,
+        }
+    }
+}
+
+/// XON/XOFF software flow control state machine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct XonXoff {
+    config: XonXoffConfig,
+    tx_paused: bool,
+    xoff_sent: bool,
+    pending: Option<u8>,
+}
+
+impl XonXoff {
+    /// Create a new state machine. TX is allowed initially.
+    pub const fn new(config: XonXoffConfig) -> Self {
+        Self {
+            config,
+            tx_paused: false,
+            xoff_sent: false,
+            pending: None,
+        }
+    }
+
+    /// Configuration.
+    pub const fn config(&self) -> XonXoffConfig {
+        self.config
+    }
+
+    /// Process a received byte.
+    ///
+    /// [XON] and [XOFF] characters update the TX pause state and are consumed, in which case
+    /// [None] is returned. All other bytes are returned as data bytes.
+    pub fn on_rx_byte(&mut self, byte: u8) -> Option<u8> {
+        match byte {
+            XON => {
+                self.tx_paused = false;
+                None
+            }
+            XOFF => {
+                self.tx_paused = true;
+                None
+            }
+            _ => Some(byte),
+        }
+    }
+
+    /// Update the state machine with the current RX buffer fill level.
+    ///
+    /// Returns the control character which should be sent, if any. The character is also
+    /// stored as pending and can be retrieved with [Self::take_pending].
+    pub fn on_rx_fill_level(&mut self, level: usize) -> Option<u8> {
+        if !self.xoff_sent && level >= self.config.xoff_threshold {
+            self.xoff_sent = true;
+            self.pending = Some(XOFF);
+            return self.pending;
+        }
+        if self.xoff_sent && level <= self.config.xon_threshold {
+            self.xoff_sent = false;
+            self.pending = Some(XON);
+            return self.pending;
+        }
+        None
+    }
+
+    /// Retrieve the pending control character which should be sent next.
+    pub fn take_pending(&mut self) -> Option<u8> {
+        self.pending.take()
+    }
+
+    /// TX is paused because [XOFF] was received.
+    pub const fn tx_paused(&self) -> bool {
+        self.tx_paused
+    }
+
+    /// [XOFF] was sent to the remote end and it was not resumed yet.
+    pub const fn xoff_sent(&self) -> bool {
+        self.xoff_sent
+    }
+}
@@ -0,0 +1,166 @@
+//! # Interrupt-driven frame reception
+//!
+//! The [FrameChannel] decodes the received bytes with a [FrameCodec] inside the interrupt
+//! handler and hands completed frames to the application through an [embassy_sync] channel.
+//! Application tasks then wait for whole validated frames instead of processing raw bytes.
+//!
+//! Frames are dropped and counted if the channel is full or if the payload exceeds the frame
+//! size `N`. Decoding errors are counted as well, the codec resynchronizes on its own like
+//! with [crate::framing::Framed].
+//!
+//! # Example
+//!
+//! ```ignore
+//! static FRAMES: FrameChannel<CobsDecoder<64>, 64, 4> = FrameChannel::new(CobsDecoder::new());
+//!
+//! // Interrupt handler.
+//! let mut rx = unsafe { Rx::steal(UARTLITE_BASE as usize) };
+//! FRAMES.on_interrupt_rx(&mut rx);
+//!
+//! // Application task.
+//! loop {
+//!     let frame = FRAMES.receive().await;
+//!     handle_frame(frame.as_slice());
+//! }
+//! ```
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel};
+
+use crate::{FIFO_DEPTH, Rx, framing::FrameCodec, registers::RegisterAccess};
+
+/// Decoded frame with a maximum payload size of `N`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RxFrame<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> RxFrame<N> {
+    /// Payload of the frame.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[0..self.len]
+    }
+
+    /// Payload length.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The frame has no payload.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<const N: usize> core::ops::Deref for RxFrame<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+/// Frame decoder fed by the interrupt handler, with a channel for `DEPTH` frames with a
+/// maximum payload size of `N`.
+///
+/// This structure is shared between the interrupt handler and the application tasks and is
+/// usually placed inside a `static`.
+pub struct FrameChannel<C, const N: usize, const DEPTH: usize> {
+    codec: Mutex<RefCell<C>>,
+    frames: Channel<CriticalSectionRawMutex, RxFrame<N>, DEPTH>,
+    dropped_frames: Mutex<Cell<usize>>,
+    decode_errors: Mutex<Cell<usize>>,
+}
+
+impl<C: FrameCodec, const N: usize, const DEPTH: usize> FrameChannel<C, N, DEPTH> {
+    /// Create a new frame channel with the given codec.
+    pub const fn new(codec: C) -> Self {
+        Self {
+            codec: Mutex::new(RefCell::new(codec)),
+            frames: Channel::new(),
+            dropped_frames: Mutex::new(Cell::new(0)),
+            decode_errors: Mutex::new(Cell::new(0)),
+        }
+    }
+
+    /// Interrupt handler which drains the RX FIFO into the codec.
+    ///
+    /// This has to be called in the interrupt handler of the UART Lite. [Rx::steal] can be
+    /// used to create the required handle. Returns the number of completed frames, including
+    /// dropped frames.
+    pub fn on_interrupt_rx<R: RegisterAccess>(&self, rx: &mut Rx<R>) -> usize {
+        rx.record_interrupt();
+        let mut buf = [0; FIFO_DEPTH];
+        let read = rx.read_whole_fifo(&mut buf);
+        self.feed(&buf[0..read])
+    }
+
+    /// Feed received bytes into the codec. Returns the number of completed frames, including
+    /// dropped frames.
+    ///
+    /// This can be used instead of [Self::on_interrupt_rx] if the bytes are received by other
+    /// means.
+    pub fn feed(&self, data: &[u8]) -> usize {
+        critical_section::with(|cs| {
+            let mut codec = self.codec.borrow(cs).borrow_mut();
+            let mut completed = 0;
+            for &byte in data {
+                match codec.push_byte(byte) {
+                    Ok(Some(len)) => {
+                        completed += 1;
+                        if len > N || !self.try_push(codec.frame(len)) {
+                            let dropped = self.dropped_frames.borrow(cs);
+                            dropped.set(dropped.get().wrapping_add(1));
+                        }
+                    }
+                    Ok(None) => (),
+                    Err(_) => {
+                        let errors = self.decode_errors.borrow(cs);
+                        errors.set(errors.get().wrapping_add(1));
+                    }
+                }
+            }
+            completed
+        })
+    }
+
+    fn try_push(&self, payload: &[u8]) -> bool {
+        let mut frame = RxFrame {
+            buf: [0; N],
+            len: payload.len(),
+        };
+        frame.buf[0..payload.len()].copy_from_slice(payload);
+        self.frames.try_send(frame).is_ok()
+    }
+
+    /// Wait for the next frame.
+    pub async fn receive(&self) -> RxFrame<N> {
+        self.frames.receive().await
+    }
+
+    /// Next frame if one is available.
+    pub fn try_receive(&self) -> Option<RxFrame<N>> {
+        self.frames.try_receive().ok()
+    }
+
+    /// Number of frames which were dropped because the channel was full or the payload
+    /// exceeded the frame size. The counter wraps around.
+    pub fn dropped_frames(&self) -> usize {
+        critical_section::with(|cs| self.dropped_frames.borrow(cs).get())
+    }
+
+    /// Number of decoding errors. The counter wraps around.
+    pub fn decode_errors(&self) -> usize {
+        critical_section::with(|cs| self.decode_errors.borrow(cs).get())
+    }
+
+    /// Reset the codec, discarding the frame which is currently being decoded.
+    pub fn reset_codec(&self) {
+        critical_section::with(|cs| self.codec.borrow(cs).borrow_mut().reset());
+    }
+}
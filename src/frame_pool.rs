@@ -0,0 +1,261 @@
+//! # Zero-copy frame reception into a buffer pool
+//!
+//! The [FramePool] owns `COUNT` frame buffers of size `N`. The interrupt handler writes the
+//! received bytes directly into a free buffer, and completed frames are handed to the
+//! application as [PooledFrame] handles which point into the pool. Dropping a handle returns
+//! the buffer to the pool, so no data is copied between the interrupt handler and the
+//! application.
+//!
+//! Frames are terminated by a delimiter byte, which is not part of the frame, or explicitly
+//! with [FramePool::end_frame], for example when an [crate::IdleDetector] reports an idle line.
+//! Frames are dropped and counted if no buffer is free or if the frame exceeds the buffer size.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static FRAMES: FramePool<64, 4> = FramePool::new(Some(b'\n'));
+//!
+//! // Interrupt handler.
+//! let mut rx = unsafe { Rx::steal(UARTLITE_BASE as usize) };
+//! FRAMES.on_interrupt_rx(&mut rx);
+//!
+//! // Application task.
+//! loop {
+//!     let frame = FRAMES.receive().await;
+//!     handle_frame(&frame);
+//!     // The buffer is returned to the pool here.
+//! }
+//! ```
+use core::{
+    cell::{RefCell, UnsafeCell},
+    future::poll_fn,
+    task::Poll,
+};
+
+use critical_section::Mutex;
+use embassy_sync::waitqueue::AtomicWaker;
+
+use crate::{FIFO_DEPTH, Rx, registers::RegisterAccess};
+
+struct PoolState<const COUNT: usize> {
+    free: [bool; COUNT],
+    // Buffer index and length of the frame which is currently received.
+    filling: Option<(usize, usize)>,
+    // The current frame is dropped until the next delimiter.
+    discarding: bool,
+    // Queue of completed frames as buffer index and length.
+    ready: [(usize, usize); COUNT],
+    ready_head: usize,
+    ready_len: usize,
+    dropped_frames: usize,
+}
+
+/// Pool of `COUNT` frame buffers of size `N` which are filled by the interrupt handler.
+///
+/// It is usually placed inside a `static`.
+pub struct FramePool<const N: usize, const COUNT: usize> {
+    bufs: [UnsafeCell<[u8; N]>; COUNT],
+    state: Mutex<RefCell<PoolState<COUNT>>>,
+    delimiter: Option<u8>,
+    waker: AtomicWaker,
+}
+
+// Safety: A buffer is either free, filled by the interrupt handler or owned by a single
+// [PooledFrame], and the ownership is only transferred inside a critical section.
+unsafe impl<const N: usize, const COUNT: usize> Sync for FramePool<N, COUNT> {}
+
+impl<const N: usize, const COUNT: usize> FramePool<N, COUNT> {
+    /// Create a new pool with all buffers free. Frames are terminated by the delimiter byte if
+    /// one is given, and otherwise only by [Self::end_frame].
+    pub const fn new(delimiter: Option<u8>) -> Self {
+        Self {
+            bufs: [const { UnsafeCell::new([0; N]) }; COUNT],
+            state: Mutex::new(RefCell::new(PoolState {
+                free: [true; COUNT],
+                filling: None,
+                discarding: false,
+                ready: [(0, 0); COUNT],
+                ready_head: 0,
+                ready_len: 0,
+                dropped_frames: 0,
+            })),
+            delimiter,
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// This has to be called in the interrupt handler of the UART Lite. It drains the RX FIFO
+    /// into the pool. [Rx::steal] can be used to create the required handle.
+    ///
+    /// Returns the number of completed frames, including dropped frames.
+    pub fn on_interrupt_rx<R: RegisterAccess>(&self, rx: &mut Rx<R>) -> usize {
+        rx.record_interrupt();
+        let mut buf = [0; FIFO_DEPTH];
+        let read = rx.read_whole_fifo(&mut buf);
+        self.feed(&buf[0..read])
+    }
+
+    /// Feed received bytes into the pool. Returns the number of completed frames, including
+    /// dropped frames.
+    ///
+    /// This can be used instead of [Self::on_interrupt_rx] if the bytes are received by other
+    /// means.
+    pub fn feed(&self, data: &[u8]) -> usize {
+        let completed = critical_section::with(|cs| {
+            let mut state = self.state.borrow(cs).borrow_mut();
+            let mut completed = 0;
+            for &byte in data {
+                if Some(byte) == self.delimiter {
+                    completed += usize::from(self.complete(&mut state));
+                    continue;
+                }
+                self.push(&mut state, byte);
+            }
+            completed
+        });
+        if completed > 0 {
+            self.waker.wake();
+        }
+        completed
+    }
+
+    /// Terminate the frame which is currently received. Returns true if a frame was completed.
+    ///
+    /// This can be used for frames which are delimited by an idle line.
+    pub fn end_frame(&self) -> bool {
+        let completed =
+            critical_section::with(|cs| self.complete(&mut self.state.borrow(cs).borrow_mut()));
+        if completed {
+            self.waker.wake();
+        }
+        completed
+    }
+
+    fn push(&self, state: &mut PoolState<COUNT>, byte: u8) {
+        if state.discarding {
+            return;
+        }
+        let (idx, len) = match state.filling {
+            Some(filling) => filling,
+            None => {
+                let Some(idx) = state.free.iter().position(|&free| free) else {
+                    state.discarding = true;
+                    return;
+                };
+                state.free[idx] = false;
+                (idx, 0)
+            }
+        };
+        if len == N {
+            state.free[idx] = true;
+            state.filling = None;
+            state.discarding = true;
+            return;
+        }
+        // Safety: The buffer is not free and not ready, so it is owned by the interrupt
+        // handler.
+        unsafe { (*self.bufs[idx].get())[len] = byte };
+        state.filling = Some((idx, len + 1));
+    }
+
+    fn complete(&self, state: &mut PoolState<COUNT>) -> bool {
+        if core::mem::take(&mut state.discarding) {
+            state.dropped_frames = state.dropped_frames.wrapping_add(1);
+            return true;
+        }
+        let Some(frame) = state.filling.take() else {
+            return false;
+        };
+        // Every buffer is queued at most once, so the queue can not overflow.
+        let tail = (state.ready_head + state.ready_len) % COUNT;
+        state.ready[tail] = frame;
+        state.ready_len += 1;
+        true
+    }
+
+    /// Next completed frame if one is available.
+    pub fn try_receive(&self) -> Option<PooledFrame<'_, N, COUNT>> {
+        critical_section::with(|cs| {
+            let mut state = self.state.borrow(cs).borrow_mut();
+            if state.ready_len == 0 {
+                return None;
+            }
+            let (idx, len) = state.ready[state.ready_head];
+            state.ready_head = (state.ready_head + 1) % COUNT;
+            state.ready_len -= 1;
+            Some(PooledFrame {
+                pool: self,
+                idx,
+                len,
+            })
+        })
+    }
+
+    /// Wait for the next completed frame.
+    pub async fn receive(&self) -> PooledFrame<'_, N, COUNT> {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+            match self.try_receive() {
+                Some(frame) => Poll::Ready(frame),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Number of free buffers.
+    pub fn free_buffers(&self) -> usize {
+        critical_section::with(|cs| {
+            let state = self.state.borrow(cs).borrow();
+            state.free.iter().filter(|&&free| free).count()
+        })
+    }
+
+    /// Number of frames which were dropped because no buffer was free or the frame exceeded
+    /// the buffer size. The counter wraps around.
+    pub fn dropped_frames(&self) -> usize {
+        critical_section::with(|cs| self.state.borrow(cs).borrow().dropped_frames)
+    }
+
+    fn release(&self, idx: usize) {
+        critical_section::with(|cs| self.state.borrow(cs).borrow_mut().free[idx] = true);
+    }
+}
+
+/// Completed frame inside a [FramePool] buffer. The buffer is returned to the pool on drop.
+pub struct PooledFrame<'pool, const N: usize, const COUNT: usize> {
+    pool: &'pool FramePool<N, COUNT>,
+    idx: usize,
+    len: usize,
+}
+
+impl<const N: usize, const COUNT: usize> core::ops::Deref for PooledFrame<'_, N, COUNT> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // Safety: The buffer is owned by this handle.
+        unsafe { &(&*self.pool.bufs[self.idx].get())[0..self.len] }
+    }
+}
+
+impl<const N: usize, const COUNT: usize> core::ops::DerefMut for PooledFrame<'_, N, COUNT> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // Safety: The buffer is owned by this handle.
+        unsafe { &mut (&mut *self.pool.bufs[self.idx].get())[0..self.len] }
+    }
+}
+
+impl<const N: usize, const COUNT: usize> core::fmt::Debug for PooledFrame<'_, N, COUNT> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PooledFrame")
+            .field("idx", &self.idx)
+            .field("data", &&**self)
+            .finish()
+    }
+}
+
+impl<const N: usize, const COUNT: usize> Drop for PooledFrame<'_, N, COUNT> {
+    fn drop(&mut self) {
+        self.pool.release(self.idx);
+    }
+}
@@ -0,0 +1,311 @@
+//! # Consistent Overhead Byte Stuffing (COBS) codec
+//!
+//! COBS frames are delimited by a zero byte and do not contain zero bytes otherwise.
+//! The encoders write the encoded frame including the trailing zero delimiter.
+use super::BufferTooSmall;
+#[cfg(feature = "embedded-io")]
+use super::{
+    FrameCodec, FrameReadError, FrameWriteError, read_until_frame, read_until_frame_async,
+};
+
+/// Frame delimiter.
+pub const DELIMITER: u8 = 0x00;
+
+/// Maximum encoded length for a frame with the given length, including the zero delimiter.
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / 254 + 2
+}
+
+/// COBS decoding error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CobsDecodeError {
+    /// The frame does not fit into the decoder buffer. The remaining bytes until the next
+    /// delimiter are discarded.
+    Overflow,
+    /// The frame ended inside a COBS block.
+    Truncated,
+}
+
+impl core::fmt::Display for CobsDecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "frame exceeds decoder buffer"),
+            Self::Truncated => write!(f, "frame ended inside a COBS block"),
+        }
+    }
+}
+
+impl core::error::Error for CobsDecodeError {}
+
+/// Iterate over the COBS blocks of the passed data. Calls the passed closure with the code
+/// byte and the data bytes of each block.
+fn for_each_block<E>(data: &[u8], mut f: impl FnMut(u8, &[u8]) -> Result<(), E>) -> Result<(), E> {
+    let mut rest = data;
+    loop {
+        let max_block = core::cmp::min(rest.len(), 254);
+        match rest[0..max_block].iter().position(|&byte| byte == 0) {
+            Some(zero_idx) => {
+                f(zero_idx as u8 + 1, &rest[0..zero_idx])?;
+                rest = &rest[zero_idx + 1..];
+            }
+            None if max_block == 254 => {
+                f(0xFF, &rest[0..254])?;
+                rest = &rest[254..];
+                if rest.is_empty() {
+                    return Ok(());
+                }
+            }
+            None => {
+                return f(rest.len() as u8 + 1, rest);
+            }
+        }
+    }
+}
+
+/// Encode the passed data into the output buffer, including the zero delimiter.
+///
+/// Returns the length of the encoded frame.
+pub fn encode(data: &[u8], out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+    let mut written = 0;
+    for_each_block(data, |code, block| {
+        if out.len() < written + 1 + block.len() {
+            return Err(BufferTooSmall);
+        }
+        out[written] = code;
+        out[written + 1..written + 1 + block.len()].copy_from_slice(block);
+        written += 1 + block.len();
+        Ok(())
+    })?;
+    if written >= out.len() {
+        return Err(BufferTooSmall);
+    }
+    out[written] = DELIMITER;
+    Ok(written + 1)
+}
+
+/// Encode the passed data into a new vector, including the zero delimiter.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_to_vec(data: &[u8]) -> alloc::vec::Vec<u8> {
+    let mut out = alloc::vec![0; max_encoded_len(data.len())];
+    // The buffer has the maximum encoded length.
+    let len = encode(data, &mut out).unwrap();
+    out.truncate(len);
+    out
+}
+
+/// Encode the passed data and write it through the writer, including the zero delimiter.
+///
+/// No scratch buffer is required, because the data blocks are written directly from the
+/// passed data.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub fn encode_to_writer<W: embedded_io::Write>(
+    writer: &mut W,
+    data: &[u8],
+) -> Result<(), W::Error> {
+    for_each_block(data, |code, block| {
+        writer.write_all(&[code])?;
+        writer.write_all(block)
+    })?;
+    writer.write_all(&[DELIMITER])
+}
+
+/// Encode the passed data and write it through the asynchronous writer, including the zero
+/// delimiter.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub async fn encode_to_writer_async<W: embedded_io_async::Write>(
+    writer: &mut W,
+    data: &[u8],
+) -> Result<(), W::Error> {
+    let mut rest = data;
+    loop {
+        let max_block = core::cmp::min(rest.len(), 254);
+        match rest[0..max_block].iter().position(|&byte| byte == 0) {
+            Some(zero_idx) => {
+                writer.write_all(&[zero_idx as u8 + 1]).await?;
+                writer.write_all(&rest[0..zero_idx]).await?;
+                rest = &rest[zero_idx + 1..];
+            }
+            None if max_block == 254 => {
+                writer.write_all(&[0xFF]).await?;
+                writer.write_all(&rest[0..254]).await?;
+                rest = &rest[254..];
+                if rest.is_empty() {
+                    break;
+                }
+            }
+            None => {
+                writer.write_all(&[rest.len() as u8 + 1]).await?;
+                writer.write_all(rest).await?;
+                break;
+            }
+        }
+    }
+    writer.write_all(&[DELIMITER]).await
+}
+
+/// Streaming COBS decoder with an internal frame buffer of size `N`.
+#[derive(Debug)]
+pub struct CobsDecoder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    block_remaining: u8,
+    last_code: u8,
+    discarding: bool,
+}
+
+impl<const N: usize> Default for CobsDecoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> CobsDecoder<N> {
+    /// Create a new decoder.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            block_remaining: 0,
+            last_code: 0,
+            discarding: false,
+        }
+    }
+
+    /// Reset the decoder, discarding the frame which is currently being decoded.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.block_remaining = 0;
+        self.last_code = 0;
+        self.discarding = false;
+    }
+
+    /// Feed one received byte into the decoder.
+    ///
+    /// Returns the decoded frame once a complete frame was received. Empty frames are
+    /// ignored.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, CobsDecodeError> {
+        Ok(self.push(byte)?.map(|len| &self.buf[0..len]))
+    }
+
+    /// Read bytes from the reader until a complete frame was decoded.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub fn read_frame<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, CobsDecodeError>> {
+        let len = read_until_frame(reader, |byte| self.push(byte))?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the asynchronous reader until a complete frame was decoded.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub async fn read_frame_async<R: embedded_io_async::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, CobsDecodeError>> {
+        let len = read_until_frame_async(reader, |byte| self.push(byte)).await?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the reader until a complete frame was received and return the frame
+    /// as a new vector, see [Self::read_frame].
+    #[cfg(all(feature = "embedded-io", feature = "alloc"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "embedded-io", feature = "alloc"))))]
+    pub fn read_frame_vec<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<alloc::vec::Vec<u8>, FrameReadError<R::Error, CobsDecodeError>> {
+        self.read_frame(reader).map(<[u8]>::to_vec)
+    }
+
+    fn push(&mut self, byte: u8) -> Result<Option<usize>, CobsDecodeError> {
+        if byte == DELIMITER {
+            let discarding = self.discarding;
+            let truncated = self.block_remaining != 0;
+            let len = self.len;
+            self.reset();
+            if discarding {
+                return Ok(None);
+            }
+            if truncated {
+                return Err(CobsDecodeError::Truncated);
+            }
+            if len == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(len));
+        }
+        if self.discarding {
+            return Ok(None);
+        }
+        if self.block_remaining == 0 {
+            // Code byte. The previous block is followed by an implicit zero unless it was a
+            // maximum length block.
+            if self.last_code != 0 && self.last_code != 0xFF {
+                self.push_data(0)?;
+            }
+            self.last_code = byte;
+            self.block_remaining = byte - 1;
+            return Ok(None);
+        }
+        self.push_data(byte)?;
+        self.block_remaining -= 1;
+        Ok(None)
+    }
+
+    fn push_data(&mut self, byte: u8) -> Result<(), CobsDecodeError> {
+        if self.len >= N {
+            self.discarding = true;
+            return Err(CobsDecodeError::Overflow);
+        }
+        self.buf[self.len] = byte;
+        self.len += 1;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const N: usize> FrameCodec for CobsDecoder<N> {
+    type EncodeError = core::convert::Infallible;
+    type DecodeError = CobsDecodeError;
+
+    fn encode_to_writer<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, core::convert::Infallible>> {
+        encode_to_writer(writer, data).map_err(FrameWriteError::Io)
+    }
+
+    async fn encode_to_writer_async<W: embedded_io_async::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, core::convert::Infallible>> {
+        encode_to_writer_async(writer, data)
+            .await
+            .map_err(FrameWriteError::Io)
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, CobsDecodeError> {
+        self.push(byte)
+    }
+
+    fn frame(&self, len: usize) -> &[u8] {
+        &self.buf[0..len]
+    }
+
+    fn reset(&mut self) {
+        CobsDecoder::reset(self);
+    }
+}
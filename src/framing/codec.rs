@@ -0,0 +1,171 @@
+//! # Pluggable frame codecs
+//!
+//! The [FrameCodec] trait abstracts over the encoder and the streaming decoder of a framing
+//! protocol. It is implemented by the decoders of this crate, and can be implemented for custom
+//! protocols like MAVLink as well.
+//!
+//! The [Framed] wrapper carries any driver implementing the [embedded_io] or
+//! [embedded_io_async] traits, for example [crate::AxiUartlite] or the buffered driver, together
+//! with a codec and sends and receives complete frames.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut framed = Framed::new(buffered, CobsDecoder::<64>::new());
+//! framed.send_frame(b"hello")?;
+//! let frame = framed.recv_frame_async().await?;
+//! ```
+use super::{FrameReadError, read_until_frame, read_until_frame_async};
+
+/// Error when writing a frame through a writer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameWriteError<IoError, EncodeError> {
+    /// Writer I/O error.
+    Io(IoError),
+    /// Frame encoding error.
+    Encode(EncodeError),
+}
+
+impl<IoError: core::fmt::Debug, EncodeError: core::fmt::Debug> core::fmt::Display
+    for FrameWriteError<IoError, EncodeError>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+            Self::Encode(e) => write!(f, "encode error: {e:?}"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug, EncodeError: core::fmt::Debug> core::error::Error
+    for FrameWriteError<IoError, EncodeError>
+{
+}
+
+/// Encoder and streaming decoder of a framing protocol.
+pub trait FrameCodec {
+    /// Frame encoding error, [core::convert::Infallible] if every payload can be encoded.
+    type EncodeError: core::fmt::Debug;
+    /// Frame decoding error.
+    type DecodeError: core::fmt::Debug;
+
+    /// Encode the passed payload and write the frame through the writer.
+    fn encode_to_writer<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, Self::EncodeError>>;
+
+    /// Encode the passed payload and write the frame through the asynchronous writer.
+    fn encode_to_writer_async<W: embedded_io_async::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> impl Future<Output = Result<(), FrameWriteError<W::Error, Self::EncodeError>>>;
+
+    /// Feed one received byte into the decoder.
+    ///
+    /// Returns the length of the decoded payload once a complete frame was received. The
+    /// payload can then be retrieved with [Self::frame] until the next byte is pushed.
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, Self::DecodeError>;
+
+    /// Decoded payload with the length returned by [Self::push_byte].
+    fn frame(&self, len: usize) -> &[u8];
+
+    /// Reset the decoder, discarding the frame which is currently being decoded.
+    fn reset(&mut self);
+}
+
+/// Driver together with a [FrameCodec], which sends and receives complete frames.
+pub struct Framed<T, C> {
+    io: T,
+    codec: C,
+}
+
+impl<T, C: FrameCodec> Framed<T, C> {
+    /// Create a new framed driver.
+    pub const fn new(io: T, codec: C) -> Self {
+        Self { io, codec }
+    }
+
+    /// Wrapped driver.
+    #[inline]
+    pub const fn io(&self) -> &T {
+        &self.io
+    }
+
+    /// Mutable access to the wrapped driver.
+    #[inline]
+    pub fn io_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Codec.
+    #[inline]
+    pub const fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Mutable access to the codec.
+    #[inline]
+    pub fn codec_mut(&mut self) -> &mut C {
+        &mut self.codec
+    }
+
+    /// Release the driver and the codec.
+    pub fn release(self) -> (T, C) {
+        (self.io, self.codec)
+    }
+
+    /// Feed one byte which was received outside of this wrapper, for example inside an
+    /// interrupt handler, into the decoder.
+    ///
+    /// Returns the payload once a complete frame was received.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, C::DecodeError> {
+        Ok(self.codec.push_byte(byte)?.map(|len| self.codec.frame(len)))
+    }
+
+    /// Encode the passed payload and send the frame.
+    pub fn send_frame(
+        &mut self,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<T::Error, C::EncodeError>>
+    where
+        T: embedded_io::Write,
+    {
+        self.codec.encode_to_writer(&mut self.io, data)
+    }
+
+    /// Read bytes until a complete frame was received and return its payload.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    pub fn recv_frame(&mut self) -> Result<&[u8], FrameReadError<T::Error, C::DecodeError>>
+    where
+        T: embedded_io::Read,
+    {
+        let len = read_until_frame(&mut self.io, |byte| self.codec.push_byte(byte))?;
+        Ok(self.codec.frame(len))
+    }
+
+    /// Encode the passed payload and send the frame asynchronously.
+    pub async fn send_frame_async(
+        &mut self,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<T::Error, C::EncodeError>>
+    where
+        T: embedded_io_async::Write,
+    {
+        self.codec.encode_to_writer_async(&mut self.io, data).await
+    }
+
+    /// Asynchronously read bytes until a complete frame was received and return its payload.
+    pub async fn recv_frame_async(
+        &mut self,
+    ) -> Result<&[u8], FrameReadError<T::Error, C::DecodeError>>
+    where
+        T: embedded_io_async::Read,
+    {
+        let len = read_until_frame_async(&mut self.io, |byte| self.codec.push_byte(byte)).await?;
+        Ok(self.codec.frame(len))
+    }
+}
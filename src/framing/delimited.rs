@@ -0,0 +1,256 @@
+//! # Generic delimiter-based framing
+//!
+//! The [Framer] splits a byte stream into frames using a configurable end delimiter and an
+//! optional start delimiter. This is the basis for line-based protocols, AT-style command
+//! interfaces or NMEA sentences.
+//!
+//! The delimiters are not part of the returned frames. If a start delimiter is configured,
+//! all bytes received outside of a frame are ignored, and a start delimiter received inside
+//! a frame restarts the frame.
+#[cfg(feature = "embedded-io")]
+use super::{
+    FrameCodec, FrameReadError, FrameWriteError, read_until_frame, read_until_frame_async,
+};
+
+/// Framer configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FramerConfig {
+    /// Optional start delimiter.
+    pub start: Option<u8>,
+    /// End delimiter.
+    pub end: u8,
+    /// Maximum frame length. This is limited by the buffer size of the [Framer].
+    pub max_len: usize,
+    /// Strip a carriage return preceding the end delimiter.
+    pub strip_cr: bool,
+    /// Skip empty frames.
+    pub skip_empty: bool,
+}
+
+impl FramerConfig {
+    /// Configuration for text lines terminated with LF or CRLF. Empty lines are skipped.
+    pub const fn lines(max_len: usize) -> Self {
+        Self {
+            start: None,
+            end: b'\n',
+            max_len,
+            strip_cr: true,
+            skip_empty: true,
+        }
+    }
+
+    /// Configuration for NMEA 0183 sentences which start with `$` and are terminated with CRLF.
+    pub const fn nmea() -> Self {
+        Self {
+            start: Some(b'$'),
+            end: b'\n',
+            max_len: 82,
+            strip_cr: true,
+            skip_empty: true,
+        }
+    }
+}
+
+/// Delimited frame decoding error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FramerError {
+    /// Frame exceeds the configured maximum length or the framer buffer. The remainder of the
+    /// frame is discarded.
+    Overflow,
+    /// The payload of a frame which should be sent contains a delimiter.
+    Delimiter,
+}
+
+impl core::fmt::Display for FramerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "frame exceeds maximum length"),
+            Self::Delimiter => write!(f, "payload contains a delimiter"),
+        }
+    }
+}
+
+impl core::error::Error for FramerError {}
+
+/// Delimiter-based frame splitter with an internal buffer of size `N`.
+#[derive(Debug)]
+pub struct Framer<const N: usize> {
+    config: FramerConfig,
+    buf: [u8; N],
+    len: usize,
+    in_frame: bool,
+    discarding: bool,
+}
+
+impl<const N: usize> Framer<N> {
+    /// Create a new framer.
+    pub const fn new(config: FramerConfig) -> Self {
+        Self {
+            config,
+            buf: [0; N],
+            len: 0,
+            in_frame: config.start.is_none(),
+            discarding: false,
+        }
+    }
+
+    /// Framer configuration.
+    pub const fn config(&self) -> &FramerConfig {
+        &self.config
+    }
+
+    /// Reset the framer, discarding the frame which is currently being received.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.in_frame = self.config.start.is_none();
+        self.discarding = false;
+    }
+
+    /// Feed one received byte into the framer.
+    ///
+    /// Returns the frame once the end delimiter was received.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, FramerError> {
+        Ok(self.push(byte)?.map(|len| &self.buf[0..len]))
+    }
+
+    /// Read bytes from the reader until a complete frame was received.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub fn read_frame<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, FramerError>> {
+        let len = read_until_frame(reader, |byte| self.push(byte))?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the asynchronous reader until a complete frame was received.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub async fn read_frame_async<R: embedded_io_async::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, FramerError>> {
+        let len = read_until_frame_async(reader, |byte| self.push(byte)).await?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the reader until a complete frame was received and return the frame
+    /// as a new vector, see [Self::read_frame].
+    #[cfg(all(feature = "embedded-io", feature = "alloc"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "embedded-io", feature = "alloc"))))]
+    pub fn read_frame_vec<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<alloc::vec::Vec<u8>, FrameReadError<R::Error, FramerError>> {
+        self.read_frame(reader).map(<[u8]>::to_vec)
+    }
+
+    /// Check that the payload contains no delimiter and return the optional start delimiter.
+    #[cfg(feature = "embedded-io")]
+    fn check_payload(&self, data: &[u8]) -> Result<Option<u8>, FramerError> {
+        if data
+            .iter()
+            .any(|&byte| byte == self.config.end || Some(byte) == self.config.start)
+        {
+            return Err(FramerError::Delimiter);
+        }
+        Ok(self.config.start)
+    }
+
+    fn push(&mut self, byte: u8) -> Result<Option<usize>, FramerError> {
+        if Some(byte) == self.config.start {
+            self.len = 0;
+            self.in_frame = true;
+            self.discarding = false;
+            return Ok(None);
+        }
+        if !self.in_frame {
+            return Ok(None);
+        }
+        if byte == self.config.end {
+            let discarding = self.discarding;
+            let mut len = self.len;
+            self.reset();
+            if discarding {
+                return Ok(None);
+            }
+            if self.config.strip_cr && len > 0 && self.buf[len - 1] == b'\r' {
+                len -= 1;
+            }
+            if len == 0 && self.config.skip_empty {
+                return Ok(None);
+            }
+            return Ok(Some(len));
+        }
+        if self.discarding {
+            return Ok(None);
+        }
+        if self.len >= N || self.len >= self.config.max_len {
+            self.discarding = true;
+            return Err(FramerError::Overflow);
+        }
+        self.buf[self.len] = byte;
+        self.len += 1;
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const N: usize> FrameCodec for Framer<N> {
+    type EncodeError = FramerError;
+    type DecodeError = FramerError;
+
+    /// Write the start delimiter, if configured, the payload and the end delimiter. Returns
+    /// [FramerError::Delimiter] if the payload contains a delimiter.
+    fn encode_to_writer<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, FramerError>> {
+        let start = self.check_payload(data).map_err(FrameWriteError::Encode)?;
+        if let Some(start) = start {
+            writer.write_all(&[start]).map_err(FrameWriteError::Io)?;
+        }
+        writer.write_all(data).map_err(FrameWriteError::Io)?;
+        writer
+            .write_all(&[self.config.end])
+            .map_err(FrameWriteError::Io)
+    }
+
+    async fn encode_to_writer_async<W: embedded_io_async::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, FramerError>> {
+        let start = self.check_payload(data).map_err(FrameWriteError::Encode)?;
+        if let Some(start) = start {
+            writer
+                .write_all(&[start])
+                .await
+                .map_err(FrameWriteError::Io)?;
+        }
+        writer.write_all(data).await.map_err(FrameWriteError::Io)?;
+        writer
+            .write_all(&[self.config.end])
+            .await
+            .map_err(FrameWriteError::Io)
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, FramerError> {
+        self.push(byte)
+    }
+
+    fn frame(&self, len: usize) -> &[u8] {
+        &self.buf[0..len]
+    }
+
+    fn reset(&mut self) {
+        Framer::reset(self);
+    }
+}
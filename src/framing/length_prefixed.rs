@@ -0,0 +1,359 @@
+//! # Length-prefixed frames with a CRC16 trailer
+//!
+//! This is a small frame transport layer which provides integrity-checked messages. The wire
+//! format of a frame is:
+//!
+//! | Field   | Size          | Description                                            |
+//! |---------|---------------|--------------------------------------------------------|
+//! | Start   | 1             | [START] marker                                         |
+//! | Length  | 2             | Payload length, big endian                             |
+//! | Payload | Length        | Payload data                                           |
+//! | CRC     | 2             | CRC-16/CCITT-FALSE over length and payload, big endian |
+//!
+//! The decoder skips all bytes until the next [START] marker, which allows re-synchronization
+//! after corrupted frames.
+use super::BufferTooSmall;
+#[cfg(feature = "embedded-io")]
+use super::{
+    FrameCodec, FrameReadError, FrameWriteError, read_until_frame, read_until_frame_async,
+};
+use crate::crc::Crc16Ccitt;
+
+/// Start marker.
+pub const START: u8 = 0xA5;
+/// Frame overhead of start marker, length header and CRC trailer.
+pub const OVERHEAD: usize = 5;
+/// Maximum payload length.
+pub const MAX_PAYLOAD_LEN: usize = u16::MAX as usize;
+
+/// Encoding error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// Payload exceeds [MAX_PAYLOAD_LEN].
+    PayloadTooLarge,
+    /// Output buffer too small.
+    BufferTooSmall(BufferTooSmall),
+}
+
+impl core::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::PayloadTooLarge => write!(f, "payload too large"),
+            Self::BufferTooSmall(_) => write!(f, "output buffer too small"),
+        }
+    }
+}
+
+impl core::error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::BufferTooSmall(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<BufferTooSmall> for EncodeError {
+    fn from(e: BufferTooSmall) -> Self {
+        Self::BufferTooSmall(e)
+    }
+}
+
+/// Send error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SendError<IoError> {
+    /// Payload exceeds [MAX_PAYLOAD_LEN].
+    PayloadTooLarge,
+    /// Writer I/O error.
+    Io(IoError),
+}
+
+impl<IoError: core::fmt::Debug> core::fmt::Display for SendError<IoError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::PayloadTooLarge => write!(f, "payload too large"),
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug> core::error::Error for SendError<IoError> {}
+
+/// Decoding error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LengthPrefixedDecodeError {
+    /// The frame does not fit into the decoder buffer.
+    Overflow(usize),
+    /// CRC mismatch.
+    CrcMismatch {
+        /// CRC received in the frame trailer.
+        expected: u16,
+        /// CRC calculated over the received frame.
+        calculated: u16,
+    },
+}
+
+impl core::fmt::Display for LengthPrefixedDecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overflow(len) => write!(f, "frame length {len} exceeds decoder buffer"),
+            Self::CrcMismatch {
+                expected,
+                calculated,
+            } => write!(
+                f,
+                "CRC mismatch, expected {expected:#06x}, calculated {calculated:#06x}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for LengthPrefixedDecodeError {}
+
+fn header_and_crc(data: &[u8]) -> Result<([u8; 3], [u8; 2]), ()> {
+    if data.len() > MAX_PAYLOAD_LEN {
+        return Err(());
+    }
+    let len = (data.len() as u16).to_be_bytes();
+    let mut crc = Crc16Ccitt::new();
+    crc.update(&len);
+    crc.update(data);
+    Ok(([START, len[0], len[1]], crc.finalize().to_be_bytes()))
+}
+
+/// Encode the passed payload into the output buffer.
+///
+/// Returns the length of the encoded frame.
+pub fn encode(data: &[u8], out: &mut [u8]) -> Result<usize, EncodeError> {
+    let (header, crc) = header_and_crc(data).map_err(|_| EncodeError::PayloadTooLarge)?;
+    let frame_len = data.len() + OVERHEAD;
+    if out.len() < frame_len {
+        return Err(BufferTooSmall.into());
+    }
+    out[0..3].copy_from_slice(&header);
+    out[3..3 + data.len()].copy_from_slice(data);
+    out[3 + data.len()..frame_len].copy_from_slice(&crc);
+    Ok(frame_len)
+}
+
+/// Encode the passed payload into a new vector.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_to_vec(data: &[u8]) -> Result<alloc::vec::Vec<u8>, EncodeError> {
+    let mut out = alloc::vec![0; data.len() + OVERHEAD];
+    encode(data, &mut out)?;
+    Ok(out)
+}
+
+/// Send a frame with the passed payload through the writer.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub fn send<W: embedded_io::Write>(writer: &mut W, data: &[u8]) -> Result<(), SendError<W::Error>> {
+    let (header, crc) = header_and_crc(data).map_err(|_| SendError::PayloadTooLarge)?;
+    writer.write_all(&header).map_err(SendError::Io)?;
+    writer.write_all(data).map_err(SendError::Io)?;
+    writer.write_all(&crc).map_err(SendError::Io)
+}
+
+/// Send a frame with the passed payload through the asynchronous writer.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub async fn send_async<W: embedded_io_async::Write>(
+    writer: &mut W,
+    data: &[u8],
+) -> Result<(), SendError<W::Error>> {
+    let (header, crc) = header_and_crc(data).map_err(|_| SendError::PayloadTooLarge)?;
+    writer.write_all(&header).await.map_err(SendError::Io)?;
+    writer.write_all(data).await.map_err(SendError::Io)?;
+    writer.write_all(&crc).await.map_err(SendError::Io)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum DecoderState {
+    WaitStart,
+    LenHigh,
+    LenLow,
+    Payload,
+    CrcHigh,
+    CrcLow,
+}
+
+/// Streaming decoder with an internal payload buffer of size `N`.
+#[derive(Debug)]
+pub struct LengthPrefixedDecoder<const N: usize> {
+    buf: [u8; N],
+    state: DecoderState,
+    len: usize,
+    received: usize,
+    crc: Crc16Ccitt,
+    crc_high: u8,
+}
+
+impl<const N: usize> Default for LengthPrefixedDecoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> LengthPrefixedDecoder<N> {
+    /// Create a new decoder.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            state: DecoderState::WaitStart,
+            len: 0,
+            received: 0,
+            crc: Crc16Ccitt::new(),
+            crc_high: 0,
+        }
+    }
+
+    /// Reset the decoder, discarding the frame which is currently being decoded.
+    pub fn reset(&mut self) {
+        self.state = DecoderState::WaitStart;
+        self.len = 0;
+        self.received = 0;
+        self.crc = Crc16Ccitt::new();
+    }
+
+    /// Feed one received byte into the decoder.
+    ///
+    /// Returns the payload once a complete and valid frame was received.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, LengthPrefixedDecodeError> {
+        Ok(self.push(byte)?.map(|len| &self.buf[0..len]))
+    }
+
+    /// Read bytes from the reader until a complete and valid frame was received.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub fn read_frame<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, LengthPrefixedDecodeError>> {
+        let len = read_until_frame(reader, |byte| self.push(byte))?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the asynchronous reader until a complete and valid frame was received.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub async fn read_frame_async<R: embedded_io_async::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, LengthPrefixedDecodeError>> {
+        let len = read_until_frame_async(reader, |byte| self.push(byte)).await?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the reader until a complete frame was received and return the frame
+    /// as a new vector, see [Self::read_frame].
+    #[cfg(all(feature = "embedded-io", feature = "alloc"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "embedded-io", feature = "alloc"))))]
+    pub fn read_frame_vec<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<alloc::vec::Vec<u8>, FrameReadError<R::Error, LengthPrefixedDecodeError>> {
+        self.read_frame(reader).map(<[u8]>::to_vec)
+    }
+
+    fn push(&mut self, byte: u8) -> Result<Option<usize>, LengthPrefixedDecodeError> {
+        match self.state {
+            DecoderState::WaitStart => {
+                if byte == START {
+                    self.reset();
+                    self.state = DecoderState::LenHigh;
+                }
+            }
+            DecoderState::LenHigh => {
+                self.crc.update_byte(byte);
+                self.len = (byte as usize) << 8;
+                self.state = DecoderState::LenLow;
+            }
+            DecoderState::LenLow => {
+                self.crc.update_byte(byte);
+                self.len |= byte as usize;
+                if self.len > N {
+                    let len = self.len;
+                    self.reset();
+                    return Err(LengthPrefixedDecodeError::Overflow(len));
+                }
+                self.state = if self.len == 0 {
+                    DecoderState::CrcHigh
+                } else {
+                    DecoderState::Payload
+                };
+            }
+            DecoderState::Payload => {
+                self.crc.update_byte(byte);
+                self.buf[self.received] = byte;
+                self.received += 1;
+                if self.received == self.len {
+                    self.state = DecoderState::CrcHigh;
+                }
+            }
+            DecoderState::CrcHigh => {
+                self.crc_high = byte;
+                self.state = DecoderState::CrcLow;
+            }
+            DecoderState::CrcLow => {
+                let expected = u16::from_be_bytes([self.crc_high, byte]);
+                let calculated = self.crc.finalize();
+                let len = self.len;
+                self.reset();
+                if expected != calculated {
+                    return Err(LengthPrefixedDecodeError::CrcMismatch {
+                        expected,
+                        calculated,
+                    });
+                }
+                return Ok(Some(len));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const N: usize> FrameCodec for LengthPrefixedDecoder<N> {
+    type EncodeError = EncodeError;
+    type DecodeError = LengthPrefixedDecodeError;
+
+    fn encode_to_writer<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, EncodeError>> {
+        send(writer, data).map_err(|e| match e {
+            SendError::PayloadTooLarge => FrameWriteError::Encode(EncodeError::PayloadTooLarge),
+            SendError::Io(e) => FrameWriteError::Io(e),
+        })
+    }
+
+    async fn encode_to_writer_async<W: embedded_io_async::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, EncodeError>> {
+        send_async(writer, data).await.map_err(|e| match e {
+            SendError::PayloadTooLarge => FrameWriteError::Encode(EncodeError::PayloadTooLarge),
+            SendError::Io(e) => FrameWriteError::Io(e),
+        })
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, LengthPrefixedDecodeError> {
+        self.push(byte)
+    }
+
+    fn frame(&self, len: usize) -> &[u8] {
+        &self.buf[0..len]
+    }
+
+    fn reset(&mut self) {
+        LengthPrefixedDecoder::reset(self);
+    }
+}
@@ -0,0 +1,141 @@
+//! # Framing support
+//!
+//! This module contains framing codecs which can be used on top of the TX and RX halves to
+//! transfer packetized data.
+//!
+//! The encoders write through any [embedded_io::Write] or [embedded_io_async::Write]
+//! implementation, for example [crate::Tx] or [crate::TxAsync]. The decoders are streaming
+//! decoders which are fed byte by byte, for example from the RX interrupt handler, or which
+//! read directly from an [embedded_io::Read] implementation like [crate::Rx].
+//!
+//! The [stuffing] module provides a generic byte stuffing layer which wraps a writer.
+//!
+//! The [FrameCodec] trait abstracts over the encoder and decoder of a protocol, so the
+//! [Framed] wrapper can carry any codec on top of a driver, including custom protocols.
+//!
+//! The [FrameTimestamp] helper can be used together with any decoder to timestamp the first
+//! byte of each frame with a user-provided clock.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub mod codec;
+#[cfg(feature = "embedded-io")]
+pub use codec::*;
+pub mod cobs;
+pub mod delimited;
+pub mod length_prefixed;
+pub mod slip;
+pub mod stuffing;
+
+/// The provided output buffer is too small.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BufferTooSmall;
+
+impl core::fmt::Display for BufferTooSmall {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "output buffer too small")
+    }
+}
+
+impl core::error::Error for BufferTooSmall {}
+
+/// Timestamp of the first byte of each frame, using the passed closure as the clock.
+///
+/// [Self::on_byte] is called for every byte which is fed to a decoder, and [Self::take] when
+/// the decoder returned a complete frame or an error.
+pub struct FrameTimestamp<F: FnMut() -> u64> {
+    now: F,
+    start: Option<u64>,
+}
+
+impl<F: FnMut() -> u64> FrameTimestamp<F> {
+    /// Create a new frame timestamp helper.
+    pub const fn new(now: F) -> Self {
+        Self { now, start: None }
+    }
+
+    /// Record a received byte. Returns the timestamp of the first byte of the current frame.
+    pub fn on_byte(&mut self) -> u64 {
+        match self.start {
+            Some(start) => start,
+            None => {
+                let start = (self.now)();
+                self.start = Some(start);
+                start
+            }
+        }
+    }
+
+    /// Timestamp of the first byte of the current frame, [None] if no byte was received yet.
+    #[inline]
+    pub const fn current(&self) -> Option<u64> {
+        self.start
+    }
+
+    /// Read the timestamp of the first byte of the current frame and start a new frame.
+    pub fn take(&mut self) -> Option<u64> {
+        self.start.take()
+    }
+}
+
+/// Error when reading a frame from a reader.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameReadError<IoError, DecodeError> {
+    /// Reader I/O error.
+    Io(IoError),
+    /// The reader reached end of file.
+    Eof,
+    /// Frame decoding error.
+    Decode(DecodeError),
+}
+
+impl<IoError: core::fmt::Debug, DecodeError: core::fmt::Debug> core::fmt::Display
+    for FrameReadError<IoError, DecodeError>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+            Self::Eof => write!(f, "unexpected end of file"),
+            Self::Decode(e) => write!(f, "decode error: {e:?}"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug, DecodeError: core::fmt::Debug> core::error::Error
+    for FrameReadError<IoError, DecodeError>
+{
+}
+
+/// Read bytes one at a time from the reader and pass them to the decoder until it returns the
+/// length of a complete frame.
+#[cfg(feature = "embedded-io")]
+pub(crate) fn read_until_frame<R: embedded_io::Read, E>(
+    reader: &mut R,
+    mut push: impl FnMut(u8) -> Result<Option<usize>, E>,
+) -> Result<usize, FrameReadError<R::Error, E>> {
+    let mut byte = [0; 1];
+    loop {
+        if reader.read(&mut byte).map_err(FrameReadError::Io)? == 0 {
+            return Err(FrameReadError::Eof);
+        }
+        if let Some(len) = push(byte[0]).map_err(FrameReadError::Decode)? {
+            return Ok(len);
+        }
+    }
+}
+
+/// Asynchronous variant of [read_until_frame].
+#[cfg(feature = "embedded-io")]
+pub(crate) async fn read_until_frame_async<R: embedded_io_async::Read, E>(
+    reader: &mut R,
+    mut push: impl FnMut(u8) -> Result<Option<usize>, E>,
+) -> Result<usize, FrameReadError<R::Error, E>> {
+    let mut byte = [0; 1];
+    loop {
+        if reader.read(&mut byte).await.map_err(FrameReadError::Io)? == 0 {
+            return Err(FrameReadError::Eof);
+        }
+        if let Some(len) = push(byte[0]).map_err(FrameReadError::Decode)? {
+            return Ok(len);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! # Serial Line Internet Protocol (SLIP) codec
+//!
+//! Implements the SLIP framing specified in RFC 1055. The encoders emit an [END] character
+//! before and after each frame, which flushes any line noise received by the remote end.
+use super::BufferTooSmall;
+#[cfg(feature = "embedded-io")]
+use super::{
+    FrameCodec, FrameReadError, FrameWriteError, read_until_frame, read_until_frame_async,
+};
+
+/// Frame end character.
+pub const END: u8 = 0xC0;
+/// Escape character.
+pub const ESC: u8 = 0xDB;
+/// Escaped [END] character, follows [ESC].
+pub const ESC_END: u8 = 0xDC;
+/// Escaped [ESC] character, follows [ESC].
+pub const ESC_ESC: u8 = 0xDD;
+
+/// Maximum encoded length for a frame with the given length, including the [END] characters.
+pub const fn max_encoded_len(len: usize) -> usize {
+    2 * len + 2
+}
+
+/// SLIP decoding error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SlipDecodeError {
+    /// The frame does not fit into the decoder buffer. The remaining bytes until the next
+    /// [END] character are discarded.
+    Overflow,
+    /// [ESC] was followed by an invalid character. The frame is discarded.
+    InvalidEscape,
+}
+
+impl core::fmt::Display for SlipDecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "frame exceeds decoder buffer"),
+            Self::InvalidEscape => write!(f, "invalid escape sequence"),
+        }
+    }
+}
+
+impl core::error::Error for SlipDecodeError {}
+
+/// Returns the escape sequence for special characters.
+const fn escape(byte: u8) -> Option<[u8; 2]> {
+    match byte {
+        END => Some([ESC, ESC_END]),
+        ESC => Some([ESC, ESC_ESC]),
+        _ => None,
+    }
+}
+
+/// Encode the passed data into the output buffer, including the [END] characters.
+///
+/// Returns the length of the encoded frame.
+pub fn encode(data: &[u8], out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+    let mut written = 0;
+    let mut push = |byte: u8| {
+        if written >= out.len() {
+            return Err(BufferTooSmall);
+        }
+        out[written] = byte;
+        written += 1;
+        Ok(())
+    };
+    push(END)?;
+    for &byte in data {
+        match escape(byte) {
+            Some(escaped) => {
+                push(escaped[0])?;
+                push(escaped[1])?;
+            }
+            None => push(byte)?,
+        }
+    }
+    push(END)?;
+    Ok(written)
+}
+
+/// Encode the passed data into a new vector, including the [END] characters.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encode_to_vec(data: &[u8]) -> alloc::vec::Vec<u8> {
+    let mut out = alloc::vec![0; max_encoded_len(data.len())];
+    // The buffer has the maximum encoded length.
+    let len = encode(data, &mut out).unwrap();
+    out.truncate(len);
+    out
+}
+
+/// Encode the passed data and write it through the writer, including the [END] characters.
+///
+/// Runs of regular characters are written directly from the passed data, so no scratch buffer
+/// is required.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub fn encode_to_writer<W: embedded_io::Write>(
+    writer: &mut W,
+    data: &[u8],
+) -> Result<(), W::Error> {
+    writer.write_all(&[END])?;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let run = rest
+            .iter()
+            .position(|&byte| escape(byte).is_some())
+            .unwrap_or(rest.len());
+        writer.write_all(&rest[0..run])?;
+        if run < rest.len() {
+            writer.write_all(&escape(rest[run]).unwrap())?;
+            rest = &rest[run + 1..];
+        } else {
+            rest = &[];
+        }
+    }
+    writer.write_all(&[END])
+}
+
+/// Encode the passed data and write it through the asynchronous writer, including the [END]
+/// characters.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub async fn encode_to_writer_async<W: embedded_io_async::Write>(
+    writer: &mut W,
+    data: &[u8],
+) -> Result<(), W::Error> {
+    writer.write_all(&[END]).await?;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let run = rest
+            .iter()
+            .position(|&byte| escape(byte).is_some())
+            .unwrap_or(rest.len());
+        writer.write_all(&rest[0..run]).await?;
+        if run < rest.len() {
+            writer.write_all(&escape(rest[run]).unwrap()).await?;
+            rest = &rest[run + 1..];
+        } else {
+            rest = &[];
+        }
+    }
+    writer.write_all(&[END]).await
+}
+
+/// Streaming SLIP decoder with an internal frame buffer of size `N`.
+#[derive(Debug)]
+pub struct SlipDecoder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    escaped: bool,
+    discarding: bool,
+}
+
+impl<const N: usize> Default for SlipDecoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SlipDecoder<N> {
+    /// Create a new decoder.
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            escaped: false,
+            discarding: false,
+        }
+    }
+
+    /// Reset the decoder, discarding the frame which is currently being decoded.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.escaped = false;
+        self.discarding = false;
+    }
+
+    /// Feed one received byte into the decoder.
+    ///
+    /// Returns the decoded frame once a complete frame was received. Empty frames are
+    /// ignored.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<&[u8]>, SlipDecodeError> {
+        Ok(self.push(byte)?.map(|len| &self.buf[0..len]))
+    }
+
+    /// Read bytes from the reader until a complete frame was decoded.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub fn read_frame<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, SlipDecodeError>> {
+        let len = read_until_frame(reader, |byte| self.push(byte))?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the asynchronous reader until a complete frame was decoded.
+    ///
+    /// The bytes are read one at a time, so no bytes following the frame are consumed.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub async fn read_frame_async<R: embedded_io_async::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<&[u8], FrameReadError<R::Error, SlipDecodeError>> {
+        let len = read_until_frame_async(reader, |byte| self.push(byte)).await?;
+        Ok(&self.buf[0..len])
+    }
+
+    /// Read bytes from the reader until a complete frame was received and return the frame
+    /// as a new vector, see [Self::read_frame].
+    #[cfg(all(feature = "embedded-io", feature = "alloc"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "embedded-io", feature = "alloc"))))]
+    pub fn read_frame_vec<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<alloc::vec::Vec<u8>, FrameReadError<R::Error, SlipDecodeError>> {
+        self.read_frame(reader).map(<[u8]>::to_vec)
+    }
+
+    fn push(&mut self, byte: u8) -> Result<Option<usize>, SlipDecodeError> {
+        if byte == END {
+            let discarding = self.discarding;
+            let len = self.len;
+            self.reset();
+            if discarding || len == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(len));
+        }
+        if self.discarding {
+            return Ok(None);
+        }
+        let data = if self.escaped {
+            self.escaped = false;
+            match byte {
+                ESC_END => END,
+                ESC_ESC => ESC,
+                _ => {
+                    self.discarding = true;
+                    return Err(SlipDecodeError::InvalidEscape);
+                }
+            }
+        } else if byte == ESC {
+            self.escaped = true;
+            return Ok(None);
+        } else {
+            byte
+        };
+        if self.len >= N {
+            self.discarding = true;
+            return Err(SlipDecodeError::Overflow);
+        }
+        self.buf[self.len] = data;
+        self.len += 1;
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const N: usize> FrameCodec for SlipDecoder<N> {
+    type EncodeError = core::convert::Infallible;
+    type DecodeError = SlipDecodeError;
+
+    fn encode_to_writer<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, core::convert::Infallible>> {
+        encode_to_writer(writer, data).map_err(FrameWriteError::Io)
+    }
+
+    async fn encode_to_writer_async<W: embedded_io_async::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, core::convert::Infallible>> {
+        encode_to_writer_async(writer, data)
+            .await
+            .map_err(FrameWriteError::Io)
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, SlipDecodeError> {
+        self.push(byte)
+    }
+
+    fn frame(&self, len: usize) -> &[u8] {
+        &self.buf[0..len]
+    }
+
+    fn reset(&mut self) {
+        SlipDecoder::reset(self);
+    }
+}
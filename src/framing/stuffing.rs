@@ -0,0 +1,170 @@
+//! # Generic byte stuffing
+//!
+//! Protocols with reserved control characters replace each reserved byte inside the payload
+//! with an escape byte followed by a transformed byte. The [ByteStuffer] wraps any
+//! [embedded_io::Write] or [embedded_io_async::Write] implementation, for example [crate::Tx]
+//! or [crate::TxAsync], and applies the escaping on the fly, so the payload does not have to be
+//! pre-encoded into a scratch buffer. Unescaped control characters like frame delimiters can be
+//! written with [ByteStuffer::write_raw].
+
+/// Byte stuffing configuration.
+///
+/// The escape byte and all bytes of the reserved set are replaced with the escape byte followed
+/// by the original byte XORed with the `xor` mask.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StuffingConfig<'set> {
+    /// Escape byte, which is always escaped itself.
+    pub escape: u8,
+    /// Reserved bytes which are escaped.
+    pub reserved: &'set [u8],
+    /// Mask which is applied to escaped bytes.
+    pub xor: u8,
+}
+
+/// Asynchronous HDLC-like framing as used by PPP (RFC 1662). Only the flag sequence and the
+/// control escape are escaped.
+pub const HDLC: StuffingConfig<'static> = StuffingConfig::new(0x7D, &[0x7E], 0x20);
+
+impl<'set> StuffingConfig<'set> {
+    /// Create a new byte stuffing configuration.
+    pub const fn new(escape: u8, reserved: &'set [u8], xor: u8) -> Self {
+        Self {
+            escape,
+            reserved,
+            xor,
+        }
+    }
+
+    /// The byte has to be escaped.
+    pub const fn needs_escape(&self, byte: u8) -> bool {
+        if byte == self.escape {
+            return true;
+        }
+        let mut idx = 0;
+        while idx < self.reserved.len() {
+            if self.reserved[idx] == byte {
+                return true;
+            }
+            idx += 1;
+        }
+        false
+    }
+
+    /// Escape sequence for a byte.
+    #[inline]
+    pub const fn escape_sequence(&self, byte: u8) -> [u8; 2] {
+        [self.escape, byte ^ self.xor]
+    }
+}
+
+/// Writer adapter which applies byte stuffing to all written data.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+#[derive(Debug)]
+pub struct ByteStuffer<'set, W> {
+    writer: W,
+    config: StuffingConfig<'set>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<'set, W> ByteStuffer<'set, W> {
+    /// Create a new byte stuffing adapter.
+    pub const fn new(writer: W, config: StuffingConfig<'set>) -> Self {
+        Self { writer, config }
+    }
+
+    /// Byte stuffing configuration.
+    #[inline]
+    pub const fn config(&self) -> StuffingConfig<'set> {
+        self.config
+    }
+
+    /// Access the wrapped writer.
+    #[inline]
+    pub fn writer(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Release the wrapped writer.
+    pub fn release(self) -> W {
+        self.writer
+    }
+
+    /// Length of the run of bytes at the start of the data which do not need escaping.
+    fn plain_run(&self, data: &[u8]) -> usize {
+        data.iter()
+            .position(|&byte| self.config.needs_escape(byte))
+            .unwrap_or(data.len())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::Write> ByteStuffer<'_, W> {
+    /// Write the data without escaping, for example frame delimiters.
+    pub fn write_raw(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        self.writer.write_all(data)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io_async::Write> ByteStuffer<'_, W> {
+    /// Asynchronously write the data without escaping, for example frame delimiters.
+    pub async fn write_raw_async(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        self.writer.write_all(data).await
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::ErrorType> embedded_io::ErrorType for ByteStuffer<'_, W> {
+    type Error = W::Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::Write> embedded_io::Write for ByteStuffer<'_, W> {
+    /// Escapes and writes the whole buffer. Runs of regular bytes are written directly from the
+    /// passed data.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let run = self.plain_run(rest);
+            self.writer.write_all(&rest[0..run])?;
+            if run < rest.len() {
+                self.writer
+                    .write_all(&self.config.escape_sequence(rest[run]))?;
+                rest = &rest[run + 1..];
+            } else {
+                rest = &[];
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io_async::Write> embedded_io_async::Write for ByteStuffer<'_, W> {
+    /// Escapes and writes the whole buffer.
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let run = self.plain_run(rest);
+            self.writer.write_all(&rest[0..run]).await?;
+            if run < rest.len() {
+                self.writer
+                    .write_all(&self.config.escape_sequence(rest[run]))
+                    .await?;
+                rest = &rest[run + 1..];
+            } else {
+                rest = &[];
+            }
+        }
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush().await
+    }
+}
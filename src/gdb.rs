@@ -0,0 +1,73 @@
+//! # `gdbstub` connection adapter
+//!
+//! This module is only available with the `gdbstub` feature. It provides the [GdbConnection]
+//! adapter, which implements the [gdbstub::conn::Connection] and
+//! [gdbstub::conn::ConnectionExt] traits on top of the blocking driver. This allows using the
+//! UART Lite as the transport for an on-target GDB stub.
+use core::convert::Infallible;
+
+use gdbstub::conn::{Connection, ConnectionExt};
+
+use crate::AxiUartlite;
+
+/// GDB stub connection over the UART Lite.
+pub struct GdbConnection {
+    uart: AxiUartlite,
+    peeked: Option<u8>,
+}
+
+impl GdbConnection {
+    /// Create a new connection using the passed driver.
+    pub const fn new(uart: AxiUartlite) -> Self {
+        Self { uart, peeked: None }
+    }
+
+    /// Release the driver. A byte which was peeked but not read yet is discarded.
+    pub fn release(self) -> AxiUartlite {
+        self.uart
+    }
+
+    /// Try to read a byte without blocking.
+    pub fn try_read(&mut self) -> Option<u8> {
+        if let Some(byte) = self.peeked.take() {
+            return Some(byte);
+        }
+        self.uart.read_fifo().ok()
+    }
+}
+
+impl Connection for GdbConnection {
+    type Error = Infallible;
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        nb::block!(self.uart.write_fifo(byte))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        for &byte in buf {
+            nb::block!(self.uart.write_fifo(byte))?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        while !self.uart.tx_fifo_empty() {}
+        Ok(())
+    }
+}
+
+impl ConnectionExt for GdbConnection {
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        if let Some(byte) = self.peeked.take() {
+            return Ok(byte);
+        }
+        nb::block!(self.uart.read_fifo())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
+        if self.peeked.is_none() {
+            self.peeked = self.uart.read_fifo().ok();
+        }
+        Ok(self.peeked)
+    }
+}
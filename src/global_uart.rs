@@ -0,0 +1,75 @@
+//! # Globally shared print macros
+//!
+//! This module is only available with the `global-uart` feature. It provides the
+//! [crate::uprint] and [crate::uprintln] macros, which print through a global [Tx] handle that
+//! is locked with a critical section for every call. Any module or interrupt handler can emit
+//! console output this way without passing a [Tx] reference around.
+//!
+//! Unlike the once-initialized handle of the `print` module, the global handle can be
+//! replaced with [init_global_uart] and taken back with [release_global_uart], for example to
+//! hand the UART over to a bootloader protocol. [with_global_uart] gives direct access to the
+//! locked handle. Output while no handle is installed is discarded.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let uart = unsafe { AxiUartlite::new(UARTLITE_BASE) };
+//! let (tx, _rx) = uart.split();
+//! init_global_uart(tx);
+//! uprintln!("hello {}", "world");
+//! ```
+use core::{cell::RefCell, fmt::Write};
+
+use critical_section::Mutex;
+
+use crate::Tx;
+
+static GLOBAL_UART: Mutex<RefCell<Option<Tx>>> = Mutex::new(RefCell::new(None));
+
+/// Install the TX handle used by the [crate::uprint] and [crate::uprintln] macros.
+///
+/// Returns the previously installed handle.
+pub fn init_global_uart(tx: Tx) -> Option<Tx> {
+    critical_section::with(|cs| GLOBAL_UART.borrow(cs).replace(Some(tx)))
+}
+
+/// Take the installed TX handle back. Subsequent print calls are discarded.
+pub fn release_global_uart() -> Option<Tx> {
+    critical_section::with(|cs| GLOBAL_UART.borrow(cs).take())
+}
+
+/// Call the closure with the locked global TX handle.
+///
+/// Returns [None] without calling the closure if no handle is installed. The closure runs
+/// inside the critical section, so it should be short.
+pub fn with_global_uart<T>(f: impl FnOnce(&mut Tx) -> T) -> Option<T> {
+    critical_section::with(|cs| GLOBAL_UART.borrow(cs).borrow_mut().as_mut().map(f))
+}
+
+/// Print the formatted arguments through the global TX handle.
+///
+/// Used by the print macros.
+#[doc(hidden)]
+pub fn _print(args: core::fmt::Arguments<'_>) {
+    with_global_uart(|tx| tx.write_fmt(args).ok());
+}
+
+/// Print through the global TX handle installed with [init_global_uart].
+#[macro_export]
+macro_rules! uprint {
+    ($($arg:tt)*) => {
+        $crate::global_uart::_print(core::format_args!($($arg)*))
+    };
+}
+
+/// Print through the global TX handle installed with [init_global_uart], followed by a carriage
+/// return and a line feed.
+#[macro_export]
+macro_rules! uprintln {
+    () => {
+        $crate::global_uart::_print(core::format_args!("\r\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::global_uart::_print(core::format_args!("{}\r\n", core::format_args!($($arg)*)))
+    };
+}
@@ -0,0 +1,377 @@
+//! # Intel HEX and Motorola S-record streaming parsers
+//!
+//! The [IntelHexParser] and [SrecParser] are fed with received bytes one at a time and emit
+//! decoded [Record]s, so a hex file can be pasted into a terminal and loaded without
+//! buffering the whole file. Line endings and whitespace between records are ignored.
+
+/// Maximum number of binary bytes in a record, including address, type and checksum fields.
+const MAX_RECORD_BYTES: usize = 5 + 255;
+
+/// Decoded record.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Record<'data> {
+    /// Data record with the absolute load address.
+    Data {
+        /// Load address of the first data byte.
+        address: u32,
+        /// Data bytes.
+        data: &'data [u8],
+    },
+    /// Start or entry point address.
+    StartAddress(u32),
+    /// End of file. Only emitted by the Intel HEX parser and by the reader helpers. For
+    /// S-records, the termination record emits the start address and
+    /// [SrecParser::is_finished] returns true afterwards.
+    EndOfFile,
+}
+
+/// Parsing error. The parser is reset to wait for the next record start.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HexParseError {
+    /// Invalid character inside a record.
+    InvalidCharacter(u8),
+    /// Odd number of hex digits.
+    OddDigits,
+    /// Record length does not match the length field.
+    InvalidLength,
+    /// Checksum mismatch.
+    Checksum,
+    /// Unsupported record type.
+    UnsupportedRecordType(u8),
+}
+
+impl core::fmt::Display for HexParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidCharacter(c) => write!(f, "invalid character {c:#04x}"),
+            Self::OddDigits => write!(f, "odd number of hex digits"),
+            Self::InvalidLength => write!(f, "invalid record length"),
+            Self::Checksum => write!(f, "checksum mismatch"),
+            Self::UnsupportedRecordType(t) => write!(f, "unsupported record type {t}"),
+        }
+    }
+}
+
+impl core::error::Error for HexParseError {}
+
+/// Error when parsing records from a reader.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HexReadError<IoError> {
+    /// Reader I/O error.
+    Io(IoError),
+    /// The reader reached end of file before the end of file record was received.
+    Eof,
+    /// Parsing error.
+    Parse(HexParseError),
+}
+
+impl<IoError: core::fmt::Debug> core::fmt::Display for HexReadError<IoError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+            Self::Eof => write!(f, "unexpected end of file"),
+            Self::Parse(e) => write!(f, "parse error: {e}"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug> core::error::Error for HexReadError<IoError> {}
+
+/// Hex digit accumulator shared by both parsers.
+#[derive(Debug)]
+struct HexLine {
+    buf: [u8; MAX_RECORD_BYTES],
+    len: usize,
+    high_nibble: Option<u8>,
+    active: bool,
+}
+
+impl HexLine {
+    const fn new() -> Self {
+        Self {
+            buf: [0; MAX_RECORD_BYTES],
+            len: 0,
+            high_nibble: None,
+            active: false,
+        }
+    }
+
+    fn start(&mut self) {
+        self.len = 0;
+        self.high_nibble = None;
+        self.active = true;
+    }
+
+    /// Push a hex digit.
+    fn push_digit(&mut self, byte: u8) -> Result<(), HexParseError> {
+        let nibble = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'a'..=b'f' => byte - b'a' + 10,
+            b'A'..=b'F' => byte - b'A' + 10,
+            _ => {
+                self.active = false;
+                return Err(HexParseError::InvalidCharacter(byte));
+            }
+        };
+        match self.high_nibble.take() {
+            Some(high) => {
+                if self.len >= MAX_RECORD_BYTES {
+                    self.active = false;
+                    return Err(HexParseError::InvalidLength);
+                }
+                self.buf[self.len] = (high << 4) | nibble;
+                self.len += 1;
+            }
+            None => self.high_nibble = Some(nibble),
+        }
+        Ok(())
+    }
+
+    /// Finish the current line. Returns the decoded bytes.
+    fn finish(&mut self) -> Result<&[u8], HexParseError> {
+        self.active = false;
+        if self.high_nibble.is_some() {
+            return Err(HexParseError::OddDigits);
+        }
+        Ok(&self.buf[0..self.len])
+    }
+}
+
+fn be_address(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |address, &byte| (address << 8) | byte as u32)
+}
+
+/// Intel HEX streaming parser.
+///
+/// Supports the data, end of file, extended segment address, start segment address, extended
+/// linear address and start linear address record types.
+#[derive(Debug)]
+pub struct IntelHexParser {
+    line: HexLine,
+    base_address: u32,
+}
+
+impl Default for IntelHexParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntelHexParser {
+    /// Create a new parser.
+    pub const fn new() -> Self {
+        Self {
+            line: HexLine::new(),
+            base_address: 0,
+        }
+    }
+
+    /// Reset the parser, including the extended base address.
+    pub fn reset(&mut self) {
+        self.line = HexLine::new();
+        self.base_address = 0;
+    }
+
+    /// Feed one received character into the parser.
+    ///
+    /// Returns a record once a complete record line was received. Extended address records
+    /// are handled internally and do not return a record.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<Record<'_>>, HexParseError> {
+        match byte {
+            b':' => {
+                self.line.start();
+                Ok(None)
+            }
+            b'\r' | b'\n' if self.line.active => self.process_line(),
+            _ if self.line.active => self.line.push_digit(byte).map(|_| None),
+            _ => Ok(None),
+        }
+    }
+
+    /// Read characters from the reader and pass all decoded records to the user callback
+    /// until the end of file record was received.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub fn parse_from_reader<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+        mut callback: impl FnMut(Record<'_>),
+    ) -> Result<(), HexReadError<R::Error>> {
+        let mut byte = [0; 1];
+        loop {
+            if reader.read(&mut byte).map_err(HexReadError::Io)? == 0 {
+                return Err(HexReadError::Eof);
+            }
+            if let Some(record) = self.feed(byte[0]).map_err(HexReadError::Parse)? {
+                let eof = record == Record::EndOfFile;
+                callback(record);
+                if eof {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn process_line(&mut self) -> Result<Option<Record<'_>>, HexParseError> {
+        let line = self.line.finish()?;
+        if line.len() < 5 || line.len() != line[0] as usize + 5 {
+            return Err(HexParseError::InvalidLength);
+        }
+        if line.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0 {
+            return Err(HexParseError::Checksum);
+        }
+        let offset = u16::from_be_bytes([line[1], line[2]]) as u32;
+        let data = &line[4..line.len() - 1];
+        match line[3] {
+            0x00 => Ok(Some(Record::Data {
+                address: self.base_address.wrapping_add(offset),
+                data,
+            })),
+            0x01 => Ok(Some(Record::EndOfFile)),
+            0x02 if data.len() == 2 => {
+                self.base_address = be_address(data) << 4;
+                Ok(None)
+            }
+            0x03 if data.len() == 4 => {
+                let segment = u16::from_be_bytes([data[0], data[1]]) as u32;
+                let offset = u16::from_be_bytes([data[2], data[3]]) as u32;
+                Ok(Some(Record::StartAddress((segment << 4) + offset)))
+            }
+            0x04 if data.len() == 2 => {
+                self.base_address = be_address(data) << 16;
+                Ok(None)
+            }
+            0x05 if data.len() == 4 => Ok(Some(Record::StartAddress(be_address(data)))),
+            0x02..=0x05 => Err(HexParseError::InvalidLength),
+            record_type => Err(HexParseError::UnsupportedRecordType(record_type)),
+        }
+    }
+}
+
+/// Motorola S-record streaming parser.
+///
+/// Header (S0) and count (S5, S6) records are checked and skipped. Data records (S1, S2, S3)
+/// emit [Record::Data], and termination records (S7, S8, S9) emit [Record::StartAddress].
+/// The [SrecParser::feed] call which completes a termination record marks the end of the
+/// file, which can be checked with [SrecParser::is_finished]. Records received after the
+/// termination record are parsed as the start of a new file.
+#[derive(Debug)]
+pub struct SrecParser {
+    line: HexLine,
+    record_type: Option<u8>,
+    expect_type: bool,
+    finished: bool,
+}
+
+impl Default for SrecParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SrecParser {
+    /// Create a new parser.
+    pub const fn new() -> Self {
+        Self {
+            line: HexLine::new(),
+            record_type: None,
+            expect_type: false,
+            finished: false,
+        }
+    }
+
+    /// Reset the parser.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Whether a termination record was received and no new record was started since.
+    #[inline]
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Feed one received character into the parser.
+    ///
+    /// Returns a record once a complete record line was received.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<Record<'_>>, HexParseError> {
+        if self.expect_type {
+            self.expect_type = false;
+            if !byte.is_ascii_digit() {
+                self.line.active = false;
+                return Err(HexParseError::InvalidCharacter(byte));
+            }
+            self.record_type = Some(byte - b'0');
+            return Ok(None);
+        }
+        match byte {
+            b'S' => {
+                self.line.start();
+                self.expect_type = true;
+                self.finished = false;
+                Ok(None)
+            }
+            b'\r' | b'\n' if self.line.active => self.process_line(),
+            _ if self.line.active => self.line.push_digit(byte).map(|_| None),
+            _ => Ok(None),
+        }
+    }
+
+    /// Read characters from the reader and pass all decoded records to the user callback
+    /// until a termination record was received.
+    #[cfg(feature = "embedded-io")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+    pub fn parse_from_reader<R: embedded_io::Read>(
+        &mut self,
+        reader: &mut R,
+        mut callback: impl FnMut(Record<'_>),
+    ) -> Result<(), HexReadError<R::Error>> {
+        self.finished = false;
+        let mut byte = [0; 1];
+        loop {
+            if reader.read(&mut byte).map_err(HexReadError::Io)? == 0 {
+                return Err(HexReadError::Eof);
+            }
+            if let Some(record) = self.feed(byte[0]).map_err(HexReadError::Parse)? {
+                callback(record);
+            }
+            if self.finished {
+                callback(Record::EndOfFile);
+                return Ok(());
+            }
+        }
+    }
+
+    fn process_line(&mut self) -> Result<Option<Record<'_>>, HexParseError> {
+        let record_type = self.record_type.take();
+        let line = self.line.finish()?;
+        let record_type = record_type.ok_or(HexParseError::InvalidLength)?;
+        if line.len() < 2 || line.len() != line[0] as usize + 1 {
+            return Err(HexParseError::InvalidLength);
+        }
+        if line.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0xFF {
+            return Err(HexParseError::Checksum);
+        }
+        let address_len = match record_type {
+            0 | 1 | 5 | 9 => 2,
+            2 | 6 | 8 => 3,
+            3 | 7 => 4,
+            _ => return Err(HexParseError::UnsupportedRecordType(record_type)),
+        };
+        if line.len() < 2 + address_len {
+            return Err(HexParseError::InvalidLength);
+        }
+        let address = be_address(&line[1..1 + address_len]);
+        let data = &line[1 + address_len..line.len() - 1];
+        match record_type {
+            1..=3 => Ok(Some(Record::Data { address, data })),
+            7..=9 => {
+                self.finished = true;
+                Ok(Some(Record::StartAddress(address)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! # On-target test routines
+//!
+//! This module is only available with the `hil` feature. It contains hardware-in-the-loop test
+//! routines which exercise the TX, RX and interrupt paths of the driver on real hardware. The
+//! routines use the [crate::test_hooks] to bring the peripheral into a known state and are
+//! intended to be called from the test functions of an on-target test suite like the
+//! [`embedded-test`](https://crates.io/crates/embedded-test) suite in the `hil-tests` directory
+//! of this repository.
+//!
+//! All routines are generic over the [RegisterAccess] backend and poll the hardware at most
+//! `max_polls` times before they return [HilError::Timeout], so a broken design does not
+//! stall the test suite.
+//!
+//! The loopback routine requires the TX line to be wired to the RX line, either externally or
+//! inside the FPGA design.
+use crate::{AxiUartlite, FIFO_DEPTH, FifoState, RxErrorsCounted, registers::RegisterAccess};
+
+/// On-target test failure.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HilError {
+    /// The hardware did not reach the expected state in time.
+    Timeout,
+    /// The FIFOs were not empty after a FIFO reset.
+    FifoNotReset(FifoState),
+    /// The interrupt enable bit did not reflect the written value.
+    InterruptEnableNotReflected,
+    /// Unexpected byte received in the loopback test.
+    Mismatch {
+        /// Index of the byte.
+        idx: usize,
+        /// Sent byte.
+        expected: u8,
+        /// Received byte.
+        actual: u8,
+    },
+    /// RX errors occurred.
+    RxErrors(RxErrorsCounted),
+}
+
+impl core::fmt::Display for HilError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HilError::Timeout => write!(f, "timeout while polling the hardware"),
+            HilError::FifoNotReset(state) => {
+                write!(f, "FIFOs not empty after a FIFO reset: {state:?}")
+            }
+            HilError::InterruptEnableNotReflected => {
+                write!(f, "interrupt enable bit does not reflect the written value")
+            }
+            HilError::Mismatch {
+                idx,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "loopback mismatch at index {idx}: expected {expected:#04x}, got {actual:#04x}"
+            ),
+            HilError::RxErrors(errors) => write!(f, "RX errors occurred: {errors:?}"),
+        }
+    }
+}
+
+impl core::error::Error for HilError {}
+
+fn fifo_state<R: RegisterAccess>(uart: &mut AxiUartlite<R>) -> Result<FifoState, HilError> {
+    let state = uart.fifo_state();
+    if state.errors.is_some() {
+        return Err(HilError::RxErrors(uart.read_and_clear_errors()));
+    }
+    Ok(state)
+}
+
+fn poll_until<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+    max_polls: u32,
+    mut cond: impl FnMut(&FifoState) -> bool,
+) -> Result<(), HilError> {
+    for _ in 0..max_polls {
+        if cond(&fifo_state(uart)?) {
+            return Ok(());
+        }
+    }
+    Err(HilError::Timeout)
+}
+
+/// Check that a FIFO reset empties both FIFOs.
+///
+/// The TX FIFO is filled first, so the reset must discard data which was not sent yet.
+pub fn check_fifo_reset<R: RegisterAccess>(uart: &mut AxiUartlite<R>) -> Result<(), HilError> {
+    uart.force_reset();
+    for _ in 0..FIFO_DEPTH {
+        if uart.write_fifo(0x55).is_err() {
+            break;
+        }
+    }
+    uart.force_reset();
+    let state = uart.fifo_state();
+    if !state.tx_empty || state.tx_full || state.rx_valid_data || state.rx_full {
+        return Err(HilError::FifoNotReset(state));
+    }
+    Ok(())
+}
+
+/// Fill the TX FIFO and check that it drains completely.
+pub fn check_tx_drain<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+    max_polls: u32,
+) -> Result<(), HilError> {
+    uart.force_reset();
+    for idx in 0..FIFO_DEPTH {
+        let Ok(()) = nb::block!(uart.write_fifo(b'0' + (idx % 10) as u8));
+    }
+    poll_until(uart, max_polls, |state| state.tx_empty)
+}
+
+/// Send the data and check that it is received again.
+///
+/// This requires the TX line to be wired to the RX line.
+pub fn check_loopback<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+    data: &[u8],
+    max_polls: u32,
+) -> Result<(), HilError> {
+    uart.force_reset();
+    for (idx, &expected) in data.iter().enumerate() {
+        let Ok(()) = nb::block!(uart.write_fifo(expected));
+        poll_until(uart, max_polls, |state| state.rx_valid_data)?;
+        let Ok(actual) = nb::block!(uart.read_fifo());
+        if actual != expected {
+            return Err(HilError::Mismatch {
+                idx,
+                expected,
+                actual,
+            });
+        }
+    }
+    let errors = uart.read_and_clear_errors();
+    if errors.has_errors() {
+        return Err(HilError::RxErrors(errors));
+    }
+    Ok(())
+}
+
+/// Check that the interrupt enable bit of the status register follows the control register.
+///
+/// Interrupts are disabled when the routine returns.
+pub fn check_interrupt_enable<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+) -> Result<(), HilError> {
+    uart.enable_interrupt();
+    let enabled = fifo_state(uart)?.intr_enabled;
+    uart.disable_interrupt();
+    let disabled = !fifo_state(uart)?.intr_enabled;
+    if !enabled || !disabled {
+        return Err(HilError::InterruptEnableNotReflected);
+    }
+    Ok(())
+}
+
+/// Check that the TX FIFO empty interrupt is raised after a byte was sent.
+///
+/// `pending` must return whether the interrupt of the UART Lite is pending, for example by
+/// reading the interrupt status register of the interrupt controller or a flag which is set by
+/// the interrupt handler. Interrupts are disabled when the routine returns.
+pub fn check_tx_interrupt<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+    max_polls: u32,
+    mut pending: impl FnMut() -> bool,
+) -> Result<(), HilError> {
+    uart.force_reset();
+    uart.enable_interrupt();
+    let Ok(()) = nb::block!(uart.write_fifo(0x00));
+    let result = (0..max_polls)
+        .any(|_| pending())
+        .then_some(())
+        .ok_or(HilError::Timeout);
+    uart.disable_interrupt();
+    result
+}
@@ -0,0 +1,114 @@
+//! # Trace hooks
+//!
+//! This module is only available with the `hooks` feature. A [UartHooks] implementation can be
+//! registered once with [set_hooks], and all drivers call it for every byte which is written
+//! into the TX FIFO or read from the RX FIFO, for RX errors and for serviced interrupts. This
+//! allows lightweight tracing and profiling, for example toggling a GPIO or recording a cycle
+//! counter timestamp, without forking the crate.
+//!
+//! The hooks are called from the context of the driver call, which includes interrupt
+//! handlers, so they should return quickly.
+//!
+//! # Example
+//!
+//! ```
+//! use axi_uartlite::{RxErrors, UartHooks, set_hooks};
+//!
+//! struct Tracer;
+//!
+//! impl UartHooks for Tracer {
+//!     fn on_tx_byte(&self, byte: u8) {
+//!         // Record the byte in a trace buffer.
+//!     }
+//! }
+//!
+//! static TRACER: Tracer = Tracer;
+//!
+//! set_hooks(&TRACER).unwrap();
+//! ```
+use core::cell::UnsafeCell;
+
+use crate::{
+    RxErrors,
+    atomic::{AtomicU8, Ordering},
+};
+
+/// Trace hooks which are called by the drivers.
+///
+/// All methods have an empty default implementation.
+pub trait UartHooks: Sync {
+    /// A byte was written into the TX FIFO.
+    fn on_tx_byte(&self, _byte: u8) {}
+
+    /// A byte was read from the RX FIFO.
+    fn on_rx_byte(&self, _byte: u8) {}
+
+    /// RX errors were reported by the status register.
+    fn on_error(&self, _errors: RxErrors) {}
+
+    /// An interrupt was serviced by one of the interrupt handler functions of this crate.
+    fn on_interrupt(&self) {}
+}
+
+/// The hooks were already registered.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SetHooksError;
+
+impl core::fmt::Display for SetHooksError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "trace hooks were already registered")
+    }
+}
+
+impl core::error::Error for SetHooksError {}
+
+const UNINITIALIZED: u8 = 0;
+const INITIALIZING: u8 = 1;
+const INITIALIZED: u8 = 2;
+
+struct HooksCell(UnsafeCell<Option<&'static dyn UartHooks>>);
+
+// Safety: The cell is only written once by [set_hooks] before the state is set to
+// [INITIALIZED], and only read after that.
+unsafe impl Sync for HooksCell {}
+
+static STATE: AtomicU8 = AtomicU8::new(UNINITIALIZED);
+static HOOKS: HooksCell = HooksCell(UnsafeCell::new(None));
+
+/// Register the trace hooks.
+///
+/// The hooks can only be registered once.
+pub fn set_hooks(hooks: &'static dyn UartHooks) -> Result<(), SetHooksError> {
+    if STATE
+        .compare_exchange(
+            UNINITIALIZED,
+            INITIALIZING,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        )
+        .is_err()
+    {
+        return Err(SetHooksError);
+    }
+    // Safety: Only one caller can reach this point, and the cell is not read before the state
+    // is set to INITIALIZED.
+    unsafe { *HOOKS.0.get() = Some(hooks) };
+    STATE.store(INITIALIZED, Ordering::Release);
+    Ok(())
+}
+
+/// Registered trace hooks.
+pub fn hooks() -> Option<&'static dyn UartHooks> {
+    if STATE.load(Ordering::Acquire) != INITIALIZED {
+        return None;
+    }
+    // Safety: The cell is not written anymore once the state is INITIALIZED.
+    unsafe { *HOOKS.0.get() }
+}
+
+#[inline(always)]
+pub(crate) fn call(f: impl FnOnce(&dyn UartHooks)) {
+    if let Some(hooks) = hooks() {
+        f(hooks);
+    }
+}
@@ -0,0 +1,112 @@
+//! # Hardware configuration
+//!
+//! The baud rate, data bits and parity of the AXI UART Lite are fixed when the IP core is
+//! synthesized. The [HwConfig] structure describes these parameters and provides timing
+//! helpers for protocols and APIs which depend on the character time.
+//!
+//! All constructors and helpers are `const`, so the configuration can be supplied as a
+//! constant matching the synthesis parameters:
+//!
+//! ```
+//! use axi_uartlite::{DataBits, HwConfig, Parity};
+//!
+//! const HW_CONFIG: HwConfig = HwConfig::new(115200, DataBits::Eight, Parity::None);
+//! const FIFO_DRAIN_TIME_US: u64 = HW_CONFIG.fifo_drain_time_us();
+//!
+//! assert_eq!(HW_CONFIG.char_time_us(), 87);
+//! assert_eq!(FIFO_DRAIN_TIME_US, 1389);
+//! ```
+
+use crate::FIFO_DEPTH;
+
+/// Number of data bits configured for the IP core.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DataBits {
+    /// 5 data bits.
+    Five = 5,
+    /// 6 data bits.
+    Six = 6,
+    /// 7 data bits.
+    Seven = 7,
+    /// 8 data bits.
+    Eight = 8,
+}
+
+/// Parity configured for the IP core.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// Even parity.
+    Even,
+    /// Odd parity.
+    Odd,
+}
+
+/// Synthesis parameters of the AXI UART Lite IP core.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HwConfig {
+    /// Baud rate.
+    pub baud_rate: u32,
+    /// Data bits.
+    pub data_bits: DataBits,
+    /// Parity.
+    pub parity: Parity,
+}
+
+impl HwConfig {
+    /// Create a new hardware configuration.
+    pub const fn new(baud_rate: u32, data_bits: DataBits, parity: Parity) -> Self {
+        Self {
+            baud_rate,
+            data_bits,
+            parity,
+        }
+    }
+
+    /// Number of bits per character on the wire, including the start bit, the parity bit
+    /// and the single stop bit.
+    pub const fn bits_per_char(&self) -> u32 {
+        let parity_bits = match self.parity {
+            Parity::None => 0,
+            Parity::Even | Parity::Odd => 1,
+        };
+        1 + self.data_bits as u32 + parity_bits + 1
+    }
+
+    /// Time required to transmit one character in nanoseconds, rounded up.
+    pub const fn char_time_ns(&self) -> u64 {
+        self.chars_time_ns(1)
+    }
+
+    /// Time required to transmit the given number of characters in nanoseconds, rounded up.
+    pub const fn chars_time_ns(&self, chars: u32) -> u64 {
+        (self.bits_per_char() as u64 * chars as u64 * 1_000_000_000).div_ceil(self.baud_rate as u64)
+    }
+
+    /// Time required to transmit one character in microseconds, rounded up.
+    pub const fn char_time_us(&self) -> u64 {
+        self.chars_time_us(1)
+    }
+
+    /// Time required to transmit the given number of characters in microseconds, rounded up.
+    pub const fn chars_time_us(&self, chars: u32) -> u64 {
+        (self.bits_per_char() as u64 * chars as u64 * 1_000_000).div_ceil(self.baud_rate as u64)
+    }
+
+    /// Time required to transmit a completely filled TX FIFO in microseconds, rounded up.
+    ///
+    /// This is the upper bound for the time until the TX FIFO is empty after a write, and can
+    /// be used as the timeout for flush operations. It is also the time after which a full RX
+    /// FIFO overflows if it is not read.
+    pub const fn fifo_drain_time_us(&self) -> u64 {
+        self.chars_time_us(FIFO_DEPTH as u32)
+    }
+
+    /// Number of timer ticks required to transmit the given number of characters, rounded up.
+    ///
+    /// This can be used for idle detection with a timer running at `tick_hz`.
+    pub const fn chars_time_ticks(&self, chars: u32, tick_hz: u64) -> u64 {
+        (self.bits_per_char() as u64 * chars as u64 * tick_hz).div_ceil(self.baud_rate as u64)
+    }
+}
@@ -0,0 +1,147 @@
+//! # Idle-line detection
+//!
+//! The UART Lite has no receiver timeout, so frame-gap based protocols have to detect idle
+//! lines in software. The [IdleDetector] tracks the time since the last received byte with a
+//! user-provided tick source and reports when the line has been idle for a configurable number
+//! of character times. The idle event is reported once per burst of received bytes.
+//!
+//! Protocols which delimit frames by a gap on the line can use
+//! [IdleDetector::read_frame_until_idle] to receive a complete frame with a single blocking call.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut idle = IdleDetector::new(&HW_CONFIG, 3, TICK_HZ, || timer.now());
+//! loop {
+//!     idle.service(&mut rx, |byte| frame.push(byte), || handle_frame(&mut frame));
+//! }
+//! ```
+use crate::{HwConfig, Rx, registers::RegisterAccess};
+
+/// Received frame did not fit into the passed buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IdleFrameTooLarge {
+    /// Length of the received frame. Only the first bytes up to the buffer length were stored.
+    pub len: usize,
+}
+
+impl core::fmt::Display for IdleFrameTooLarge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "frame length {} exceeds receive buffer", self.len)
+    }
+}
+
+impl core::error::Error for IdleFrameTooLarge {}
+
+/// Idle-line detector using the passed closure as the tick source.
+///
+/// The tick counter is expected to be monotonic, wrapping is handled.
+pub struct IdleDetector<F: FnMut() -> u64> {
+    now: F,
+    idle_ticks: u64,
+    last_rx_tick: u64,
+    active: bool,
+}
+
+impl<F: FnMut() -> u64> IdleDetector<F> {
+    /// Create a new idle detector which reports an idle line after `chars` character times for
+    /// the given hardware configuration and a tick source with the given frequency.
+    pub fn new(hw_config: &HwConfig, chars: u32, tick_hz: u64, now: F) -> Self {
+        Self::with_idle_ticks(hw_config.chars_time_ticks(chars, tick_hz), now)
+    }
+
+    /// Create a new idle detector with an idle time in ticks.
+    pub const fn with_idle_ticks(idle_ticks: u64, now: F) -> Self {
+        Self {
+            now,
+            idle_ticks,
+            last_rx_tick: 0,
+            active: false,
+        }
+    }
+
+    /// Idle time in ticks.
+    #[inline]
+    pub const fn idle_ticks(&self) -> u64 {
+        self.idle_ticks
+    }
+
+    /// Record the reception of a byte at the current tick.
+    pub fn on_byte(&mut self) {
+        self.last_rx_tick = (self.now)();
+        self.active = true;
+    }
+
+    /// Ticks since the last received byte, or [None] if no byte was received since the last
+    /// idle event.
+    pub fn ticks_since_last_byte(&mut self) -> Option<u64> {
+        if !self.active {
+            return None;
+        }
+        Some((self.now)().wrapping_sub(self.last_rx_tick))
+    }
+
+    /// Check whether the line became idle.
+    ///
+    /// Returns true once if the idle time elapsed after the last received byte.
+    pub fn poll(&mut self) -> bool {
+        match self.ticks_since_last_byte() {
+            Some(ticks) if ticks >= self.idle_ticks => {
+                self.active = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drain the RX FIFO, passing every received byte to `on_byte`, and call `on_idle` if the
+    /// line became idle.
+    ///
+    /// Returns true if the line became idle.
+    pub fn service<R: RegisterAccess>(
+        &mut self,
+        rx: &mut Rx<R>,
+        mut on_byte: impl FnMut(u8),
+        on_idle: impl FnOnce(),
+    ) -> bool {
+        while let Ok(byte) = rx.read_fifo() {
+            self.on_byte();
+            on_byte(byte);
+        }
+        if self.poll() {
+            on_idle();
+            return true;
+        }
+        false
+    }
+    /// Blocking read of a frame which is delimited by an idle line.
+    ///
+    /// Waits for the first byte, stores the received bytes in the buffer and returns the
+    /// frame length once the line has been idle for the configured idle time. Bytes which do
+    /// not fit into the buffer are discarded until the line becomes idle, so the next call
+    /// starts at the next frame.
+    pub fn read_frame_until_idle<R: RegisterAccess>(
+        &mut self,
+        rx: &mut Rx<R>,
+        buf: &mut [u8],
+    ) -> Result<usize, IdleFrameTooLarge> {
+        let mut len = 0;
+        loop {
+            while let Ok(byte) = rx.read_fifo() {
+                self.on_byte();
+                if let Some(slot) = buf.get_mut(len) {
+                    *slot = byte;
+                }
+                len += 1;
+            }
+            if len > 0 && self.poll() {
+                break;
+            }
+        }
+        if len > buf.len() {
+            return Err(IdleFrameTooLarge { len });
+        }
+        Ok(len)
+    }
+}
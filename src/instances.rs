@@ -0,0 +1,99 @@
+//! # Peripheral singletons
+//!
+//! This module provides the [crate::uartlite_instances] macro, which generates a
+//! `Peripherals`-style structure for all UART Lite instances of a design from a single
+//! declaration of the instance names and base addresses. The generated `take` function returns
+//! the driver handles only once, so the safe API can not create two drivers for the same
+//! peripheral.
+//!
+//! The handles use the zero-sized [crate::registers::FixedMmio] backend, so each instance has
+//! its own type and the base addresses are compile-time constants.
+//!
+//! # Example
+//!
+//! ```
+//! axi_uartlite::uartlite_instances! {
+//!     /// UART Lite instances of the design.
+//!     pub struct Peripherals {
+//!         /// Debug console.
+//!         CONSOLE: 0x4060_0000,
+//!         TELEMETRY: 0x4061_0000,
+//!     }
+//! }
+//!
+//! let peripherals = Peripherals::take().unwrap();
+//! let (_tx, _rx) = peripherals.CONSOLE.split();
+//! assert!(Peripherals::take().is_none());
+//! ```
+use crate::atomic::{AtomicBool, Ordering};
+
+/// Flag which tracks whether the instances generated by [crate::uartlite_instances] were
+/// taken. This is an implementation detail of the macro.
+#[doc(hidden)]
+pub struct TakenFlag(AtomicBool);
+
+impl TakenFlag {
+    #[doc(hidden)]
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    /// Mark the flag as taken. Returns [true] if it was not taken before.
+    #[doc(hidden)]
+    pub fn take(&self) -> bool {
+        !self.0.swap(true, Ordering::AcqRel)
+    }
+}
+
+impl Default for TakenFlag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generate a structure with singleton-checked driver handles for all UART Lite instances.
+///
+/// See the [module documentation](crate::instances) for details.
+#[macro_export]
+macro_rules! uartlite_instances {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_attr:meta])* $instance:ident: $base_addr:expr),+ $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[allow(non_snake_case)]
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                pub $instance: $crate::AxiUartliteAt<{ $base_addr }>,
+            )+
+        }
+
+        impl $name {
+            /// Take the driver handles. Returns [None] if the handles were already taken.
+            pub fn take() -> Option<Self> {
+                static TAKEN: $crate::instances::TakenFlag = $crate::instances::TakenFlag::new();
+                if !TAKEN.take() {
+                    return None;
+                }
+                // Safety: The handles are only created once.
+                Some(unsafe { Self::steal() })
+            }
+
+            /// Create the driver handles without checking whether they were already taken.
+            ///
+            /// # Safety
+            ///
+            /// The same safety rules as for `AxiUartlite::new` apply.
+            pub unsafe fn steal() -> Self {
+                Self {
+                    $(
+                        $instance: unsafe { $crate::AxiUartlite::new_fixed() },
+                    )+
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,154 @@
+//! # AXI INTC companion helpers
+//!
+//! Many designs route the UART Lite interrupt through the AMD AXI Interrupt Controller
+//! (AXI INTC). The UART Lite interrupt is an edge-type interrupt, so the interrupt input of the
+//! INTC has to be acknowledged **before** the UART Lite is serviced. Otherwise, an edge which
+//! occurs while the FIFOs are serviced is cleared by the late acknowledge and the interrupt is
+//! lost, which usually results in stalled transfers.
+//!
+//! The [IntcLine] type describes the INTC input the UART Lite interrupt is connected to, and
+//! [IntcLine::on_interrupt] runs the driver interrupt handling in the correct order. The
+//! [InterruptAck] trait can be implemented for other interrupt controllers.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut intc = unsafe { IntcRegisters::new_mmio_at(INTC_BASE) };
+//! intc.enable_master();
+//! let line = IntcLine::new(UARTLITE_INTC_INPUT);
+//! line.enable(&mut intc);
+//!
+//! // Interrupt handler.
+//! let mut intc = unsafe { IntcRegisters::new_mmio_at(INTC_BASE) };
+//! let mut tx = unsafe { Tx::steal(UARTLITE_BASE) };
+//! UARTLITE_LINE.on_interrupt(&mut intc, || on_interrupt_tx(&mut tx, 0));
+//! ```
+
+/// AXI INTC register block definition.
+#[derive(derive_mmio::Mmio)]
+#[repr(C)]
+pub struct IntcRegisters {
+    /// Interrupt status register.
+    #[mmio(PureRead)]
+    isr: u32,
+    /// Interrupt pending register.
+    #[mmio(PureRead)]
+    ipr: u32,
+    /// Interrupt enable register.
+    ier: u32,
+    /// Interrupt acknowledge register.
+    #[mmio(Write)]
+    iar: u32,
+    /// Set interrupt enables register.
+    #[mmio(Write)]
+    sie: u32,
+    /// Clear interrupt enables register.
+    #[mmio(Write)]
+    cie: u32,
+    /// Interrupt vector register.
+    #[mmio(PureRead)]
+    ivr: u32,
+    /// Master enable register.
+    mer: u32,
+}
+
+const _: () = assert!(core::mem::offset_of!(IntcRegisters, iar) == 0x0C);
+const _: () = assert!(core::mem::offset_of!(IntcRegisters, mer) == 0x1C);
+
+/// AXI INTC register block.
+pub type AxiIntc = MmioIntcRegisters<'static>;
+
+impl MmioIntcRegisters<'_> {
+    /// Enable the IRQ output of the INTC by setting the master enable and the hardware
+    /// interrupt enable bit.
+    pub fn enable_master(&mut self) {
+        self.write_mer(0b11);
+    }
+}
+
+/// Interrupt acknowledge hook for the interrupt controller the UART Lite is connected to.
+pub trait InterruptAck {
+    /// Acknowledge the UART Lite interrupt at the interrupt controller.
+    fn acknowledge(&mut self);
+}
+
+/// AXI INTC input the UART Lite interrupt is connected to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IntcLine {
+    mask: u32,
+}
+
+impl IntcLine {
+    /// Create a new INTC line for the given interrupt input number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input number is larger than 31.
+    pub const fn new(input: u8) -> Self {
+        assert!(input < 32, "invalid AXI INTC input");
+        Self { mask: 1 << input }
+    }
+
+    /// Bit mask of the interrupt input.
+    pub const fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Enable the interrupt input at the INTC.
+    pub fn enable(&self, intc: &mut MmioIntcRegisters<'_>) {
+        intc.write_sie(self.mask);
+    }
+
+    /// Disable the interrupt input at the INTC.
+    pub fn disable(&self, intc: &mut MmioIntcRegisters<'_>) {
+        intc.write_cie(self.mask);
+    }
+
+    /// Is the interrupt pending at the INTC?
+    pub fn is_pending(&self, intc: &MmioIntcRegisters<'_>) -> bool {
+        intc.read_ipr() & self.mask != 0
+    }
+
+    /// Acknowledge the interrupt input at the INTC.
+    pub fn acknowledge(&self, intc: &mut MmioIntcRegisters<'_>) {
+        intc.write_iar(self.mask);
+    }
+
+    /// Acknowledge the interrupt input at the INTC and run the UART Lite interrupt handling
+    /// afterwards, which is the required order for the edge-type UART Lite interrupt.
+    pub fn on_interrupt<T>(
+        &self,
+        intc: &mut MmioIntcRegisters<'_>,
+        handler: impl FnOnce() -> T,
+    ) -> T {
+        self.acknowledge(intc);
+        handler()
+    }
+
+    /// Bind the line to an INTC register block to create an [InterruptAck] hook.
+    pub fn bind<'intc, 'regs>(
+        &self,
+        intc: &'intc mut MmioIntcRegisters<'regs>,
+    ) -> BoundIntcLine<'intc, 'regs> {
+        BoundIntcLine { line: *self, intc }
+    }
+}
+
+/// [IntcLine] bound to an INTC register block.
+pub struct BoundIntcLine<'intc, 'regs> {
+    line: IntcLine,
+    intc: &'intc mut MmioIntcRegisters<'regs>,
+}
+
+impl InterruptAck for BoundIntcLine<'_, '_> {
+    fn acknowledge(&mut self) {
+        self.line.acknowledge(self.intc);
+    }
+}
+
+/// Acknowledge the interrupt with the passed hook and run the UART Lite interrupt handling
+/// afterwards.
+pub fn on_interrupt_with_ack<T>(ack: &mut impl InterruptAck, handler: impl FnOnce() -> T) -> T {
+    ack.acknowledge();
+    handler()
+}
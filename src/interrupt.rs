@@ -0,0 +1,72 @@
+//! # Interrupt handler binding
+//!
+//! This module provides the [crate::bind_uartlite_interrupts] macro, which generates the
+//! interrupt handler functions for one or more UART Lite instances from a compact declaration,
+//! similar to the `bind_interrupts!` macro of Embassy. The generated handlers steal the
+//! required [crate::Tx] and [crate::Rx] handles for the given base address and call the
+//! interrupt handler functions of the driver with the correct waker slot.
+//!
+//! Two handler variants are supported:
+//!
+//! - `waker_slot` services the asynchronous TX engine with [crate::on_interrupt_tx]. An
+//!   optional `rx` handler is called with the bytes read from the RX FIFO.
+//! - `buffered` calls [crate::BufferedState::on_interrupt] for the given buffered state.
+//!
+//! The attributes in front of each handler are forwarded, so the platform specific interrupt
+//! attribute or an `extern "C"` export can be added by the user.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static BUFFERS: BufferedState<256, 256> = BufferedState::new();
+//!
+//! axi_uartlite::bind_uartlite_interrupts! {
+//!     #[interrupt]
+//!     fn UARTLITE0 {
+//!         base_addr: UARTLITE0_BASE,
+//!         waker_slot: 0,
+//!         rx: |data: &[u8]| RX_PIPE.try_write(data).ok(),
+//!     }
+//!
+//!     #[interrupt]
+//!     fn UARTLITE1 {
+//!         base_addr: UARTLITE1_BASE,
+//!         buffered: BUFFERS,
+//!     }
+//! }
+//! ```
+
+/// Generate UART Lite interrupt handler functions.
+///
+/// See the [module documentation](crate::interrupt) for details.
+#[macro_export]
+macro_rules! bind_uartlite_interrupts {
+    (@body base_addr: $base_addr:expr, waker_slot: $waker_slot:expr $(, rx: $rx_handler:expr)? $(,)?) => {
+        // Safety: The interrupt handler only uses interrupt specific API.
+        let mut tx = unsafe { $crate::Tx::steal($base_addr as usize) };
+        $crate::on_interrupt_tx(&mut tx, $waker_slot);
+        $(
+            let mut rx = unsafe { $crate::Rx::steal($base_addr as usize) };
+            let mut buf = [0; $crate::FIFO_DEPTH];
+            let read = rx.on_interrupt_rx(&mut buf);
+            if read > 0 {
+                ($rx_handler)(&buf[0..read]);
+            }
+        )?
+    };
+    (@body base_addr: $base_addr:expr, buffered: $state:expr $(,)?) => {
+        // Safety: The interrupt handler only uses interrupt specific API.
+        let mut tx = unsafe { $crate::Tx::steal($base_addr as usize) };
+        let mut rx = unsafe { $crate::Rx::steal($base_addr as usize) };
+        $state.on_interrupt(&mut tx, &mut rx);
+    };
+    ($($(#[$attr:meta])* $vis:vis fn $name:ident { $($body:tt)* })+) => {
+        $(
+            $(#[$attr])*
+            #[allow(non_snake_case)]
+            $vis fn $name() {
+                $crate::bind_uartlite_interrupts!(@body $($body)*);
+            }
+        )+
+    };
+}
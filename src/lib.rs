@@ -4,6 +4,32 @@
 //!
 //! # Features
 //!
+//! The `async` feature, which is enabled by default, enables the asynchronous TX and RX support
+//! of the [tx_async] and [rx_async] modules and all other modules which require the
+//! `embassy-sync` and `critical-section` dependencies: [buffered], [deferred_log],
+//! [frame_pool], [print], [interrupt], [segment_tx] and [shared_async]. Together with the
+//! `embedded-io` feature, it also enables the [frame_channel] module. The buffered driver requires the `framing` and
+//! `flow-control` features, which are enabled by the `async` feature.
+//! Purely blocking users can disable the default features for a smaller dependency tree.
+//!
+//! The protocol and utility modules are optional as well. The default `protocols` feature
+//! enables the `ccsds`, `framing`, `hex-records`, `modbus`, `mux` and `xmodem` features, and
+//! the `crc` feature which is used by some of them. The default `utils` feature enables the
+//! `baud-check`, `bridge`, `console`, `flow-control`, `idle`, `ping-pong`, `pool`, `prbs`,
+//! `rx-pump`, `transfer`, `utf8` and `vt100` features. Each feature enables the module of the
+//! same name. The most important types of these modules are also re-exported at the crate root.
+//! With `default-features = false`, only the blocking driver core is compiled: the [registers],
+//! [tx], [rx], [hw_config], [status], [traits], [probe], [panic] and [instances] modules, and
+//! the `diagnostics` module unless the `tiny` feature is enabled.
+//!
+//! The `embedded-hal-nb` and `embedded-io` features are enabled by default and provide the
+//! [embedded_hal_nb::serial] traits and the [embedded_io] and [embedded_io_async] traits
+//! respectively. The reader and writer helpers of the [framing], [hex_records], [mux] and [utf8]
+//! modules also require the `embedded-io` feature.
+//!
+//! The `eh02` feature implements the legacy `embedded-hal` 0.2 `serial::Read` and
+//! `serial::Write` traits for [Tx], [Rx] and [AxiUartlite] to support older ecosystems.
+//!
 //! If asynchronous TX operations are used, the number of wakers  which defaults to 1 waker can
 //! also be configured. The [tx_async] module provides more details on the meaning of this number.
 //!
@@ -13,12 +39,153 @@
 //! - `8-wakers`
 //! - `16-wakers`
 //! - `32-wakers`
+//!
+//! The `instance-tracking` feature enables the [tracking] module, which can be used to detect
+//! multiple driver instances for the same base address.
+//!
+//! The `bbqueue` feature enables the [bbq] module, which allows moving RX data into a
+//! [bbqueue](https://docs.rs/bbqueue) buffer from the interrupt handler.
+//!
+//! The `heapless` feature adds [Rx::drain_into] and [Tx::refill_from] to connect the driver to
+//! [heapless::spsc] queues.
+//!
+//! The `defmt-logger` feature enables the [defmt_logger] module, which provides a defmt global
+//! logger using the UART Lite as the transport.
+//!
+//! The `global-uart` feature enables the [global_uart] module, which provides the [uprint] and
+//! [uprintln] macros printing through a replaceable global TX handle locked with a critical
+//! section.
+//!
+//! The `log` feature enables the [logger] module, which provides a [log] crate backend.
+//!
+//! The `mock` feature enables the [mock] module, which provides a software model of the register
+//! block for testing the driver logic on the host.
+//!
+//! The `axi-intc` feature enables the [intc] module, which provides helpers to acknowledge the
+//! UART Lite interrupt at an AXI INTC in the correct order.
+//!
+//! The `mmio-barriers` feature emits a memory barrier after control register writes and before
+//! status register reads, see [registers::mmio_barrier].
+//!
+//! The `swap-register-bytes` feature swaps the bytes of all register accesses, which is required
+//! for big-endian cores attached to the AXI interconnect without byte lane swapping. All
+//! registers are accessed as 32-bit words, so no swapping is required for big-endian cores if
+//! the interconnect performs the byte lane mapping.
+//!
+//! The `portable-atomic` feature uses the
+//! [portable-atomic](https://docs.rs/portable-atomic) crate for all atomic operations, which is
+//! required for targets without atomic instructions. One of the `portable-atomic` features
+//! `critical-section` or `unsafe-assume-single-core` has to be enabled by the user.
+//!
+//! The `rtic` feature enables the [rtic] module, which provides wrapper types for RTIC 2
+//! applications.
+//!
+//! The `alloc` feature enables the [owned] module, which adds read methods returning owned
+//! `Vec` and `String` buffers, and `Vec` based encoding and frame reading functions to the
+//! [framing] codecs. It is enabled by the `std` feature.
+//!
+//! The `std` feature enables the [userspace] module, which allows using the driver from Linux
+//! userspace with a mapped register block. Together with the `mock` feature, it also enables the
+//! [sim] module which connects the software model of the register block to a TCP socket or pty.
+//!
+//! The `build-helper` feature enables the [build_helper] module, which generates Rust constants
+//! for all UART Lite instances from a Vitis `xparameters.h` header inside a build script.
+//!
+//! The `selftest` feature enables the [selftest] module, which contains test routines for test
+//! firmware running on the target or inside an emulator. The `qemu` feature enables the [qemu]
+//! module, which provides a host runner for these routines inside QEMU. The `renode` feature
+//! enables the [renode] module with test firmware helpers and a platform configuration for the
+//! Renode UART Lite model.
+//!
+//! The `test-hooks` feature enables the [test_hooks] module, which adds FIFO state
+//! introspection and forced resets to the drivers. The `hil` feature enables the [hil] module
+//! with on-target test routines for the TX, RX and interrupt paths, which are used by the
+//! `embedded-test` suite in the `hil-tests` directory.
+//!
+//! The `bench` feature enables the [bench] module, which measures the TX throughput, the
+//! interrupt service latency, the bytes serviced per interrupt and the loopback round-trip time
+//! with a user-provided cycle counter.
+//!
+//! The `stats` feature enables the [stats] module, which tracks cumulative byte, FIFO reset,
+//! interrupt and [nb::Error::WouldBlock] counters for long-running health monitoring.
+//!
+//! The `debug-audit` feature enables the [audit] module, which records the last control
+//! register writes of each driver for the `diagnostics` snapshot.
+//!
+//! The `tx-service` feature enables the [tx_service] module, which tops up the TX FIFO from a
+//! queue inside a periodic timer callback for systems without the UART Lite interrupt.
+//!
+//! The `pacing` feature enables the [pacing] module, which inserts configurable inter-byte and
+//! inter-frame gaps for slow legacy devices using an [embedded_hal::delay::DelayNs] provider.
+//!
+//! The `rs485` feature enables the [rs485] module, which drives the DE/RE pin of a half-duplex
+//! RS-485 transceiver around transmissions.
+//!
+//! The `channel` feature enables the [channel] module, which sends and receives typed
+//! [postcard] messages in COBS frames.
+//!
+//! The `upload` feature enables the [upload] module, which receives CRC-checked image chunks
+//! and writes them into an `embedded-storage` NOR flash for in-application firmware updates.
+//!
+//! The `tiny` feature is intended for boot ROMs and tiny MicroBlaze configurations together
+//! with `default-features = false`. It trims exactly the following:
+//!
+//! - The RX error capture of all drivers: [handle_status_reg_errors] always returns [None], so
+//!   no errors are recorded and the [RxErrorsCounted] counters stay at zero.
+//! - The `diagnostics` module together with the `diagnostics` snapshot methods of the drivers.
+//! - The `stats`, `hooks` and `debug-audit` features, which can not be enabled together with it.
+//!
+//! It does not disable any other module. The optional protocol and utility modules are only
+//! excluded by disabling the default features, and have to stay disabled for the smallest
+//! build. The [core::fmt::Debug] implementations are kept, they only end up in the binary if
+//! they are used.
+//!
+//! The `hooks` feature enables the [hooks] module, which allows registering a [UartHooks]
+//! implementation for lightweight tracing and profiling of the driver.
+//!
+//! The `ffi` feature enables the [ffi] module, which exports a C API over the driver for mixed C
+//! and Rust projects. It can not be combined with the `stats`, `hooks` and `debug-audit`
+//! features, because the opaque C driver handle is only sized for the default driver state.
+//!
+//! The `gdbstub` feature enables the [gdb] module, which provides a connection adapter for
+//! the [gdbstub](https://docs.rs/gdbstub) crate.
+//!
+//! The `embedded-cli` feature enables the [cli] module, which provides writer and reader
+//! adapters for the [embedded-cli](https://docs.rs/embedded-cli) crate.
+//!
+//! The `embassy-time` feature enables the [timeout] module, which adds timeouts with driver
+//! cleanup on expiry to the asynchronous driver futures.
 #![no_std]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![deny(missing_docs)]
 
-use core::convert::Infallible;
-use registers::Control;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(all(
+    feature = "tiny",
+    any(feature = "stats", feature = "hooks", feature = "debug-audit")
+))]
+compile_error!(
+    "the `tiny` feature can not be combined with the `stats`, `hooks` or `debug-audit` features"
+);
+
+#[cfg(all(feature = "ffi", feature = "debug-audit"))]
+compile_error!(
+    "the `ffi` feature can not be combined with the `debug-audit` feature, the audit ring does \
+     not fit into the opaque C driver handle"
+);
+
+#[cfg(all(feature = "ffi", any(feature = "stats", feature = "hooks")))]
+compile_error!(
+    "the `ffi` feature can not be combined with the `stats` or `hooks` features, the opaque C \
+     driver handle is only sized for the default driver state"
+);
+
+use core::{convert::Infallible, mem::MaybeUninit};
+use registers::{ControlState, FixedMmio, MmioRegisters, RegisterAccess};
 pub mod registers;
 
 pub mod tx;
@@ -27,14 +194,328 @@ pub use tx::*;
 pub mod rx;
 pub use rx::*;
 
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub mod tx_async;
+#[cfg(feature = "async")]
 pub use tx_async::*;
 
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod rx_async;
+#[cfg(feature = "async")]
+pub use rx_async::*;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod buffered;
+#[cfg(feature = "async")]
+pub use buffered::*;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod shared_async;
+#[cfg(feature = "async")]
+pub use shared_async::*;
+
+#[cfg(feature = "flow-control")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flow-control")))]
+pub mod flow_control;
+#[cfg(feature = "flow-control")]
+pub use flow_control::{XOFF, XON, XonXoff, XonXoffConfig};
+
+#[cfg(feature = "baud-check")]
+#[cfg_attr(docsrs, doc(cfg(feature = "baud-check")))]
+pub mod baud_check;
+#[cfg(feature = "bridge")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bridge")))]
+pub mod bridge;
+#[cfg(feature = "bridge")]
+pub use bridge::{Bridge, BridgeStats, bridge};
+#[cfg(feature = "ccsds")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ccsds")))]
+pub mod ccsds;
+#[cfg(feature = "console")]
+#[cfg_attr(docsrs, doc(cfg(feature = "console")))]
+pub mod console;
+#[cfg(feature = "console")]
+pub use console::{Console, ConsoleInput, ConsoleMode};
+#[cfg(feature = "crc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "crc")))]
+pub mod crc;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod deferred_log;
+#[cfg(feature = "async")]
+pub use deferred_log::*;
+#[cfg(all(feature = "async", feature = "embedded-io"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "async", feature = "embedded-io"))))]
+pub mod frame_channel;
+#[cfg(all(feature = "async", feature = "embedded-io"))]
+pub use frame_channel::*;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod frame_pool;
+#[cfg(feature = "async")]
+pub use frame_pool::*;
+#[cfg(not(feature = "tiny"))]
+pub mod diagnostics;
+#[cfg(not(feature = "tiny"))]
+pub use diagnostics::*;
+#[cfg(feature = "framing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "framing")))]
+pub mod framing;
+#[cfg(feature = "hex-records")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hex-records")))]
+pub mod hex_records;
+pub mod hw_config;
+pub use hw_config::*;
+#[cfg(feature = "idle")]
+#[cfg_attr(docsrs, doc(cfg(feature = "idle")))]
+pub mod idle;
+#[cfg(feature = "idle")]
+pub use idle::{IdleDetector, IdleFrameTooLarge};
+pub mod instances;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod interrupt;
+#[cfg(feature = "modbus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "modbus")))]
+pub mod modbus;
+#[cfg(feature = "mux")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mux")))]
+pub mod mux;
+pub mod panic;
+#[cfg(feature = "ping-pong")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ping-pong")))]
+pub mod ping_pong;
+#[cfg(feature = "ping-pong")]
+pub use ping_pong::{PingPong, PingPongConsumer};
+#[cfg(feature = "pool")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pool")))]
+pub mod pool;
+#[cfg(feature = "pool")]
+pub use pool::{PoolStats, UartPool};
+#[cfg(feature = "prbs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prbs")))]
+pub mod prbs;
+pub mod probe;
+pub use probe::*;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod print;
+#[cfg(feature = "async")]
+pub use print::init_global_tx;
+#[cfg(feature = "async")]
+mod mpsc_ring;
+#[cfg(any(
+    feature = "bridge",
+    feature = "mux",
+    feature = "pool",
+    feature = "rx-pump",
+    feature = "tx-service"
+))]
+// Not every user of the ring buffer needs all of its methods.
+#[allow(dead_code)]
+mod ring;
+#[cfg(feature = "rx-pump")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rx-pump")))]
+pub mod rx_pump;
+#[cfg(feature = "rx-pump")]
+pub use rx_pump::RxPump;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub mod segment_tx;
+#[cfg(feature = "async")]
+pub use segment_tx::*;
+pub mod status;
+pub use status::*;
+pub mod traits;
+pub use traits::*;
+#[cfg(feature = "transfer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transfer")))]
+pub mod transfer;
+#[cfg(feature = "transfer")]
+pub use transfer::Transfer;
+#[cfg(feature = "utf8")]
+#[cfg_attr(docsrs, doc(cfg(feature = "utf8")))]
+pub mod utf8;
+#[cfg(feature = "vt100")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vt100")))]
+pub mod vt100;
+#[cfg(feature = "vt100")]
+pub use vt100::Vt100;
+#[cfg(feature = "xmodem")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xmodem")))]
+pub mod xmodem;
+
+#[cfg(feature = "defmt-logger")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt-logger")))]
+pub mod defmt_logger;
+#[cfg(feature = "defmt-logger")]
+pub use defmt_logger::*;
+
+#[cfg(feature = "global-uart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "global-uart")))]
+pub mod global_uart;
+#[cfg(feature = "global-uart")]
+pub use global_uart::{init_global_uart, release_global_uart, with_global_uart};
+
+#[cfg(feature = "log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+pub mod logger;
+#[cfg(feature = "log")]
+pub use logger::*;
+
+#[cfg(feature = "rtic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rtic")))]
+pub mod rtic;
+
+#[cfg(feature = "axi-intc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axi-intc")))]
+pub mod intc;
+
+#[cfg(feature = "gdbstub")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gdbstub")))]
+pub mod gdb;
+#[cfg(feature = "gdbstub")]
+pub use gdb::*;
+
+#[cfg(feature = "embedded-cli")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-cli")))]
+pub mod cli;
+#[cfg(feature = "embedded-cli")]
+pub use cli::*;
+
+#[cfg(feature = "embassy-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embassy-time")))]
+pub mod timeout;
+
+#[cfg(feature = "bbqueue")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bbqueue")))]
+pub mod bbq;
+#[cfg(feature = "bbqueue")]
+pub use bbq::*;
+
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub mod mock;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod owned;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod userspace;
+
+#[cfg(all(feature = "std", feature = "mock"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "mock"))))]
+pub mod sim;
+
+#[cfg(feature = "selftest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "selftest")))]
+pub mod selftest;
+
+#[cfg(feature = "qemu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "qemu")))]
+pub mod qemu;
+
+#[cfg(feature = "renode")]
+#[cfg_attr(docsrs, doc(cfg(feature = "renode")))]
+pub mod renode;
+
+#[cfg(feature = "test-hooks")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-hooks")))]
+pub mod test_hooks;
+#[cfg(feature = "test-hooks")]
+pub use test_hooks::*;
+
+#[cfg(feature = "hil")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hil")))]
+pub mod hil;
+
+#[cfg(feature = "bench")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bench")))]
+pub mod bench;
+
+#[cfg(feature = "stats")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+pub mod stats;
+#[cfg(feature = "stats")]
+pub use stats::*;
+
+#[cfg(feature = "debug-audit")]
+#[cfg_attr(docsrs, doc(cfg(feature = "debug-audit")))]
+pub mod audit;
+#[cfg(feature = "debug-audit")]
+pub use audit::*;
+
+#[cfg(feature = "tx-service")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tx-service")))]
+pub mod tx_service;
+#[cfg(feature = "tx-service")]
+pub use tx_service::*;
+
+#[cfg(feature = "pacing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pacing")))]
+pub mod pacing;
+#[cfg(feature = "pacing")]
+pub use pacing::*;
+
+#[cfg(feature = "rs485")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rs485")))]
+pub mod rs485;
+#[cfg(feature = "rs485")]
+pub use rs485::*;
+
+#[cfg(feature = "channel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "channel")))]
+pub mod channel;
+#[cfg(feature = "channel")]
+pub use channel::*;
+
+#[cfg(feature = "upload")]
+#[cfg_attr(docsrs, doc(cfg(feature = "upload")))]
+pub mod upload;
+#[cfg(feature = "upload")]
+pub use upload::*;
+
+#[cfg(feature = "hooks")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hooks")))]
+pub mod hooks;
+#[cfg(feature = "hooks")]
+pub use hooks::*;
+
+#[cfg(feature = "ffi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ffi")))]
+pub mod ffi;
+
+#[cfg(feature = "build-helper")]
+#[cfg_attr(docsrs, doc(cfg(feature = "build-helper")))]
+pub mod build_helper;
+
+#[cfg(feature = "instance-tracking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "instance-tracking")))]
+pub mod tracking;
+#[cfg(feature = "instance-tracking")]
+pub use tracking::*;
+
+// Only used by some feature combinations.
+#[cfg(feature = "portable-atomic")]
+#[allow(unused_imports)]
+pub(crate) use portable_atomic as atomic;
+
+#[cfg(not(feature = "portable-atomic"))]
+#[allow(unused_imports)]
+pub(crate) use core::sync::atomic;
+
 /// Maximum FIFO depth of the AXI UART Lite.
 pub const FIFO_DEPTH: usize = 16;
 
 /// RX error structure.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RxErrorsCounted {
     parity: u8,
     frame: u8,
@@ -70,12 +551,29 @@ impl RxErrorsCounted {
     pub fn has_errors(&self) -> bool {
         self.parity > 0 || self.frame > 0 || self.overrun > 0
     }
+
+    /// Increment the error counters for all errors set in the passed error structure.
+    #[inline]
+    pub fn add(&mut self, errors: RxErrors) {
+        if errors.frame() {
+            self.frame = self.frame.saturating_add(1);
+        }
+        if errors.parity() {
+            self.parity = self.parity.saturating_add(1);
+        }
+        if errors.overrun() {
+            self.overrun = self.overrun.saturating_add(1);
+        }
+    }
 }
 
 /// AXI UART Lite peripheral driver.
-pub struct AxiUartlite {
-    rx: Rx,
-    tx: Tx,
+///
+/// The driver is generic over the [RegisterAccess] backend, which defaults to the
+/// memory-mapped register block.
+pub struct AxiUartlite<R = MmioRegisters<'static>> {
+    rx: Rx<R>,
+    tx: Tx<R>,
     errors: RxErrorsCounted,
 }
 
@@ -93,19 +591,125 @@ impl AxiUartlite {
     /// - The driver performs **volatile** reads and writes to the provided address.
     pub const unsafe fn new(base_addr: u32) -> Self {
         let regs = unsafe { registers::Registers::new_mmio_at(base_addr as usize) };
+        Self::new_with_regs(unsafe { regs.clone() }, regs)
+    }
+
+    /// Create a new AXI UART Lite peripheral driver from a raw pointer to the register block.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must point to the register block of an AXI UART Lite peripheral. The same
+    /// safety rules specified in [Self::new] apply.
+    pub const unsafe fn from_ptr(ptr: *mut registers::Registers) -> Self {
+        let regs = unsafe { registers::Registers::new_mmio(ptr) };
+        Self::new_with_regs(unsafe { regs.clone() }, regs)
+    }
+
+    /// Create a new AXI UART Lite peripheral driver from the peripheral singleton of a
+    /// peripheral access crate (PAC), for example one generated by `svd2rust`.
+    ///
+    /// The peripheral is consumed, so the ownership of the peripheral is transferred to the
+    /// driver. The register block is accessed through the address returned by [Deref].
+    /// The size and alignment of the PAC register block are verified at compile time.
+    ///
+    /// [Deref]: core::ops::Deref
+    ///
+    /// # Safety
+    ///
+    /// The PAC register block must model an AXI UART Lite register block, and the same safety
+    /// rules specified in [Self::new] apply.
+    pub unsafe fn from_pac<P: core::ops::Deref<Target = RB>, RB>(peripheral: P) -> Self {
+        const {
+            assert!(core::mem::size_of::<RB>() == core::mem::size_of::<registers::Registers>());
+            assert!(core::mem::align_of::<RB>() >= core::mem::align_of::<registers::Registers>());
+        }
+        let ptr: *const RB = &*peripheral;
+        unsafe { Self::from_ptr(ptr.cast_mut().cast()) }
+    }
+
+    /// Create a new AXI UART Lite peripheral driver and register its base address in the
+    /// instance table.
+    ///
+    /// Returns [InstanceTrackingError::AlreadyExists] if a driver was already created for the
+    /// same base address using this constructor.
+    ///
+    /// # Safety
+    ///
+    /// The same safety rules specified in [Self::new] apply.
+    #[cfg(feature = "instance-tracking")]
+    pub unsafe fn new_tracked(base_addr: u32) -> Result<Self, InstanceTrackingError> {
+        track_instance(base_addr as usize)?;
+        Ok(unsafe { Self::new(base_addr) })
+    }
+}
+
+impl<'mapping> AxiUartlite<MmioRegisters<'mapping>> {
+    /// Create a new AXI UART Lite peripheral driver over a register block inside a temporarily
+    /// mapped region, for example a mapping of a hypervisor guest or a test fixture.
+    ///
+    /// The driver borrows the `mapping` handle, so it can not outlive the mapping. The halves
+    /// created with [Self::split] inherit this lifetime.
+    ///
+    /// # Safety
+    ///
+    /// - The pointer must point to the register block of an AXI UART Lite peripheral which
+    ///   stays mapped as long as `mapping` is borrowed, and must be aligned to 4 bytes.
+    /// - The same safety rules specified in [AxiUartlite::new] apply.
+    pub const unsafe fn new_borrowed<M: ?Sized>(
+        ptr: *mut registers::Registers,
+        mapping: &'mapping M,
+    ) -> Self {
+        let _ = mapping;
+        let regs: MmioRegisters<'mapping> = unsafe { registers::Registers::new_mmio(ptr) };
+        Self::new_with_regs(unsafe { regs.clone() }, regs)
+    }
+
+    /// Raw pointer to the register block, for example for FFI code or debugger scripts.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut registers::Registers {
+        self.tx.as_ptr()
+    }
+}
+
+/// AXI UART Lite driver for a peripheral at the fixed base address `BASE`.
+///
+/// The register backend is the zero-sized [FixedMmio] backend, so no pointer is stored and the
+/// register addresses are compile-time constants.
+pub type AxiUartliteAt<const BASE: usize> = AxiUartlite<FixedMmio<BASE>>;
+
+impl<const BASE: usize> AxiUartlite<FixedMmio<BASE>> {
+    /// Create a new AXI UART Lite peripheral driver for the fixed base address `BASE`.
+    ///
+    /// This is the fixed address variant of [AxiUartlite::new].
+    ///
+    /// # Safety
+    ///
+    /// The same safety rules specified in [AxiUartlite::new] apply.
+    pub const unsafe fn new_fixed() -> Self {
+        unsafe { Self::new_with_regs(FixedMmio::new(), FixedMmio::new()) }
+    }
+
+    /// Raw pointer to the register block at `BASE`.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *mut registers::Registers {
+        self.tx.as_ptr()
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Create a new AXI UART Lite peripheral driver from two handles to the same register
+    /// backend, one for the [Tx] and one for the [Rx] half.
+    pub const fn new_with_regs(tx_regs: R, rx_regs: R) -> Self {
         Self {
-            rx: Rx {
-                regs: unsafe { regs.clone() },
-                errors: None,
-            },
-            tx: Tx { regs, errors: None },
+            rx: Rx::new_with_regs(rx_regs),
+            tx: Tx::new_with_regs(tx_regs),
             errors: RxErrorsCounted::new(),
         }
     }
 
     /// Direct register access.
     #[inline(always)]
-    pub const fn regs(&mut self) -> &mut registers::MmioRegisters<'static> {
+    pub const fn regs(&mut self) -> &mut R {
         &mut self.tx.regs
     }
 
@@ -114,13 +718,21 @@ impl AxiUartlite {
     /// Returns [nb::Error::WouldBlock] if the TX FIFO is full.
     #[inline]
     pub fn write_fifo(&mut self, data: u8) -> nb::Result<(), Infallible> {
-        self.tx.write_fifo(data).unwrap();
-        if let Some(errors) = self.tx.errors {
+        self.tx.write_fifo(data)?;
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
             self.handle_status_reg_errors(errors);
         }
         Ok(())
     }
 
+    /// Write into the UART Lite without capturing RX errors, see [Tx::write_fifo_fast].
+    ///
+    /// Returns [nb::Error::WouldBlock] if the TX FIFO is full.
+    #[inline]
+    pub fn write_fifo_fast(&mut self, data: u8) -> nb::Result<(), Infallible> {
+        self.tx.write_fifo_fast(data)
+    }
+
     /// Write into the FIFO without checking the FIFO fill status.
     ///
     /// This can be useful to completely fill the FIFO if it is known to be empty.
@@ -129,18 +741,176 @@ impl AxiUartlite {
         self.tx.write_fifo_unchecked(data);
     }
 
+    /// Write up to [FIFO_DEPTH] bytes from the slice without checking the FIFO fill status, see
+    /// [Tx::write_fifo_unchecked_slice].
+    ///
+    /// Returns the amount of written data.
+    #[inline]
+    pub fn write_fifo_unchecked_slice(&mut self, buf: &[u8]) -> usize {
+        self.tx.write_fifo_unchecked_slice(buf)
+    }
+
+    /// Write a burst of bytes with a single status register read, see [Tx::write_burst].
+    ///
+    /// Returns the amount of written data.
+    #[inline]
+    pub fn write_burst(&mut self, buf: &[u8]) -> usize {
+        let written = self.tx.write_burst(buf);
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        written
+    }
+
+    /// Write as much of the buffer as possible with at most `max_status_polls` status register
+    /// reads, see [Tx::write_with_budget].
+    ///
+    /// Returns the amount of written data.
+    pub fn write_with_budget(&mut self, buf: &[u8], max_status_polls: u32) -> usize {
+        let written = self.tx.write_with_budget(buf, max_status_polls);
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        written
+    }
+
+    /// Write bytes which are pulled on demand from the passed closure while the TX FIFO has
+    /// space, see [Tx::write_from].
+    pub fn write_from(&mut self, next: impl FnMut() -> Option<u8>) -> usize {
+        let written = self.tx.write_from(next);
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        written
+    }
+
+    /// Blocking flush with a stuck transmitter watchdog, see [Tx::flush_with_watchdog].
+    #[inline]
+    pub fn flush_with_watchdog(
+        &mut self,
+        hw_config: &HwConfig,
+        now: impl FnMut() -> u64,
+        tick_hz: u64,
+    ) -> Result<(), TxStuck> {
+        self.tx.flush_with_watchdog(hw_config, now, tick_hz)
+    }
+
+    /// Blocking flush bounded by the character time, see [Tx::flush_bounded].
+    #[inline]
+    pub fn flush_bounded(
+        &mut self,
+        hw_config: &HwConfig,
+        now: impl FnMut() -> u64,
+        tick_hz: u64,
+    ) -> Result<(), TxStuck> {
+        self.tx.flush_bounded(hw_config, now, tick_hz)
+    }
+
+    /// Blocking write of multiple buffers as a single logical write, see [Tx::write_vectored].
+    ///
+    /// Returns the total number of written bytes.
+    pub fn write_vectored(&mut self, bufs: &[&[u8]]) -> usize {
+        let written = self.tx.write_vectored(bufs);
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        written
+    }
+
+    /// Blocking write of the whole buffer which only returns after the data has left the TX
+    /// pin, see [Tx::write_all_and_drain].
+    pub fn write_all_and_drain(
+        &mut self,
+        buf: &[u8],
+        hw_config: &HwConfig,
+        delay_ns: impl FnMut(u32),
+    ) {
+        self.tx.write_all_and_drain(buf, hw_config, delay_ns);
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+    }
+
     /// Read from the UART Lite.
     ///
     /// Offers a
     #[inline]
     pub fn read_fifo(&mut self) -> nb::Result<u8, Infallible> {
-        let val = self.rx.read_fifo()?;
-        if let Some(errors) = self.rx.errors {
+        let (val, errors) = self.rx.read_fifo_with_errors()?;
+        if let Some(errors) = errors {
             self.handle_status_reg_errors(errors);
         }
         Ok(val)
     }
 
+    /// Copy the bytes which are currently in the RX FIFO into the buffer and return
+    /// immediately, see [Rx::read_nonblocking].
+    ///
+    /// The errors of every read byte are counted.
+    pub fn read_nonblocking(&mut self, buf: &mut [u8]) -> usize {
+        // Safety: The read only writes received bytes into the buffer.
+        self.read_uninit(unsafe { rx::as_uninit(buf) }).len()
+    }
+
+    /// Copy the bytes which are currently in the RX FIFO into the uninitialized buffer and
+    /// return immediately, see [Rx::read_uninit].
+    ///
+    /// The errors of every read byte are counted. Returns the initialized part of the buffer.
+    pub fn read_uninit<'buf>(&mut self, buf: &'buf mut [MaybeUninit<u8>]) -> &'buf [u8] {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.read_fifo() {
+                Ok(byte) => {
+                    buf[read].write(byte);
+                    read += 1;
+                }
+                Err(nb::Error::WouldBlock) => break,
+            }
+        }
+        // Safety: The first `read` bytes were initialized.
+        unsafe { rx::assume_init(buf, read) }
+    }
+
+    /// Blocking read which gives up after `max_status_polls` status register polls, see
+    /// [Rx::read_bounded].
+    pub fn read_bounded(&mut self, buf: &mut [u8], max_status_polls: u32) -> usize {
+        let read = self.rx.read_bounded(buf, max_status_polls);
+        if let Some(errors) = self.rx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        read
+    }
+
+    /// Blocking read which gives up at a deadline of a user-provided clock, see
+    /// [Rx::read_with_deadline].
+    pub fn read_with_deadline(
+        &mut self,
+        buf: &mut [u8],
+        now: impl FnMut() -> u64,
+        deadline_ticks: u64,
+    ) -> usize {
+        let read = self.rx.read_with_deadline(buf, now, deadline_ticks);
+        if let Some(errors) = self.rx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        read
+    }
+
+    /// Blocking read into an uninitialized buffer which gives up at a deadline of a
+    /// user-provided clock, see [Rx::read_with_deadline_uninit].
+    pub fn read_with_deadline_uninit<'buf>(
+        &mut self,
+        buf: &'buf mut [MaybeUninit<u8>],
+        now: impl FnMut() -> u64,
+        deadline_ticks: u64,
+    ) -> &'buf [u8] {
+        let read = self.rx.read_with_deadline_uninit(buf, now, deadline_ticks);
+        if let Some(errors) = self.rx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        read
+    }
+
     /// Read from the FIFO without checking the FIFO fill status.
     #[inline(always)]
     pub fn read_fifo_unchecked(&mut self) -> u8 {
@@ -165,6 +935,18 @@ impl AxiUartlite {
         self.rx.has_data()
     }
 
+    /// Read the status register, for example for a diagnostic dump.
+    ///
+    /// This has side effects: the read clears the RX error bits of the status register. The
+    /// errors are added to the error counters, so they are not lost.
+    pub fn status_snapshot(&mut self) -> registers::Status {
+        let status_reg = self.tx.read_status();
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        status_reg
+    }
+
     /// Read the error counters and also resets them.
     pub fn read_and_clear_errors(&mut self) -> RxErrorsCounted {
         let errors = self.errors;
@@ -174,80 +956,177 @@ impl AxiUartlite {
 
     #[inline(always)]
     fn handle_status_reg_errors(&mut self, errors: RxErrors) {
-        if errors.frame() {
-            self.errors.frame = self.errors.frame.saturating_add(1);
-        }
-        if errors.parity() {
-            self.errors.parity = self.errors.parity.saturating_add(1);
-        }
-        if errors.overrun() {
-            self.errors.overrun = self.errors.overrun.saturating_add(1);
-        }
+        self.errors.add(errors);
     }
 
     /// Reset the RX FIFO.
     #[inline]
     pub fn reset_rx_fifo(&mut self) {
-        self.regs().write_ctrl_reg(
-            Control::builder()
-                .with_enable_interrupt(false)
-                .with_reset_rx_fifo(true)
-                .with_reset_tx_fifo(false)
-                .build(),
-        );
+        let control = self.control_state();
+        self.tx.write_ctrl_reg(control, true, false);
+        #[cfg(feature = "stats")]
+        {
+            self.rx.stats.fifo_resets = self.rx.stats.fifo_resets.wrapping_add(1);
+        }
     }
 
     /// Reset the TX FIFO.
     #[inline]
     pub fn reset_tx_fifo(&mut self) {
-        self.regs().write_ctrl_reg(
-            Control::builder()
-                .with_enable_interrupt(false)
-                .with_reset_rx_fifo(false)
-                .with_reset_tx_fifo(true)
-                .build(),
-        );
+        let control = self.control_state();
+        self.tx.write_ctrl_reg(control, false, true);
+        #[cfg(feature = "stats")]
+        {
+            self.tx.stats.fifo_resets = self.tx.stats.fifo_resets.wrapping_add(1);
+        }
     }
 
     /// Split the driver into [Tx] and [Rx] halves.
     #[inline]
-    pub fn split(self) -> (Tx, Rx) {
+    pub fn split(self) -> (Tx<R>, Rx<R>) {
         (self.tx, self.rx)
     }
 
+    /// Release the register backends of the [Tx] and the [Rx] half, for example to temporarily
+    /// use raw register access.
+    ///
+    /// The driver can be rebuilt with [Self::new_with_regs] afterwards. The error counters are
+    /// discarded.
+    #[inline]
+    pub fn release(self) -> (R, R) {
+        (self.tx.regs, self.rx.regs)
+    }
+
+    /// Is the UART Lite interrupt enabled?
+    ///
+    /// This returns the cached interrupt enable state of the driver, see
+    /// [Tx::invalidate_interrupt_shadow]. The status register is only read if the state is not
+    /// known yet.
+    pub fn is_interrupt_enabled(&mut self) -> bool {
+        self.control_state().interrupt_enabled()
+    }
+
+    /// Shadow of the write-only control register, see [Tx::control_state].
+    ///
+    /// RX errors reported by the status register read are added to the error counters.
+    pub fn control_state(&mut self) -> ControlState {
+        let control = self.tx.control_state();
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.handle_status_reg_errors(errors);
+        }
+        control
+    }
+
+    /// Write the given state into the control register, see [Tx::apply_control].
+    #[inline]
+    pub fn apply_control(&mut self, control: ControlState) {
+        self.tx.apply_control(control);
+    }
+
     /// Enable UART Lite interrupts.
+    ///
+    /// Returns the previous interrupt enable state, which allows layered code to restore it.
     #[inline]
-    pub fn enable_interrupt(&mut self) {
-        self.regs().write_ctrl_reg(
-            Control::builder()
-                .with_enable_interrupt(true)
-                .with_reset_rx_fifo(false)
-                .with_reset_tx_fifo(false)
-                .build(),
-        );
+    pub fn enable_interrupt(&mut self) -> bool {
+        let control = self.control_state();
+        self.tx.apply_control(control.with_interrupt_enabled(true));
+        control.interrupt_enabled()
     }
 
     /// Disable UART Lite interrupts.
+    ///
+    /// Returns the previous interrupt enable state, which allows layered code to restore it.
+    #[inline]
+    pub fn disable_interrupt(&mut self) -> bool {
+        let control = self.control_state();
+        self.tx.apply_control(control.with_interrupt_enabled(false));
+        control.interrupt_enabled()
+    }
+
+    /// Disable the UART Lite interrupt until the returned guard is dropped.
+    ///
+    /// The guard dereferences to the driver, so it can be used for FIFO manipulation inside
+    /// the critical region. The interrupt enable state before the call is restored when the
+    /// guard is dropped, also if the critical region is left early.
+    pub fn interrupt_guard(&mut self) -> InterruptGuard<'_, R> {
+        let was_enabled = self.disable_interrupt();
+        InterruptGuard {
+            uart: self,
+            was_enabled,
+        }
+    }
+}
+
+/// RAII guard which keeps the UART Lite interrupt disabled, see
+/// [AxiUartlite::interrupt_guard].
+pub struct InterruptGuard<'uart, R: RegisterAccess = MmioRegisters<'static>> {
+    uart: &'uart mut AxiUartlite<R>,
+    was_enabled: bool,
+}
+
+impl<R: RegisterAccess> InterruptGuard<'_, R> {
+    /// Interrupt enable state which is restored when the guard is dropped.
+    #[inline]
+    pub const fn was_enabled(&self) -> bool {
+        self.was_enabled
+    }
+}
+
+impl<R: RegisterAccess> core::ops::Deref for InterruptGuard<'_, R> {
+    type Target = AxiUartlite<R>;
+
     #[inline]
-    pub fn disable_interrupt(&mut self) {
-        self.regs().write_ctrl_reg(
-            Control::builder()
-                .with_enable_interrupt(false)
-                .with_reset_rx_fifo(false)
-                .with_reset_tx_fifo(false)
-                .build(),
-        );
+    fn deref(&self) -> &Self::Target {
+        self.uart
+    }
+}
+
+impl<R: RegisterAccess> core::ops::DerefMut for InterruptGuard<'_, R> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.uart
+    }
+}
+
+impl<R: RegisterAccess> Drop for InterruptGuard<'_, R> {
+    fn drop(&mut self) {
+        let control = self.uart.tx.control_state();
+        self.uart
+            .tx
+            .apply_control(control.with_interrupt_enabled(self.was_enabled));
+    }
+}
+
+impl<R: RegisterAccess> core::fmt::Debug for AxiUartlite<R> {
+    /// Shows the base address, the control register shadow, the status register value of the
+    /// last [AxiUartlite::status_snapshot] and the error counters.
+    ///
+    /// No register is accessed, so the status is [None] until the first snapshot was taken.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AxiUartlite")
+            .field(
+                "base_addr",
+                &registers::DebugBaseAddr(self.tx.regs.base_addr()),
+            )
+            .field("control", &self.tx.control)
+            .field("last_status", &self.tx.last_status)
+            .field("errors", &self.errors)
+            .finish()
     }
 }
 
-impl embedded_hal_nb::serial::ErrorType for AxiUartlite {
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::ErrorType for AxiUartlite<R> {
     type Error = Infallible;
 }
 
-impl embedded_hal_nb::serial::Write for AxiUartlite {
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::Write for AxiUartlite<R> {
     #[inline]
     fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
-        self.tx.write(word)
+        self.write_fifo(word)
     }
 
     #[inline]
@@ -256,29 +1135,109 @@ impl embedded_hal_nb::serial::Write for AxiUartlite {
     }
 }
 
-impl embedded_hal_nb::serial::Read for AxiUartlite {
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::Read for AxiUartlite<R> {
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.read_fifo()
+    }
+}
+
+#[cfg(feature = "eh02")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eh02")))]
+impl<R: RegisterAccess> embedded_hal_02::serial::Write<u8> for AxiUartlite<R> {
+    type Error = Infallible;
+
+    #[inline]
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.write_fifo(word)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        embedded_hal_02::serial::Write::flush(&mut self.tx)
+    }
+}
+
+#[cfg(feature = "eh02")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eh02")))]
+impl<R: RegisterAccess> embedded_hal_02::serial::Read<u8> for AxiUartlite<R> {
+    type Error = Infallible;
+
     #[inline]
     fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        self.rx.read()
+        self.read_fifo()
     }
 }
 
-impl embedded_io::ErrorType for AxiUartlite {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ErrorType for AxiUartlite<R> {
     type Error = Infallible;
 }
 
-impl embedded_io::Read for AxiUartlite {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::Read for AxiUartlite<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        self.rx.read(buf)
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // Status register reads clear the error bits, so poll with FIFO reads which capture
+        // the errors.
+        let Ok(first) = nb::block!(self.read_fifo());
+        buf[0] = first;
+        Ok(1 + self.read_nonblocking(&mut buf[1..]))
     }
 }
 
-impl embedded_io::Write for AxiUartlite {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::Write for AxiUartlite<R> {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        self.tx.write(buf)
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // Status register reads clear the error bits, so poll with burst writes which capture
+        // the errors.
+        let mut written = 0;
+        while written == 0 {
+            written = self.write_burst(buf);
+        }
+        while written < buf.len() {
+            match self.write_burst(&buf[written..]) {
+                0 => break,
+                burst => written += burst,
+            }
+        }
+        Ok(written)
     }
 
     fn flush(&mut self) -> Result<(), Self::Error> {
         self.tx.flush()
     }
 }
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ReadReady for AxiUartlite<R> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.status_snapshot().rx_fifo_valid_data())
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::WriteReady for AxiUartlite<R> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.status_snapshot().tx_fifo_full())
+    }
+}
+
+impl<R: RegisterAccess> core::fmt::Write for AxiUartlite<R> {
+    /// Blocking write of the string.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.tx.write_str(s)
+    }
+}
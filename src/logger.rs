@@ -0,0 +1,97 @@
+//! # `log` crate backend
+//!
+//! This module is only available with the `log` feature. It provides the [UartLogger], which
+//! implements [log::Log] and writes the formatted log records through a UART Lite [Tx] handle.
+//! The TX handle is protected by a critical section, so logging is blocking and can be
+//! performed from any context.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static LOGGER: UartLogger = UartLogger::new(log::LevelFilter::Info);
+//!
+//! let uart = unsafe { AxiUartlite::new(UARTLITE_BASE) };
+//! let (tx, _rx) = uart.split();
+//! init_logger(&LOGGER, tx).unwrap();
+//! log::info!("hello world");
+//! ```
+use core::{cell::RefCell, fmt::Write};
+
+use critical_section::Mutex;
+
+use crate::Tx;
+
+/// Logger which writes log records through a critical-section protected [Tx] handle.
+pub struct UartLogger {
+    tx: Mutex<RefCell<Option<Tx>>>,
+    level: log::LevelFilter,
+}
+
+impl UartLogger {
+    /// Create a new logger with the given level filter.
+    ///
+    /// Log records are discarded until a TX handle was set with [Self::set_tx] or
+    /// [init_logger].
+    pub const fn new(level: log::LevelFilter) -> Self {
+        Self {
+            tx: Mutex::new(RefCell::new(None)),
+            level,
+        }
+    }
+
+    /// Level filter.
+    pub const fn level(&self) -> log::LevelFilter {
+        self.level
+    }
+
+    /// Set the TX handle used by the logger. Returns the previous handle.
+    pub fn set_tx(&self, tx: Tx) -> Option<Tx> {
+        critical_section::with(|cs| self.tx.borrow(cs).replace(Some(tx)))
+    }
+
+    /// Release the TX handle used by the logger.
+    pub fn release_tx(&self) -> Option<Tx> {
+        critical_section::with(|cs| self.tx.borrow(cs).take())
+    }
+}
+
+impl log::Log for UartLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        critical_section::with(|cs| {
+            if let Some(tx) = self.tx.borrow(cs).borrow_mut().as_mut() {
+                write!(
+                    tx,
+                    "{:<5} [{}] {}\r\n",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                )
+                .ok();
+            }
+        });
+    }
+
+    fn flush(&self) {
+        critical_section::with(|cs| {
+            if let Some(tx) = self.tx.borrow(cs).borrow().as_ref() {
+                while !tx.fifo_empty() {}
+            }
+        });
+    }
+}
+
+/// Set the TX handle of the passed logger, install it as the global logger and set the
+/// maximum log level.
+pub fn init_logger(logger: &'static UartLogger, tx: Tx) -> Result<(), log::SetLoggerError> {
+    logger.set_tx(tx);
+    log::set_logger(logger)?;
+    log::set_max_level(logger.level);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! # Expectation based register backend
+//!
+//! The [ExpectUartlite] backend checks the bytes written by the driver against a list of
+//! expected [Transaction]s and serves scripted RX data, similar to the serial mock of
+//! `embedded-hal-mock`. Downstream crates which depend on this driver can use it to unit-test
+//! their own protocol logic without hardware.
+//!
+//! The backend panics on the first unexpected access, and [ExpectUartlite::done] has to be
+//! called at the end of the test to verify that all transactions were consumed.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let expectations = [
+//!     Transaction::Write(b"AT\r\n"),
+//!     Transaction::Read(b"OK\r\n"),
+//! ];
+//! let expect = ExpectUartlite::new(&expectations);
+//! let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+//! // Run the code under test using the UART driver.
+//! expect.done();
+//! ```
+use core::cell::Cell;
+
+use super::ErrorInjection;
+use crate::registers::{Control, RegisterAccess, RxFifo, Status, TxFifo};
+
+/// Expected UART transaction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Transaction<'data> {
+    /// The driver is expected to write these bytes into the TX FIFO.
+    Write(&'data [u8]),
+    /// These bytes are served to the driver through the RX FIFO.
+    Read(&'data [u8]),
+    /// The error bits are reported by the next status register read.
+    Errors(ErrorInjection),
+}
+
+/// Expectation based register backend.
+#[derive(Debug)]
+pub struct ExpectUartlite<'data> {
+    transactions: &'data [Transaction<'data>],
+    idx: Cell<usize>,
+    offset: Cell<usize>,
+    intr_enabled: Cell<bool>,
+}
+
+impl<'data> ExpectUartlite<'data> {
+    /// Create a new backend with the given list of expected transactions.
+    pub const fn new(transactions: &'data [Transaction<'data>]) -> Self {
+        Self {
+            transactions,
+            idx: Cell::new(0),
+            offset: Cell::new(0),
+            intr_enabled: Cell::new(false),
+        }
+    }
+
+    /// Number of transactions which were not consumed yet.
+    pub fn remaining(&self) -> usize {
+        self.transactions.len() - self.idx.get()
+    }
+
+    /// Check that all transactions were consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are transactions left.
+    #[track_caller]
+    pub fn done(&self) {
+        assert_eq!(
+            self.remaining(),
+            0,
+            "not all transactions were consumed, next transaction: {:?}",
+            self.current()
+        );
+    }
+
+    fn current(&self) -> Option<Transaction<'data>> {
+        self.transactions.get(self.idx.get()).copied()
+    }
+
+    /// Advance to the next byte of the current transaction.
+    fn advance(&self, len: usize) {
+        let offset = self.offset.get() + 1;
+        if offset >= len {
+            self.idx.set(self.idx.get() + 1);
+            self.offset.set(0);
+        } else {
+            self.offset.set(offset);
+        }
+    }
+}
+
+impl RegisterAccess for &ExpectUartlite<'_> {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        match self.current() {
+            Some(Transaction::Read(data)) if !data.is_empty() => {
+                let byte = data[self.offset.get()];
+                self.advance(data.len());
+                RxFifo::new_with_raw_value(byte as u32)
+            }
+            other => panic!("unexpected RX FIFO read, expected transaction: {other:?}"),
+        }
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        let byte = (value.raw_value() & 0xFF) as u8;
+        match self.current() {
+            Some(Transaction::Write(data)) if !data.is_empty() => {
+                let expected = data[self.offset.get()];
+                assert_eq!(
+                    byte, expected,
+                    "unexpected TX byte {byte:#04x}, expected {expected:#04x}"
+                );
+                self.advance(data.len());
+            }
+            other => {
+                panic!("unexpected TX FIFO write {byte:#04x}, expected transaction: {other:?}")
+            }
+        }
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        let mut errors = ErrorInjection::default();
+        if let Some(Transaction::Errors(injected)) = self.current() {
+            errors = injected;
+            self.idx.set(self.idx.get() + 1);
+        }
+        let rx_valid = matches!(self.current(), Some(Transaction::Read(data)) if !data.is_empty());
+        // The TX FIFO is always reported as empty, so the driver never blocks.
+        let raw_value = ((errors.parity as u32) << 7)
+            | ((errors.frame as u32) << 6)
+            | ((errors.overrun as u32) << 5)
+            | ((self.intr_enabled.get() as u32) << 4)
+            | (1 << 2)
+            | (rx_valid as u32);
+        Status::new_with_raw_value(raw_value)
+    }
+
+    fn write_ctrl_reg(&mut self, value: Control) {
+        self.intr_enabled.set((value.raw_value() >> 4) & 0b1 == 1);
+    }
+}
@@ -0,0 +1,288 @@
+//! # Mock register backend
+//!
+//! This module provides the [MockUartlite] software model of the UART Lite register block. It
+//! emulates the RX and TX FIFOs, the status register and the control register and allows
+//! testing the driver logic, for example partial writes, error capture and interrupt handling,
+//! on the host with `cargo test`.
+//!
+//! The [RegisterAccess] trait is implemented for shared references to the model, so the same
+//! model can be used by the [crate::Tx] and [crate::Rx] halves while the test code emulates the
+//! remote end of the serial line.
+//!
+//! Errors can be injected with [MockUartlite::inject_errors] or scheduled at an arbitrary point
+//! with [MockUartlite::schedule_errors], and a full TX FIFO can be forced with
+//! [MockUartlite::force_tx_fifo_full] to cover the error handling paths.
+//!
+//! The model does not perform any raw MMIO accesses, so the driver logic, including the
+//! transfer context handling of the [crate::tx_async] module, can also be checked with Miri:
+//!
+//! ```sh
+//! cargo +nightly miri test --features mock
+//! ```
+//!
+//! The [expect] module provides an expectation based backend in the style of
+//! `embedded-hal-mock`, which is useful for downstream crates to test their own logic.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mock = MockUartlite::new();
+//! let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+//! uart.write_fifo(0x42).unwrap();
+//! assert_eq!(mock.pop_tx(), Some(0x42));
+//! mock.push_rx(0x01);
+//! assert_eq!(uart.read_fifo(), Ok(0x01));
+//! ```
+use core::cell::RefCell;
+
+pub mod expect;
+
+use crate::{
+    FIFO_DEPTH,
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+#[derive(Debug, Copy, Clone)]
+struct Fifo {
+    buf: [u8; FIFO_DEPTH],
+    read_idx: usize,
+    len: usize,
+}
+
+impl Fifo {
+    const fn new() -> Self {
+        Self {
+            buf: [0; FIFO_DEPTH],
+            read_idx: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> bool {
+        if self.len == FIFO_DEPTH {
+            return false;
+        }
+        self.buf[(self.read_idx + self.len) % FIFO_DEPTH] = byte;
+        self.len += 1;
+        true
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.buf[self.read_idx];
+        self.read_idx = (self.read_idx + 1) % FIFO_DEPTH;
+        self.len -= 1;
+        Some(byte)
+    }
+
+    fn clear(&mut self) {
+        self.read_idx = 0;
+        self.len = 0;
+    }
+}
+
+/// Errors to inject into the status register of the model.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ErrorInjection {
+    /// Set the parity error bit.
+    pub parity: bool,
+    /// Set the frame error bit.
+    pub frame: bool,
+    /// Set the overrun error bit.
+    pub overrun: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct MockState {
+    rx: Fifo,
+    tx: Fifo,
+    intr_enabled: bool,
+    parity_error: bool,
+    frame_error: bool,
+    overrun_error: bool,
+    scheduled_errors: Option<(usize, ErrorInjection)>,
+    tx_forced_full: bool,
+}
+
+impl MockState {
+    fn apply(&mut self, errors: ErrorInjection) {
+        self.parity_error |= errors.parity;
+        self.frame_error |= errors.frame;
+        self.overrun_error |= errors.overrun;
+    }
+
+    fn tx_full(&self) -> bool {
+        self.tx_forced_full || self.tx.len == FIFO_DEPTH
+    }
+}
+
+/// Software model of the UART Lite register block.
+#[derive(Debug)]
+pub struct MockUartlite {
+    state: RefCell<MockState>,
+}
+
+impl Default for MockUartlite {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockUartlite {
+    /// Create a new model with empty FIFOs and disabled interrupts.
+    pub const fn new() -> Self {
+        Self {
+            state: RefCell::new(MockState {
+                rx: Fifo::new(),
+                tx: Fifo::new(),
+                intr_enabled: false,
+                parity_error: false,
+                frame_error: false,
+                overrun_error: false,
+                scheduled_errors: None,
+                tx_forced_full: false,
+            }),
+        }
+    }
+
+    /// Emulate the reception of a byte.
+    ///
+    /// If the RX FIFO is full, the byte is dropped and the overrun error bit is set.
+    /// Returns whether the byte was placed into the RX FIFO.
+    pub fn push_rx(&self, byte: u8) -> bool {
+        let mut state = self.state.borrow_mut();
+        if !state.rx.push(byte) {
+            state.overrun_error = true;
+            return false;
+        }
+        true
+    }
+
+    /// Emulate the reception of multiple bytes. Returns the number of bytes which were placed
+    /// into the RX FIFO.
+    pub fn push_rx_slice(&self, bytes: &[u8]) -> usize {
+        bytes.iter().filter(|&&byte| self.push_rx(byte)).count()
+    }
+
+    /// Emulate the transmission of a byte by removing it from the TX FIFO.
+    pub fn pop_tx(&self) -> Option<u8> {
+        self.state.borrow_mut().tx.pop()
+    }
+
+    /// Emulate the transmission of multiple bytes. Returns the number of bytes which were
+    /// removed from the TX FIFO.
+    pub fn pop_tx_slice(&self, buf: &mut [u8]) -> usize {
+        let mut state = self.state.borrow_mut();
+        let mut read = 0;
+        while read < buf.len() {
+            match state.tx.pop() {
+                Some(byte) => {
+                    buf[read] = byte;
+                    read += 1;
+                }
+                None => break,
+            }
+        }
+        read
+    }
+
+    /// Number of bytes in the RX FIFO.
+    pub fn rx_len(&self) -> usize {
+        self.state.borrow().rx.len
+    }
+
+    /// Number of bytes in the TX FIFO.
+    pub fn tx_len(&self) -> usize {
+        self.state.borrow().tx.len
+    }
+
+    /// Set the passed error bits in the status register. The bits are cleared by the next
+    /// status register read.
+    pub fn inject_errors(&self, errors: ErrorInjection) {
+        self.state.borrow_mut().apply(errors);
+    }
+
+    /// Emulate the reception of a byte with errors.
+    ///
+    /// The error bits are set in the status register, which is how the IP core reports errors
+    /// for the received data.
+    pub fn push_rx_with_errors(&self, byte: u8, errors: ErrorInjection) -> bool {
+        let pushed = self.push_rx(byte);
+        self.inject_errors(errors);
+        pushed
+    }
+
+    /// Schedule an error injection. The error bits are set right before the status register
+    /// read after the given number of further status register reads, so an injection with
+    /// `status_reads` set to 0 is reported by the next status register read.
+    ///
+    /// This allows injecting errors at arbitrary points inside driver operations which read
+    /// the status register multiple times. Only one injection can be scheduled at a time.
+    pub fn schedule_errors(&self, status_reads: usize, errors: ErrorInjection) {
+        self.state.borrow_mut().scheduled_errors = Some((status_reads, errors));
+    }
+
+    /// Force the TX FIFO full condition. Writes to the TX FIFO are ignored while this is
+    /// active, like for a real full FIFO.
+    pub fn force_tx_fifo_full(&self, full: bool) {
+        self.state.borrow_mut().tx_forced_full = full;
+    }
+
+    /// Interrupt enable state of the model.
+    pub fn interrupt_enabled(&self) -> bool {
+        self.state.borrow().intr_enabled
+    }
+}
+
+impl RegisterAccess for &MockUartlite {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(self.state.borrow_mut().rx.pop().unwrap_or(0) as u32)
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        // Writes to a full FIFO are ignored by the IP core.
+        let mut state = self.state.borrow_mut();
+        if !state.tx_forced_full {
+            state.tx.push((value.raw_value() & 0xFF) as u8);
+        }
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        let mut state = self.state.borrow_mut();
+        match state.scheduled_errors {
+            Some((0, errors)) => {
+                state.apply(errors);
+                state.scheduled_errors = None;
+            }
+            Some((remaining, errors)) => state.scheduled_errors = Some((remaining - 1, errors)),
+            None => (),
+        }
+        let raw_value = ((state.parity_error as u32) << 7)
+            | ((state.frame_error as u32) << 6)
+            | ((state.overrun_error as u32) << 5)
+            | ((state.intr_enabled as u32) << 4)
+            | ((state.tx_full() as u32) << 3)
+            | (((!state.tx_forced_full && state.tx.len == 0) as u32) << 2)
+            | (((state.rx.len == FIFO_DEPTH) as u32) << 1)
+            | ((state.rx.len > 0) as u32);
+        // The error bits are cleared by reading the status register.
+        state.parity_error = false;
+        state.frame_error = false;
+        state.overrun_error = false;
+        Status::new_with_raw_value(raw_value)
+    }
+
+    fn write_ctrl_reg(&mut self, value: Control) {
+        let raw_value = value.raw_value();
+        let mut state = self.state.borrow_mut();
+        state.intr_enabled = (raw_value >> 4) & 0b1 == 1;
+        if (raw_value >> 1) & 0b1 == 1 {
+            state.rx.clear();
+        }
+        if raw_value & 0b1 == 1 {
+            state.tx.clear();
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! # Modbus RTU timing support
+//!
+//! Modbus RTU frames are delimited by silent intervals of at least 3.5 character times, and
+//! a gap of more than 1.5 character times inside a frame invalidates the frame. This module
+//! provides the [RtuTiming] helper which derives these intervals from the [HwConfig] and a
+//! user tick source, and the [RtuFrameReceiver] which assembles complete RTU frames.
+//!
+//! For baud rates above 19200, the fixed values of 750 us for the inter-character timeout and
+//! 1.75 ms for the inter-frame delay recommended by the Modbus specification are used.
+use crate::{HwConfig, Rx, crc::crc16_modbus, registers::RegisterAccess};
+
+/// Minimum RTU frame length: Address, function code and CRC.
+pub const MIN_FRAME_LEN: usize = 4;
+/// Maximum RTU frame length.
+pub const MAX_FRAME_LEN: usize = 256;
+
+/// Modbus RTU timing intervals in ticks of a user tick source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RtuTiming {
+    inter_char_ticks: u64,
+    inter_frame_ticks: u64,
+}
+
+impl RtuTiming {
+    /// Calculate the timing intervals for the given hardware configuration and a tick source
+    /// with the given frequency.
+    pub const fn new(hw_config: &HwConfig, tick_hz: u64) -> Self {
+        let (inter_char_ns, inter_frame_ns) = if hw_config.baud_rate > 19200 {
+            (750_000, 1_750_000)
+        } else {
+            let char_time_ns = hw_config.char_time_ns();
+            (
+                (char_time_ns * 3).div_ceil(2),
+                (char_time_ns * 7).div_ceil(2),
+            )
+        };
+        Self {
+            inter_char_ticks: (inter_char_ns * tick_hz).div_ceil(1_000_000_000),
+            inter_frame_ticks: (inter_frame_ns * tick_hz).div_ceil(1_000_000_000),
+        }
+    }
+
+    /// Inter-character timeout (1.5 character times) in ticks.
+    pub const fn inter_char_ticks(&self) -> u64 {
+        self.inter_char_ticks
+    }
+
+    /// Inter-frame delay (3.5 character times) in ticks.
+    pub const fn inter_frame_ticks(&self) -> u64 {
+        self.inter_frame_ticks
+    }
+}
+
+/// RTU frame error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RtuFrameError {
+    /// Frame is shorter than [MIN_FRAME_LEN].
+    TooShort,
+    /// Frame exceeds the receiver buffer.
+    Overflow,
+    /// A silent interval of more than 1.5 character times occurred inside the frame.
+    InterCharTimeout,
+    /// CRC mismatch.
+    Crc,
+}
+
+impl core::fmt::Display for RtuFrameError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort => write!(f, "frame too short"),
+            Self::Overflow => write!(f, "frame exceeds receiver buffer"),
+            Self::InterCharTimeout => write!(f, "inter-character timeout inside frame"),
+            Self::Crc => write!(f, "CRC mismatch"),
+        }
+    }
+}
+
+impl core::error::Error for RtuFrameError {}
+
+/// Modbus RTU frame receiver with an internal buffer of size `N`.
+///
+/// Received bytes are passed to [Self::on_byte] together with the current tick count, and
+/// [Self::poll] has to be called regularly to detect the end of a frame. The tick counter
+/// is expected to be monotonic, wrapping is handled.
+#[derive(Debug)]
+pub struct RtuFrameReceiver<const N: usize = MAX_FRAME_LEN> {
+    timing: RtuTiming,
+    buf: [u8; N],
+    len: usize,
+    last_rx_tick: u64,
+    frame_start_tick: u64,
+    error: Option<RtuFrameError>,
+}
+
+impl<const N: usize> RtuFrameReceiver<N> {
+    /// Create a new frame receiver.
+    pub const fn new(timing: RtuTiming) -> Self {
+        Self {
+            timing,
+            buf: [0; N],
+            len: 0,
+            last_rx_tick: 0,
+            frame_start_tick: 0,
+            error: None,
+        }
+    }
+
+    /// Timing intervals.
+    pub const fn timing(&self) -> RtuTiming {
+        self.timing
+    }
+
+    /// Tick of the first byte of the current frame, or of the last frame returned by
+    /// [Self::poll] or [Self::receive_frame].
+    pub const fn frame_start_tick(&self) -> u64 {
+        self.frame_start_tick
+    }
+
+    /// Reset the receiver, discarding the current frame.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.error = None;
+    }
+
+    /// Pass a received byte to the receiver.
+    ///
+    /// If the previous frame was not retrieved with [Self::poll] before a new frame started,
+    /// it is discarded.
+    pub fn on_byte(&mut self, byte: u8, now: u64) {
+        if self.len > 0 || self.error.is_some() {
+            let gap = now.wrapping_sub(self.last_rx_tick);
+            if gap >= self.timing.inter_frame_ticks {
+                self.reset();
+            } else if gap > self.timing.inter_char_ticks && self.error.is_none() {
+                self.error = Some(RtuFrameError::InterCharTimeout);
+            }
+        }
+        if self.len == 0 && self.error.is_none() {
+            self.frame_start_tick = now;
+        }
+        self.last_rx_tick = now;
+        if self.len >= N {
+            self.error.get_or_insert(RtuFrameError::Overflow);
+            return;
+        }
+        self.buf[self.len] = byte;
+        self.len += 1;
+    }
+
+    /// Check whether a complete frame was received.
+    ///
+    /// Returns the frame without the CRC once the inter-frame delay elapsed after the last
+    /// received byte and the CRC is valid.
+    pub fn poll(&mut self, now: u64) -> Result<Option<&[u8]>, RtuFrameError> {
+        Ok(self.poll_len(now)?.map(|len| &self.buf[0..len]))
+    }
+
+    /// Receive a frame from the RX handle, using the passed closure as the tick source.
+    ///
+    /// Blocks until a frame was received.
+    pub fn receive_frame<R: RegisterAccess>(
+        &mut self,
+        rx: &mut Rx<R>,
+        mut now: impl FnMut() -> u64,
+    ) -> Result<&[u8], RtuFrameError> {
+        let len = loop {
+            match rx.read_fifo() {
+                Ok(byte) => self.on_byte(byte, now()),
+                Err(nb::Error::WouldBlock) => {
+                    if let Some(len) = self.poll_len(now())? {
+                        break len;
+                    }
+                }
+            }
+        };
+        Ok(&self.buf[0..len])
+    }
+
+    fn poll_len(&mut self, now: u64) -> Result<Option<usize>, RtuFrameError> {
+        if self.len == 0 && self.error.is_none() {
+            return Ok(None);
+        }
+        if now.wrapping_sub(self.last_rx_tick) < self.timing.inter_frame_ticks {
+            return Ok(None);
+        }
+        let len = self.len;
+        let error = self.error;
+        self.reset();
+        if let Some(error) = error {
+            return Err(error);
+        }
+        if len < MIN_FRAME_LEN {
+            return Err(RtuFrameError::TooShort);
+        }
+        let crc = u16::from_le_bytes([self.buf[len - 2], self.buf[len - 1]]);
+        if crc != crc16_modbus(&self.buf[0..len - 2]) {
+            return Err(RtuFrameError::Crc);
+        }
+        Ok(Some(len - 2))
+    }
+}
@@ -0,0 +1,145 @@
+//! Lock-free multi-producer single-consumer byte ring buffer used by the buffered driver.
+//!
+//! Producers reserve space by advancing the write position with a compare-and-swap, copy their
+//! data and leave the ring again. The write position and the number of producers which are
+//! still copying share one atomic word, so the consumer knows that all reserved bytes were
+//! written once it observes no active producer. A producer which is preempted while copying,
+//! for example by an interrupt handler which enqueues data as well, never blocks the other
+//! producers. Its data and the data reserved after it are only published after it left.
+use core::cell::UnsafeCell;
+
+use crate::atomic::{AtomicUsize, Ordering};
+
+/// Number of low bits of the packed write state which count the active producers.
+const PRODUCER_BITS: u32 = 8;
+const PRODUCER_MASK: usize = (1 << PRODUCER_BITS) - 1;
+
+pub(crate) struct MpscRing<const N: usize> {
+    buf: UnsafeCell<[u8; N]>,
+    // Write position modulo 2 * N in the upper bits and the number of active producers in the
+    // lower bits.
+    write: AtomicUsize,
+    // Read position modulo 2 * N. Only written by the consumer.
+    read: AtomicUsize,
+    // Write position up to which all bytes are written. Only used by the consumer.
+    published: AtomicUsize,
+}
+
+// Safety: Producers only write the region they reserved, and the consumer only reads the
+// published region between the read position and the published position.
+unsafe impl<const N: usize> Sync for MpscRing<N> {}
+
+impl<const N: usize> MpscRing<N> {
+    const POSITIONS_FIT: () = assert!(
+        N <= (usize::MAX >> PRODUCER_BITS) / 2,
+        "ring buffer is too large for the packed write state"
+    );
+
+    pub(crate) const fn new() -> Self {
+        let () = Self::POSITIONS_FIT;
+        Self {
+            buf: UnsafeCell::new([0; N]),
+            write: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+            published: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of bytes between two positions.
+    #[inline]
+    const fn distance(from: usize, to: usize) -> usize {
+        if N == 0 {
+            return 0;
+        }
+        (to + 2 * N - from) % (2 * N)
+    }
+
+    /// Number of reserved bytes, including bytes which are still being written.
+    pub(crate) fn len(&self) -> usize {
+        let write = self.write.load(Ordering::Acquire) >> PRODUCER_BITS;
+        Self::distance(self.read.load(Ordering::Acquire), write)
+    }
+
+    #[inline]
+    pub(crate) fn free_capacity(&self) -> usize {
+        N - self.len()
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub(crate) fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Write as many bytes as possible and return the number of written bytes.
+    #[inline]
+    pub(crate) fn try_write(&self, data: &[u8]) -> usize {
+        self.write_with(data, false)
+    }
+
+    /// Write all bytes or nothing. Returns whether the bytes were written.
+    #[inline]
+    pub(crate) fn try_write_all(&self, data: &[u8]) -> bool {
+        data.is_empty() || self.write_with(data, true) == data.len()
+    }
+
+    fn write_with(&self, data: &[u8], all: bool) -> usize {
+        let mut state = self.write.load(Ordering::Relaxed);
+        let (pos, len) = loop {
+            let pos = state >> PRODUCER_BITS;
+            // The consumer releases the slots it read with the read position.
+            let free = N - Self::distance(self.read.load(Ordering::Acquire), pos);
+            let len = data.len().min(free);
+            let producers = state & PRODUCER_MASK;
+            if len == 0 || (all && len < data.len()) || producers == PRODUCER_MASK {
+                return 0;
+            }
+            let next = (((pos + len) % (2 * N)) << PRODUCER_BITS) | (producers + 1);
+            match self.write.compare_exchange_weak(
+                state,
+                next,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break (pos, len),
+                Err(current) => state = current,
+            }
+        };
+        let buf = self.buf.get().cast::<u8>();
+        for (offset, &byte) in data[0..len].iter().enumerate() {
+            // Safety: The slots were reserved by this producer and are not read by the consumer
+            // before this producer left the ring.
+            unsafe { buf.add((pos + offset) % N).write(byte) };
+        }
+        // Leave the ring. This releases the written bytes to the consumer once no producer is
+        // active anymore.
+        self.write.fetch_sub(1, Ordering::SeqCst);
+        len
+    }
+
+    /// Read the published bytes. Must not be called by multiple consumers concurrently.
+    ///
+    /// Returns the number of read bytes.
+    pub(crate) fn try_read(&self, buf: &mut [u8]) -> usize {
+        let state = self.write.load(Ordering::SeqCst);
+        if state & PRODUCER_MASK == 0 {
+            self.published
+                .store(state >> PRODUCER_BITS, Ordering::Relaxed);
+        }
+        let read = self.read.load(Ordering::Relaxed);
+        let len = Self::distance(read, self.published.load(Ordering::Relaxed)).min(buf.len());
+        let src = self.buf.get().cast::<u8>().cast_const();
+        for (offset, byte) in buf[0..len].iter_mut().enumerate() {
+            // Safety: The slot is inside the published region.
+            *byte = unsafe { src.add((read + offset) % N).read() };
+        }
+        if len > 0 {
+            self.read.store((read + len) % (2 * N), Ordering::Release);
+        }
+        len
+    }
+}
@@ -0,0 +1,537 @@
+//! # Virtual channel multiplexing
+//!
+//! This module interleaves multiple logical channels, for example a console, log output and
+//! binary telemetry, over a single UART Lite link, similar to the up and down buffers of SEGGER
+//! RTT. The data is transferred in chunks with a small header:
+//!
+//! | Byte | Content |
+//! |------|---------|
+//! | 0 | Channel ID |
+//! | 1 | Payload length N, 0 to [MAX_CHUNK] |
+//! | 2 to N + 1 | Payload |
+//!
+//! The [Mux] wraps the writer of the link, for example [crate::Tx] or [crate::TxAsync], and
+//! hands out a [TxChannel] per logical channel. The [Demux] wraps the reader of the link and
+//! sorts the received payload into one ring buffer per channel, which are read through
+//! [RxChannel] handles. Both handles implement the blocking [embedded_io] and the asynchronous
+//! [embedded_io_async] traits.
+//!
+//! The format has no resynchronization, so both sides have to start at a chunk boundary, for
+//! example after a reset of the link. Reliable transports can use a framing layer like COBS on
+//! top of a channel instead.
+//!
+//! The handles share the [Mux] or [Demux] by reference and are not [Send], so all handles of
+//! one link have to be used from the same execution context. Asynchronous handles of different
+//! tasks on the same executor wait for each other.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mux = Mux::<_, 3>::new(tx);
+//! let mut console = mux.channel(0);
+//! let mut telemetry = mux.channel(2);
+//! writeln!(console, "booted").unwrap();
+//! telemetry.write_all(&packet).unwrap();
+//!
+//! let demux = Demux::<_, 3, 128>::new(rx);
+//! let mut console_rx = demux.channel(0);
+//! let read = console_rx.read(&mut buf).unwrap();
+//! ```
+#[cfg(feature = "embedded-io")]
+use core::{
+    cell::{RefCell, RefMut},
+    future::poll_fn,
+    ops::{Deref, DerefMut},
+    task::{Poll, Waker},
+};
+
+use crate::ring::Ring;
+
+/// Maximum payload length of a single chunk.
+pub const MAX_CHUNK: usize = u8::MAX as usize;
+
+/// Length of the chunk header.
+pub const HEADER_LEN: usize = 2;
+
+/// Chunk header for the passed channel ID and payload length.
+///
+/// # Panics
+///
+/// Panics if the payload length is larger than [MAX_CHUNK].
+#[inline]
+pub const fn chunk_header(channel: u8, len: usize) -> [u8; HEADER_LEN] {
+    assert!(len <= MAX_CHUNK);
+    [channel, len as u8]
+}
+
+/// Statistics of the [Demux].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DemuxStats {
+    /// Number of payload bytes which were dropped because the channel ring buffer was full.
+    pub overflows: u32,
+    /// Number of payload bytes which were dropped because of an unknown channel ID.
+    pub unknown_channel: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ParserState {
+    Channel,
+    Len { channel: u8 },
+    Payload { channel: u8, remaining: u8 },
+}
+
+/// Chunk parser which sorts the payload into the channel ring buffers.
+///
+/// This is the transport independent part of the [Demux]. It can also be fed directly, for
+/// example from an interrupt-driven RX pump.
+pub struct DemuxState<const CHANNELS: usize, const BUF: usize> {
+    state: ParserState,
+    rings: [Ring<BUF>; CHANNELS],
+    stats: DemuxStats,
+}
+
+impl<const CHANNELS: usize, const BUF: usize> Default for DemuxState<CHANNELS, BUF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CHANNELS: usize, const BUF: usize> DemuxState<CHANNELS, BUF> {
+    /// Create a new parser which expects a chunk header first.
+    pub const fn new() -> Self {
+        Self {
+            state: ParserState::Channel,
+            rings: [const { Ring::new() }; CHANNELS],
+            stats: DemuxStats {
+                overflows: 0,
+                unknown_channel: 0,
+            },
+        }
+    }
+
+    /// Feed received link data into the parser.
+    pub fn feed(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.state = match self.state {
+                ParserState::Channel => ParserState::Len { channel: byte },
+                ParserState::Len { .. } if byte == 0 => ParserState::Channel,
+                ParserState::Len { channel } => ParserState::Payload {
+                    channel,
+                    remaining: byte,
+                },
+                ParserState::Payload { channel, remaining } => {
+                    match self.rings.get_mut(channel as usize) {
+                        Some(ring) => {
+                            if !ring.push(byte) {
+                                self.stats.overflows = self.stats.overflows.wrapping_add(1);
+                            }
+                        }
+                        None => {
+                            self.stats.unknown_channel = self.stats.unknown_channel.wrapping_add(1);
+                        }
+                    }
+                    if remaining == 1 {
+                        ParserState::Channel
+                    } else {
+                        ParserState::Payload {
+                            channel,
+                            remaining: remaining - 1,
+                        }
+                    }
+                }
+            };
+        }
+    }
+
+    /// Read buffered payload of a channel.
+    ///
+    /// Returns the number of read bytes, which might be 0 if no data is buffered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    pub fn read(&mut self, channel: usize, buf: &mut [u8]) -> usize {
+        self.rings[channel].pop(buf)
+    }
+
+    /// Number of buffered payload bytes of a channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    #[inline]
+    pub fn len(&self, channel: usize) -> usize {
+        self.rings[channel].len()
+    }
+
+    /// No payload is buffered for the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    #[inline]
+    pub fn is_empty(&self, channel: usize) -> bool {
+        self.len(channel) == 0
+    }
+
+    /// Snapshot of the statistics.
+    #[inline]
+    pub const fn stats(&self) -> DemuxStats {
+        self.stats
+    }
+
+    /// Snapshot of the statistics which also resets them.
+    pub fn take_stats(&mut self) -> DemuxStats {
+        core::mem::take(&mut self.stats)
+    }
+}
+
+/// Exclusive access to a shared resource for one handle at a time.
+///
+/// Blocking handles borrow the resource directly. Asynchronous handles wait if another task
+/// currently uses the resource, and all waiting handles are woken when it is released.
+#[cfg(feature = "embedded-io")]
+struct Shared<T, const CHANNELS: usize> {
+    inner: RefCell<T>,
+    waiters: RefCell<[Option<Waker>; CHANNELS]>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T, const CHANNELS: usize> Shared<T, CHANNELS> {
+    const fn new(inner: T) -> Self {
+        Self {
+            inner: RefCell::new(inner),
+            waiters: RefCell::new([const { None }; CHANNELS]),
+        }
+    }
+
+    fn lock(&self) -> Guard<'_, T, CHANNELS> {
+        Guard {
+            inner: self.inner.borrow_mut(),
+            shared: self,
+        }
+    }
+
+    async fn lock_async(&self, channel: usize) -> Guard<'_, T, CHANNELS> {
+        poll_fn(|cx| match self.inner.try_borrow_mut() {
+            Ok(inner) => Poll::Ready(Guard {
+                inner,
+                shared: self,
+            }),
+            Err(_) => {
+                let slot = &mut self.waiters.borrow_mut()[channel];
+                match slot {
+                    Some(waker) if waker.will_wake(cx.waker()) => (),
+                    _ => *slot = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    fn wake_all(&self) {
+        for waker in self.waiters.borrow_mut().iter_mut() {
+            if let Some(waker) = waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+struct Guard<'shared, T, const CHANNELS: usize> {
+    inner: RefMut<'shared, T>,
+    shared: &'shared Shared<T, CHANNELS>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T, const CHANNELS: usize> Deref for Guard<'_, T, CHANNELS> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T, const CHANNELS: usize> DerefMut for Guard<'_, T, CHANNELS> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T, const CHANNELS: usize> Drop for Guard<'_, T, CHANNELS> {
+    fn drop(&mut self) {
+        self.shared.wake_all();
+    }
+}
+
+/// Multiplexer which interleaves the data of `CHANNELS` logical channels on one link writer.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub struct Mux<W, const CHANNELS: usize> {
+    writer: Shared<W, CHANNELS>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W, const CHANNELS: usize> Mux<W, CHANNELS> {
+    /// Create a new multiplexer for the passed link writer.
+    pub const fn new(writer: W) -> Self {
+        const { assert!(CHANNELS <= 256, "channel IDs are transmitted as one byte") };
+        Self {
+            writer: Shared::new(writer),
+        }
+    }
+
+    /// Write handle for a logical channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    pub fn channel(&self, channel: usize) -> TxChannel<'_, W, CHANNELS> {
+        assert!(channel < CHANNELS, "invalid channel ID {channel}");
+        TxChannel { mux: self, channel }
+    }
+
+    /// Release the link writer.
+    pub fn release(self) -> W {
+        self.writer.inner.into_inner()
+    }
+}
+
+/// Write handle of one logical channel of a [Mux].
+///
+/// Each write transmits one chunk with up to [MAX_CHUNK] bytes of the passed data.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub struct TxChannel<'mux, W, const CHANNELS: usize> {
+    mux: &'mux Mux<W, CHANNELS>,
+    channel: usize,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W, const CHANNELS: usize> TxChannel<'_, W, CHANNELS> {
+    /// Channel ID of this handle.
+    #[inline]
+    pub const fn id(&self) -> usize {
+        self.channel
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::ErrorType, const CHANNELS: usize> embedded_io::ErrorType
+    for TxChannel<'_, W, CHANNELS>
+{
+    type Error = W::Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io::Write, const CHANNELS: usize> embedded_io::Write
+    for TxChannel<'_, W, CHANNELS>
+{
+    /// Blocking write of one chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an asynchronous write of another channel is in progress.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = buf.len().min(MAX_CHUNK);
+        let mut writer = self.mux.writer.lock();
+        writer.write_all(&chunk_header(self.channel as u8, len))?;
+        writer.write_all(&buf[0..len])?;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.mux.writer.lock().flush()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W: embedded_io_async::Write, const CHANNELS: usize> embedded_io_async::Write
+    for TxChannel<'_, W, CHANNELS>
+{
+    /// Asynchronous write of one chunk. Waits until the chunk of another channel is written.
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = buf.len().min(MAX_CHUNK);
+        let mut writer = self.mux.writer.lock_async(self.channel).await;
+        writer
+            .write_all(&chunk_header(self.channel as u8, len))
+            .await?;
+        writer.write_all(&buf[0..len]).await?;
+        Ok(len)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.mux.writer.lock_async(self.channel).await.flush().await
+    }
+}
+
+/// Demultiplexer which sorts the data of `CHANNELS` logical channels received from one link
+/// reader into ring buffers with a size of `BUF`.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub struct Demux<R, const CHANNELS: usize, const BUF: usize = 64> {
+    reader: Shared<R, CHANNELS>,
+    state: RefCell<DemuxState<CHANNELS, BUF>>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R, const CHANNELS: usize, const BUF: usize> Demux<R, CHANNELS, BUF> {
+    /// Create a new demultiplexer for the passed link reader.
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader: Shared::new(reader),
+            state: RefCell::new(DemuxState::new()),
+        }
+    }
+
+    /// Read handle for a logical channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    pub fn channel(&self, channel: usize) -> RxChannel<'_, R, CHANNELS, BUF> {
+        assert!(channel < CHANNELS, "invalid channel ID {channel}");
+        RxChannel {
+            demux: self,
+            channel,
+        }
+    }
+
+    /// Number of buffered payload bytes of a channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    pub fn len(&self, channel: usize) -> usize {
+        self.state.borrow().len(channel)
+    }
+
+    /// No payload is buffered for the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not smaller than `CHANNELS`.
+    pub fn is_empty(&self, channel: usize) -> bool {
+        self.state.borrow().is_empty(channel)
+    }
+
+    /// Snapshot of the statistics.
+    pub fn stats(&self) -> DemuxStats {
+        self.state.borrow().stats()
+    }
+
+    /// Release the link reader. Buffered data is discarded.
+    pub fn release(self) -> R {
+        self.reader.inner.into_inner()
+    }
+
+    fn read_buffered(&self, channel: usize, buf: &mut [u8]) -> usize {
+        self.state.borrow_mut().read(channel, buf)
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io::Read, const CHANNELS: usize, const BUF: usize> Demux<R, CHANNELS, BUF> {
+    /// Blocking read of the link reader which sorts the received data into the channel ring
+    /// buffers.
+    ///
+    /// This only has to be called if channels are polled with [Self::len] instead of being read
+    /// through their handles. Returns the number of bytes read from the link.
+    pub fn service(&self) -> Result<usize, R::Error> {
+        let mut chunk = [0; crate::FIFO_DEPTH];
+        let read = self.reader.lock().read(&mut chunk)?;
+        self.state.borrow_mut().feed(&chunk[0..read]);
+        Ok(read)
+    }
+}
+
+/// Read handle of one logical channel of a [Demux].
+///
+/// Reading a channel reads the link until data for this channel was received. Data of other
+/// channels is buffered in their ring buffers in the meantime.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub struct RxChannel<'demux, R, const CHANNELS: usize, const BUF: usize> {
+    demux: &'demux Demux<R, CHANNELS, BUF>,
+    channel: usize,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R, const CHANNELS: usize, const BUF: usize> RxChannel<'_, R, CHANNELS, BUF> {
+    /// Channel ID of this handle.
+    #[inline]
+    pub const fn id(&self) -> usize {
+        self.channel
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io::ErrorType, const CHANNELS: usize, const BUF: usize> embedded_io::ErrorType
+    for RxChannel<'_, R, CHANNELS, BUF>
+{
+    type Error = R::Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io::Read, const CHANNELS: usize, const BUF: usize> embedded_io::Read
+    for RxChannel<'_, R, CHANNELS, BUF>
+{
+    /// Blocking read which returns as soon as data for this channel is available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an asynchronous read of another channel is in progress.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = self.demux.read_buffered(self.channel, buf);
+            if read > 0 {
+                return Ok(read);
+            }
+            self.demux.service()?;
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R: embedded_io_async::Read, const CHANNELS: usize, const BUF: usize> embedded_io_async::Read
+    for RxChannel<'_, R, CHANNELS, BUF>
+{
+    /// Asynchronous read which completes as soon as data for this channel is available.
+    ///
+    /// Only one task reads the link at a time. Tasks reading other channels are woken after
+    /// every read of the link, so they can pick up their buffered data.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = self.demux.read_buffered(self.channel, buf);
+            if read > 0 {
+                return Ok(read);
+            }
+            let mut reader = self.demux.reader.lock_async(self.channel).await;
+            // Another task might have received data for this channel while waiting.
+            let read = self.demux.read_buffered(self.channel, buf);
+            if read > 0 {
+                return Ok(read);
+            }
+            let mut chunk = [0; crate::FIFO_DEPTH];
+            let read = reader.read(&mut chunk).await?;
+            self.demux.state.borrow_mut().feed(&chunk[0..read]);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! # Owned buffer conveniences
+//!
+//! This module provides read methods returning owned [Vec] and [String] buffers for users with
+//! a global allocator, for example Linux userspace tools or RTOS applications.
+//!
+//! The framing codecs provide `encode_to_vec` functions and `read_frame_vec` methods as well
+//! if the `alloc` feature is enabled.
+use alloc::{
+    string::{FromUtf8Error, String},
+    vec::Vec,
+};
+
+use crate::{AxiUartlite, Rx, UartLiteRx, registers::RegisterAccess};
+
+fn read_to_vec(uart: &mut impl UartLiteRx, max: usize) -> Vec<u8> {
+    let mut buf = alloc::vec![0; max];
+    let read = uart.read_nonblocking(&mut buf);
+    buf.truncate(read);
+    buf
+}
+
+fn read_line_string(uart: &mut impl UartLiteRx) -> Result<String, FromUtf8Error> {
+    let mut line = Vec::new();
+    loop {
+        let Ok(byte) = nb::block!(uart.read_fifo());
+        if byte == b'\n' {
+            break;
+        }
+        line.push(byte);
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line)
+}
+
+impl<R: RegisterAccess> Rx<R> {
+    /// Read the bytes which are currently in the RX FIFO, but at most `max` bytes, into a new
+    /// vector.
+    ///
+    /// This never waits for new data, so the returned vector might be empty.
+    pub fn read_to_vec(&mut self, max: usize) -> Vec<u8> {
+        read_to_vec(self, max)
+    }
+
+    /// Blocking read of the next line.
+    ///
+    /// The line is terminated by `\n` or `\r\n`, which is not included in the returned string.
+    pub fn read_line_string(&mut self) -> Result<String, FromUtf8Error> {
+        read_line_string(self)
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Read the bytes which are currently in the RX FIFO into a new vector, see
+    /// [Rx::read_to_vec].
+    ///
+    /// The errors of every read byte are counted.
+    pub fn read_to_vec(&mut self, max: usize) -> Vec<u8> {
+        read_to_vec(self, max)
+    }
+
+    /// Blocking read of the next line, see [Rx::read_line_string].
+    ///
+    /// The errors of every read byte are counted.
+    pub fn read_line_string(&mut self) -> Result<String, FromUtf8Error> {
+        read_line_string(self)
+    }
+}
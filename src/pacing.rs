@@ -0,0 +1,116 @@
+//! # TX pacing
+//!
+//! This module is only available with the `pacing` feature. Slow legacy devices can not always
+//! keep up with back-to-back bytes at the synthesized baud rate. The [PacedTx] wrapper inserts
+//! configurable gaps between bytes and between frames with an [embedded_hal::delay::DelayNs]
+//! implementation.
+//!
+//! The UART Lite does not report when the last byte left the shift register, only when the TX
+//! FIFO is empty. The gaps are therefore measured from the moment a byte was moved from the FIFO
+//! into the shift register. [PacingConfig::with_wire_gaps] adds one character time so that the
+//! configured gaps are the idle times on the wire.
+use embedded_hal::delay::DelayNs;
+
+use crate::{HwConfig, Tx, registers::RegisterAccess};
+
+/// Gaps inserted by the [PacedTx] wrapper.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PacingConfig {
+    /// Delay after each byte of a frame, measured from the TX FIFO becoming empty.
+    pub inter_byte_ns: u32,
+    /// Delay after each frame, measured from the TX FIFO becoming empty.
+    pub inter_frame_ns: u32,
+}
+
+impl PacingConfig {
+    /// Create a new pacing configuration with delays which are measured from the TX FIFO
+    /// becoming empty.
+    pub const fn new(inter_byte_ns: u32, inter_frame_ns: u32) -> Self {
+        Self {
+            inter_byte_ns,
+            inter_frame_ns,
+        }
+    }
+
+    /// Create a new pacing configuration from the idle times on the wire, by adding the
+    /// character time of the given hardware configuration to both gaps.
+    pub const fn with_wire_gaps(
+        hw_config: &HwConfig,
+        inter_byte_gap_ns: u32,
+        inter_frame_gap_ns: u32,
+    ) -> Self {
+        let char_time_ns = hw_config.char_time_ns();
+        let char_time_ns = if char_time_ns > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            char_time_ns as u32
+        };
+        Self {
+            inter_byte_ns: inter_byte_gap_ns.saturating_add(char_time_ns),
+            inter_frame_ns: inter_frame_gap_ns.saturating_add(char_time_ns),
+        }
+    }
+}
+
+/// Paced TX wrapper which owns the TX handle and a delay provider.
+pub struct PacedTx<R, D> {
+    tx: Tx<R>,
+    delay: D,
+    config: PacingConfig,
+}
+
+impl<R: RegisterAccess, D: DelayNs> PacedTx<R, D> {
+    /// Create a new paced TX wrapper.
+    pub const fn new(tx: Tx<R>, delay: D, config: PacingConfig) -> Self {
+        Self { tx, delay, config }
+    }
+
+    /// Pacing configuration.
+    #[inline]
+    pub const fn config(&self) -> PacingConfig {
+        self.config
+    }
+
+    /// Change the pacing configuration.
+    #[inline]
+    pub fn set_config(&mut self, config: PacingConfig) {
+        self.config = config;
+    }
+
+    /// Blocking write of a single byte, followed by the inter-byte gap.
+    pub fn write_byte(&mut self, byte: u8) {
+        self.send(byte);
+        self.delay.delay_ns(self.config.inter_byte_ns);
+    }
+
+    /// Blocking write of a frame. The inter-byte gap is inserted between the bytes and the
+    /// inter-frame gap after the last byte.
+    pub fn write_frame(&mut self, frame: &[u8]) {
+        for (idx, &byte) in frame.iter().enumerate() {
+            self.send(byte);
+            if idx + 1 < frame.len() {
+                self.delay.delay_ns(self.config.inter_byte_ns);
+            }
+        }
+        if !frame.is_empty() {
+            self.delay.delay_ns(self.config.inter_frame_ns);
+        }
+    }
+
+    /// Access the owned TX handle.
+    #[inline]
+    pub const fn tx(&mut self) -> &mut Tx<R> {
+        &mut self.tx
+    }
+
+    /// Release the owned TX handle and delay provider.
+    pub fn release(self) -> (Tx<R>, D) {
+        (self.tx, self.delay)
+    }
+
+    /// Write a byte and wait until it was moved into the shift register.
+    fn send(&mut self, byte: u8) {
+        let Ok(()) = nb::block!(self.tx.write_fifo(byte));
+        while !self.tx.fifo_empty() {}
+    }
+}
@@ -0,0 +1,61 @@
+//! # Panic output support
+//!
+//! This module provides helpers which can be called from a panic handler to synchronously
+//! print the panic message and location over the UART Lite. A new [Tx] handle is stolen and
+//! the TX FIFO is reset first, so the output does not depend on the state of any other driver,
+//! for example an asynchronous transfer which was interrupted by the panic.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[panic_handler]
+//! fn panic(info: &core::panic::PanicInfo) -> ! {
+//!     unsafe { axi_uartlite::panic::write_panic_info(UARTLITE_BASE, info) };
+//!     loop {}
+//! }
+//! ```
+use core::{fmt::Write, panic::PanicInfo};
+
+use crate::{Tx, registers::RegisterAccess};
+
+/// Steal a TX handle and reset the TX FIFO, discarding any pending data.
+///
+/// # Safety
+///
+/// The same safety rules specified in [crate::AxiUartlite::new] apply. This function should
+/// only be used in a panic handler or similar contexts where no other code uses the TX side
+/// anymore.
+pub unsafe fn panic_tx(base_addr: usize) -> Tx {
+    let mut tx = unsafe { Tx::steal(base_addr) };
+    tx.reset_fifo();
+    tx
+}
+
+/// Write the panic location and message over the passed TX handle and wait until the TX
+/// FIFO is empty.
+pub fn write_panic_info_with<R: RegisterAccess>(tx: &mut Tx<R>, info: &PanicInfo<'_>) {
+    write!(tx, "\r\npanic").ok();
+    if let Some(location) = info.location() {
+        write!(
+            tx,
+            " at {}:{}:{}",
+            location.file(),
+            location.line(),
+            location.column()
+        )
+        .ok();
+    }
+    write!(tx, ": {}\r\n", info.message()).ok();
+    while !tx.fifo_empty() {}
+}
+
+/// Steal a TX handle for the given base address, reset the TX FIFO and write the panic
+/// location and message.
+///
+/// # Safety
+///
+/// See [panic_tx].
+pub unsafe fn write_panic_info(base_addr: usize, info: &PanicInfo<'_>) {
+    let mut tx = unsafe { panic_tx(base_addr) };
+    write_panic_info_with(&mut tx, info);
+}
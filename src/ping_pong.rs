@@ -0,0 +1,148 @@
+//! # Double-buffered RX
+//!
+//! This module provides the [PingPong] buffer for bulk streaming use cases. The interrupt
+//! handler fills one half while the application consumes the other half, which avoids the
+//! per-byte queue overhead of ring buffers. The application swaps the halves with
+//! [PingPongConsumer::swap], which atomically redirects the interrupt handler to the empty half
+//! and returns the filled half.
+//!
+//! Received bytes which do not fit into the current half are read from the FIFO anyway and
+//! dropped, so the hardware FIFO never overruns because of a slow consumer. The number of
+//! dropped bytes is tracked, see [PingPong::overflows].
+//!
+//! The interrupt handler and the consumer are expected to run on the same core, with the
+//! interrupt handler preempting the consumer.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static RX_BUF: PingPong<512> = PingPong::new();
+//!
+//! // Interrupt handler.
+//! RX_BUF.on_interrupt_rx(&mut rx);
+//!
+//! // Application.
+//! let mut consumer = RX_BUF.consumer().unwrap();
+//! loop {
+//!     handle_data(consumer.swap());
+//! }
+//! ```
+use core::cell::UnsafeCell;
+
+use crate::{
+    Rx,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    registers::RegisterAccess,
+};
+
+/// Double buffer with two halves of size `N`.
+///
+/// It is usually placed inside a `static`.
+pub struct PingPong<const N: usize> {
+    bufs: UnsafeCell<[[u8; N]; 2]>,
+    lens: [AtomicUsize; 2],
+    // Index of the half filled by the interrupt handler.
+    fill: AtomicUsize,
+    overflows: AtomicUsize,
+    consumer_taken: AtomicBool,
+}
+
+// Safety: The interrupt handler only writes the half selected by the fill index, and the
+// consumer only reads the other half while holding the unique consumer handle.
+unsafe impl<const N: usize> Sync for PingPong<N> {}
+
+impl<const N: usize> PingPong<N> {
+    /// Create a new double buffer with two empty halves.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            bufs: UnsafeCell::new([[0; N]; 2]),
+            lens: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            fill: AtomicUsize::new(0),
+            overflows: AtomicUsize::new(0),
+            consumer_taken: AtomicBool::new(false),
+        }
+    }
+
+    /// Take the unique consumer handle. Returns [None] if the handle was already taken and not
+    /// dropped yet.
+    pub fn consumer(&self) -> Option<PingPongConsumer<'_, N>> {
+        if self.consumer_taken.swap(true, Ordering::Acquire) {
+            return None;
+        }
+        Some(PingPongConsumer { buf: self })
+    }
+
+    /// This has to be called in the interrupt handler of the UART Lite. It drains the RX FIFO
+    /// into the current half.
+    ///
+    /// Returns the number of bytes read from the FIFO, including dropped bytes.
+    pub fn on_interrupt_rx<R: RegisterAccess>(&self, rx: &mut Rx<R>) -> usize {
+        rx.record_interrupt();
+        let fill = self.fill.load(Ordering::Acquire);
+        let mut len = self.lens[fill].load(Ordering::Relaxed);
+        let buf = self.bufs.get() as *mut u8;
+        let mut read = 0;
+        while let Ok(byte) = rx.read_fifo() {
+            read += 1;
+            if len == N {
+                self.overflows.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            // Safety: The half selected by the fill index is owned by the interrupt handler.
+            unsafe { buf.add(fill * N + len).write(byte) };
+            len += 1;
+        }
+        self.lens[fill].store(len, Ordering::Release);
+        read
+    }
+
+    /// Number of bytes in the half which is currently filled by the interrupt handler.
+    pub fn pending(&self) -> usize {
+        self.lens[self.fill.load(Ordering::Acquire)].load(Ordering::Acquire)
+    }
+
+    /// Number of bytes which were dropped because the current half was full.
+    pub fn overflows(&self) -> usize {
+        self.overflows.load(Ordering::Relaxed)
+    }
+
+    /// Read and reset the number of dropped bytes.
+    pub fn take_overflows(&self) -> usize {
+        self.overflows.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// Unique consumer handle of a [PingPong] buffer.
+pub struct PingPongConsumer<'buf, const N: usize> {
+    buf: &'buf PingPong<N>,
+}
+
+impl<const N: usize> PingPongConsumer<'_, N> {
+    /// Swap the halves and return the data of the half which was filled by the interrupt
+    /// handler.
+    ///
+    /// The returned data stays valid until the next swap. The returned slice is empty if no
+    /// data was received since the last swap.
+    pub fn swap(&mut self) -> &[u8] {
+        let old = self.buf.fill.load(Ordering::Relaxed);
+        let new = old ^ 1;
+        // The new half was consumed by the previous swap.
+        self.buf.lens[new].store(0, Ordering::Relaxed);
+        self.buf.fill.store(new, Ordering::Release);
+        let len = self.buf.lens[old].load(Ordering::Acquire);
+        // Safety: The interrupt handler only writes the new half after the store above.
+        unsafe { core::slice::from_raw_parts((self.buf.bufs.get() as *const u8).add(old * N), len) }
+    }
+
+    /// Number of bytes which the next swap returns.
+    pub fn pending(&self) -> usize {
+        self.buf.pending()
+    }
+}
+
+impl<const N: usize> Drop for PingPongConsumer<'_, N> {
+    fn drop(&mut self) {
+        self.buf.consumer_taken.store(false, Ordering::Release);
+    }
+}
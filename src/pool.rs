@@ -0,0 +1,250 @@
+//! # Multi-UART pool
+//!
+//! This module provides the [UartPool], which owns several [Tx]/[Rx] pairs and services all of
+//! them from a single interrupt entry point. This is common when the interrupt outputs of
+//! many UART Lite instances are aggregated by one AXI INTC output.
+//!
+//! Each instance has its own RX ring buffer, TX queue and [PoolStats]. [UartPool::on_interrupt]
+//! drains all RX FIFOs and tops up all TX FIFOs. The instances are serviced round-robin: the
+//! first serviced instance advances by one on every call, so no instance is permanently
+//! starved when the interrupt handler runs under time pressure.
+//!
+//! The pool itself is not synchronized. Share it between the interrupt handler and the
+//! application with a mutex, for example a `critical_section::Mutex`, or an RTIC resource.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut pool = UartPool::<2, _, 128>::new([uart0.split(), uart1.split()]);
+//! pool.enable_interrupts();
+//!
+//! // Interrupt handler for the shared interrupt line.
+//! pool.on_interrupt();
+//!
+//! // Application.
+//! pool.write(1, b"hello");
+//! let mut buf = [0; 64];
+//! let read = pool.read(0, &mut buf);
+//! ```
+use crate::{
+    FIFO_DEPTH, Rx, RxErrorsCounted, Tx,
+    registers::{ControlState, MmioRegisters, RegisterAccess},
+    ring::Ring,
+};
+
+/// Statistics of one pool instance.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PoolStats {
+    /// Number of bytes read from the RX FIFO.
+    pub rx_bytes: u32,
+    /// Number of bytes written into the TX FIFO.
+    pub tx_bytes: u32,
+    /// Number of received bytes which were dropped because the RX ring buffer was full.
+    pub rx_overflows: u32,
+    /// RX errors reported by the status register.
+    pub errors: RxErrorsCounted,
+}
+
+struct PoolUart<R, const BUF: usize> {
+    tx: Tx<R>,
+    rx: Rx<R>,
+    rx_ring: Ring<BUF>,
+    tx_queue: Ring<BUF>,
+    stats: PoolStats,
+}
+
+impl<R: RegisterAccess, const BUF: usize> PoolUart<R, BUF> {
+    fn service_rx(&mut self) -> usize {
+        let mut read = 0;
+        while let Ok(byte) = self.rx.read_fifo() {
+            read += 1;
+            if !self.rx_ring.push(byte) {
+                self.stats.rx_overflows = self.stats.rx_overflows.wrapping_add(1);
+            }
+        }
+        self.stats.rx_bytes = self.stats.rx_bytes.wrapping_add(read as u32);
+        if let Some(errors) = self.rx.read_and_clear_last_error() {
+            self.stats.errors.add(errors);
+        }
+        read
+    }
+
+    fn service_tx(&mut self) -> usize {
+        let mut burst = [0; FIFO_DEPTH];
+        let len = self.tx_queue.peek(&mut burst);
+        let written = self.tx.write_burst(&burst[0..len]);
+        self.tx_queue.consume(written);
+        self.stats.tx_bytes = self.stats.tx_bytes.wrapping_add(written as u32);
+        if let Some(errors) = self.tx.read_and_clear_last_error() {
+            self.stats.errors.add(errors);
+        }
+        written
+    }
+}
+
+/// Pool of `N` UART Lite instances with RX ring buffers and TX queues with a size of `BUF`.
+pub struct UartPool<const N: usize, R = MmioRegisters<'static>, const BUF: usize = 64> {
+    uarts: [PoolUart<R, BUF>; N],
+    next: usize,
+}
+
+impl<const N: usize, R: RegisterAccess, const BUF: usize> UartPool<N, R, BUF> {
+    /// Create a new pool which owns the passed [Tx]/[Rx] pairs. Instance indices follow the
+    /// order of the passed array.
+    pub fn new(uarts: [(Tx<R>, Rx<R>); N]) -> Self {
+        Self {
+            uarts: uarts.map(|(tx, rx)| PoolUart {
+                tx,
+                rx,
+                rx_ring: Ring::new(),
+                tx_queue: Ring::new(),
+                stats: PoolStats::default(),
+            }),
+            next: 0,
+        }
+    }
+
+    /// Number of instances in the pool.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Is the pool empty?
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Enable the interrupt of all instances.
+    pub fn enable_interrupts(&mut self) {
+        for uart in self.uarts.iter_mut() {
+            uart.tx.apply_control(ControlState::new(true));
+        }
+    }
+
+    /// Disable the interrupt of all instances.
+    pub fn disable_interrupts(&mut self) {
+        for uart in self.uarts.iter_mut() {
+            uart.tx.apply_control(ControlState::new(false));
+        }
+    }
+
+    /// Service all instances. This should be called from the shared interrupt handler.
+    ///
+    /// Returns the total number of bytes read from the RX FIFOs and written into the TX FIFOs.
+    pub fn on_interrupt(&mut self) -> usize {
+        let mut serviced = 0;
+        for offset in 0..N {
+            let uart = &mut self.uarts[(self.next + offset) % N];
+            serviced += uart.service_rx();
+            serviced += uart.service_tx();
+        }
+        if N > 0 {
+            self.next = (self.next + 1) % N;
+        }
+        serviced
+    }
+
+    /// Queue data for transmission on instance `idx`.
+    ///
+    /// The TX FIFO is topped up immediately, and the rest of the data is written by
+    /// [Self::on_interrupt]. Returns the number of accepted bytes, which might be smaller than
+    /// the data length if the TX queue is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    pub fn write(&mut self, idx: usize, data: &[u8]) -> usize {
+        let uart = &mut self.uarts[idx];
+        let queued = uart.tx_queue.extend(data);
+        uart.service_tx();
+        queued
+    }
+
+    /// Read received data of instance `idx` from its RX ring buffer.
+    ///
+    /// Returns the number of read bytes, which might be 0 if the ring buffer is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    pub fn read(&mut self, idx: usize, buf: &mut [u8]) -> usize {
+        self.uarts[idx].rx_ring.pop(buf)
+    }
+
+    /// Number of bytes in the RX ring buffer of instance `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    #[inline]
+    pub fn rx_len(&self, idx: usize) -> usize {
+        self.uarts[idx].rx_ring.len()
+    }
+
+    /// Number of bytes in the TX queue of instance `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    #[inline]
+    pub fn tx_queued(&self, idx: usize) -> usize {
+        self.uarts[idx].tx_queue.len()
+    }
+
+    /// Discard the data in the RX ring buffer and the TX queue of instance `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    pub fn clear(&mut self, idx: usize) {
+        self.uarts[idx].rx_ring.clear();
+        self.uarts[idx].tx_queue.clear();
+    }
+
+    /// Snapshot of the statistics of instance `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    #[inline]
+    pub fn stats(&self, idx: usize) -> PoolStats {
+        self.uarts[idx].stats
+    }
+
+    /// Snapshot of the statistics of instance `idx` which also resets them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    pub fn take_stats(&mut self, idx: usize) -> PoolStats {
+        core::mem::take(&mut self.uarts[idx].stats)
+    }
+
+    /// Access the TX handle of instance `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    #[inline]
+    pub fn tx(&mut self, idx: usize) -> &mut Tx<R> {
+        &mut self.uarts[idx].tx
+    }
+
+    /// Access the RX handle of instance `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not smaller than `N`.
+    #[inline]
+    pub fn rx(&mut self, idx: usize) -> &mut Rx<R> {
+        &mut self.uarts[idx].rx
+    }
+
+    /// Release the owned [Tx]/[Rx] pairs. Buffered data is discarded.
+    pub fn release(self) -> [(Tx<R>, Rx<R>); N] {
+        self.uarts.map(|uart| (uart.tx, uart.rx))
+    }
+}
@@ -0,0 +1,214 @@
+//! # PRBS bit error rate tests
+//!
+//! This module contains a [PrbsGenerator] and [PrbsChecker] pair for pseudo-random binary
+//! sequences, which can be used to validate new board spins and long cable runs. The
+//! [run_ber_test] routine transmits the sequence through a loopback, for example a wire between
+//! the TX and the RX pin or a remote echo, and counts the bit errors and RX errors.
+//!
+//! The bytes are filled LSB first, matching the bit order of the UART, so the bit stream on
+//! the wire is the plain PRBS sequence. The checker runs in lockstep with the generator, so a
+//! lost byte shows up as a burst of bit errors and is counted in [BerStats::lost] if it was
+//! not received at all.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let stats = run_ber_test(&mut uart, PrbsPattern::Prbs15, 100_000, 10_000);
+//! log::info!("bit errors: {} of {} bits", stats.bit_errors, stats.bits());
+//! ```
+use crate::{AxiUartlite, FIFO_DEPTH, RxErrorsCounted, registers::RegisterAccess};
+
+/// PRBS pattern.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PrbsPattern {
+    /// PRBS7 with the polynomial x^7 + x^6 + 1 and a period of 127 bits.
+    Prbs7,
+    /// PRBS15 with the polynomial x^15 + x^14 + 1 and a period of 32767 bits.
+    Prbs15,
+}
+
+impl PrbsPattern {
+    /// Order of the polynomial.
+    pub const fn order(&self) -> u32 {
+        match self {
+            PrbsPattern::Prbs7 => 7,
+            PrbsPattern::Prbs15 => 15,
+        }
+    }
+
+    /// Period of the sequence in bits.
+    pub const fn period(&self) -> u32 {
+        (1 << self.order()) - 1
+    }
+}
+
+/// PRBS byte generator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PrbsGenerator {
+    pattern: PrbsPattern,
+    state: u16,
+}
+
+impl PrbsGenerator {
+    /// Create a generator with the all ones seed.
+    pub const fn new(pattern: PrbsPattern) -> Self {
+        Self {
+            pattern,
+            state: ((1u32 << pattern.order()) - 1) as u16,
+        }
+    }
+
+    /// Pattern of the generator.
+    pub const fn pattern(&self) -> PrbsPattern {
+        self.pattern
+    }
+
+    /// Next bit of the sequence.
+    pub const fn next_bit(&mut self) -> bool {
+        let order = self.pattern.order();
+        let bit = ((self.state >> (order - 1)) ^ (self.state >> (order - 2))) & 1;
+        self.state = ((self.state << 1) | bit) & (((1u32 << order) - 1) as u16);
+        bit != 0
+    }
+
+    /// Next byte of the sequence, filled LSB first.
+    pub const fn next_byte(&mut self) -> u8 {
+        let mut byte = 0;
+        let mut bit = 0;
+        while bit < 8 {
+            byte |= (self.next_bit() as u8) << bit;
+            bit += 1;
+        }
+        byte
+    }
+}
+
+impl Iterator for PrbsGenerator {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        Some(self.next_byte())
+    }
+}
+
+/// Bit error rate test statistics.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BerStats {
+    /// Number of checked bytes.
+    pub bytes: u32,
+    /// Number of bytes which contained at least one bit error.
+    pub byte_errors: u32,
+    /// Number of bit errors.
+    pub bit_errors: u32,
+    /// Number of transmitted bytes which were not received.
+    pub lost: u32,
+    /// RX errors reported by the status register.
+    pub rx_errors: RxErrorsCounted,
+}
+
+impl BerStats {
+    /// Number of checked bits.
+    pub const fn bits(&self) -> u64 {
+        self.bytes as u64 * 8
+    }
+
+    /// Bit error rate in errors per one million bits. Returns 0 if no byte was checked.
+    pub const fn bit_errors_ppm(&self) -> u32 {
+        if self.bytes == 0 {
+            return 0;
+        }
+        (self.bit_errors as u64 * 1_000_000 / self.bits()) as u32
+    }
+
+    /// No bit errors, lost bytes or RX errors were detected.
+    pub fn is_error_free(&self) -> bool {
+        self.bit_errors == 0 && self.lost == 0 && !self.rx_errors.has_errors()
+    }
+}
+
+/// PRBS checker which compares the received bytes with the expected sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PrbsChecker {
+    generator: PrbsGenerator,
+    stats: BerStats,
+}
+
+impl PrbsChecker {
+    /// Create a checker which expects the sequence of [PrbsGenerator::new].
+    pub const fn new(pattern: PrbsPattern) -> Self {
+        Self {
+            generator: PrbsGenerator::new(pattern),
+            stats: BerStats {
+                bytes: 0,
+                byte_errors: 0,
+                bit_errors: 0,
+                lost: 0,
+                rx_errors: RxErrorsCounted::new(),
+            },
+        }
+    }
+
+    /// Check the next received byte. Returns the number of bit errors of the byte.
+    pub fn check(&mut self, byte: u8) -> u32 {
+        let bit_errors = (byte ^ self.generator.next_byte()).count_ones();
+        self.stats.bytes = self.stats.bytes.wrapping_add(1);
+        if bit_errors > 0 {
+            self.stats.byte_errors = self.stats.byte_errors.wrapping_add(1);
+            self.stats.bit_errors = self.stats.bit_errors.wrapping_add(bit_errors);
+        }
+        bit_errors
+    }
+
+    /// Statistics of the checked bytes.
+    pub const fn stats(&self) -> BerStats {
+        self.stats
+    }
+
+    /// Restart the expected sequence and reset the statistics.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.generator.pattern());
+    }
+}
+
+/// Transmit `len` bytes of the PRBS sequence and check the bytes received through a loopback.
+///
+/// At most [FIFO_DEPTH] bytes are in flight, so the RX FIFO does not overflow. The test ends
+/// when all bytes were received or when no byte was transmitted or received for
+/// `max_idle_polls` consecutive polls, which should cover a few character times. The RX error
+/// counters of the driver are reset at the start of the test and reported in
+/// [BerStats::rx_errors].
+pub fn run_ber_test<R: RegisterAccess>(
+    uart: &mut AxiUartlite<R>,
+    pattern: PrbsPattern,
+    len: u32,
+    max_idle_polls: u32,
+) -> BerStats {
+    let mut generator = PrbsGenerator::new(pattern);
+    let mut checker = PrbsChecker::new(pattern);
+    uart.read_and_clear_errors();
+    let mut pending = None;
+    let mut sent = 0;
+    let mut idle_polls = 0;
+    while checker.stats.bytes < len && idle_polls < max_idle_polls {
+        idle_polls += 1;
+        if sent < len && sent.saturating_sub(checker.stats.bytes) < FIFO_DEPTH as u32 {
+            let byte = *pending.get_or_insert_with(|| generator.next_byte());
+            if uart.write_fifo(byte).is_ok() {
+                pending = None;
+                sent += 1;
+                idle_polls = 0;
+            }
+        }
+        if let Ok(byte) = uart.read_fifo() {
+            checker.check(byte);
+            idle_polls = 0;
+        }
+    }
+    BerStats {
+        lost: sent.saturating_sub(checker.stats.bytes),
+        rx_errors: uart.read_and_clear_errors(),
+        ..checker.stats
+    }
+}
@@ -0,0 +1,72 @@
+//! # Early boot print support
+//!
+//! This module provides the [crate::uart_print] and [crate::uart_println] macros, which print
+//! through a global [Tx] handle. The global handle is protected by a critical section and has
+//! to be initialized once with [init_global_tx]. This allows printing during early bring-up
+//! before any executor or logger was set up. Output before the initialization is discarded.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let uart = unsafe { AxiUartlite::new(UARTLITE_BASE) };
+//! let (tx, _rx) = uart.split();
+//! init_global_tx(tx).unwrap();
+//! uart_println!("hello {}", "world");
+//! ```
+use core::{cell::RefCell, fmt::Write};
+
+use critical_section::Mutex;
+
+use crate::Tx;
+
+static GLOBAL_TX: Mutex<RefCell<Option<Tx>>> = Mutex::new(RefCell::new(None));
+
+/// Initialize the global TX handle used by the print macros.
+///
+/// The global handle can only be initialized once. The passed handle is returned if the
+/// global handle was already initialized.
+// The audit ring of the `debug-audit` feature makes the TX handle large, which is acceptable
+// for debug builds.
+#[cfg_attr(feature = "debug-audit", allow(clippy::result_large_err))]
+pub fn init_global_tx(tx: Tx) -> Result<(), Tx> {
+    critical_section::with(|cs| {
+        let mut global_tx = GLOBAL_TX.borrow(cs).borrow_mut();
+        if global_tx.is_some() {
+            return Err(tx);
+        }
+        *global_tx = Some(tx);
+        Ok(())
+    })
+}
+
+/// Print the formatted arguments through the global TX handle.
+///
+/// Used by the print macros.
+#[doc(hidden)]
+pub fn _print(args: core::fmt::Arguments<'_>) {
+    critical_section::with(|cs| {
+        if let Some(tx) = GLOBAL_TX.borrow(cs).borrow_mut().as_mut() {
+            tx.write_fmt(args).ok();
+        }
+    });
+}
+
+/// Print through the global TX handle initialized with [init_global_tx].
+#[macro_export]
+macro_rules! uart_print {
+    ($($arg:tt)*) => {
+        $crate::print::_print(core::format_args!($($arg)*))
+    };
+}
+
+/// Print through the global TX handle initialized with [init_global_tx], followed by a
+/// carriage return and a line feed.
+#[macro_export]
+macro_rules! uart_println {
+    () => {
+        $crate::print::_print(core::format_args!("\r\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::print::_print(core::format_args!("{}\r\n", core::format_args!($($arg)*)))
+    };
+}
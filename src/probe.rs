@@ -0,0 +1,97 @@
+//! # Base address sanity check
+//!
+//! The [probe] function performs a best-effort check whether an AXI UART Lite is present at a
+//! given base address. It can be used to detect a wrong address or a missing IP core before the
+//! application commits to using the peripheral.
+//!
+//! The check is destructive: both FIFOs are reset, so pending TX and RX data is discarded, and
+//! the interrupt enable bit is toggled before it is restored. The probe should therefore be
+//! performed before the interrupt is enabled at the interrupt controller.
+use crate::registers::{Control, RegisterAccess, Registers};
+
+/// Mask of the status register bits which are defined by the IP core. All other bits are
+/// reserved and read as zero.
+const STATUS_DEFINED_BITS: u32 = 0xFF;
+
+/// Probe error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProbeError {
+    /// Reserved bits of the status register are set. The raw status register value is
+    /// contained. A value of `0xFFFF_FFFF` usually means that no peripheral responded.
+    ReservedBitsSet(u32),
+    /// The FIFOs were not empty after a FIFO reset. The raw status register value is contained.
+    FifoResetFailed(u32),
+    /// The interrupt enable bit written to the control register was not reflected in the status
+    /// register.
+    ControlNotReflected,
+}
+
+impl core::fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ReservedBitsSet(raw) => {
+                write!(f, "reserved status register bits set: {raw:#010x}")
+            }
+            Self::FifoResetFailed(raw) => {
+                write!(
+                    f,
+                    "FIFOs not empty after reset, status register: {raw:#010x}"
+                )
+            }
+            Self::ControlNotReflected => {
+                write!(f, "control register write not reflected in status register")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ProbeError {}
+
+/// Check whether an AXI UART Lite is present at the given base address.
+///
+/// See the [module documentation](self) for the side effects of the check.
+///
+/// # Safety
+///
+/// The base address must be mapped and readable as well as writable, otherwise the access
+/// might cause a bus error. The same safety rules specified in [crate::AxiUartlite::new] apply
+/// for the duration of the call.
+pub unsafe fn probe(base_addr: usize) -> Result<(), ProbeError> {
+    let mut regs = unsafe { Registers::new_mmio_at(base_addr) };
+    probe_regs(&mut regs)
+}
+
+/// Check whether the register backend behaves like an AXI UART Lite.
+///
+/// This is the backend-generic variant of [probe].
+pub fn probe_regs<R: RegisterAccess>(regs: &mut R) -> Result<(), ProbeError> {
+    let status = regs.read_stat_reg();
+    if status.raw_value() & !STATUS_DEFINED_BITS != 0 {
+        return Err(ProbeError::ReservedBitsSet(status.raw_value()));
+    }
+    let intr_enabled = status.intr_enabled();
+    for enable_interrupt in [!intr_enabled, intr_enabled] {
+        regs.write_ctrl_reg(
+            Control::builder()
+                .with_enable_interrupt(enable_interrupt)
+                .with_reset_rx_fifo(true)
+                .with_reset_tx_fifo(true)
+                .build(),
+        );
+        let status = regs.read_stat_reg();
+        if status.raw_value() & !STATUS_DEFINED_BITS != 0 {
+            return Err(ProbeError::ReservedBitsSet(status.raw_value()));
+        }
+        if status.rx_fifo_valid_data()
+            || status.rx_fifo_full()
+            || !status.tx_fifo_empty()
+            || status.tx_fifo_full()
+        {
+            return Err(ProbeError::FifoResetFailed(status.raw_value()));
+        }
+        if status.intr_enabled() != enable_interrupt {
+            return Err(ProbeError::ControlNotReflected);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,214 @@
+//! # QEMU integration test runner
+//!
+//! This module is only available with the `qemu` feature. The MicroBlaze machine models of QEMU
+//! emulate the UART Lite, which allows end-to-end tests of the driver in emulation. The
+//! [QemuRunner] starts QEMU with the test firmware and connects to the serial backend of the
+//! UART Lite through the standard I/O of the QEMU process.
+//!
+//! The test firmware is built for the MicroBlaze target with the `selftest` feature and runs
+//! the routines of the [crate::selftest] module on the UART Lite at
+//! [QemuMachine::uartlite_base_addr]. The host side of these routines is provided by
+//! [QemuRunner::run_echo_test] and [QemuRunner::run_async_tx_test].
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[test]
+//! fn echo() {
+//!     let mut runner =
+//!         QemuRunner::spawn(QemuMachine::PetalogixS3adsp1800, "target/selftest.elf").unwrap();
+//!     runner.run_echo_test(b"hello world", Duration::from_secs(10)).unwrap();
+//! }
+//! ```
+use std::{
+    format,
+    io::{self, Write},
+    path::Path,
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+use crate::selftest::{ASYNC_PATTERN_LEN, END_OF_TEST, READY_BANNER, REPORT_PREFIX};
+
+/// QEMU machine models which include a UART Lite.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QemuMachine {
+    /// MicroBlaze on the Spartan-3A DSP 1800 board.
+    PetalogixS3adsp1800,
+}
+
+impl QemuMachine {
+    /// QEMU system emulator binary.
+    pub const fn qemu_system(&self) -> &'static str {
+        match self {
+            QemuMachine::PetalogixS3adsp1800 => "qemu-system-microblaze",
+        }
+    }
+
+    /// QEMU machine name.
+    pub const fn machine_name(&self) -> &'static str {
+        match self {
+            QemuMachine::PetalogixS3adsp1800 => "petalogix-s3adsp1800",
+        }
+    }
+
+    /// Base address of the emulated UART Lite.
+    pub const fn uartlite_base_addr(&self) -> usize {
+        match self {
+            QemuMachine::PetalogixS3adsp1800 => 0x8400_0000,
+        }
+    }
+
+    /// Interrupt line of the emulated UART Lite at the AXI INTC.
+    pub const fn uartlite_irq(&self) -> u32 {
+        match self {
+            QemuMachine::PetalogixS3adsp1800 => 3,
+        }
+    }
+}
+
+/// QEMU process with the serial backend of the UART Lite connected to the standard I/O.
+///
+/// The QEMU process is killed when the runner is dropped.
+#[derive(Debug)]
+pub struct QemuRunner {
+    child: Child,
+    stdin: ChildStdin,
+    rx: mpsc::Receiver<Vec<u8>>,
+    received: Vec<u8>,
+}
+
+impl QemuRunner {
+    /// QEMU command for the machine and the test firmware.
+    ///
+    /// The command can be customized before it is passed to [Self::from_command].
+    pub fn command(machine: QemuMachine, kernel: impl AsRef<Path>) -> Command {
+        let mut command = Command::new(machine.qemu_system());
+        command
+            .args(["-M", machine.machine_name()])
+            .args(["-display", "none", "-monitor", "none", "-serial", "stdio"])
+            .arg("-kernel")
+            .arg(kernel.as_ref());
+        command
+    }
+
+    /// Start QEMU with the test firmware.
+    pub fn spawn(machine: QemuMachine, kernel: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_command(Self::command(machine, kernel))
+    }
+
+    /// Start the passed command and connect to its standard I/O.
+    pub fn from_command(mut command: Command) -> io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0; 256];
+            loop {
+                match io::Read::read(&mut stdout, &mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => {
+                        if tx.send(buf[0..read].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            rx,
+            received: Vec::new(),
+        })
+    }
+
+    /// Send data to the UART Lite.
+    pub fn send(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stdin.write_all(data)?;
+        self.stdin.flush()
+    }
+
+    /// Wait until the UART Lite sent the passed pattern.
+    ///
+    /// Returns all data up to and including the pattern. Data following the pattern is kept
+    /// for the next call.
+    pub fn expect(&mut self, pattern: &[u8], timeout: Duration) -> io::Result<Vec<u8>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(pos) = self
+                .received
+                .windows(pattern.len().max(1))
+                .position(|window| window == pattern)
+            {
+                let rest = self.received.split_off(pos + pattern.len());
+                return Ok(core::mem::replace(&mut self.received, rest));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.rx.recv_timeout(remaining) {
+                Ok(data) => self.received.extend_from_slice(&data),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("timeout while waiting for {:?}", pattern.escape_ascii()),
+                    ));
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+            }
+        }
+    }
+
+    /// Host side of [crate::selftest::run_echo].
+    ///
+    /// The data must not contain [END_OF_TEST]. Returns an error if the echoed data or the
+    /// report line do not match the sent data.
+    pub fn run_echo_test(&mut self, data: &[u8], timeout: Duration) -> io::Result<()> {
+        assert!(!data.contains(&END_OF_TEST));
+        self.expect(READY_BANNER, timeout)?;
+        self.send(data)?;
+        self.send(&[END_OF_TEST])?;
+        let echoed = self.expect(REPORT_PREFIX.as_bytes(), timeout)?;
+        let report = self.expect(b"\n", timeout)?;
+        let expected_report = format!(" echoed={} parity=0 frame=0 overrun=0\n", data.len());
+        if &echoed[0..echoed.len() - REPORT_PREFIX.len()] != data
+            || report != expected_report.as_bytes()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unexpected echo {:?}, report {:?}",
+                    echoed.escape_ascii(),
+                    report.escape_ascii()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Host side of [crate::selftest::run_async_tx].
+    pub fn run_async_tx_test(&mut self, timeout: Duration) -> io::Result<()> {
+        let mut pattern: Vec<u8> = (0..ASYNC_PATTERN_LEN)
+            .map(crate::selftest::async_tx_pattern)
+            .collect();
+        pattern.push(b'\n');
+        self.expect(&pattern, timeout)?;
+        Ok(())
+    }
+}
+
+impl Drop for QemuRunner {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -17,7 +17,7 @@ pub struct TxFifo {
 }
 
 /// Status register.
-#[bitbybit::bitfield(u32)]
+#[bitbybit::bitfield(u32, debug)]
 pub struct Status {
     /// Parity error bit.
     #[bit(7, r)]
@@ -59,6 +59,54 @@ pub struct Control {
     reset_tx_fifo: bool,
 }
 
+/// Shadow of the persistent state of the write-only control register.
+///
+/// Every control register write sets all bits, so a write which only intends to reset a FIFO
+/// also writes the interrupt enable bit. The drivers keep this shadow and build every control
+/// register value with [Self::apply], so bits which are not touched keep their state.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlState {
+    interrupt_enabled: bool,
+}
+
+impl ControlState {
+    /// Create a control register state.
+    #[inline]
+    pub const fn new(interrupt_enabled: bool) -> Self {
+        Self { interrupt_enabled }
+    }
+
+    /// Control register state reported by the status register.
+    #[inline]
+    pub const fn from_status(status: &Status) -> Self {
+        Self::new(status.intr_enabled())
+    }
+
+    /// Is the interrupt enabled?
+    #[inline]
+    pub const fn interrupt_enabled(&self) -> bool {
+        self.interrupt_enabled
+    }
+
+    /// Same state with the given interrupt enable state.
+    #[inline]
+    pub const fn with_interrupt_enabled(self, interrupt_enabled: bool) -> Self {
+        Self { interrupt_enabled }
+    }
+
+    /// Control register value which keeps this state and performs the given one-shot FIFO
+    /// resets.
+    #[inline]
+    pub const fn apply(&self, reset_rx_fifo: bool, reset_tx_fifo: bool) -> Control {
+        Control::builder()
+            .with_enable_interrupt(self.interrupt_enabled)
+            .with_reset_rx_fifo(reset_rx_fifo)
+            .with_reset_tx_fifo(reset_tx_fifo)
+            .build()
+    }
+}
+
 /// AXI UARTLITE register block definition.
 #[derive(derive_mmio::Mmio)]
 #[repr(C)]
@@ -70,3 +118,214 @@ pub struct Registers {
     stat_reg: Status,
     ctrl_reg: Control,
 }
+
+/// Offset of the RX FIFO register.
+pub const RX_FIFO_OFFSET: usize = 0x0;
+/// Offset of the TX FIFO register.
+pub const TX_FIFO_OFFSET: usize = 0x4;
+/// Offset of the status register.
+pub const STAT_REG_OFFSET: usize = 0x8;
+/// Offset of the control register.
+pub const CTRL_REG_OFFSET: usize = 0xC;
+/// Size of the register block.
+pub const REGISTER_BLOCK_SIZE: usize = 0x10;
+
+// Compile-time checks of the register map. The offsets are defined by the IP core and must not
+// change when the register block definition is modified.
+const _: () = assert!(core::mem::offset_of!(Registers, rx_fifo) == RX_FIFO_OFFSET);
+const _: () = assert!(core::mem::offset_of!(Registers, tx_fifo) == TX_FIFO_OFFSET);
+const _: () = assert!(core::mem::offset_of!(Registers, stat_reg) == STAT_REG_OFFSET);
+const _: () = assert!(core::mem::offset_of!(Registers, ctrl_reg) == CTRL_REG_OFFSET);
+const _: () = assert!(core::mem::size_of::<Registers>() == REGISTER_BLOCK_SIZE);
+
+// All registers must be accessed as full 32-bit words. The value of a word access does not
+// depend on the endianness of the CPU, while byte accesses would hit different byte lanes on
+// big-endian cores.
+const _: () = assert!(core::mem::size_of::<RxFifo>() == 4);
+const _: () = assert!(core::mem::size_of::<TxFifo>() == 4);
+const _: () = assert!(core::mem::size_of::<Status>() == 4);
+const _: () = assert!(core::mem::size_of::<Control>() == 4);
+
+/// Convert a raw register value between the bus and the CPU representation.
+///
+/// This swaps the bytes if the `swap-register-bytes` feature is enabled, which is required for
+/// big-endian cores attached to the little-endian AXI interconnect without byte lane swapping.
+#[inline(always)]
+pub const fn bus_to_cpu(raw_value: u32) -> u32 {
+    if cfg!(feature = "swap-register-bytes") {
+        raw_value.swap_bytes()
+    } else {
+        raw_value
+    }
+}
+
+/// Register access abstraction.
+///
+/// The drivers are generic over this trait. It is implemented for the [MmioRegisters] block
+/// which performs volatile accesses to the IP core, and it can be implemented by software
+/// models of the register block, for example the [crate::mock::MockUartlite] backend which
+/// allows testing the driver logic on the host.
+pub trait RegisterAccess {
+    /// Read the RX FIFO register. This removes the byte from the RX FIFO.
+    fn read_rx_fifo(&mut self) -> RxFifo;
+    /// Write the TX FIFO register.
+    fn write_tx_fifo(&mut self, value: TxFifo);
+    /// Read the status register. This clears the error bits.
+    fn read_stat_reg(&self) -> Status;
+    /// Write the control register.
+    fn write_ctrl_reg(&mut self, value: Control);
+
+    /// Base address of the register block if it is memory-mapped. This is only used for
+    /// diagnostic output like the [core::fmt::Debug] implementations of the drivers.
+    #[inline]
+    fn base_addr(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// [core::fmt::Debug] helper which formats an optional base address as hexadecimal.
+pub(crate) struct DebugBaseAddr(pub(crate) Option<usize>);
+
+impl core::fmt::Debug for DebugBaseAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0 {
+            Some(addr) => write!(f, "{addr:#010x}"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+/// Memory barrier which is emitted after control register writes and before status register
+/// reads if the `mmio-barriers` feature is enabled.
+///
+/// This is required on platforms with posted AXI writes or out-of-order cores, where a status
+/// register read could otherwise be performed before a preceding control register write, for
+/// example a FIFO reset, took effect. The barrier is `mbar 1` on MicroBlaze, `dmb sy` on ARM and
+/// a sequentially consistent fence on other architectures. Without the feature, this is a
+/// no-op.
+#[inline(always)]
+pub fn mmio_barrier() {
+    #[cfg(all(feature = "mmio-barriers", target_arch = "microblaze"))]
+    // Safety: `mbar 1` only orders the data accesses of the core. It does not access memory,
+    // registers or the stack and leaves the flags untouched, as declared by the options. The
+    // block is not marked `nomem`, so the compiler does not move memory accesses across it.
+    unsafe {
+        core::arch::asm!("mbar 1", options(nostack, preserves_flags));
+    }
+    #[cfg(all(
+        feature = "mmio-barriers",
+        any(target_arch = "arm", target_arch = "aarch64")
+    ))]
+    // Safety: `dmb sy` only orders the memory accesses of the core for the full system. It does
+    // not access memory, registers or the stack and leaves the flags untouched, as declared by
+    // the options. The block is not marked `nomem`, so the compiler does not move memory
+    // accesses across it.
+    unsafe {
+        core::arch::asm!("dmb sy", options(nostack, preserves_flags));
+    }
+    #[cfg(all(
+        feature = "mmio-barriers",
+        not(any(
+            target_arch = "microblaze",
+            target_arch = "arm",
+            target_arch = "aarch64"
+        ))
+    ))]
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+impl RegisterAccess for MmioRegisters<'_> {
+    #[inline(always)]
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        let raw_value = MmioRegisters::read_rx_fifo(self).raw_value();
+        RxFifo::new_with_raw_value(bus_to_cpu(raw_value))
+    }
+
+    #[inline(always)]
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        MmioRegisters::write_tx_fifo(
+            self,
+            TxFifo::new_with_raw_value(bus_to_cpu(value.raw_value())),
+        )
+    }
+
+    #[inline(always)]
+    fn read_stat_reg(&self) -> Status {
+        mmio_barrier();
+        let raw_value = MmioRegisters::read_stat_reg(self).raw_value();
+        Status::new_with_raw_value(bus_to_cpu(raw_value))
+    }
+
+    #[inline(always)]
+    fn write_ctrl_reg(&mut self, value: Control) {
+        MmioRegisters::write_ctrl_reg(
+            self,
+            Control::new_with_raw_value(bus_to_cpu(value.raw_value())),
+        );
+        mmio_barrier();
+    }
+
+    #[inline]
+    fn base_addr(&self) -> Option<usize> {
+        // Safety: Only the address is read, the registers are not accessed.
+        Some(unsafe { self.ptr() } as usize)
+    }
+}
+
+/// Zero-sized register backend for a register block at the fixed address `BASE`.
+///
+/// Unlike [MmioRegisters], no pointer is stored. The address is a compile-time constant, which
+/// allows the optimizer to fold all register addresses. This is useful if the memory map is
+/// fixed at synthesis time. The drivers using this backend only store their error state.
+///
+/// The [crate::AxiUartliteAt], [crate::TxAt] and [crate::RxAt] type aliases can be used to
+/// name the drivers using this backend.
+#[derive(Debug)]
+pub struct FixedMmio<const BASE: usize>(());
+
+const _: () = assert!(core::mem::size_of::<FixedMmio<0>>() == 0);
+
+impl<const BASE: usize> FixedMmio<BASE> {
+    /// Create a register handle for the register block at `BASE`.
+    ///
+    /// # Safety
+    ///
+    /// `BASE` must be the valid memory-mapped register address of an AXI UART Lite peripheral.
+    /// The same safety rules specified in [crate::AxiUartlite::new] apply.
+    #[inline(always)]
+    pub const unsafe fn new() -> Self {
+        Self(())
+    }
+
+    #[inline(always)]
+    const fn mmio() -> MmioRegisters<'static> {
+        unsafe { Registers::new_mmio_at(BASE) }
+    }
+}
+
+impl<const BASE: usize> RegisterAccess for FixedMmio<BASE> {
+    #[inline(always)]
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RegisterAccess::read_rx_fifo(&mut Self::mmio())
+    }
+
+    #[inline(always)]
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        RegisterAccess::write_tx_fifo(&mut Self::mmio(), value)
+    }
+
+    #[inline(always)]
+    fn read_stat_reg(&self) -> Status {
+        RegisterAccess::read_stat_reg(&Self::mmio())
+    }
+
+    #[inline(always)]
+    fn write_ctrl_reg(&mut self, value: Control) {
+        RegisterAccess::write_ctrl_reg(&mut Self::mmio(), value)
+    }
+
+    #[inline]
+    fn base_addr(&self) -> Option<usize> {
+        Some(BASE)
+    }
+}
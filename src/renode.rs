@@ -0,0 +1,62 @@
+//! # Renode test support
+//!
+//! This module is only available with the `renode` feature. It provides helpers for test
+//! firmware which runs inside the [Renode](https://renode.io) framework, together with a known
+//! configuration for the Renode UART Lite model:
+//!
+//! - `renode/axi_uartlite.repl` is a platform description snippet which places the UART Lite
+//!   at [BASE_ADDR] and connects it to interrupt line [INTC_IRQ] of the interrupt controller.
+//!   It is also available as the [REPL] constant.
+//! - `renode/selftest.robot` is a Robot Framework test suite which drives the
+//!   [crate::selftest] routines and checks the result lines written by [report_result].
+//!
+//! The result lines have the format `<name>: TEST PASSED` or `<name>: TEST FAILED: <reason>`,
+//! which can be matched with the `Wait For Line On Uart` keyword of Renode for deterministic
+//! system tests.
+use core::fmt::Write as _;
+
+use crate::{AxiUartlite, registers::RegisterAccess};
+
+/// Base address of the UART Lite in the platform description snippet.
+pub const BASE_ADDR: usize = 0x4060_0000;
+
+/// Interrupt line of the UART Lite at the interrupt controller in the platform description
+/// snippet.
+pub const INTC_IRQ: u32 = 1;
+
+/// Platform description snippet for the Renode UART Lite model.
+pub const REPL: &str = include_str!("../renode/axi_uartlite.repl");
+
+/// Marker which is written for passed tests.
+pub const PASSED_MARKER: &str = "TEST PASSED";
+
+/// Marker which is written for failed tests.
+pub const FAILED_MARKER: &str = "TEST FAILED";
+
+/// Write the result line of a test.
+pub fn report_result<R: RegisterAccess, E: core::fmt::Display>(
+    uart: &mut AxiUartlite<R>,
+    name: &str,
+    result: Result<(), E>,
+) {
+    // The blocking write can not fail.
+    let _ = match result {
+        Ok(()) => writeln!(uart, "{name}: {PASSED_MARKER}"),
+        Err(e) => writeln!(uart, "{name}: {FAILED_MARKER}: {e}"),
+    };
+}
+
+/// Run the [crate::selftest::run_echo] routine and report the result.
+///
+/// The test fails if RX errors occurred or if no data was echoed.
+pub fn run_echo_test<R: RegisterAccess>(uart: &mut AxiUartlite<R>) {
+    let report = crate::selftest::run_echo(uart);
+    let result = if report.errors.has_errors() {
+        Err("RX errors")
+    } else if report.echoed == 0 {
+        Err("no data")
+    } else {
+        Ok(())
+    };
+    report_result(uart, "echo", result);
+}
@@ -0,0 +1,75 @@
+//! Byte ring buffer used by the software buffered drivers.
+
+pub(crate) struct Ring<const N: usize> {
+    buf: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> Ring<N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub(crate) const fn free(&self) -> usize {
+        N - self.len
+    }
+
+    /// Append a byte. Returns false if the buffer is full.
+    #[inline]
+    pub(crate) fn push(&mut self, byte: u8) -> bool {
+        if self.len == N {
+            return false;
+        }
+        self.buf[(self.head + self.len) % N] = byte;
+        self.len += 1;
+        true
+    }
+
+    /// Append as many bytes as possible and return the number of appended bytes.
+    pub(crate) fn extend(&mut self, data: &[u8]) -> usize {
+        let len = data.len().min(self.free());
+        for &byte in &data[0..len] {
+            self.push(byte);
+        }
+        len
+    }
+
+    /// Copy bytes from the front without removing them and return the number of copied bytes.
+    pub(crate) fn peek(&self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.len);
+        for (idx, byte) in buf[0..len].iter_mut().enumerate() {
+            *byte = self.buf[(self.head + idx) % N];
+        }
+        len
+    }
+
+    /// Remove `len` bytes from the front.
+    pub(crate) fn consume(&mut self, len: usize) {
+        let len = len.min(self.len);
+        self.head = (self.head + len) % N;
+        self.len -= len;
+    }
+
+    /// Move bytes from the front into the buffer and return the number of moved bytes.
+    pub(crate) fn pop(&mut self, buf: &mut [u8]) -> usize {
+        let len = self.peek(buf);
+        self.consume(len);
+        len
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
@@ -0,0 +1,66 @@
+//! # RS-485 direction control
+//!
+//! This module is only available with the `rs485` feature. Half-duplex RS-485 transceivers
+//! which are hooked to the UART Lite require the driver enable (DE) and receiver enable (RE)
+//! signals to be driven around transmissions. The [Rs485Tx] wrapper drives an
+//! [embedded_hal::digital::OutputPin] for the combined DE/RE signal:
+//!
+//! 1. The pin is set high to enable the driver before writing.
+//! 2. After writing, the wrapper waits until the TX FIFO is empty and one additional character
+//!    time elapsed, because the UART Lite does not report when the last byte left the shift
+//!    register.
+//! 3. The pin is set low to switch the transceiver back to reception.
+use embedded_hal::{delay::DelayNs, digital::OutputPin};
+
+use crate::{HwConfig, Tx, registers::RegisterAccess};
+
+/// RS-485 TX wrapper which owns the TX handle, the DE/RE pin and a delay provider.
+pub struct Rs485Tx<R, P, D> {
+    tx: Tx<R>,
+    de: P,
+    delay: D,
+    hw_config: HwConfig,
+}
+
+impl<R: RegisterAccess, P: OutputPin, D: DelayNs> Rs485Tx<R, P, D> {
+    /// Create a new RS-485 TX wrapper. The character time is calculated from the given
+    /// hardware configuration.
+    ///
+    /// The DE/RE pin is set low to enable the receiver.
+    pub fn new(tx: Tx<R>, mut de: P, delay: D, hw_config: &HwConfig) -> Result<Self, P::Error> {
+        de.set_low()?;
+        Ok(Self {
+            tx,
+            de,
+            delay,
+            hw_config: *hw_config,
+        })
+    }
+
+    /// Blocking write of the data with direction control.
+    ///
+    /// The function returns after the last byte was transmitted on the wire and the
+    /// transceiver was switched back to reception.
+    pub fn write(&mut self, data: &[u8]) -> Result<(), P::Error> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.de.set_high()?;
+        self.tx
+            .write_all_and_drain(data, &self.hw_config, |ns| self.delay.delay_ns(ns));
+        self.de.set_low()
+    }
+
+    /// Access the owned TX handle.
+    ///
+    /// Data written directly through the handle is not covered by the direction control.
+    #[inline]
+    pub const fn tx(&mut self) -> &mut Tx<R> {
+        &mut self.tx
+    }
+
+    /// Release the owned TX handle, DE/RE pin and delay provider.
+    pub fn release(self) -> (Tx<R>, P, D) {
+        (self.tx, self.de, self.delay)
+    }
+}
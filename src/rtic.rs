@@ -0,0 +1,121 @@
+//! # RTIC integration
+//!
+//! This module provides resource-friendly wrapper types for using the driver inside an
+//! [RTIC 2](https://rtic.rs) application. [split_rtic] splits the driver into an awaitable
+//! [TxAsync] handle which can be used by software tasks or the idle loop, and an
+//! [InterruptHandler] which is placed into the local resources of the hardware task bound to
+//! the UART Lite interrupt. The interrupt handler services the asynchronous TX engine using the
+//! waker slot which was assigned to the [TxAsync] handle and passes received data to a user
+//! closure, for example to enqueue it into a `heapless::spsc` queue which is consumed by a
+//! software task. Both types implement [Send], so they can be used as RTIC resources.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[rtic::app(device = pac, dispatchers = [SWI0])]
+//! mod app {
+//!     use axi_uartlite::{rtic::*, *};
+//!     use heapless::spsc::{Consumer, Producer, Queue};
+//!
+//!     #[shared]
+//!     struct Shared {}
+//!
+//!     #[local]
+//!     struct Local {
+//!         irq: InterruptHandler,
+//!         producer: Producer<'static, u8, 256>,
+//!         consumer: Consumer<'static, u8, 256>,
+//!         tx: TxAsync,
+//!     }
+//!
+//!     #[init(local = [queue: Queue<u8, 256> = Queue::new()])]
+//!     fn init(cx: init::Context) -> (Shared, Local) {
+//!         let uart = unsafe { AxiUartlite::new(UARTLITE_BASE) };
+//!         // Waker slot 0 is used for the TX engine of this UART Lite.
+//!         let (tx, irq) = split_rtic(uart, 0).unwrap();
+//!         let (producer, consumer) = cx.local.queue.split();
+//!         echo::spawn().unwrap();
+//!         (Shared {}, Local { irq, producer, consumer, tx })
+//!     }
+//!
+//!     #[task(binds = UARTLITE, local = [irq, producer])]
+//!     fn uartlite_irq(cx: uartlite_irq::Context) {
+//!         cx.local.irq.on_interrupt(|data, _errors| {
+//!             for &byte in data {
+//!                 cx.local.producer.enqueue(byte).ok();
+//!             }
+//!         });
+//!     }
+//!
+//!     #[task(local = [consumer, tx])]
+//!     async fn echo(cx: echo::Context) {
+//!         loop {
+//!             while let Some(byte) = cx.local.consumer.dequeue() {
+//!                 cx.local.tx.write(&[byte]).await;
+//!             }
+//!             // Wait for new data, for example with a timer or a signal from the ISR.
+//!         }
+//!     }
+//! }
+//! ```
+use crate::{
+    AxiUartlite, FIFO_DEPTH, InvalidWakerIndex, Rx, RxErrors, Tx, TxAsync, on_interrupt_tx,
+};
+
+/// Split the driver for usage inside an RTIC application.
+///
+/// The returned [TxAsync] handle uses the passed waker slot, and the [InterruptHandler] is
+/// wired to the same slot. Both FIFOs are reset and the UART Lite interrupt is enabled.
+pub fn split_rtic(
+    mut uart: AxiUartlite,
+    waker_slot: usize,
+) -> Result<(TxAsync, InterruptHandler), InvalidWakerIndex> {
+    uart.reset_rx_fifo();
+    uart.reset_tx_fifo();
+    uart.enable_interrupt();
+    let (tx, rx) = uart.split();
+    let irq_tx = Tx::new_with_regs(unsafe { tx.regs.clone() });
+    let tx_async = TxAsync::new(tx, waker_slot)?;
+    Ok((
+        tx_async,
+        InterruptHandler {
+            tx: irq_tx,
+            rx,
+            waker_slot,
+        },
+    ))
+}
+
+/// Interrupt handler resource for the hardware task bound to the UART Lite interrupt.
+pub struct InterruptHandler {
+    tx: Tx,
+    rx: Rx,
+    waker_slot: usize,
+}
+
+impl InterruptHandler {
+    /// Waker slot which is serviced by this handler.
+    pub const fn waker_slot(&self) -> usize {
+        self.waker_slot
+    }
+
+    /// Interrupt handler function.
+    ///
+    /// This services the asynchronous TX engine and reads the whole RX FIFO. The received
+    /// bytes and the RX errors which occurred since the last call are passed to the closure,
+    /// which is only called if data was received or errors occurred.
+    pub fn on_interrupt(&mut self, mut on_rx: impl FnMut(&[u8], Option<RxErrors>)) {
+        on_interrupt_tx(&mut self.tx, self.waker_slot);
+        let mut buf = [0; FIFO_DEPTH];
+        let read = self.rx.on_interrupt_rx(&mut buf);
+        let errors = self.rx.read_and_clear_last_error();
+        if read > 0 || errors.is_some() {
+            on_rx(&buf[0..read], errors);
+        }
+    }
+
+    /// Release the TX and RX handles.
+    pub fn release(self) -> (Tx, Rx) {
+        (self.tx, self.rx)
+    }
+}
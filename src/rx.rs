@@ -1,10 +1,14 @@
 //! # Receiver (RX) support module
-use core::convert::Infallible;
+use core::{convert::Infallible, mem::MaybeUninit};
 
-use crate::registers::{self, Registers, Status};
+use crate::{
+    FIFO_DEPTH,
+    registers::{DebugBaseAddr, FixedMmio, MmioRegisters, RegisterAccess, Registers, Status},
+};
 
 /// RX error structure which tracks if an error has occurred.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RxErrors {
     parity: bool,
     frame: bool,
@@ -40,15 +44,31 @@ impl RxErrors {
     pub const fn has_errors(&self) -> bool {
         self.parity || self.frame || self.overrun
     }
+
+    /// Errors which occurred in either of both error sets.
+    pub(crate) const fn union(self, other: Self) -> Self {
+        Self {
+            parity: self.parity || other.parity,
+            frame: self.frame || other.frame,
+            overrun: self.overrun || other.overrun,
+        }
+    }
 }
 
 /// AXI UARTLITE TX driver.
 ///
 /// Can be created by [super::AxiUartlite::split]ting a regular AXI UARTLITE structure or
 /// by [Self::steal]ing it unsafely.
-pub struct Rx {
-    pub(crate) regs: registers::MmioRegisters<'static>,
+///
+/// The driver is generic over the [RegisterAccess] backend, which defaults to the
+/// memory-mapped register block.
+pub struct Rx<R = MmioRegisters<'static>> {
+    pub(crate) regs: R,
     pub(crate) errors: Option<RxErrors>,
+    /// Status register value of the last [Rx::status_snapshot], shown by the Debug output.
+    pub(crate) last_status: Option<Status>,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: crate::Stats,
 }
 
 impl Rx {
@@ -59,7 +79,7 @@ impl Rx {
     ///
     /// This function assumes that the setup of the UART was already done.
     /// It can be used to create an RX handle inside an interrupt handler without having to use
-    /// a [critical_section::Mutex] if the user can guarantee that the RX handle will only be
+    /// a `critical_section::Mutex` if the user can guarantee that the RX handle will only be
     /// used by the interrupt handler or only interrupt specific API will be used.
     ///
     /// # Safety
@@ -67,33 +87,111 @@ impl Rx {
     /// The same safey rules specified in [super::AxiUartlite] apply.
     #[inline]
     pub const unsafe fn steal(base_addr: usize) -> Self {
+        Self::new_with_regs(unsafe { Registers::new_mmio_at(base_addr) })
+    }
+}
+
+impl Rx<MmioRegisters<'_>> {
+    /// Raw pointer to the register block, for example for FFI code or debugger scripts.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut Registers {
+        // Safety: Retrieving the pointer does not access the registers. Dereferencing it is
+        // left to the unsafe code of the caller.
+        unsafe { self.regs.ptr() }
+    }
+}
+
+/// RX driver for a UART Lite at the fixed base address `BASE`, see [FixedMmio].
+pub type RxAt<const BASE: usize> = Rx<FixedMmio<BASE>>;
+
+impl<const BASE: usize> Rx<FixedMmio<BASE>> {
+    /// Steal the RX part of the UART Lite at the fixed base address `BASE`.
+    ///
+    /// This is the fixed address variant of [Rx::steal].
+    ///
+    /// # Safety
+    ///
+    /// The same safey rules specified in [super::AxiUartlite] apply.
+    #[inline]
+    pub const unsafe fn steal_fixed() -> Self {
+        Self::new_with_regs(unsafe { FixedMmio::new() })
+    }
+
+    /// Raw pointer to the register block at `BASE`.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *mut Registers {
+        BASE as *mut Registers
+    }
+}
+
+impl<R: RegisterAccess> Rx<R> {
+    /// Create an RX handle from a register backend.
+    ///
+    /// This function assumes that the setup of the UART was already done.
+    #[inline]
+    pub const fn new_with_regs(regs: R) -> Self {
         Self {
-            regs: unsafe { Registers::new_mmio_at(base_addr) },
+            regs,
             errors: None,
+            last_status: None,
+            #[cfg(feature = "stats")]
+            stats: crate::Stats::new(),
         }
     }
 
+    /// Release the register backend, for example to temporarily use raw register access.
+    ///
+    /// The driver can be rebuilt with [Self::new_with_regs] afterwards. The recorded RX errors
+    /// are discarded.
+    #[inline]
+    pub fn release(self) -> R {
+        self.regs
+    }
+
     /// Read the RX FIFO.
     ///
     /// This functions offers a [nb::Result] based API and returns [nb::Error::WouldBlock] if there
     /// is nothing to read.
     #[inline]
     pub fn read_fifo(&mut self) -> nb::Result<u8, Infallible> {
+        self.read_fifo_with_errors().map(|(val, _)| val)
+    }
+
+    /// Read the RX FIFO and also return the errors which were reported by the status register
+    /// read preceding the FIFO read.
+    ///
+    /// The errors are also recorded for [Self::read_and_clear_last_error].
+    #[inline]
+    pub fn read_fifo_with_errors(&mut self) -> nb::Result<(u8, Option<RxErrors>), Infallible> {
         let status_reg = self.regs.read_stat_reg();
         if !status_reg.rx_fifo_valid_data() {
+            #[cfg(feature = "stats")]
+            {
+                self.stats.would_block = self.stats.would_block.wrapping_add(1);
+            }
             return Err(nb::Error::WouldBlock);
         }
         let val = self.read_fifo_unchecked();
-        if let Some(errors) = handle_status_reg_errors(&status_reg) {
+        let errors = handle_status_reg_errors(&status_reg);
+        if let Some(errors) = errors {
+            #[cfg(feature = "hooks")]
+            crate::hooks::call(|hooks| hooks.on_error(errors));
             self.errors = Some(errors);
         }
-        Ok(val)
+        Ok((val, errors))
     }
 
     /// Read from the FIFO without checking the FIFO fill status.
     #[inline(always)]
     pub fn read_fifo_unchecked(&mut self) -> u8 {
-        self.regs.read_rx_fifo().data()
+        #[cfg(feature = "stats")]
+        {
+            self.stats.rx_bytes = self.stats.rx_bytes.wrapping_add(1);
+        }
+        let byte = self.regs.read_rx_fifo().data();
+        #[cfg(feature = "hooks")]
+        crate::hooks::call(|hooks| hooks.on_rx_byte(byte));
+        byte
     }
 
     /// Does the RX FIFO have valid data?
@@ -107,17 +205,39 @@ impl Rx {
     /// It returns the number of read bytes.
     #[inline]
     pub fn read_whole_fifo(&mut self, buf: &mut [u8; 16]) -> usize {
+        self.read_nonblocking(buf)
+    }
+
+    /// Copy the bytes which are currently in the RX FIFO into the buffer and return
+    /// immediately.
+    ///
+    /// This never waits for new data and is the primitive for cooperative schedulers and
+    /// polling loops. Returns the number of read bytes, which might be 0.
+    #[inline]
+    pub fn read_nonblocking(&mut self, buf: &mut [u8]) -> usize {
+        // Safety: The read only writes received bytes into the buffer.
+        self.read_uninit(unsafe { as_uninit(buf) }).len()
+    }
+
+    /// Copy the bytes which are currently in the RX FIFO into the uninitialized buffer and
+    /// return immediately, like [Self::read_nonblocking].
+    ///
+    /// Large frame buffers do not need to be zero-initialized before use, which saves time on
+    /// slow softcores. Returns the initialized part of the buffer, which might be empty.
+    #[inline]
+    pub fn read_uninit<'buf>(&mut self, buf: &'buf mut [MaybeUninit<u8>]) -> &'buf [u8] {
         let mut read = 0;
         while read < buf.len() {
             match self.read_fifo() {
                 Ok(byte) => {
-                    buf[read] = byte;
+                    buf[read].write(byte);
                     read += 1;
                 }
                 Err(nb::Error::WouldBlock) => break,
             }
         }
-        read
+        // Safety: The first `read` bytes were initialized.
+        unsafe { assume_init(buf, read) }
     }
 
     /// Can be called in the interrupt handler for the UART Lite to handle RX reception.
@@ -125,9 +245,181 @@ impl Rx {
     /// Simply calls [Rx::read_whole_fifo].
     #[inline]
     pub fn on_interrupt_rx(&mut self, buf: &mut [u8; 16]) -> usize {
+        self.record_interrupt();
         self.read_whole_fifo(buf)
     }
 
+    /// Reads all available bytes in the RX FIFO and records the errors for each read byte
+    /// inside the parallel `errors` array.
+    ///
+    /// Bytes which were read without errors have an empty [RxErrors] entry. This allows
+    /// protocol layers to only discard the bytes which arrived with errors.
+    /// It returns the number of read bytes.
+    #[inline]
+    pub fn read_whole_fifo_with_errors(
+        &mut self,
+        buf: &mut [u8; FIFO_DEPTH],
+        errors: &mut [RxErrors; FIFO_DEPTH],
+    ) -> usize {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.read_fifo_with_errors() {
+                Ok((byte, byte_errors)) => {
+                    buf[read] = byte;
+                    errors[read] = byte_errors.unwrap_or_default();
+                    read += 1;
+                }
+                Err(nb::Error::WouldBlock) => break,
+            }
+        }
+        read
+    }
+
+    /// Can be called in the interrupt handler for the UART Lite to handle RX reception with
+    /// per-byte error attribution.
+    ///
+    /// Simply calls [Rx::read_whole_fifo_with_errors].
+    #[inline]
+    pub fn on_interrupt_rx_with_errors(
+        &mut self,
+        buf: &mut [u8; FIFO_DEPTH],
+        errors: &mut [RxErrors; FIFO_DEPTH],
+    ) -> usize {
+        self.record_interrupt();
+        self.read_whole_fifo_with_errors(buf, errors)
+    }
+
+    /// Drain the RX FIFO into a [heapless::spsc::Producer].
+    ///
+    /// Bytes are only read from the FIFO while the queue has space, so no data is lost if the
+    /// queue is full. Returns the number of bytes moved into the queue.
+    #[cfg(feature = "heapless")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "heapless")))]
+    pub fn drain_into<const N: usize>(
+        &mut self,
+        producer: &mut heapless::spsc::Producer<'_, u8, N>,
+    ) -> usize {
+        let mut moved = 0;
+        while producer.ready() {
+            match self.read_fifo() {
+                Ok(byte) => {
+                    // Can not fail, we checked that the queue is ready.
+                    producer.enqueue(byte).ok();
+                    moved += 1;
+                }
+                Err(nb::Error::WouldBlock) => break,
+            }
+        }
+        moved
+    }
+
+    /// Blocking read which gives up after `max_status_polls` status register polls.
+    ///
+    /// Every byte read performs one status register poll, so the number of polls bounds the
+    /// time spent inside this function and a silent peer can not wedge a superloop without a
+    /// watchdog.
+    ///
+    /// Returns the number of read bytes, which is smaller than the buffer length if the poll
+    /// budget was exhausted.
+    pub fn read_bounded(&mut self, buf: &mut [u8], max_status_polls: u32) -> usize {
+        let mut read = 0;
+        for _ in 0..max_status_polls {
+            if read == buf.len() {
+                break;
+            }
+            if let Ok(byte) = self.read_fifo() {
+                buf[read] = byte;
+                read += 1;
+            }
+        }
+        read
+    }
+
+    /// Blocking read which gives up at a deadline of a user-provided clock.
+    ///
+    /// Reads until the buffer is full or the `now` closure returns a tick value at or after
+    /// `deadline_ticks`. The clock can be any free-running counter, wrapping is handled as long
+    /// as the deadline is less than 2^63 ticks in the future.
+    ///
+    /// Returns the number of read bytes, which is smaller than the buffer length if the
+    /// deadline expired.
+    pub fn read_with_deadline(
+        &mut self,
+        buf: &mut [u8],
+        now: impl FnMut() -> u64,
+        deadline_ticks: u64,
+    ) -> usize {
+        // Safety: The read only writes received bytes into the buffer.
+        self.read_with_deadline_uninit(unsafe { as_uninit(buf) }, now, deadline_ticks)
+            .len()
+    }
+
+    /// Blocking read into an uninitialized buffer which gives up at a deadline of a
+    /// user-provided clock, like [Self::read_with_deadline].
+    ///
+    /// Returns the initialized part of the buffer, which is shorter than the buffer if the
+    /// deadline expired.
+    pub fn read_with_deadline_uninit<'buf>(
+        &mut self,
+        buf: &'buf mut [MaybeUninit<u8>],
+        mut now: impl FnMut() -> u64,
+        deadline_ticks: u64,
+    ) -> &'buf [u8] {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.read_fifo() {
+                Ok(byte) => {
+                    buf[read].write(byte);
+                    read += 1;
+                }
+                Err(nb::Error::WouldBlock) => {
+                    if deadline_reached(now(), deadline_ticks) {
+                        break;
+                    }
+                }
+            }
+        }
+        // Safety: The first `read` bytes were initialized.
+        unsafe { assume_init(buf, read) }
+    }
+
+    /// Blocking iterator over the received bytes.
+    ///
+    /// Each call to [Iterator::next] busy-waits until a byte is available, so the iterator never
+    /// ends. RX errors are recorded for [Self::read_and_clear_last_error].
+    #[inline]
+    pub fn bytes(&mut self) -> Bytes<'_, R> {
+        Bytes { rx: self }
+    }
+
+    /// Blocking iterator over the received bytes together with the errors reported for each
+    /// byte, see [Self::read_fifo_with_errors].
+    #[inline]
+    pub fn bytes_with_errors(&mut self) -> BytesWithErrors<'_, R> {
+        BytesWithErrors { rx: self }
+    }
+
+    /// Read the status register, for example for a diagnostic dump.
+    ///
+    /// This has side effects: the read clears the RX error bits of the status register. The
+    /// errors are recorded for [Self::read_and_clear_last_error], so they are not lost.
+    pub fn status_snapshot(&mut self) -> Status {
+        self.read_status()
+    }
+
+    /// Read the status register and record the RX errors which are cleared by the read.
+    #[inline]
+    pub(crate) fn read_status(&mut self) -> Status {
+        let status_reg = self.regs.read_stat_reg();
+        if let Some(errors) = handle_status_reg_errors(&status_reg) {
+            #[cfg(feature = "hooks")]
+            crate::hooks::call(|hooks| hooks.on_error(errors));
+            self.errors = Some(errors);
+        }
+        self.last_status = Some(status_reg);
+        status_reg
+    }
+
     /// Read and clear the last RX errors.
     ///
     /// Returns [None] if no errors have occured.
@@ -136,45 +428,168 @@ impl Rx {
         self.errors = None;
         Some(errors)
     }
+
+    #[inline(always)]
+    pub(crate) fn record_interrupt(&mut self) {
+        #[cfg(feature = "stats")]
+        {
+            self.stats.interrupts = self.stats.interrupts.wrapping_add(1);
+        }
+        #[cfg(feature = "hooks")]
+        crate::hooks::call(|hooks| hooks.on_interrupt());
+    }
+}
+
+/// Wrapping comparison of a tick value against a deadline.
+#[inline]
+const fn deadline_reached(now: u64, deadline: u64) -> bool {
+    (now.wrapping_sub(deadline) as i64) >= 0
+}
+
+/// View an initialized buffer as uninitialized buffer, which allows implementing the reads into
+/// initialized buffers with the `MaybeUninit` based reads.
+///
+/// # Safety
+///
+/// The caller must only write initialized bytes into the returned slice. Writing
+/// [MaybeUninit::uninit] would leave uninitialized bytes in the initialized buffer.
+pub(crate) unsafe fn as_uninit(buf: &mut [u8]) -> &mut [MaybeUninit<u8>] {
+    // Safety: MaybeUninit<u8> has the same layout as u8, and the caller only writes initialized
+    // bytes into the buffer.
+    unsafe { core::slice::from_raw_parts_mut(buf.as_mut_ptr().cast(), buf.len()) }
+}
+
+/// # Safety
+///
+/// The first `len` elements of the buffer must be initialized.
+pub(crate) unsafe fn assume_init(buf: &[MaybeUninit<u8>], len: usize) -> &[u8] {
+    // Safety: MaybeUninit<u8> has the same layout as u8 and the prefix is initialized.
+    unsafe { core::slice::from_raw_parts(buf.as_ptr().cast::<u8>(), len) }
+}
+
+impl<R: RegisterAccess> core::fmt::Debug for Rx<R> {
+    /// Shows the base address, the status register value of the last [Rx::status_snapshot]
+    /// and the last recorded errors.
+    ///
+    /// No register is accessed, so the status is [None] until the first snapshot was taken.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Rx")
+            .field("base_addr", &DebugBaseAddr(self.regs.base_addr()))
+            .field("last_status", &self.last_status)
+            .field("last_error", &self.errors)
+            .finish()
+    }
+}
+
+// Safety: The handle owns all of its state, so moving it never shares that state with another
+// execution context. The only member which is not `Send` is the raw register block pointer,
+// which points to a peripheral mapped for the whole program lifetime, see the `Tx` impl.
+unsafe impl Send for Rx {}
+
+/// Blocking byte iterator, see [Rx::bytes].
+pub struct Bytes<'rx, R: RegisterAccess> {
+    rx: &'rx mut Rx<R>,
+}
+
+impl<R: RegisterAccess> Iterator for Bytes<'_, R> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let Ok(byte) = nb::block!(self.rx.read_fifo());
+        Some(byte)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+/// Blocking byte iterator with per-byte errors, see [Rx::bytes_with_errors].
+pub struct BytesWithErrors<'rx, R: RegisterAccess> {
+    rx: &'rx mut Rx<R>,
+}
+
+impl<R: RegisterAccess> Iterator for BytesWithErrors<'_, R> {
+    type Item = (u8, Option<RxErrors>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let Ok(item) = nb::block!(self.rx.read_fifo_with_errors());
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
 }
 
-impl embedded_hal_nb::serial::ErrorType for Rx {
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::ErrorType for Rx<R> {
     type Error = Infallible;
 }
 
-impl embedded_hal_nb::serial::Read for Rx {
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::Read for Rx<R> {
     #[inline]
     fn read(&mut self) -> nb::Result<u8, Self::Error> {
         self.read_fifo()
     }
 }
 
-impl embedded_io::ErrorType for Rx {
+#[cfg(feature = "eh02")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eh02")))]
+impl<R: RegisterAccess> embedded_hal_02::serial::Read<u8> for Rx<R> {
     type Error = Infallible;
+
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.read_fifo()
+    }
 }
 
-impl embedded_io::Read for Rx {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ErrorType for Rx<R> {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::Read for Rx<R> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
         if buf.is_empty() {
             return Ok(0);
         }
-        while !self.has_data() {}
-        let mut read = 0;
-        for byte in buf.iter_mut() {
-            match self.read_fifo() {
-                Ok(data) => {
-                    *byte = data;
-                    read += 1;
-                }
-                Err(nb::Error::WouldBlock) => break,
-            }
-        }
-        Ok(read)
+        // Status register reads clear the error bits, so poll with FIFO reads which capture
+        // the errors.
+        let Ok(first) = nb::block!(self.read_fifo());
+        buf[0] = first;
+        Ok(1 + self.read_nonblocking(&mut buf[1..]))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ReadReady for Rx<R> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        // Status register reads clear the error bits, so the errors are recorded.
+        Ok(self.read_status().rx_fifo_valid_data())
     }
 }
 
 /// Extract RX errors from the status register.
+///
+/// Always returns [None] with the `tiny` feature, which compiles out the error capture of all
+/// drivers.
 pub const fn handle_status_reg_errors(status_reg: &Status) -> Option<RxErrors> {
+    if cfg!(feature = "tiny") {
+        return None;
+    }
     let mut errors = RxErrors::new();
     if status_reg.frame_error() {
         errors.frame = true;
@@ -0,0 +1,116 @@
+//! # Asynchronous RX support.
+//!
+//! The [RxAsync] structure waits for received data asynchronously. It uses the waker slot of
+//! the [TxAsync](crate::TxAsync) structure of the same UART Lite instance, so a full-duplex
+//! pair only needs a single slot of the [NUM_WAKERS] budget. The interrupt handler has to call
+//! [crate::on_interrupt_tx_rx] once with this slot to service both directions.
+//!
+//! The received data is read directly from the RX FIFO by the task, so the interrupt handler
+//! only wakes the task. The task has to read the data before the RX FIFO overflows.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut uart = unsafe { AxiUartlite::new(UARTLITE_BASE) };
+//! uart.enable_interrupt();
+//! let (tx, rx) = uart.split();
+//! let mut tx_async = TxAsync::new(tx, 0).unwrap();
+//! let mut rx_async = RxAsync::new(rx, 0).unwrap();
+//!
+//! // Inside the interrupt handler.
+//! let mut isr_tx = unsafe { Tx::steal(UARTLITE_BASE) };
+//! on_interrupt_tx_rx(&mut isr_tx, 0);
+//! ```
+use core::task::Poll;
+
+use crate::{
+    InvalidWakerIndex, NUM_WAKERS, Rx, RxErrors,
+    registers::{MmioRegisters, RegisterAccess},
+    tx_async::{RX_ERRORS, UART_RX_WAKERS},
+};
+
+/// Asynchronous RX structure.
+///
+/// The UART Lite interrupt has to be enabled and serviced with [crate::on_interrupt_tx_rx].
+pub struct RxAsync<R = MmioRegisters<'static>> {
+    rx: Rx<R>,
+    waker_idx: usize,
+}
+
+impl<R: RegisterAccess> RxAsync<R> {
+    /// Create a new asynchronous RX structure.
+    ///
+    /// The waker slot can be the same as the one of the [TxAsync](crate::TxAsync) structure
+    /// of the same UART Lite instance.
+    pub fn new(rx: Rx<R>, waker_idx: usize) -> Result<Self, InvalidWakerIndex> {
+        if waker_idx >= NUM_WAKERS {
+            return Err(InvalidWakerIndex(waker_idx));
+        }
+        Ok(Self { rx, waker_idx })
+    }
+
+    /// Wait until data was received and copy the bytes which are in the RX FIFO into the
+    /// buffer.
+    ///
+    /// Returns the number of read bytes, which is only 0 for an empty buffer. Dropping the
+    /// future does not lose any data, the received bytes stay in the RX FIFO.
+    pub async fn read(&mut self, buf: &mut [u8]) -> usize {
+        if buf.is_empty() {
+            return 0;
+        }
+        core::future::poll_fn(|cx| {
+            // Register first, so data which arrives after the empty read wakes the task.
+            UART_RX_WAKERS[self.waker_idx].register(cx.waker());
+            let read = self.rx.read_nonblocking(buf);
+            if read == 0 {
+                return Poll::Pending;
+            }
+            Poll::Ready(read)
+        })
+        .await
+    }
+
+    /// Read and clear the last RX errors, including the errors which were cleared by the
+    /// status register read of the interrupt handler.
+    ///
+    /// Returns [None] if no errors have occured.
+    pub fn read_and_clear_last_error(&mut self) -> Option<RxErrors> {
+        let isr_errors = critical_section::with(|cs| RX_ERRORS[self.waker_idx].borrow(cs).take());
+        match (self.rx.read_and_clear_last_error(), isr_errors) {
+            (Some(errors), Some(isr_errors)) => Some(errors.union(isr_errors)),
+            (errors, isr_errors) => errors.or(isr_errors),
+        }
+    }
+
+    /// Release the owned RX structure.
+    ///
+    /// Errors which were recorded by the interrupt handler and not read yet are discarded.
+    pub fn release(self) -> Rx<R> {
+        critical_section::with(|cs| RX_ERRORS[self.waker_idx].borrow(cs).set(None));
+        self.rx
+    }
+}
+
+impl<R: RegisterAccess> core::fmt::Debug for RxAsync<R> {
+    /// Shows the waker slot and the [Debug](core::fmt::Debug) output of the [Rx] handle.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RxAsync")
+            .field("waker_idx", &self.waker_idx)
+            .field("rx", &self.rx)
+            .finish()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ErrorType for RxAsync<R> {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io_async::Read for RxAsync<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.read(buf).await)
+    }
+}
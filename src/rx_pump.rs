@@ -0,0 +1,102 @@
+//! # Cooperative RX pump
+//!
+//! This module provides the [RxPump], which moves received data from the RX FIFO into a
+//! software ring buffer. It is the counterpart of the [crate::Transfer] state machine for
+//! applications which can not enable the UART interrupt: [RxPump::pump] is called periodically
+//! from the main superloop, and often enough to avoid hardware overruns for the used baud rate,
+//! see [crate::HwConfig::fifo_drain_time_us].
+//!
+//! Bytes which do not fit into the ring buffer are read from the FIFO anyway and dropped, so
+//! the hardware FIFO never overruns because of a full software buffer. The number of dropped
+//! bytes is tracked, see [RxPump::overflows].
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut pump = RxPump::<_, 256>::new(rx);
+//! loop {
+//!     pump.pump();
+//!     let mut buf = [0; 64];
+//!     let read = pump.read(&mut buf);
+//!     handle_data(&buf[0..read]);
+//!     // Other superloop work.
+//! }
+//! ```
+use crate::{
+    Rx,
+    registers::{MmioRegisters, RegisterAccess},
+    ring::Ring,
+};
+
+/// Cooperative RX pump into a ring buffer with a size of `N`.
+pub struct RxPump<R = MmioRegisters<'static>, const N: usize = 256> {
+    rx: Rx<R>,
+    ring: Ring<N>,
+    overflows: usize,
+}
+
+impl<R: RegisterAccess, const N: usize> RxPump<R, N> {
+    /// Create a new RX pump with an empty ring buffer which owns the RX handle.
+    pub const fn new(rx: Rx<R>) -> Self {
+        Self {
+            rx,
+            ring: Ring::new(),
+            overflows: 0,
+        }
+    }
+
+    /// Drain the RX FIFO into the ring buffer.
+    ///
+    /// Returns the number of bytes read from the FIFO, including dropped bytes.
+    pub fn pump(&mut self) -> usize {
+        let mut read = 0;
+        while let Ok(byte) = self.rx.read_fifo() {
+            read += 1;
+            if !self.ring.push(byte) {
+                self.overflows = self.overflows.saturating_add(1);
+            }
+        }
+        read
+    }
+
+    /// Read as many bytes as possible from the ring buffer.
+    ///
+    /// Returns the number of read bytes, which might be 0 if the ring buffer is empty.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        self.ring.pop(buf)
+    }
+
+    /// Number of bytes in the ring buffer.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Is the ring buffer empty?
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.ring.len() == 0
+    }
+
+    /// Number of bytes which were dropped because the ring buffer was full.
+    #[inline]
+    pub const fn overflows(&self) -> usize {
+        self.overflows
+    }
+
+    /// Read and reset the number of dropped bytes.
+    pub fn take_overflows(&mut self) -> usize {
+        core::mem::take(&mut self.overflows)
+    }
+
+    /// Access the owned RX handle, for example to read the last RX errors.
+    #[inline]
+    pub const fn rx(&mut self) -> &mut Rx<R> {
+        &mut self.rx
+    }
+
+    /// Release the owned RX handle. Data in the ring buffer is discarded.
+    pub fn release(self) -> Rx<R> {
+        self.rx
+    }
+}
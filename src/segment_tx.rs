@@ -0,0 +1,203 @@
+//! # Descriptor-list TX
+//!
+//! The [SegmentTx] queues `&'static [u8]` segments, for example the header, payload and
+//! trailer of a frame, which the interrupt handler transmits back-to-back. This avoids copying
+//! the segments into a single buffer before the transfer.
+//!
+//! A group of segments is queued as one logical transfer with [SegmentTx::try_enqueue], which
+//! either queues all segments or none of them. [SegmentTx::start] starts the transfer by
+//! filling the TX FIFO, the interrupt handler then calls [SegmentTx::on_interrupt_tx] to
+//! transmit the remaining data. [SegmentTx::flush] waits until all segments were written into
+//! the TX FIFO.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static SEGMENTS: SegmentTx<8> = SegmentTx::new();
+//! static HEADER: [u8; 4] = [0x1A, 0xCF, 0xFC, 0x1D];
+//!
+//! // Interrupt handler.
+//! let mut tx = unsafe { Tx::steal(UARTLITE_BASE as usize) };
+//! SEGMENTS.on_interrupt_tx(&mut tx);
+//!
+//! // Application task.
+//! SEGMENTS.try_enqueue(&[&HEADER, payload, &TRAILER]).unwrap();
+//! SEGMENTS.start(&mut tx);
+//! SEGMENTS.flush().await;
+//! ```
+use core::{cell::RefCell, future::poll_fn, task::Poll};
+
+use critical_section::Mutex;
+use embassy_sync::waitqueue::AtomicWaker;
+
+use crate::{FIFO_DEPTH, Tx, registers::RegisterAccess};
+
+/// The segment queue has not enough free slots for all segments of a transfer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SegmentQueueFull {
+    /// Number of segments of the rejected transfer.
+    pub segments: usize,
+    /// Number of free slots in the queue.
+    pub free: usize,
+}
+
+impl core::fmt::Display for SegmentQueueFull {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "segment queue full: {} segments requested, {} slots free",
+            self.segments, self.free
+        )
+    }
+}
+
+impl core::error::Error for SegmentQueueFull {}
+
+struct SegmentQueue<const N: usize> {
+    segments: [&'static [u8]; N],
+    head: usize,
+    len: usize,
+    // Number of bytes of the head segment which were already written.
+    offset: usize,
+}
+
+impl<const N: usize> SegmentQueue<N> {
+    // Copy up to FIFO_DEPTH queued bytes into the burst buffer without consuming them.
+    fn peek(&self, burst: &mut [u8; FIFO_DEPTH]) -> usize {
+        let mut filled = 0;
+        let mut offset = self.offset;
+        for i in 0..self.len {
+            let segment = &self.segments[(self.head + i) % N][offset..];
+            let len = segment.len().min(FIFO_DEPTH - filled);
+            burst[filled..filled + len].copy_from_slice(&segment[0..len]);
+            filled += len;
+            offset = 0;
+            if filled == FIFO_DEPTH {
+                break;
+            }
+        }
+        filled
+    }
+
+    fn consume(&mut self, mut amount: usize) {
+        while self.len > 0 {
+            let remaining = self.segments[self.head].len() - self.offset;
+            if amount < remaining {
+                self.offset += amount;
+                return;
+            }
+            amount -= remaining;
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+            self.offset = 0;
+        }
+    }
+}
+
+/// Queue of up to `N` static TX segments which are transmitted by the interrupt handler.
+///
+/// This structure is shared between the interrupt handler and the application tasks and is
+/// usually placed inside a `static`.
+pub struct SegmentTx<const N: usize> {
+    queue: Mutex<RefCell<SegmentQueue<N>>>,
+    waker: AtomicWaker,
+}
+
+impl<const N: usize> Default for SegmentTx<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SegmentTx<N> {
+    /// Create a new segment queue.
+    pub const fn new() -> Self {
+        Self {
+            queue: Mutex::new(RefCell::new(SegmentQueue {
+                segments: [&[]; N],
+                head: 0,
+                len: 0,
+                offset: 0,
+            })),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// Queue the segments of one logical transfer. Either all segments are queued or none.
+    ///
+    /// Empty segments are skipped.
+    pub fn try_enqueue(&self, segments: &[&'static [u8]]) -> Result<(), SegmentQueueFull> {
+        let count = segments
+            .iter()
+            .filter(|segment| !segment.is_empty())
+            .count();
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow(cs).borrow_mut();
+            let free = N - queue.len;
+            if count > free {
+                return Err(SegmentQueueFull {
+                    segments: count,
+                    free,
+                });
+            }
+            for segment in segments.iter().filter(|segment| !segment.is_empty()) {
+                let tail = (queue.head + queue.len) % N;
+                queue.segments[tail] = segment;
+                queue.len += 1;
+            }
+            Ok(())
+        })
+    }
+
+    /// Start the transfer of the queued segments by filling the TX FIFO.
+    ///
+    /// This has to be called after queueing segments while no transfer is active, because the
+    /// interrupt is only raised when the TX FIFO becomes empty. Returns the number of bytes
+    /// written into the TX FIFO.
+    pub fn start<R: RegisterAccess>(&self, tx: &mut Tx<R>) -> usize {
+        self.refill(tx)
+    }
+
+    /// This has to be called in the interrupt handler of the UART Lite. It tops up the TX FIFO
+    /// from the queued segments. [Tx::steal] can be used to create the required handle.
+    ///
+    /// Returns the number of bytes written into the TX FIFO.
+    pub fn on_interrupt_tx<R: RegisterAccess>(&self, tx: &mut Tx<R>) -> usize {
+        self.refill(tx)
+    }
+
+    fn refill<R: RegisterAccess>(&self, tx: &mut Tx<R>) -> usize {
+        let (written, done) = critical_section::with(|cs| {
+            let mut queue = self.queue.borrow(cs).borrow_mut();
+            let mut burst = [0; FIFO_DEPTH];
+            let len = queue.peek(&mut burst);
+            let written = tx.write_burst(&burst[0..len]);
+            queue.consume(written);
+            (written, queue.len == 0)
+        });
+        if done {
+            self.waker.wake();
+        }
+        written
+    }
+
+    /// Number of segments which were not completely written into the TX FIFO yet.
+    pub fn pending_segments(&self) -> usize {
+        critical_section::with(|cs| self.queue.borrow(cs).borrow().len)
+    }
+
+    /// Wait until all queued segments were written into the TX FIFO.
+    ///
+    /// The last bytes might still be in the TX FIFO when this returns.
+    pub async fn flush(&self) {
+        poll_fn(|cx| {
+            self.waker.register(cx.waker());
+            if self.pending_segments() == 0 {
+                return Poll::Ready(());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
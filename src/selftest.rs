@@ -0,0 +1,101 @@
+//! # Self-test firmware routines
+//!
+//! This module is only available with the `selftest` feature. It contains test routines which
+//! are run by test firmware on the target or inside an emulator, while a host runner like the
+//! [crate::qemu] runner drives the other end of the serial line. The routines are generic
+//! over the [RegisterAccess] backend, so they can be checked on the host with the
+//! [crate::mock] backend as well.
+//!
+//! The echo test exercises the blocking TX and RX paths:
+//!
+//! 1. The target sends the [READY_BANNER].
+//! 2. All bytes received until [END_OF_TEST] are echoed.
+//! 3. The target sends a report line, see [SelftestReport].
+//!
+//! The asynchronous TX test sends the [async_tx_pattern] of [ASYNC_PATTERN_LEN] bytes followed
+//! by a newline using [crate::TxAsync].
+use core::fmt::Write as _;
+
+use crate::{AxiUartlite, RxErrorsCounted, registers::RegisterAccess};
+
+/// Banner which is sent by the target when the echo test starts.
+pub const READY_BANNER: &[u8] = b"AXI-UARTLITE SELFTEST READY\n";
+
+/// Byte which ends the echo test. It is not echoed.
+pub const END_OF_TEST: u8 = 0x04;
+
+/// Length of the asynchronous TX test pattern.
+pub const ASYNC_PATTERN_LEN: usize = 64;
+
+/// Prefix of the report line which is sent at the end of the echo test.
+pub const REPORT_PREFIX: &str = "SELFTEST DONE";
+
+/// Result of the echo test.
+///
+/// The report line sent by the target has the format
+/// `SELFTEST DONE echoed=<n> parity=<n> frame=<n> overrun=<n>`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SelftestReport {
+    /// Number of echoed bytes.
+    pub echoed: usize,
+    /// RX errors which occurred during the test.
+    pub errors: RxErrorsCounted,
+}
+
+impl core::fmt::Display for SelftestReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{REPORT_PREFIX} echoed={} parity={} frame={} overrun={}",
+            self.echoed,
+            self.errors.parity(),
+            self.errors.frame(),
+            self.errors.overrun()
+        )
+    }
+}
+
+/// Byte of the asynchronous TX test pattern at the given index.
+pub const fn async_tx_pattern(idx: usize) -> u8 {
+    b'A' + (idx % 26) as u8
+}
+
+/// Run the blocking echo test.
+pub fn run_echo<R: RegisterAccess>(uart: &mut AxiUartlite<R>) -> SelftestReport {
+    // Discard stale errors.
+    uart.read_and_clear_errors();
+    for &byte in READY_BANNER {
+        let Ok(()) = nb::block!(uart.write_fifo(byte));
+    }
+    let mut echoed = 0;
+    loop {
+        let Ok(byte) = nb::block!(uart.read_fifo());
+        if byte == END_OF_TEST {
+            break;
+        }
+        let Ok(()) = nb::block!(uart.write_fifo(byte));
+        echoed += 1;
+    }
+    let report = SelftestReport {
+        echoed,
+        errors: uart.read_and_clear_errors(),
+    };
+    // The blocking write can not fail.
+    let _ = writeln!(uart, "{report}");
+    report
+}
+
+/// Run the asynchronous TX test.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn run_async_tx<R: RegisterAccess>(tx: &mut crate::TxAsync<R>) {
+    let mut pattern = [0; ASYNC_PATTERN_LEN + 1];
+    for (idx, byte) in pattern.iter_mut().enumerate() {
+        *byte = async_tx_pattern(idx);
+    }
+    pattern[ASYNC_PATTERN_LEN] = b'\n';
+    let mut written = 0;
+    while written < pattern.len() {
+        written += tx.write(&pattern[written..]).await;
+    }
+}
@@ -0,0 +1,100 @@
+//! # Shared asynchronous TX
+//!
+//! The [SharedUartAsync] wraps a [TxAsync] handle into an [embassy_sync::mutex::Mutex] and
+//! hands out cheap [SharedWriter] handles, which can be copied into multiple tasks, for
+//! example for logging and console output. Each write locks the mutex and transmits the whole
+//! buffer, so the output of different tasks is never interleaved within one write. Sequences
+//! of writes which have to stay together can use [SharedUartAsync::lock].
+//!
+//! # Example
+//!
+//! ```ignore
+//! static SHARED: StaticCell<SharedUartAsync> = StaticCell::new();
+//!
+//! let shared = SHARED.init(SharedUartAsync::new(TxAsync::new(tx, 0).unwrap()));
+//! spawner.spawn(logger_task(shared.writer())).unwrap();
+//! spawner.spawn(console_task(shared.writer())).unwrap();
+//! ```
+use embassy_sync::{
+    blocking_mutex::raw::{CriticalSectionRawMutex, RawMutex},
+    mutex::{Mutex, MutexGuard},
+};
+
+use crate::{TxAsync, registers::MmioRegisters, registers::RegisterAccess};
+
+/// Asynchronous TX handle shared between multiple tasks.
+///
+/// The raw mutex type `M` defaults to the [CriticalSectionRawMutex], which allows sharing
+/// between executors with different priorities. The
+/// [NoopRawMutex](embassy_sync::blocking_mutex::raw::NoopRawMutex) can be used if all tasks run
+/// on the same executor.
+pub struct SharedUartAsync<M: RawMutex = CriticalSectionRawMutex, R = MmioRegisters<'static>> {
+    tx: Mutex<M, TxAsync<R>>,
+}
+
+impl<M: RawMutex, R: RegisterAccess> SharedUartAsync<M, R> {
+    /// Create a new shared TX handle.
+    pub const fn new(tx: TxAsync<R>) -> Self {
+        Self { tx: Mutex::new(tx) }
+    }
+
+    /// Writer handle which can be copied into multiple tasks.
+    #[inline]
+    pub fn writer(&self) -> SharedWriter<'_, M, R> {
+        SharedWriter { shared: self }
+    }
+
+    /// Lock the TX handle for a sequence of writes which must not be interleaved with the
+    /// output of other tasks.
+    pub async fn lock(&self) -> MutexGuard<'_, M, TxAsync<R>> {
+        self.tx.lock().await
+    }
+
+    /// Release the TX handle.
+    pub fn release(self) -> TxAsync<R> {
+        self.tx.into_inner()
+    }
+}
+
+/// Writer handle of a [SharedUartAsync].
+pub struct SharedWriter<'shared, M: RawMutex = CriticalSectionRawMutex, R = MmioRegisters<'static>>
+{
+    shared: &'shared SharedUartAsync<M, R>,
+}
+
+impl<M: RawMutex, R> Clone for SharedWriter<'_, M, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: RawMutex, R> Copy for SharedWriter<'_, M, R> {}
+
+impl<M: RawMutex, R: RegisterAccess> SharedWriter<'_, M, R> {
+    /// Lock the shared TX handle and write the whole buffer.
+    ///
+    /// Waits until other tasks finished their write. Returns the number of written bytes.
+    pub async fn write(&self, buf: &[u8]) -> usize {
+        self.shared.tx.lock().await.write(buf).await
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<M: RawMutex, R: RegisterAccess> embedded_io::ErrorType for SharedWriter<'_, M, R> {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<M: RawMutex, R: RegisterAccess> embedded_io_async::Write for SharedWriter<'_, M, R> {
+    /// Lock the shared TX handle and write the whole buffer.
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(SharedWriter::write(self, buf).await)
+    }
+
+    /// This implementation does not do anything, like the [TxAsync] implementation.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! # Host-side UART Lite simulator
+//!
+//! This module connects the wire side of the [MockUartlite] register model to a byte stream,
+//! for example a TCP socket or a pty. Bytes written into the TX FIFO by the driver are
+//! forwarded to the stream, and bytes received from the stream are placed into the RX FIFO.
+//! This allows full-stack testing of application protocols on the host without hardware, with
+//! the remote end being a regular host tool like a terminal or a test script.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mock = MockUartlite::new();
+//! let listener = TcpListener::bind("127.0.0.1:7301")?;
+//! let mut bridge = WireBridge::accept(&mock, &listener)?;
+//! let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+//! loop {
+//!     bridge.poll()?;
+//!     // Run the application logic using the UART driver.
+//! }
+//! ```
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use crate::{FIFO_DEPTH, mock::MockUartlite};
+
+/// Bridge between the wire side of a [MockUartlite] model and a byte stream.
+#[derive(Debug)]
+pub struct WireBridge<'mock, S> {
+    mock: &'mock MockUartlite,
+    stream: S,
+    pending_rx: VecDeque<u8>,
+}
+
+impl<'mock> WireBridge<'mock, TcpStream> {
+    /// Connect the model to a TCP server.
+    pub fn connect(mock: &'mock MockUartlite, addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(mock, stream))
+    }
+
+    /// Wait for a TCP client on the listener and connect the model to it.
+    pub fn accept(mock: &'mock MockUartlite, listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(mock, stream))
+    }
+}
+
+impl<'mock, S: Read + Write> WireBridge<'mock, S> {
+    /// Create a new bridge for the given stream.
+    ///
+    /// The stream should be configured as non-blocking, otherwise [Self::poll] blocks until
+    /// data is received from the stream.
+    pub fn new(mock: &'mock MockUartlite, stream: S) -> Self {
+        Self {
+            mock,
+            stream,
+            pending_rx: VecDeque::new(),
+        }
+    }
+
+    /// Underlying stream.
+    pub fn stream(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Number of bytes which were received from the stream but which did not fit into the
+    /// RX FIFO yet.
+    pub fn pending_rx(&self) -> usize {
+        self.pending_rx.len()
+    }
+
+    /// Move the data between the model and the stream.
+    ///
+    /// The TX FIFO is drained into the stream, and received bytes are moved into the RX FIFO
+    /// as long as it has space. Returns [io::ErrorKind::UnexpectedEof] if the remote end
+    /// closed the stream.
+    pub fn poll(&mut self) -> io::Result<()> {
+        let mut buf = [0; FIFO_DEPTH];
+        let tx_len = self.mock.pop_tx_slice(&mut buf);
+        if tx_len > 0 {
+            self.stream.write_all(&buf[0..tx_len])?;
+            self.stream.flush()?;
+        }
+        let mut buf = [0; 256];
+        match self.stream.read(&mut buf) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => self.pending_rx.extend(&buf[0..read]),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => (),
+            Err(e) => return Err(e),
+        }
+        while self.mock.rx_len() < FIFO_DEPTH {
+            match self.pending_rx.pop_front() {
+                Some(byte) => {
+                    self.mock.push_rx(byte);
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Release the stream.
+    pub fn release(self) -> S {
+        self.stream
+    }
+}
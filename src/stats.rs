@@ -0,0 +1,93 @@
+//! # Driver statistics
+//!
+//! This module is only available with the `stats` feature. The [Tx] and [Rx] drivers track
+//! cumulative [Stats] counters which can be used for long-running health monitoring. The
+//! counters of the [AxiUartlite] driver are the sum of the counters of both halves.
+//!
+//! All counters wrap around on overflow, so the difference of two snapshots stays correct as
+//! long as the snapshots are taken often enough. Alternatively, the `take_stats` methods return
+//! the counters and reset them.
+use crate::{AxiUartlite, Rx, Tx, registers::RegisterAccess};
+
+/// Cumulative driver statistics.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stats {
+    /// Number of bytes written into the TX FIFO.
+    pub tx_bytes: u32,
+    /// Number of bytes read from the RX FIFO.
+    pub rx_bytes: u32,
+    /// Number of FIFO resets.
+    pub fifo_resets: u32,
+    /// Number of serviced interrupts.
+    pub interrupts: u32,
+    /// Number of FIFO accesses which returned [nb::Error::WouldBlock].
+    pub would_block: u32,
+}
+
+impl Stats {
+    /// Create zeroed statistics.
+    pub const fn new() -> Self {
+        Self {
+            tx_bytes: 0,
+            rx_bytes: 0,
+            fifo_resets: 0,
+            interrupts: 0,
+            would_block: 0,
+        }
+    }
+
+    /// Sum of both statistics.
+    pub const fn merge(&self, other: &Stats) -> Stats {
+        Stats {
+            tx_bytes: self.tx_bytes.wrapping_add(other.tx_bytes),
+            rx_bytes: self.rx_bytes.wrapping_add(other.rx_bytes),
+            fifo_resets: self.fifo_resets.wrapping_add(other.fifo_resets),
+            interrupts: self.interrupts.wrapping_add(other.interrupts),
+            would_block: self.would_block.wrapping_add(other.would_block),
+        }
+    }
+}
+
+impl<R: RegisterAccess> Tx<R> {
+    /// Snapshot of the TX statistics.
+    #[inline]
+    pub const fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Snapshot of the TX statistics which also resets them.
+    #[inline]
+    pub fn take_stats(&mut self) -> Stats {
+        core::mem::take(&mut self.stats)
+    }
+}
+
+impl<R: RegisterAccess> Rx<R> {
+    /// Snapshot of the RX statistics.
+    #[inline]
+    pub const fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Snapshot of the RX statistics which also resets them.
+    #[inline]
+    pub fn take_stats(&mut self) -> Stats {
+        core::mem::take(&mut self.stats)
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Snapshot of the combined TX and RX statistics.
+    #[inline]
+    pub const fn stats(&self) -> Stats {
+        self.tx.stats.merge(&self.rx.stats)
+    }
+
+    /// Snapshot of the combined TX and RX statistics which also resets them.
+    #[inline]
+    pub fn take_stats(&mut self) -> Stats {
+        let tx = self.tx.take_stats();
+        tx.merge(&self.rx.take_stats())
+    }
+}
@@ -0,0 +1,187 @@
+//! # Read-only status handle
+//!
+//! The [StatusReader] can only read the status register. It can be cloned and used with a
+//! shared reference, so a monitor or health task can observe the FIFO and interrupt state
+//! concurrently with the driver which owns the writable registers.
+//!
+//! Reading the status register clears the error bits of the IP core. The errors observed by a
+//! [StatusReader] are therefore not reported by the owning driver anymore, and vice versa.
+//!
+//! The IP core has a single interrupt without a cause register. [InterruptReason::infer]
+//! classifies an interrupt from the status before and after the wakeup, which simplifies
+//! handlers of interrupt lines shared by multiple peripherals.
+use crate::{
+    AxiUartlite, Rx, Tx,
+    registers::{FixedMmio, MmioRegisters, RegisterAccess, Registers, Status},
+};
+
+/// Register backends which can hand out an additional handle for status register reads.
+///
+/// The returned handle must only be used for [RegisterAccess::read_stat_reg], which does not
+/// conflict with the accesses of the owning driver.
+pub trait ShareStatusReg: RegisterAccess {
+    /// Create an additional handle to the same register block.
+    fn share_status_reg(&self) -> Self;
+}
+
+impl ShareStatusReg for MmioRegisters<'_> {
+    #[inline]
+    fn share_status_reg(&self) -> Self {
+        // Safety: The handle is only used for status register reads.
+        unsafe { self.clone() }
+    }
+}
+
+impl<const BASE: usize> ShareStatusReg for FixedMmio<BASE> {
+    #[inline]
+    fn share_status_reg(&self) -> Self {
+        // Safety: The base address was already validated when creating this handle, and the
+        // new handle is only used for status register reads.
+        unsafe { FixedMmio::new() }
+    }
+}
+
+impl<'regs, T: ?Sized> ShareStatusReg for &'regs T
+where
+    &'regs T: RegisterAccess,
+{
+    #[inline]
+    fn share_status_reg(&self) -> Self {
+        self
+    }
+}
+
+/// Read-only handle to the status register of a UART Lite.
+pub struct StatusReader<R = MmioRegisters<'static>> {
+    regs: R,
+}
+
+impl StatusReader {
+    /// Create a status reader for the UART Lite at the given base address.
+    ///
+    /// # Safety
+    ///
+    /// The `base_addr` must be a valid memory-mapped register address of an AXI UART Lite
+    /// peripheral.
+    pub const unsafe fn new(base_addr: usize) -> Self {
+        Self {
+            regs: unsafe { Registers::new_mmio_at(base_addr) },
+        }
+    }
+}
+
+impl<R: RegisterAccess> StatusReader<R> {
+    /// Read the status register. This clears the error bits.
+    #[inline]
+    pub fn read(&self) -> Status {
+        self.regs.read_stat_reg()
+    }
+
+    /// Does the RX FIFO contain data?
+    #[inline]
+    pub fn rx_has_data(&self) -> bool {
+        self.read().rx_fifo_valid_data()
+    }
+
+    /// Is the TX FIFO empty?
+    #[inline]
+    pub fn tx_fifo_empty(&self) -> bool {
+        self.read().tx_fifo_empty()
+    }
+
+    /// Is the TX FIFO full?
+    #[inline]
+    pub fn tx_fifo_full(&self) -> bool {
+        self.read().tx_fifo_full()
+    }
+}
+
+impl<R: ShareStatusReg> Clone for StatusReader<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            regs: self.regs.share_status_reg(),
+        }
+    }
+}
+
+impl<R: RegisterAccess> core::fmt::Debug for StatusReader<R> {
+    /// Shows the base address, if available, and a snapshot of the status register. This
+    /// clears the error bits.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StatusReader")
+            .field(
+                "base_addr",
+                &crate::registers::DebugBaseAddr(self.regs.base_addr()),
+            )
+            .field("status", &self.read())
+            .finish()
+    }
+}
+
+/// Inferred reason of a UART Lite interrupt.
+///
+/// The IP core raises its interrupt when the RX FIFO receives data and when the TX FIFO
+/// becomes empty.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InterruptReason {
+    /// The RX FIFO contains data.
+    pub rx_data: bool,
+    /// The TX FIFO became empty.
+    pub tx_empty: bool,
+}
+
+impl InterruptReason {
+    /// Classify an interrupt from the last status seen before the wakeup, for example by the
+    /// previous interrupt or the last write, and the status read inside the interrupt handler.
+    ///
+    /// If the interrupt of the UART Lite is disabled, the interrupt is classified as
+    /// spurious.
+    pub const fn infer(status_before: &Status, status_after: &Status) -> Self {
+        if !status_after.intr_enabled() {
+            return Self {
+                rx_data: false,
+                tx_empty: false,
+            };
+        }
+        Self {
+            rx_data: status_after.rx_fifo_valid_data(),
+            tx_empty: status_after.tx_fifo_empty() && !status_before.tx_fifo_empty(),
+        }
+    }
+
+    /// The interrupt was not caused by this UART Lite, for example on a shared interrupt line.
+    #[inline]
+    pub const fn is_spurious(&self) -> bool {
+        !self.rx_data && !self.tx_empty
+    }
+}
+
+impl<R: ShareStatusReg> Tx<R> {
+    /// Create a read-only [StatusReader] for this UART Lite.
+    #[inline]
+    pub fn status_reader(&self) -> StatusReader<R> {
+        StatusReader {
+            regs: self.regs.share_status_reg(),
+        }
+    }
+}
+
+impl<R: ShareStatusReg> Rx<R> {
+    /// Create a read-only [StatusReader] for this UART Lite.
+    #[inline]
+    pub fn status_reader(&self) -> StatusReader<R> {
+        StatusReader {
+            regs: self.regs.share_status_reg(),
+        }
+    }
+}
+
+impl<R: ShareStatusReg> AxiUartlite<R> {
+    /// Create a read-only [StatusReader] for this UART Lite.
+    #[inline]
+    pub fn status_reader(&self) -> StatusReader<R> {
+        self.tx.status_reader()
+    }
+}
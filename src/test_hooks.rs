@@ -0,0 +1,118 @@
+//! # Test hooks
+//!
+//! This module is only available with the `test-hooks` feature. It adds FIFO state
+//! introspection and forced resets to the [Tx], [Rx] and [AxiUartlite] drivers, which are
+//! required by on-target test suites like the [crate::hil] routines to bring the peripheral
+//! into a known state between tests.
+use crate::{
+    AxiUartlite, Rx, RxErrors, RxErrorsCounted, Tx, handle_status_reg_errors,
+    registers::{Control, RegisterAccess, Status},
+};
+
+/// Snapshot of the FIFO and interrupt state of the UART Lite.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FifoState {
+    /// The RX FIFO contains data.
+    pub rx_valid_data: bool,
+    /// The RX FIFO is full.
+    pub rx_full: bool,
+    /// The TX FIFO is empty.
+    pub tx_empty: bool,
+    /// The TX FIFO is full.
+    pub tx_full: bool,
+    /// Interrupts are enabled.
+    pub intr_enabled: bool,
+    /// RX errors which were reported by the status register read.
+    pub errors: Option<RxErrors>,
+}
+
+impl FifoState {
+    /// Create the snapshot from a status register value.
+    pub const fn from_status(status: &Status) -> Self {
+        Self {
+            rx_valid_data: status.rx_fifo_valid_data(),
+            rx_full: status.rx_fifo_full(),
+            tx_empty: status.tx_fifo_empty(),
+            tx_full: status.tx_fifo_full(),
+            intr_enabled: status.intr_enabled(),
+            errors: handle_status_reg_errors(status),
+        }
+    }
+}
+
+fn reset_fifos<R: RegisterAccess>(regs: &mut R) {
+    let intr_enabled = regs.read_stat_reg().intr_enabled();
+    regs.write_ctrl_reg(
+        Control::builder()
+            .with_enable_interrupt(intr_enabled)
+            .with_reset_rx_fifo(true)
+            .with_reset_tx_fifo(true)
+            .build(),
+    );
+    // Clear the error bits.
+    regs.read_stat_reg();
+}
+
+impl<R: RegisterAccess> Tx<R> {
+    /// Read a snapshot of the FIFO state.
+    ///
+    /// RX errors reported by the status register read are also recorded for
+    /// [Self::read_and_clear_last_error].
+    pub fn fifo_state(&mut self) -> FifoState {
+        let state = FifoState::from_status(&self.regs.read_stat_reg());
+        if state.errors.is_some() {
+            self.errors = state.errors;
+        }
+        state
+    }
+
+    /// Reset both FIFOs and clear all recorded errors. The interrupt enable state is kept.
+    pub fn force_reset(&mut self) {
+        let control = self.control_state();
+        self.write_ctrl_reg(control, true, true);
+        // Clear the error bits.
+        self.regs.read_stat_reg();
+        self.errors = None;
+    }
+}
+
+impl<R: RegisterAccess> Rx<R> {
+    /// Read a snapshot of the FIFO state.
+    ///
+    /// RX errors reported by the status register read are also recorded for
+    /// [Self::read_and_clear_last_error].
+    pub fn fifo_state(&mut self) -> FifoState {
+        let state = FifoState::from_status(&self.regs.read_stat_reg());
+        if state.errors.is_some() {
+            self.errors = state.errors;
+        }
+        state
+    }
+
+    /// Reset both FIFOs and clear all recorded errors. The interrupt enable state is kept.
+    pub fn force_reset(&mut self) {
+        reset_fifos(&mut self.regs);
+        self.errors = None;
+    }
+}
+
+impl<R: RegisterAccess> AxiUartlite<R> {
+    /// Read a snapshot of the FIFO state.
+    ///
+    /// RX errors reported by the status register read are also added to the error counters.
+    pub fn fifo_state(&mut self) -> FifoState {
+        let state = FifoState::from_status(&self.regs().read_stat_reg());
+        if let Some(errors) = state.errors {
+            self.errors.add(errors);
+        }
+        state
+    }
+
+    /// Reset both FIFOs and clear all recorded errors and error counters. The interrupt
+    /// enable state is kept.
+    pub fn force_reset(&mut self) {
+        self.tx.force_reset();
+        self.rx.errors = None;
+        self.errors = RxErrorsCounted::new();
+    }
+}
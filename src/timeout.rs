@@ -0,0 +1,75 @@
+//! # Timeouts for driver futures
+//!
+//! This module is only available with the `embassy-time` feature. The [WithTimeout] extension
+//! trait adds [WithTimeout::with_timeout] to the futures of this crate, for example the futures
+//! returned by [crate::TxAsync::write] or [crate::SharedWriter::write].
+//!
+//! Racing a driver future against a timer with a generic `select` keeps the losing future
+//! alive until the `select` itself is dropped, and with it the transfer context and the lock of
+//! a shared writer. The [Timeout] future drops the driver future as soon as the timeout expires,
+//! so the driver cleanup, for example clearing the TX transfer context according to the
+//! [crate::TxDropPolicy], has already run when the [TimeoutError] is returned.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use axi_uartlite::timeout::WithTimeout;
+//! use embassy_time::Duration;
+//!
+//! if tx.write(b"ping\n").with_timeout(Duration::from_millis(10)).await.is_err() {
+//!     // The transfer was cancelled and the TX driver can be used again.
+//! }
+//! ```
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use embassy_time::Timer;
+pub use embassy_time::{Duration, TimeoutError};
+
+/// Future returned by [WithTimeout::with_timeout].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Timeout<F> {
+    fut: Option<F>,
+    timer: Timer,
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, TimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: The inner future is never moved. It is only dropped in place, which is
+        // allowed for pinned data.
+        let this = unsafe { self.get_unchecked_mut() };
+        let Some(fut) = this.fut.as_mut() else {
+            panic!("timeout future polled after completion");
+        };
+        if let Poll::Ready(output) = unsafe { Pin::new_unchecked(fut) }.poll(cx) {
+            this.fut = None;
+            return Poll::Ready(Ok(output));
+        }
+        if Pin::new(&mut this.timer).poll(cx).is_ready() {
+            // Drop the driver future immediately to run its cleanup.
+            this.fut = None;
+            return Poll::Ready(Err(TimeoutError));
+        }
+        Poll::Pending
+    }
+}
+
+/// Extension trait which adds a timeout to the futures of this crate.
+pub trait WithTimeout: Future + Sized {
+    /// Complete with [TimeoutError] if the future did not complete within the given duration.
+    ///
+    /// The future is dropped when the timeout expires, which cancels the operation.
+    fn with_timeout(self, timeout: Duration) -> Timeout<Self> {
+        Timeout {
+            fut: Some(self),
+            timer: Timer::after(timeout),
+        }
+    }
+}
+
+impl<F: Future> WithTimeout for F {}
@@ -0,0 +1,83 @@
+//! # Driver instance tracking
+//!
+//! This module is only available with the `instance-tracking` feature. It keeps a small static
+//! table of base addresses for which an [crate::AxiUartlite] driver was created with
+//! [crate::AxiUartlite::new_tracked], which allows catching accidental double instantiation of
+//! the same peripheral early.
+//!
+//! Handles created with [crate::Tx::steal] and [crate::Rx::steal] are not tracked, because
+//! stealing is the documented way to access a peripheral from an interrupt handler while the
+//! main driver is still alive.
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+/// Maximum number of driver instances which can be tracked.
+pub const MAX_TRACKED_INSTANCES: usize = 8;
+
+static INSTANCES: Mutex<RefCell<[Option<usize>; MAX_TRACKED_INSTANCES]>> =
+    Mutex::new(RefCell::new([None; MAX_TRACKED_INSTANCES]));
+
+/// Instance tracking error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InstanceTrackingError {
+    /// A driver for the given base address was already created.
+    AlreadyExists(usize),
+    /// The instance table is full, see [MAX_TRACKED_INSTANCES].
+    TableFull,
+}
+
+impl core::fmt::Display for InstanceTrackingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::AlreadyExists(addr) => {
+                write!(f, "driver for base address {addr:#x} already exists")
+            }
+            Self::TableFull => write!(f, "instance tracking table is full"),
+        }
+    }
+}
+
+impl core::error::Error for InstanceTrackingError {}
+
+/// Register the given base address as being in use.
+pub fn track_instance(base_addr: usize) -> Result<(), InstanceTrackingError> {
+    critical_section::with(|cs| {
+        let mut instances = INSTANCES.borrow(cs).borrow_mut();
+        if instances.contains(&Some(base_addr)) {
+            return Err(InstanceTrackingError::AlreadyExists(base_addr));
+        }
+        let free_slot = instances
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(InstanceTrackingError::TableFull)?;
+        *free_slot = Some(base_addr);
+        Ok(())
+    })
+}
+
+/// Remove the given base address from the instance table.
+///
+/// Returns [false] if the base address was not tracked.
+///
+/// # Safety
+///
+/// The caller must ensure that the driver which was created for this base address is not used
+/// anymore.
+pub unsafe fn untrack_instance(base_addr: usize) -> bool {
+    critical_section::with(|cs| {
+        let mut instances = INSTANCES.borrow(cs).borrow_mut();
+        match instances.iter_mut().find(|slot| **slot == Some(base_addr)) {
+            Some(slot) => {
+                *slot = None;
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Check whether a driver was created for the given base address.
+pub fn is_instance_tracked(base_addr: usize) -> bool {
+    critical_section::with(|cs| INSTANCES.borrow(cs).borrow().contains(&Some(base_addr)))
+}
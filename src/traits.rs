@@ -0,0 +1,137 @@
+//! # Generic driver traits
+//!
+//! The [UartLiteTx] and [UartLiteRx] traits are implemented by the combined [AxiUartlite]
+//! driver and by the respective split half, so application helpers can be written once and
+//! accept either of them. [UartLiteIo] combines both traits.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn send_banner(tx: &mut impl UartLiteTx) {
+//!     let mut rest: &[u8] = b"booted\r\n";
+//!     while !rest.is_empty() {
+//!         rest = &rest[tx.write_burst(rest)..];
+//!     }
+//! }
+//!
+//! send_banner(&mut uart);
+//! let (mut tx, rx) = uart.split();
+//! send_banner(&mut tx);
+//! ```
+use core::convert::Infallible;
+
+use crate::{AxiUartlite, Rx, Tx, registers::RegisterAccess};
+
+/// Transmit side of a UART Lite driver.
+pub trait UartLiteTx {
+    /// Write a byte into the TX FIFO, [nb::Error::WouldBlock] if the FIFO is full.
+    fn write_fifo(&mut self, data: u8) -> nb::Result<(), Infallible>;
+
+    /// Write as many bytes as possible into the TX FIFO without blocking and return the number
+    /// of written bytes.
+    fn write_burst(&mut self, buf: &[u8]) -> usize;
+
+    /// Is the TX FIFO empty?
+    fn tx_fifo_empty(&self) -> bool;
+
+    /// Is the TX FIFO full?
+    fn tx_fifo_full(&self) -> bool;
+}
+
+/// Receive side of a UART Lite driver.
+pub trait UartLiteRx {
+    /// Read a byte from the RX FIFO, [nb::Error::WouldBlock] if the FIFO is empty.
+    fn read_fifo(&mut self) -> nb::Result<u8, Infallible>;
+
+    /// Read as many bytes as possible from the RX FIFO without blocking and return the number
+    /// of read bytes.
+    fn read_nonblocking(&mut self, buf: &mut [u8]) -> usize;
+
+    /// Does the RX FIFO have data?
+    fn rx_has_data(&self) -> bool;
+}
+
+/// Combined transmit and receive side of a UART Lite driver.
+///
+/// This is implemented for all types which implement [UartLiteTx] and [UartLiteRx].
+pub trait UartLiteIo: UartLiteTx + UartLiteRx {}
+
+impl<T: UartLiteTx + UartLiteRx> UartLiteIo for T {}
+
+impl<R: RegisterAccess> UartLiteTx for Tx<R> {
+    #[inline]
+    fn write_fifo(&mut self, data: u8) -> nb::Result<(), Infallible> {
+        Tx::write_fifo(self, data)
+    }
+
+    #[inline]
+    fn write_burst(&mut self, buf: &[u8]) -> usize {
+        Tx::write_burst(self, buf)
+    }
+
+    #[inline]
+    fn tx_fifo_empty(&self) -> bool {
+        self.fifo_empty()
+    }
+
+    #[inline]
+    fn tx_fifo_full(&self) -> bool {
+        self.fifo_full()
+    }
+}
+
+impl<R: RegisterAccess> UartLiteRx for Rx<R> {
+    #[inline]
+    fn read_fifo(&mut self) -> nb::Result<u8, Infallible> {
+        Rx::read_fifo(self)
+    }
+
+    #[inline]
+    fn read_nonblocking(&mut self, buf: &mut [u8]) -> usize {
+        Rx::read_nonblocking(self, buf)
+    }
+
+    #[inline]
+    fn rx_has_data(&self) -> bool {
+        self.has_data()
+    }
+}
+
+impl<R: RegisterAccess> UartLiteTx for AxiUartlite<R> {
+    #[inline]
+    fn write_fifo(&mut self, data: u8) -> nb::Result<(), Infallible> {
+        AxiUartlite::write_fifo(self, data)
+    }
+
+    #[inline]
+    fn write_burst(&mut self, buf: &[u8]) -> usize {
+        AxiUartlite::write_burst(self, buf)
+    }
+
+    #[inline]
+    fn tx_fifo_empty(&self) -> bool {
+        AxiUartlite::tx_fifo_empty(self)
+    }
+
+    #[inline]
+    fn tx_fifo_full(&self) -> bool {
+        AxiUartlite::tx_fifo_full(self)
+    }
+}
+
+impl<R: RegisterAccess> UartLiteRx for AxiUartlite<R> {
+    #[inline]
+    fn read_fifo(&mut self) -> nb::Result<u8, Infallible> {
+        AxiUartlite::read_fifo(self)
+    }
+
+    #[inline]
+    fn read_nonblocking(&mut self, buf: &mut [u8]) -> usize {
+        AxiUartlite::read_nonblocking(self, buf)
+    }
+
+    #[inline]
+    fn rx_has_data(&self) -> bool {
+        AxiUartlite::rx_has_data(self)
+    }
+}
@@ -0,0 +1,96 @@
+//! # Poll-driven TX transfers
+//!
+//! This module provides the [Transfer] state machine, which gives non-async firmware background
+//! transmission without interrupts. A transfer is started with [Transfer::start] and advanced
+//! with [Transfer::pump], which is called from the main superloop. Each call writes at most one
+//! burst into the TX FIFO, see [Tx::write_burst], so it never blocks.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut transfer = Transfer::new(tx);
+//! transfer.start(b"hello world\n");
+//! loop {
+//!     if let Poll::Ready(written) = transfer.pump() {
+//!         // The transfer is complete.
+//!     }
+//!     // Other superloop work.
+//! }
+//! ```
+use core::task::Poll;
+
+use crate::{
+    Tx,
+    registers::{MmioRegisters, RegisterAccess},
+};
+
+/// Poll-driven TX transfer state machine.
+pub struct Transfer<'buf, R = MmioRegisters<'static>> {
+    tx: Tx<R>,
+    buf: &'buf [u8],
+    written: usize,
+}
+
+impl<'buf, R: RegisterAccess> Transfer<'buf, R> {
+    /// Create a new idle transfer state machine which owns the TX handle.
+    pub const fn new(tx: Tx<R>) -> Self {
+        Self {
+            tx,
+            buf: &[],
+            written: 0,
+        }
+    }
+
+    /// Start a new transfer.
+    ///
+    /// An active transfer is aborted. Bytes of the aborted transfer which were already written
+    /// into the TX FIFO are still sent. No data is written until [Self::pump] is called.
+    pub fn start(&mut self, buf: &'buf [u8]) {
+        self.buf = buf;
+        self.written = 0;
+    }
+
+    /// Advance the transfer.
+    ///
+    /// Returns [Poll::Ready] with the length of the transfer once all bytes were written into
+    /// the TX FIFO, and [Poll::Pending] otherwise. An idle state machine returns
+    /// [Poll::Ready] with the length of the last transfer.
+    pub fn pump(&mut self) -> Poll<usize> {
+        if self.written < self.buf.len() {
+            self.written += self.tx.write_burst(&self.buf[self.written..]);
+        }
+        if self.written < self.buf.len() {
+            return Poll::Pending;
+        }
+        Poll::Ready(self.written)
+    }
+
+    /// Number of bytes written into the TX FIFO for the current transfer.
+    #[inline]
+    pub const fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Number of bytes which still have to be written into the TX FIFO.
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        self.buf.len() - self.written
+    }
+
+    /// Is a transfer active?
+    #[inline]
+    pub const fn is_active(&self) -> bool {
+        self.written < self.buf.len()
+    }
+
+    /// Access the owned TX handle, for example to check for RX errors recorded by the transfer.
+    #[inline]
+    pub const fn tx(&mut self) -> &mut Tx<R> {
+        &mut self.tx
+    }
+
+    /// Release the owned TX handle. An active transfer is aborted.
+    pub fn release(self) -> Tx<R> {
+        self.tx
+    }
+}
@@ -2,17 +2,58 @@
 use core::convert::Infallible;
 
 use crate::{
-    RxErrors, handle_status_reg_errors,
-    registers::{self, Control, TxFifo},
+    FIFO_DEPTH, HwConfig, RxErrors, handle_status_reg_errors,
+    registers::{
+        self, ControlState, DebugBaseAddr, FixedMmio, MmioRegisters, RegisterAccess, TxFifo,
+    },
 };
 
+/// Factor applied to the FIFO drain time of the [HwConfig] to get the stuck transmitter limit
+/// of [Tx::flush_with_watchdog].
+pub const TX_WATCHDOG_DRAIN_FACTOR: u32 = 4;
+
+/// The TX FIFO did not drain within the watchdog limit.
+///
+/// This is a sign that the IP core is held in reset or its clock is gone.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxStuck {
+    /// Number of clock ticks waited for the TX FIFO to drain.
+    pub waited_ticks: u64,
+}
+
+impl core::fmt::Display for TxStuck {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "TX FIFO did not drain within {} ticks, transmitter is stuck",
+            self.waited_ticks
+        )
+    }
+}
+
+impl core::error::Error for TxStuck {}
+
 /// AXI UARTLITE TX driver.
 ///
 /// Can be created by [super::AxiUartlite::split]ting a regular AXI UARTLITE structure or
 /// by [Self::steal]ing it unsafely.
-pub struct Tx {
-    pub(crate) regs: registers::MmioRegisters<'static>,
+///
+/// The driver is generic over the [RegisterAccess] backend, which defaults to the
+/// memory-mapped register block.
+pub struct Tx<R = MmioRegisters<'static>> {
+    pub(crate) regs: R,
     pub(crate) errors: Option<RxErrors>,
+    /// Shadow of the intended interrupt enable state, [None] if it is not known yet.
+    pub(crate) control: Option<ControlState>,
+    /// Status register value of the last [Tx::status_snapshot], shown by the Debug output.
+    pub(crate) last_status: Option<registers::Status>,
+    #[cfg(feature = "stats")]
+    pub(crate) stats: crate::Stats,
+    #[cfg(feature = "debug-audit")]
+    pub(crate) audit: crate::ControlAudit,
+    #[cfg(feature = "debug-audit")]
+    pub(crate) audit_clock: Option<fn() -> u64>,
 }
 
 impl Tx {
@@ -23,7 +64,7 @@ impl Tx {
     ///
     /// This function assumes that the setup of the UART was already done.
     /// It can be used to create a TX handle inside an interrupt handler without having to use
-    /// a [critical_section::Mutex] if the user can guarantee that the TX handle will only be
+    /// a `critical_section::Mutex` if the user can guarantee that the TX handle will only be
     /// used by the interrupt handler, or only interrupt specific API will be used.
     ///
     /// # Safety
@@ -31,7 +72,70 @@ impl Tx {
     /// The same safey rules specified in [super::AxiUartlite] apply.
     pub unsafe fn steal(base_addr: usize) -> Self {
         let regs = unsafe { registers::Registers::new_mmio_at(base_addr) };
-        Self { regs, errors: None }
+        Self::new_with_regs(regs)
+    }
+}
+
+impl Tx<MmioRegisters<'_>> {
+    /// Raw pointer to the register block, for example for FFI code or debugger scripts.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut registers::Registers {
+        // Safety: Retrieving the pointer does not access the registers. Dereferencing it is
+        // left to the unsafe code of the caller.
+        unsafe { self.regs.ptr() }
+    }
+}
+
+/// TX driver for a UART Lite at the fixed base address `BASE`, see [FixedMmio].
+pub type TxAt<const BASE: usize> = Tx<FixedMmio<BASE>>;
+
+impl<const BASE: usize> Tx<FixedMmio<BASE>> {
+    /// Steal the TX part of the UART Lite at the fixed base address `BASE`.
+    ///
+    /// This is the fixed address variant of [Tx::steal].
+    ///
+    /// # Safety
+    ///
+    /// The same safey rules specified in [super::AxiUartlite] apply.
+    #[inline]
+    pub const unsafe fn steal_fixed() -> Self {
+        Self::new_with_regs(unsafe { FixedMmio::new() })
+    }
+
+    /// Raw pointer to the register block at `BASE`.
+    #[inline(always)]
+    pub const fn as_ptr(&self) -> *mut registers::Registers {
+        BASE as *mut registers::Registers
+    }
+}
+
+impl<R: RegisterAccess> Tx<R> {
+    /// Create a TX handle from a register backend.
+    ///
+    /// This function assumes that the setup of the UART was already done.
+    #[inline]
+    pub const fn new_with_regs(regs: R) -> Self {
+        Self {
+            regs,
+            errors: None,
+            control: None,
+            last_status: None,
+            #[cfg(feature = "stats")]
+            stats: crate::Stats::new(),
+            #[cfg(feature = "debug-audit")]
+            audit: crate::ControlAudit::new(),
+            #[cfg(feature = "debug-audit")]
+            audit_clock: None,
+        }
+    }
+
+    /// Release the register backend, for example to temporarily use raw register access.
+    ///
+    /// The driver can be rebuilt with [Self::new_with_regs] afterwards. The recorded RX errors
+    /// are discarded.
+    #[inline]
+    pub fn release(self) -> R {
+        self.regs
     }
 
     /// Write into the UART Lite.
@@ -41,26 +145,62 @@ impl Tx {
     pub fn write_fifo(&mut self, data: u8) -> nb::Result<(), Infallible> {
         let status_reg = self.regs.read_stat_reg();
         if status_reg.tx_fifo_full() {
+            #[cfg(feature = "stats")]
+            {
+                self.stats.would_block = self.stats.would_block.wrapping_add(1);
+            }
             return Err(nb::Error::WouldBlock);
         }
         self.write_fifo_unchecked(data);
         if let Some(errors) = handle_status_reg_errors(&status_reg) {
+            #[cfg(feature = "hooks")]
+            crate::hooks::call(|hooks| hooks.on_error(errors));
             self.errors = Some(errors);
         }
         Ok(())
     }
 
+    /// Write into the UART Lite without capturing RX errors.
+    ///
+    /// This only checks the TX FIFO full bit, which reduces the per-byte work for
+    /// applications which handle RX errors elsewhere. The status register read clears the
+    /// error bits, so RX errors reported by this read are lost.
+    ///
+    /// Returns [nb::Error::WouldBlock] if the TX FIFO is full.
+    #[inline]
+    pub fn write_fifo_fast(&mut self, data: u8) -> nb::Result<(), Infallible> {
+        if self.regs.read_stat_reg().tx_fifo_full() {
+            #[cfg(feature = "stats")]
+            {
+                self.stats.would_block = self.stats.would_block.wrapping_add(1);
+            }
+            return Err(nb::Error::WouldBlock);
+        }
+        self.write_fifo_unchecked(data);
+        Ok(())
+    }
+
     /// Reset the TX FIFO.
     #[inline]
     pub fn reset_fifo(&mut self) {
-        let status = self.regs.read_stat_reg();
-        self.regs.write_ctrl_reg(
-            Control::builder()
-                .with_enable_interrupt(status.intr_enabled())
-                .with_reset_rx_fifo(false)
-                .with_reset_tx_fifo(true)
-                .build(),
-        );
+        let control = self.control_state();
+        self.write_ctrl_reg(control, false, true);
+        #[cfg(feature = "stats")]
+        {
+            self.stats.fifo_resets = self.stats.fifo_resets.wrapping_add(1);
+        }
+    }
+
+    /// Reset the RX FIFO through the control register, which is shared by both directions.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub(crate) fn reset_rx_fifo(&mut self) {
+        let control = self.control_state();
+        self.write_ctrl_reg(control, true, false);
+        #[cfg(feature = "stats")]
+        {
+            self.stats.fifo_resets = self.stats.fifo_resets.wrapping_add(1);
+        }
     }
 
     /// Write into the FIFO without checking the FIFO fill status.
@@ -70,6 +210,27 @@ impl Tx {
     pub fn write_fifo_unchecked(&mut self, data: u8) {
         self.regs
             .write_tx_fifo(TxFifo::new_with_raw_value(data as u32));
+        #[cfg(feature = "stats")]
+        {
+            self.stats.tx_bytes = self.stats.tx_bytes.wrapping_add(1);
+        }
+        #[cfg(feature = "hooks")]
+        crate::hooks::call(|hooks| hooks.on_tx_byte(data));
+    }
+
+    /// Write up to [FIFO_DEPTH] bytes from the slice without checking the FIFO fill status.
+    ///
+    /// No status register is read, so this can be used by interrupt handlers and by callers
+    /// which have just verified that the TX FIFO is empty.
+    ///
+    /// Returns the amount of written data.
+    #[inline]
+    pub fn write_fifo_unchecked_slice(&mut self, buf: &[u8]) -> usize {
+        let len = buf.len().min(FIFO_DEPTH);
+        for &byte in &buf[0..len] {
+            self.write_fifo_unchecked(byte);
+        }
+        len
     }
 
     /// Is the TX FIFO empty?
@@ -84,6 +245,178 @@ impl Tx {
         self.regs.read_stat_reg().tx_fifo_full()
     }
 
+    /// Write a burst of bytes with a single status register read.
+    ///
+    /// The status register only reports whether the TX FIFO is empty or full, so up to
+    /// [FIFO_DEPTH] bytes are written if the FIFO is empty, one byte is written if the FIFO is
+    /// neither empty nor full, and nothing is written if the FIFO is full. RX errors reported by
+    /// the status register read are recorded for [Self::read_and_clear_last_error].
+    ///
+    /// Returns the amount of written data.
+    pub fn write_burst(&mut self, buf: &[u8]) -> usize {
+        if buf.is_empty() {
+            return 0;
+        }
+        let status_reg = self.regs.read_stat_reg();
+        if let Some(errors) = handle_status_reg_errors(&status_reg) {
+            #[cfg(feature = "hooks")]
+            crate::hooks::call(|hooks| hooks.on_error(errors));
+            self.errors = Some(errors);
+        }
+        if status_reg.tx_fifo_empty() {
+            return self.write_fifo_unchecked_slice(buf);
+        }
+        if status_reg.tx_fifo_full() {
+            #[cfg(feature = "stats")]
+            {
+                self.stats.would_block = self.stats.would_block.wrapping_add(1);
+            }
+            return 0;
+        }
+        self.write_fifo_unchecked(buf[0]);
+        1
+    }
+
+    /// Write as much of the buffer as possible with at most `max_status_polls` status register
+    /// reads.
+    ///
+    /// Each poll writes a burst like [Self::write_burst]. This bounds the time spent waiting
+    /// for a full FIFO, which makes it easy to interleave UART output with other tasks of a
+    /// superloop. The remainder of the buffer can be written in the next iteration.
+    ///
+    /// Returns the amount of written data.
+    pub fn write_with_budget(&mut self, buf: &[u8], max_status_polls: u32) -> usize {
+        let mut written = 0;
+        for _ in 0..max_status_polls {
+            if written == buf.len() {
+                break;
+            }
+            written += self.write_burst(&buf[written..]);
+        }
+        written
+    }
+
+    /// Write bytes which are pulled on demand from the passed closure while the TX FIFO has
+    /// space.
+    ///
+    /// A byte is only pulled if it can be written, so no byte is lost if the FIFO is full.
+    /// This enables streaming encoders without intermediate buffers. The function returns if
+    /// the FIFO is full or the closure returned [None]. Like [Self::write_burst], up to
+    /// [FIFO_DEPTH] bytes are written per status register read if the FIFO is empty.
+    ///
+    /// Returns the number of written bytes.
+    pub fn write_from(&mut self, mut next: impl FnMut() -> Option<u8>) -> usize {
+        let mut written = 0;
+        loop {
+            let status_reg = self.regs.read_stat_reg();
+            if let Some(errors) = handle_status_reg_errors(&status_reg) {
+                #[cfg(feature = "hooks")]
+                crate::hooks::call(|hooks| hooks.on_error(errors));
+                self.errors = Some(errors);
+            }
+            if status_reg.tx_fifo_full() {
+                #[cfg(feature = "stats")]
+                {
+                    self.stats.would_block = self.stats.would_block.wrapping_add(1);
+                }
+                return written;
+            }
+            let space = if status_reg.tx_fifo_empty() {
+                FIFO_DEPTH
+            } else {
+                1
+            };
+            for _ in 0..space {
+                let Some(byte) = next() else {
+                    return written;
+                };
+                self.write_fifo_unchecked(byte);
+                written += 1;
+            }
+        }
+    }
+
+    /// Blocking write of multiple buffers as a single logical write.
+    ///
+    /// The buffers are written back-to-back like [Self::write_from], so a TX FIFO burst can
+    /// span several buffers. This avoids concatenating separate header and payload buffers.
+    ///
+    /// Returns the total number of written bytes.
+    pub fn write_vectored(&mut self, bufs: &[&[u8]]) -> usize {
+        let total = bufs.iter().map(|buf| buf.len()).sum();
+        let mut bytes = bufs.iter().flat_map(|buf| buf.iter().copied());
+        let mut written = 0;
+        while written < total {
+            written += self.write_from(|| bytes.next());
+        }
+        written
+    }
+
+    /// Blocking write of the whole buffer which only returns after the data has left the TX
+    /// pin.
+    ///
+    /// The UART Lite only reports when the TX FIFO is empty, so after the FIFO drained, the
+    /// `delay_ns` closure is called with one character time of the given hardware
+    /// configuration to wait for the last byte in the shift register. This is required before
+    /// powering down the programmable logic or switching a multiplexer.
+    pub fn write_all_and_drain(
+        &mut self,
+        buf: &[u8],
+        hw_config: &HwConfig,
+        mut delay_ns: impl FnMut(u32),
+    ) {
+        let mut written = 0;
+        while written < buf.len() {
+            written += self.write_burst(&buf[written..]);
+        }
+        while !self.fifo_empty() {}
+        delay_ns(u32::try_from(hw_config.char_time_ns()).unwrap_or(u32::MAX));
+    }
+
+    /// Blocking flush with a stuck transmitter watchdog.
+    ///
+    /// Waits until the TX FIFO is empty like a regular flush, but gives up with [TxStuck] if
+    /// the FIFO stays non-empty for [TX_WATCHDOG_DRAIN_FACTOR] times the FIFO drain time of
+    /// the given hardware configuration. The `now` closure is a free-running clock with a
+    /// frequency of `tick_hz`, wrapping is handled.
+    pub fn flush_with_watchdog(
+        &mut self,
+        hw_config: &HwConfig,
+        now: impl FnMut() -> u64,
+        tick_hz: u64,
+    ) -> Result<(), TxStuck> {
+        let limit =
+            hw_config.chars_time_ticks(FIFO_DEPTH as u32 * TX_WATCHDOG_DRAIN_FACTOR, tick_hz);
+        self.flush_within(limit, now)
+    }
+
+    /// Blocking flush which is bounded by the character time of the hardware configuration.
+    ///
+    /// A full TX FIFO plus the byte in the shift register drains within [FIFO_DEPTH] + 1
+    /// character times, so this is the tightest bound for [Self::flush_with_watchdog]. The
+    /// function gives up with [TxStuck] after this time.
+    pub fn flush_bounded(
+        &mut self,
+        hw_config: &HwConfig,
+        now: impl FnMut() -> u64,
+        tick_hz: u64,
+    ) -> Result<(), TxStuck> {
+        let limit = hw_config.chars_time_ticks(FIFO_DEPTH as u32 + 1, tick_hz);
+        self.flush_within(limit, now)
+    }
+
+    /// Wait until the TX FIFO is empty, giving up with [TxStuck] after `limit` clock ticks.
+    fn flush_within(&mut self, limit: u64, mut now: impl FnMut() -> u64) -> Result<(), TxStuck> {
+        let start = now();
+        while !self.fifo_empty() {
+            let waited_ticks = now().wrapping_sub(start);
+            if waited_ticks > limit {
+                return Err(TxStuck { waited_ticks });
+            }
+        }
+        Ok(())
+    }
+
     /// Fills the FIFO with user provided data until the user data
     /// is consumed or the FIFO is full.
     ///
@@ -99,19 +432,150 @@ impl Tx {
         written
     }
 
+    /// Refill the TX FIFO from a [heapless::spsc::Consumer].
+    ///
+    /// Bytes are only removed from the queue if they could be written into the FIFO. Returns
+    /// the number of bytes written.
+    #[cfg(feature = "heapless")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "heapless")))]
+    pub fn refill_from<const N: usize>(
+        &mut self,
+        consumer: &mut heapless::spsc::Consumer<'_, u8, N>,
+    ) -> usize {
+        let mut written = 0;
+        while let Some(&byte) = consumer.peek() {
+            match self.write_fifo(byte) {
+                Ok(_) => {
+                    consumer.dequeue();
+                    written += 1;
+                }
+                Err(nb::Error::WouldBlock) => break,
+            }
+        }
+        written
+    }
+
+    /// Read the status register, for example for a diagnostic dump.
+    ///
+    /// This has side effects: the read clears the RX error bits of the status register. The
+    /// errors are recorded for [Self::read_and_clear_last_error], so they are not lost.
+    pub fn status_snapshot(&mut self) -> registers::Status {
+        self.read_status()
+    }
+
+    /// Read the status register and record the RX errors which are cleared by the read.
+    #[inline]
+    pub(crate) fn read_status(&mut self) -> registers::Status {
+        let status_reg = self.regs.read_stat_reg();
+        if let Some(errors) = handle_status_reg_errors(&status_reg) {
+            #[cfg(feature = "hooks")]
+            crate::hooks::call(|hooks| hooks.on_error(errors));
+            self.errors = Some(errors);
+        }
+        self.last_status = Some(status_reg);
+        status_reg
+    }
+
     /// Read and clear the last recorded RX errors.
     pub fn read_and_clear_last_error(&mut self) -> Option<RxErrors> {
         let errors = self.errors?;
         self.errors = None;
         Some(errors)
     }
+
+    /// Forget the cached interrupt enable state.
+    ///
+    /// The driver keeps a shadow of the interrupt enable state, so control register writes like
+    /// [Self::reset_fifo] do not have to read the status register. The shadow is initialized
+    /// from the status register on first use and updated by the interrupt control methods of
+    /// [super::AxiUartlite]. This method has to be called if the interrupt enable state was
+    /// changed through another handle.
+    pub fn invalidate_interrupt_shadow(&mut self) {
+        self.control = None;
+    }
+
+    /// Shadow of the write-only control register.
+    ///
+    /// Reads the status register if the state is not known yet. RX errors reported by this read
+    /// are recorded for [Self::read_and_clear_last_error].
+    pub fn control_state(&mut self) -> ControlState {
+        if let Some(control) = self.control {
+            return control;
+        }
+        let status_reg = self.regs.read_stat_reg();
+        if let Some(errors) = handle_status_reg_errors(&status_reg) {
+            self.errors = Some(errors);
+        }
+        let control = ControlState::from_status(&status_reg);
+        self.control = Some(control);
+        control
+    }
+
+    /// Write the given state into the control register and update the shadow.
+    #[inline]
+    pub fn apply_control(&mut self, control: ControlState) {
+        self.write_ctrl_reg(control, false, false);
+    }
+
+    /// Write the control register with the given one-shot FIFO resets and update the shadow.
+    #[inline]
+    pub(crate) fn write_ctrl_reg(&mut self, control: ControlState, reset_rx: bool, reset_tx: bool) {
+        let value = control.apply(reset_rx, reset_tx);
+        self.regs.write_ctrl_reg(value);
+        #[cfg(feature = "debug-audit")]
+        self.record_control_write(value.raw_value());
+        self.control = Some(control);
+    }
 }
 
-impl embedded_hal_nb::serial::ErrorType for Tx {
+impl<R: RegisterAccess> core::fmt::Debug for Tx<R> {
+    /// Shows the base address, the control register shadow, the status register value of the
+    /// last [Tx::status_snapshot] and the last recorded errors.
+    ///
+    /// No register is accessed, so the status is [None] until the first snapshot was taken.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Tx")
+            .field("base_addr", &DebugBaseAddr(self.regs.base_addr()))
+            .field("control", &self.control)
+            .field("last_status", &self.last_status)
+            .field("last_error", &self.errors)
+            .finish()
+    }
+}
+
+// Safety: The handle owns all of its state, including the interrupt enable shadow and the
+// optional statistics and audit ring, so moving it never shares that state with another
+// execution context. The only member which is not `Send` is the raw register block pointer. It
+// points to a peripheral which is mapped for the whole program lifetime and can be accessed from
+// every execution context, including interrupt handlers. Exclusive access to the TX FIFO
+// follows from the ownership of the handle, like on the original execution context.
+unsafe impl Send for Tx {}
+
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::ErrorType for Tx<R> {
     type Error = Infallible;
 }
 
-impl embedded_hal_nb::serial::Write for Tx {
+#[cfg(feature = "embedded-hal-nb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-hal-nb")))]
+impl<R: RegisterAccess> embedded_hal_nb::serial::Write for Tx<R> {
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.write_fifo(word)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        while !self.fifo_empty() {}
+        Ok(())
+    }
+}
+
+#[cfg(feature = "eh02")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eh02")))]
+impl<R: RegisterAccess> embedded_hal_02::serial::Write<u8> for Tx<R> {
+    type Error = Infallible;
+
+    #[inline]
     fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
         self.write_fifo(word)
     }
@@ -122,21 +586,29 @@ impl embedded_hal_nb::serial::Write for Tx {
     }
 }
 
-impl embedded_io::ErrorType for Tx {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ErrorType for Tx<R> {
     type Error = Infallible;
 }
 
-impl embedded_io::Write for Tx {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::Write for Tx<R> {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         if buf.is_empty() {
             return Ok(0);
         }
-        while self.fifo_full() {}
+        // Status register reads clear the error bits, so poll with burst writes which capture
+        // the errors.
         let mut written = 0;
-        for &byte in buf.iter() {
-            match self.write_fifo(byte) {
-                Ok(_) => written += 1,
-                Err(nb::Error::WouldBlock) => break,
+        while written == 0 {
+            written = self.write_burst(buf);
+        }
+        while written < buf.len() {
+            match self.write_burst(&buf[written..]) {
+                0 => break,
+                burst => written += burst,
             }
         }
         Ok(written)
@@ -147,3 +619,22 @@ impl embedded_io::Write for Tx {
         Ok(())
     }
 }
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::WriteReady for Tx<R> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        // Status register reads clear the error bits, so the errors are recorded.
+        Ok(!self.read_status().tx_fifo_full())
+    }
+}
+
+impl<R: RegisterAccess> core::fmt::Write for Tx<R> {
+    /// Blocking write of the string.
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &byte in s.as_bytes() {
+            nb::block!(self.write_fifo(byte)).unwrap();
+        }
+        Ok(())
+    }
+}
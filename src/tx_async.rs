@@ -7,6 +7,11 @@
 //! to be to explicitely assigned a waker when creating an awaitable [TxAsync] structure
 //! as well as when calling the [on_interrupt_tx] handler.
 //!
+//! An [crate::RxAsync] structure of the same UART Lite instance can share the waker slot of
+//! its [TxAsync] counterpart, because the IP core only has one interrupt line. A full-duplex
+//! pair therefore only needs a single slot, and a single [on_interrupt_tx_rx] call in the
+//! interrupt handler services both directions.
+//!
 //! The maximum number of available wakers is configured via the waker feature flags:
 //!
 //! - `1-waker`
@@ -15,13 +20,20 @@
 //! - `8-wakers`
 //! - `16-wakers`
 //! - `32-wakers`
-use core::{cell::RefCell, convert::Infallible, sync::atomic::AtomicBool};
+use core::cell::{Cell, RefCell};
 
 use critical_section::Mutex;
 use embassy_sync::waitqueue::AtomicWaker;
 use raw_slice::RawBufSlice;
 
-use crate::{FIFO_DEPTH, Tx};
+#[cfg(feature = "stats")]
+use crate::atomic::AtomicU32;
+use crate::{
+    RxErrors, Tx,
+    atomic::{AtomicBool, Ordering},
+    handle_status_reg_errors,
+    registers::{MmioRegisters, RegisterAccess, Status},
+};
 
 /// 1 waker (default).
 #[cfg(feature = "1-waker")]
@@ -47,12 +59,107 @@ static TX_CONTEXTS: [Mutex<RefCell<TxContext>>; NUM_WAKERS] =
 // Completion flag. Kept outside of the context structure as an atomic to avoid
 // critical section.
 static TX_DONE: [AtomicBool; NUM_WAKERS] = [const { AtomicBool::new(false) }; NUM_WAKERS];
+// RX half of a shared waker slot, see [crate::RxAsync].
+pub(crate) static UART_RX_WAKERS: [AtomicWaker; NUM_WAKERS] =
+    [const { AtomicWaker::new() }; NUM_WAKERS];
+// RX errors which were cleared by the status register read of [on_interrupt_tx_rx], until
+// they are picked up by the [crate::RxAsync] structure of the slot.
+pub(crate) static RX_ERRORS: [Mutex<Cell<Option<RxErrors>>>; NUM_WAKERS] =
+    [const { Mutex::new(Cell::new(None)) }; NUM_WAKERS];
+
+#[cfg(feature = "stats")]
+static SLOT_EVENTS: [SlotCounters; NUM_WAKERS] = [const { SlotCounters::new() }; NUM_WAKERS];
+
+#[cfg(feature = "stats")]
+struct SlotCounters {
+    interrupts: AtomicU32,
+    tx_bytes: AtomicU32,
+    wakeups: AtomicU32,
+}
+
+#[cfg(feature = "stats")]
+impl SlotCounters {
+    const fn new() -> Self {
+        Self {
+            interrupts: AtomicU32::new(0),
+            tx_bytes: AtomicU32::new(0),
+            wakeups: AtomicU32::new(0),
+        }
+    }
+
+    fn add_tx_bytes(&self, bytes: usize) {
+        self.tx_bytes.fetch_add(bytes as u32, Ordering::Relaxed);
+    }
+}
+
+/// Event counters of a waker slot.
+///
+/// The counters are only available with the `stats` feature and wrap around on overflow.
+#[cfg(feature = "stats")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WakerSlotEvents {
+    /// Number of interrupts serviced by [on_interrupt_tx].
+    pub interrupts: u32,
+    /// Number of bytes written into the TX FIFO by the asynchronous TX engine.
+    pub tx_bytes: u32,
+    /// Number of futures woken after a completed transfer.
+    pub wakeups: u32,
+}
+
+#[cfg(feature = "stats")]
+impl WakerSlotEvents {
+    /// Average number of bytes written per serviced interrupt, which shows how efficiently the
+    /// TX FIFO is used. Returns 0 if no interrupt was serviced yet.
+    pub const fn bytes_per_interrupt(&self) -> u32 {
+        if self.interrupts == 0 {
+            return 0;
+        }
+        self.tx_bytes / self.interrupts
+    }
+}
+
+/// Event counters of the given waker slot.
+///
+/// Returns [None] if the waker slot is invalid.
+#[cfg(feature = "stats")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+pub fn waker_slot_events(waker_slot: usize) -> Option<WakerSlotEvents> {
+    let counters = SLOT_EVENTS.get(waker_slot)?;
+    Some(WakerSlotEvents {
+        interrupts: counters.interrupts.load(Ordering::Relaxed),
+        tx_bytes: counters.tx_bytes.load(Ordering::Relaxed),
+        wakeups: counters.wakeups.load(Ordering::Relaxed),
+    })
+}
+
+/// Event counters of the given waker slot which also resets them.
+///
+/// Returns [None] if the waker slot is invalid.
+#[cfg(feature = "stats")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+pub fn take_waker_slot_events(waker_slot: usize) -> Option<WakerSlotEvents> {
+    let counters = SLOT_EVENTS.get(waker_slot)?;
+    Some(WakerSlotEvents {
+        interrupts: counters.interrupts.swap(0, Ordering::Relaxed),
+        tx_bytes: counters.tx_bytes.swap(0, Ordering::Relaxed),
+        wakeups: counters.wakeups.swap(0, Ordering::Relaxed),
+    })
+}
 
 /// Invalid waker index for [NUM_WAKERS].
-#[derive(Debug, thiserror::Error)]
-#[error("invalid waker slot index: {0}")]
+#[derive(Debug)]
 pub struct InvalidWakerIndex(pub usize);
 
+impl core::fmt::Display for InvalidWakerIndex {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid waker slot index: {}", self.0)
+    }
+}
+
+impl core::error::Error for InvalidWakerIndex {}
+
 /// This is a generic interrupt handler to handle asynchronous UART TX operations for a given
 /// UART peripheral.
 ///
@@ -60,15 +167,64 @@ pub struct InvalidWakerIndex(pub usize);
 /// triggered by the UARTLite using [TxAsync]. The relevant [Tx] handle of the UARTLite and the
 /// waker slot used for it must be passed as well. [Tx::steal] can be used to create the required
 /// handle.
-pub fn on_interrupt_tx(uartlite_tx: &mut Tx, waker_slot: usize) {
-    if waker_slot >= NUM_WAKERS {
+pub fn on_interrupt_tx<R: RegisterAccess>(uartlite_tx: &mut Tx<R>, waker_slot: usize) {
+    if let Some(status) = read_interrupt_status(uartlite_tx, waker_slot) {
+        service_tx(uartlite_tx, waker_slot, status);
+    }
+}
+
+/// Interrupt handler for a [TxAsync] and [crate::RxAsync] pair which shares the given waker
+/// slot.
+///
+/// This services the TX transfer like [on_interrupt_tx] and wakes the task waiting on the
+/// [crate::RxAsync] structure if the RX FIFO contains data. The RX errors cleared by the
+/// status register read are passed on to the [crate::RxAsync] structure. The handler can also
+/// be used if only one of both halves is asynchronous.
+pub fn on_interrupt_tx_rx<R: RegisterAccess>(uartlite_tx: &mut Tx<R>, waker_slot: usize) {
+    let Some(status) = read_interrupt_status(uartlite_tx, waker_slot) else {
         return;
+    };
+    if let Some(errors) = handle_status_reg_errors(&status) {
+        critical_section::with(|cs| {
+            let pending = RX_ERRORS[waker_slot].borrow(cs);
+            pending.set(Some(
+                pending.get().map_or(errors, |prev| prev.union(errors)),
+            ));
+        });
+    }
+    if status.rx_fifo_valid_data() {
+        UART_RX_WAKERS[waker_slot].wake();
+    }
+    service_tx(uartlite_tx, waker_slot, status);
+}
+
+/// Read the status register for the interrupt handlers. Returns [None] if the waker slot is
+/// invalid or the interrupt is not enabled.
+fn read_interrupt_status<R: RegisterAccess>(
+    uartlite_tx: &mut Tx<R>,
+    waker_slot: usize,
+) -> Option<Status> {
+    if waker_slot >= NUM_WAKERS {
+        return None;
     }
     let status = uartlite_tx.regs.read_stat_reg();
     // Interrupt are not even enabled.
     if !status.intr_enabled() {
-        return;
+        return None;
     }
+    #[cfg(feature = "stats")]
+    {
+        uartlite_tx.stats.interrupts = uartlite_tx.stats.interrupts.wrapping_add(1);
+        SLOT_EVENTS[waker_slot]
+            .interrupts
+            .fetch_add(1, Ordering::Relaxed);
+    }
+    #[cfg(feature = "hooks")]
+    crate::hooks::call(|hooks| hooks.on_interrupt());
+    Some(status)
+}
+
+fn service_tx<R: RegisterAccess>(uartlite_tx: &mut Tx<R>, waker_slot: usize, status: Status) {
     let mut context = critical_section::with(|cs| {
         let context_ref = TX_CONTEXTS[waker_slot].borrow(cs);
         *context_ref.borrow()
@@ -85,13 +241,24 @@ pub fn on_interrupt_tx(uartlite_tx: &mut Tx, waker_slot: usize) {
             *context_ref.borrow_mut() = context;
         });
         // Transfer is done.
-        TX_DONE[waker_slot].store(true, core::sync::atomic::Ordering::Relaxed);
+        TX_DONE[waker_slot].store(true, Ordering::Relaxed);
         UART_TX_WAKERS[waker_slot].wake();
+        #[cfg(feature = "stats")]
+        SLOT_EVENTS[waker_slot]
+            .wakeups
+            .fetch_add(1, Ordering::Relaxed);
         return;
     }
     // Safety: We documented that the user provided slice must outlive the future, so we convert
     // the raw pointer back to the slice here.
     let slice = unsafe { context.slice.get() }.expect("slice is invalid");
+    #[cfg(feature = "stats")]
+    let start_progress = context.progress;
+    if status.tx_fifo_empty() {
+        // Safety: TX structure is owned by the future which does not write into the the data
+        // register, so we can assume we are the only one writing to the data register.
+        context.progress += uartlite_tx.write_fifo_unchecked_slice(&slice[context.progress..]);
+    }
     while context.progress < slice_len {
         if uartlite_tx.regs.read_stat_reg().tx_fifo_full() {
             break;
@@ -101,6 +268,8 @@ pub fn on_interrupt_tx(uartlite_tx: &mut Tx, waker_slot: usize) {
         uartlite_tx.write_fifo_unchecked(slice[context.progress]);
         context.progress += 1;
     }
+    #[cfg(feature = "stats")]
+    SLOT_EVENTS[waker_slot].add_tx_bytes(context.progress - start_progress);
     // Write back updated context structure.
     critical_section::with(|cs| {
         let context_ref = TX_CONTEXTS[waker_slot].borrow(cs);
@@ -126,13 +295,57 @@ impl TxContext {
     }
 }
 
+/// Progress of an active asynchronous TX transfer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxTransferProgress {
+    /// Number of bytes written into the TX FIFO.
+    pub written: usize,
+    /// Length of the transfer.
+    pub len: usize,
+}
+
+/// Progress of the active asynchronous TX transfer for the given waker slot.
+///
+/// Returns [None] if no transfer is active or the waker slot is invalid.
+pub fn tx_transfer_progress(waker_slot: usize) -> Option<TxTransferProgress> {
+    if waker_slot >= NUM_WAKERS {
+        return None;
+    }
+    let context = critical_section::with(|cs| *TX_CONTEXTS[waker_slot].borrow(cs).borrow());
+    Some(TxTransferProgress {
+        written: context.progress,
+        len: context.slice.len()?,
+    })
+}
+
+/// Behavior of a [TxFuture] which is dropped before the transfer completed, for example
+/// because the write was cancelled by a timeout.
+///
+/// The transfer context is always cleared, so no further data of the cancelled write is
+/// written into the TX FIFO by [on_interrupt_tx]. The policy determines what happens to the
+/// data which is already inside the TX FIFO.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxDropPolicy {
+    /// Reset the TX FIFO. Only the data which was already shifted out is transmitted.
+    #[default]
+    ResetFifo,
+    /// Block until the TX FIFO is empty, so the data written into the FIFO is transmitted
+    /// completely before the drop returns.
+    FinishFifo,
+    /// Leave the TX FIFO as is. The remaining data is transmitted in the background, unless
+    /// the next write resets the TX FIFO before.
+    LeaveAsIs,
+}
+
 /// TX future structure.
-pub struct TxFuture<'tx> {
+pub struct TxFuture<'tx, R: RegisterAccess = MmioRegisters<'static>> {
     waker_idx: usize,
-    tx: &'tx mut TxAsync,
+    tx: &'tx mut TxAsync<R>,
 }
 
-impl<'tx> TxFuture<'tx> {
+impl<'tx, R: RegisterAccess> TxFuture<'tx, R> {
     /// Create a new TX future which can be used for asynchronous TX operations.
     ///
     /// # Safety
@@ -140,31 +353,67 @@ impl<'tx> TxFuture<'tx> {
     /// This function stores the raw pointer of the passed data slice. The user MUST ensure
     /// that the slice outlives the data structure.
     pub unsafe fn new(
-        tx: &'tx mut TxAsync,
+        tx: &'tx mut TxAsync<R>,
         waker_idx: usize,
         data: &[u8],
-    ) -> Result<TxFuture<'tx>, InvalidWakerIndex> {
-        TX_DONE[waker_idx].store(false, core::sync::atomic::Ordering::Relaxed);
+    ) -> Result<TxFuture<'tx, R>, InvalidWakerIndex> {
+        TX_DONE[waker_idx].store(false, Ordering::Relaxed);
+        tx.paused = None;
         tx.tx.reset_fifo();
 
-        let init_fill_count = core::cmp::min(data.len(), FIFO_DEPTH);
         // We fill the FIFO with initial data.
-        for data in data.iter().take(init_fill_count) {
-            tx.tx.write_fifo_unchecked(*data);
-        }
+        let init_fill_count = tx.tx.write_fifo_unchecked_slice(data);
+        #[cfg(feature = "stats")]
+        SLOT_EVENTS[waker_idx].add_tx_bytes(init_fill_count);
+        unsafe { Self::start(tx, waker_idx, data, init_fill_count) }
+    }
+
+    /// Create a TX future which resumes a cancelled transfer of the same data, see
+    /// [TxAsync::paused_transfer].
+    ///
+    /// Falls back to [Self::new] if no paused transfer of the same length was recorded. The
+    /// future returns the full length of the data on completion.
+    ///
+    /// # Safety
+    ///
+    /// This function stores the raw pointer of the passed data slice. The user MUST ensure
+    /// that the slice outlives the data structure.
+    pub unsafe fn resume(
+        tx: &'tx mut TxAsync<R>,
+        waker_idx: usize,
+        data: &[u8],
+    ) -> Result<TxFuture<'tx, R>, InvalidWakerIndex> {
+        let written = match tx.paused.take() {
+            Some(paused) if paused.len == data.len() => paused.written,
+            _ => return unsafe { Self::new(tx, waker_idx, data) },
+        };
+        TX_DONE[waker_idx].store(false, Ordering::Relaxed);
+        // The TX FIFO may still contain the data of the paused transfer, so it is not reset.
+        let init_fill_count = tx.tx.write_burst(&data[written..]);
+        #[cfg(feature = "stats")]
+        SLOT_EVENTS[waker_idx].add_tx_bytes(init_fill_count);
+        unsafe { Self::start(tx, waker_idx, data, written + init_fill_count) }
+    }
+
+    unsafe fn start(
+        tx: &'tx mut TxAsync<R>,
+        waker_idx: usize,
+        data: &[u8],
+        progress: usize,
+    ) -> Result<TxFuture<'tx, R>, InvalidWakerIndex> {
         critical_section::with(|cs| {
             let context_ref = TX_CONTEXTS[waker_idx].borrow(cs);
             let mut context = context_ref.borrow_mut();
             unsafe {
                 context.slice.set(data);
             }
-            context.progress = init_fill_count;
+            context.progress = progress;
         });
         Ok(Self { waker_idx, tx })
     }
 }
 
-impl Future for TxFuture<'_> {
+impl<R: RegisterAccess> Future for TxFuture<'_, R> {
     type Output = usize;
 
     fn poll(
@@ -172,7 +421,7 @@ impl Future for TxFuture<'_> {
         cx: &mut core::task::Context<'_>,
     ) -> core::task::Poll<Self::Output> {
         UART_TX_WAKERS[self.waker_idx].register(cx.waker());
-        if TX_DONE[self.waker_idx].swap(false, core::sync::atomic::Ordering::Relaxed) {
+        if TX_DONE[self.waker_idx].swap(false, Ordering::Relaxed) {
             let progress = critical_section::with(|cs| {
                 let mut ctx = TX_CONTEXTS[self.waker_idx].borrow(cs).borrow_mut();
                 ctx.slice.set_null();
@@ -184,40 +433,85 @@ impl Future for TxFuture<'_> {
     }
 }
 
-impl Drop for TxFuture<'_> {
+impl<R: RegisterAccess> Drop for TxFuture<'_, R> {
     fn drop(&mut self) {
-        if !TX_DONE[self.waker_idx].load(core::sync::atomic::Ordering::Relaxed) {
-            critical_section::with(|cs| {
+        if !TX_DONE[self.waker_idx].load(Ordering::Relaxed) {
+            let progress = critical_section::with(|cs| {
                 let context_ref = TX_CONTEXTS[self.waker_idx].borrow(cs);
                 let mut context_mut = context_ref.borrow_mut();
+                let progress = context_mut.slice.len().map(|len| TxTransferProgress {
+                    written: context_mut.progress,
+                    len,
+                });
                 context_mut.slice.set_null();
                 context_mut.progress = 0;
-                // We can not disable interrupts, might be active for RX as well.
-                self.tx.tx.reset_fifo();
+                progress
             });
+            // Bytes discarded by a FIFO reset are lost, so the transfer can not be resumed.
+            if self.tx.drop_policy != TxDropPolicy::ResetFifo {
+                self.tx.paused = progress;
+            }
+            // We can not disable interrupts, might be active for RX as well.
+            match self.tx.drop_policy {
+                TxDropPolicy::ResetFifo => self.tx.tx.reset_fifo(),
+                TxDropPolicy::FinishFifo => while !self.tx.tx.fifo_empty() {},
+                TxDropPolicy::LeaveAsIs => (),
+            }
         }
     }
 }
 
 /// Asynchronous TX structure.
-pub struct TxAsync {
-    pub(crate) tx: Tx,
+pub struct TxAsync<R = MmioRegisters<'static>> {
+    pub(crate) tx: Tx<R>,
     waker_idx: usize,
+    drop_policy: TxDropPolicy,
+    paused: Option<TxTransferProgress>,
 }
 
-impl TxAsync {
+impl<R: RegisterAccess> TxAsync<R> {
     /// Create a new asynchronous TX structure.
-    pub fn new(tx: Tx, waker_idx: usize) -> Result<Self, InvalidWakerIndex> {
+    ///
+    /// Cancelled writes reset the TX FIFO, see [TxDropPolicy::ResetFifo].
+    pub fn new(tx: Tx<R>, waker_idx: usize) -> Result<Self, InvalidWakerIndex> {
+        Self::new_with_drop_policy(tx, waker_idx, TxDropPolicy::default())
+    }
+
+    /// Create a new asynchronous TX structure with the given [TxDropPolicy] for cancelled
+    /// writes.
+    pub fn new_with_drop_policy(
+        tx: Tx<R>,
+        waker_idx: usize,
+        drop_policy: TxDropPolicy,
+    ) -> Result<Self, InvalidWakerIndex> {
         if waker_idx >= NUM_WAKERS {
             return Err(InvalidWakerIndex(waker_idx));
         }
-        Ok(Self { tx, waker_idx })
+        Ok(Self {
+            tx,
+            waker_idx,
+            drop_policy,
+            paused: None,
+        })
+    }
+
+    /// Policy for cancelled writes.
+    #[inline]
+    pub fn drop_policy(&self) -> TxDropPolicy {
+        self.drop_policy
+    }
+
+    /// Change the policy for cancelled writes.
+    #[inline]
+    pub fn set_drop_policy(&mut self, drop_policy: TxDropPolicy) {
+        self.drop_policy = drop_policy;
     }
 
     /// Write a buffer asynchronously.
     ///
     /// This implementation is not side effect free, and a started future might have already
-    /// written part of the passed buffer.
+    /// written part of the passed buffer. The [TxDropPolicy] determines what happens to the
+    /// data inside the TX FIFO if the future is dropped.
     pub async fn write(&mut self, buf: &[u8]) -> usize {
         if buf.is_empty() {
             return 0;
@@ -226,17 +520,260 @@ impl TxAsync {
         fut.await
     }
 
+    /// Write multiple buffers asynchronously as a single logical write.
+    ///
+    /// The buffers are written one after another, which avoids concatenating separate header
+    /// and payload buffers. Returns the total number of written bytes. If the future is
+    /// dropped, the [TxDropPolicy] applies to the buffer which is currently written.
+    pub async fn write_vectored(&mut self, bufs: &[&[u8]]) -> usize {
+        let mut written = 0;
+        for buf in bufs {
+            written += self.write(buf).await;
+        }
+        written
+    }
+
+    /// Write formatted output asynchronously, which allows using `write!(tx, ...).await`.
+    ///
+    /// The arguments are formatted once into a stack buffer of [FMT_BUFFER_SIZE] bytes, which
+    /// is kept across the await and then written like with [Self::write]. The formatted output
+    /// is therefore limited to [FMT_BUFFER_SIZE] bytes. Longer output returns
+    /// [WriteFmtError::TooLong] without writing anything, and has to be split into multiple
+    /// writes by the caller.
+    pub async fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> Result<(), WriteFmtError> {
+        let mut output = FmtBuffer {
+            buf: [0; FMT_BUFFER_SIZE],
+            len: 0,
+            overflow: false,
+        };
+        if core::fmt::write(&mut output, args).is_err() {
+            if output.overflow {
+                return Err(WriteFmtError::TooLong);
+            }
+            return Err(WriteFmtError::Format);
+        }
+        self.write(&output.buf[0..output.len]).await;
+        Ok(())
+    }
+
+    /// Resume a write which was cancelled by dropping its future, for example because it lost
+    /// a `select`, or start a new write if no cancelled write of the same length was recorded.
+    ///
+    /// The buffer must be the same as for the cancelled write. Returns the full length of the
+    /// buffer on completion. Only writes cancelled with [TxDropPolicy::FinishFifo] or
+    /// [TxDropPolicy::LeaveAsIs] can be resumed, see [Self::paused_transfer].
+    pub async fn resume(&mut self, buf: &[u8]) -> usize {
+        if self
+            .paused
+            .is_some_and(|paused| paused.len == buf.len() && paused.written >= buf.len())
+        {
+            self.paused = None;
+            return buf.len();
+        }
+        if buf.is_empty() {
+            return 0;
+        }
+        let fut = unsafe { TxFuture::resume(self, self.waker_idx, buf).unwrap() };
+        fut.await
+    }
+
+    /// Progress of the last cancelled write, which can be continued with [Self::resume].
+    ///
+    /// This is only recorded for the [TxDropPolicy::FinishFifo] and [TxDropPolicy::LeaveAsIs]
+    /// policies, because the bytes discarded by [TxDropPolicy::ResetFifo] are lost. It is
+    /// cleared when a new write is started.
+    #[inline]
+    pub fn paused_transfer(&self) -> Option<TxTransferProgress> {
+        self.paused
+    }
+
+    /// Snapshot of the driver state including the progress of the active transfer.
+    ///
+    /// RX errors reported by the status register read are recorded for
+    /// [Tx::read_and_clear_last_error]. The error counters contain the last recorded RX
+    /// errors, if there are any.
+    #[cfg(not(feature = "tiny"))]
+    pub fn diagnostics(&mut self) -> crate::Diagnostics {
+        let status = self.tx.regs.read_stat_reg();
+        if let Some(errors) = crate::handle_status_reg_errors(&status) {
+            self.tx.errors = Some(errors);
+        }
+        let mut errors = crate::RxErrorsCounted::new();
+        if let Some(last) = self.tx.errors {
+            errors.add(last);
+        }
+        crate::Diagnostics {
+            tx_transfer: tx_transfer_progress(self.waker_idx),
+            #[cfg(feature = "stats")]
+            stats: self.tx.stats,
+            #[cfg(feature = "debug-audit")]
+            control_writes: self.tx.audit,
+            ..crate::Diagnostics::new(&status, errors)
+        }
+    }
+
     /// Release the owned TX structure.
-    pub fn release(self) -> Tx {
+    pub fn release(self) -> Tx<R> {
+        self.tx
+    }
+}
+
+/// Asynchronous TX structure which collects small writes in a buffer of size `N`.
+///
+/// The buffer is written to the hardware when a newline is written, when it is full or when
+/// [Self::flush] is called. This reduces the number of transfers and interrupts for line based
+/// output like logging, where the individual writes are often only a few bytes long.
+///
+/// The buffered data is discarded if a flush is cancelled, even if only part of it was written.
+pub struct BufferedTxAsync<const N: usize, R = MmioRegisters<'static>> {
+    tx: TxAsync<R>,
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize, R: RegisterAccess> BufferedTxAsync<N, R> {
+    /// Create a new buffered writer on top of an asynchronous TX structure.
+    pub const fn new(tx: TxAsync<R>) -> Self {
+        const { assert!(N > 0, "buffer size must not be zero") };
+        Self {
+            tx,
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Number of buffered bytes which were not written to the hardware yet.
+    #[inline]
+    pub const fn buffered(&self) -> usize {
+        self.len
+    }
+
+    /// Write a buffer. The data is only written to the hardware if it contains a newline or if
+    /// the internal buffer is full.
+    pub async fn write(&mut self, buf: &[u8]) -> usize {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let n = rest.len().min(N - self.len);
+            let (chunk, remaining) = rest.split_at(n);
+            self.buf[self.len..self.len + n].copy_from_slice(chunk);
+            self.len += n;
+            rest = remaining;
+            if self.len == N || chunk.contains(&b'\n') {
+                self.flush().await;
+            }
+        }
+        buf.len()
+    }
+
+    /// Write all buffered data to the hardware.
+    pub async fn flush(&mut self) {
+        let len = core::mem::take(&mut self.len);
+        self.tx.write(&self.buf[0..len]).await;
+    }
+
+    /// Release the asynchronous TX structure. Buffered data which was not flushed is discarded.
+    pub fn release(self) -> TxAsync<R> {
         self.tx
     }
 }
 
-impl embedded_io::ErrorType for TxAsync {
-    type Error = Infallible;
+impl<const N: usize, R: RegisterAccess> core::fmt::Debug for BufferedTxAsync<N, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BufferedTxAsync")
+            .field("buffered", &self.len)
+            .field("tx", &self.tx)
+            .finish()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const N: usize, R: RegisterAccess> embedded_io::ErrorType for BufferedTxAsync<N, R> {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<const N: usize, R: RegisterAccess> embedded_io_async::Write for BufferedTxAsync<N, R> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(self.write(buf).await)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flush().await;
+        Ok(())
+    }
+}
+
+/// Size of the stack buffer used by [TxAsync::write_fmt], which is the maximum length of the
+/// formatted output.
+pub const FMT_BUFFER_SIZE: usize = 256;
+
+/// Error of [TxAsync::write_fmt].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WriteFmtError {
+    /// A formatting trait implementation returned an error.
+    Format,
+    /// The formatted output is longer than [FMT_BUFFER_SIZE] bytes. Nothing was written.
+    TooLong,
+}
+
+impl core::fmt::Display for WriteFmtError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WriteFmtError::Format => write!(f, "formatting error"),
+            WriteFmtError::TooLong => write!(
+                f,
+                "formatted output exceeds the buffer size of {FMT_BUFFER_SIZE} bytes"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for WriteFmtError {}
+
+/// Stack buffer for the formatted output of [TxAsync::write_fmt].
+struct FmtBuffer {
+    buf: [u8; FMT_BUFFER_SIZE],
+    len: usize,
+    overflow: bool,
+}
+
+impl core::fmt::Write for FmtBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > FMT_BUFFER_SIZE {
+            self.overflow = true;
+            return Err(core::fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl<R: RegisterAccess> core::fmt::Debug for TxAsync<R> {
+    /// Shows the waker slot, the drop policy and the [Debug](core::fmt::Debug) output of the
+    /// [Tx] handle.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TxAsync")
+            .field("waker_idx", &self.waker_idx)
+            .field("drop_policy", &self.drop_policy)
+            .field("tx", &self.tx)
+            .finish()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io::ErrorType for TxAsync<R> {
+    type Error = core::convert::Infallible;
 }
 
-impl embedded_io_async::Write for TxAsync {
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: RegisterAccess> embedded_io_async::Write for TxAsync<R> {
     /// Write a buffer asynchronously.
     ///
     /// This implementation is not side effect free, and a started future might have already
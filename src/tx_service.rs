@@ -0,0 +1,87 @@
+//! # Timer-driven background TX service
+//!
+//! This module is only available with the `tx-service` feature. The [TxService] provides a
+//! "pseudo-DMA" mode for systems where the UART Lite interrupt is not available but a periodic
+//! tick timer is: data is queued with [TxService::write] from any context, and the tick timer
+//! callback calls [TxService::on_tick], which tops up the TX FIFO from the queue. This way,
+//! long messages are transmitted in the background.
+//!
+//! At most [FIFO_DEPTH] bytes are written per tick, so the tick period should be shorter than
+//! the time required to send the FIFO content, see [crate::HwConfig::fifo_drain_time_us], to
+//! keep the transmitter busy.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static TX_SERVICE: TxService<512> = TxService::new();
+//!
+//! fn main() {
+//!     TX_SERVICE.write(b"long message which is sent in the background\n");
+//! }
+//!
+//! fn timer_tick_handler() {
+//!     let mut tx = unsafe { Tx::steal(UARTLITE_BASE) };
+//!     TX_SERVICE.on_tick(&mut tx);
+//! }
+//! ```
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::{FIFO_DEPTH, Tx, registers::RegisterAccess, ring::Ring};
+
+/// Timer-driven background TX service with a queue size of `N`.
+pub struct TxService<const N: usize> {
+    queue: Mutex<RefCell<Ring<N>>>,
+}
+
+impl<const N: usize> Default for TxService<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> TxService<N> {
+    /// Create a new TX service with an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            queue: Mutex::new(RefCell::new(Ring::new())),
+        }
+    }
+
+    /// Queue as many bytes as possible without blocking.
+    ///
+    /// Returns the number of queued bytes, which might be 0 if the queue is full.
+    pub fn write(&self, data: &[u8]) -> usize {
+        critical_section::with(|cs| self.queue.borrow(cs).borrow_mut().extend(data))
+    }
+
+    /// Tick timer callback which tops up the TX FIFO from the queue.
+    ///
+    /// Returns the number of bytes written into the TX FIFO.
+    pub fn on_tick<R: RegisterAccess>(&self, tx: &mut Tx<R>) -> usize {
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow(cs).borrow_mut();
+            let mut burst = [0; FIFO_DEPTH];
+            let len = queue.peek(&mut burst);
+            let written = tx.write_burst(&burst[0..len]);
+            queue.consume(written);
+            written
+        })
+    }
+
+    /// Number of queued bytes which were not written into the TX FIFO yet.
+    pub fn queued(&self) -> usize {
+        critical_section::with(|cs| self.queue.borrow(cs).borrow().len())
+    }
+
+    /// Free space in the queue.
+    pub fn free(&self) -> usize {
+        N - self.queued()
+    }
+
+    /// Discard all queued bytes.
+    pub fn clear(&self) {
+        critical_section::with(|cs| self.queue.borrow(cs).borrow_mut().clear());
+    }
+}
@@ -0,0 +1,423 @@
+//! # Chunked upload service
+//!
+//! This module is only available with the `upload` feature. The [UploadService] receives an
+//! image in CRC-checked chunks over the UART and writes it into an [embedded_storage]
+//! [NorFlash] region, which enables in-application firmware updates over the UART Lite.
+//!
+//! The commands and responses are sent in the length-prefixed frames of the
+//! [crate::framing::length_prefixed] module, so every chunk is protected by the CRC16 of the
+//! frame. The payload of a command frame is an [UploadCommand]:
+//!
+//! | Command | Payload                                                        |
+//! |---------|----------------------------------------------------------------|
+//! | Start   | `0x01`, image length (u32, big endian)                         |
+//! | Chunk   | `0x02`, offset inside the image (u32, big endian), chunk data  |
+//! | Finish  | `0x03`                                                         |
+//!
+//! Every valid command frame is answered with an [UploadResponse] frame, which contains the
+//! [UploadStatus] and the next expected offset (u32, big endian). Frames with a CRC error are
+//! not answered, so the sender retransmits the command after a timeout. Chunks which were
+//! already written are acknowledged again without writing them, so a lost response is
+//! recovered by retransmitting the chunk as well.
+//!
+//! The start command erases the flash pages covering the image. The chunks have to be sent in
+//! order, and the chunk offsets and lengths have to be multiples of the [NorFlash::WRITE_SIZE]
+//! of the flash, so the sender has to pad the image accordingly.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut service = UploadService::<_, 256>::new(flash, UPDATE_PARTITION_OFFSET, UPDATE_PARTITION_LEN);
+//! loop {
+//!     service.process(&mut uart)?;
+//!     if let UploadState::Complete { len } = service.state() {
+//!         mark_update_pending(len);
+//!     }
+//! }
+//! ```
+use embedded_storage::nor_flash::NorFlash;
+
+use crate::framing::{
+    BufferTooSmall, FrameReadError,
+    length_prefixed::{self, LengthPrefixedDecodeError, LengthPrefixedDecoder},
+};
+
+/// Start command identifier.
+pub const CMD_START: u8 = 0x01;
+/// Chunk command identifier.
+pub const CMD_CHUNK: u8 = 0x02;
+/// Finish command identifier.
+pub const CMD_FINISH: u8 = 0x03;
+/// Length of the chunk command header in front of the chunk data.
+pub const CHUNK_HEADER_LEN: usize = 5;
+/// Length of an encoded [UploadResponse].
+pub const RESPONSE_LEN: usize = 5;
+
+/// Upload command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UploadCommand<'data> {
+    /// Start a new upload with the given image length, which erases the flash region.
+    Start {
+        /// Image length.
+        len: u32,
+    },
+    /// Chunk of the image.
+    Chunk {
+        /// Offset of the chunk inside the image.
+        offset: u32,
+        /// Chunk data.
+        data: &'data [u8],
+    },
+    /// Finish the upload.
+    Finish,
+}
+
+impl<'data> UploadCommand<'data> {
+    /// Parse a command from a frame payload.
+    pub fn parse(payload: &'data [u8]) -> Option<Self> {
+        let (&cmd, rest) = payload.split_first()?;
+        match cmd {
+            CMD_START => Some(Self::Start {
+                len: u32::from_be_bytes(rest.try_into().ok()?),
+            }),
+            CMD_CHUNK if rest.len() >= 4 => Some(Self::Chunk {
+                offset: u32::from_be_bytes(rest[0..4].try_into().unwrap()),
+                data: &rest[4..],
+            }),
+            CMD_FINISH if rest.is_empty() => Some(Self::Finish),
+            _ => None,
+        }
+    }
+
+    /// Encode the command into the output buffer.
+    ///
+    /// Returns the length of the frame payload, which can be sent with
+    /// [length_prefixed::send].
+    pub fn encode(&self, out: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        let len = match self {
+            Self::Start { .. } => 5,
+            Self::Chunk { data, .. } => CHUNK_HEADER_LEN + data.len(),
+            Self::Finish => 1,
+        };
+        if out.len() < len {
+            return Err(BufferTooSmall);
+        }
+        match self {
+            Self::Start { len } => {
+                out[0] = CMD_START;
+                out[1..5].copy_from_slice(&len.to_be_bytes());
+            }
+            Self::Chunk { offset, data } => {
+                out[0] = CMD_CHUNK;
+                out[1..5].copy_from_slice(&offset.to_be_bytes());
+                out[CHUNK_HEADER_LEN..len].copy_from_slice(data);
+            }
+            Self::Finish => out[0] = CMD_FINISH,
+        }
+        Ok(len)
+    }
+}
+
+/// Status of an [UploadResponse].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum UploadStatus {
+    /// The command was executed.
+    Ok = 0x00,
+    /// The frame payload is not a valid command.
+    Malformed = 0x01,
+    /// No upload was started.
+    NotStarted = 0x02,
+    /// The image or the chunk exceeds the flash region.
+    TooLarge = 0x03,
+    /// The chunk offset or length is not a multiple of the flash write size.
+    Misaligned = 0x04,
+    /// The chunk offset does not match the next expected offset.
+    OutOfSequence = 0x05,
+    /// The upload was finished before all bytes were received.
+    Incomplete = 0x06,
+    /// Erasing or writing the flash failed. The upload has to be restarted.
+    FlashError = 0x07,
+}
+
+impl UploadStatus {
+    /// Convert a raw status value.
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0x00 => Self::Ok,
+            0x01 => Self::Malformed,
+            0x02 => Self::NotStarted,
+            0x03 => Self::TooLarge,
+            0x04 => Self::Misaligned,
+            0x05 => Self::OutOfSequence,
+            0x06 => Self::Incomplete,
+            0x07 => Self::FlashError,
+            _ => return None,
+        })
+    }
+}
+
+/// Response to an upload command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UploadResponse {
+    /// Command status.
+    pub status: UploadStatus,
+    /// Next expected chunk offset.
+    pub next_offset: u32,
+}
+
+impl UploadResponse {
+    /// Encode the response into a frame payload.
+    pub const fn to_bytes(&self) -> [u8; RESPONSE_LEN] {
+        let offset = self.next_offset.to_be_bytes();
+        [
+            self.status as u8,
+            offset[0],
+            offset[1],
+            offset[2],
+            offset[3],
+        ]
+    }
+
+    /// Parse a response from a frame payload.
+    pub fn from_bytes(payload: &[u8]) -> Option<Self> {
+        let payload: &[u8; RESPONSE_LEN] = payload.try_into().ok()?;
+        Some(Self {
+            status: UploadStatus::from_u8(payload[0])?,
+            next_offset: u32::from_be_bytes(payload[1..5].try_into().unwrap()),
+        })
+    }
+}
+
+/// State of the [UploadService].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum UploadState {
+    /// No upload was started, or the last upload failed.
+    Idle,
+    /// An upload is in progress.
+    Receiving {
+        /// Image length.
+        len: u32,
+        /// Number of received bytes.
+        received: u32,
+    },
+    /// The upload was finished and all bytes were written.
+    Complete {
+        /// Image length.
+        len: u32,
+    },
+}
+
+/// Upload service error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UploadError<IoError, FlashError> {
+    /// Reader or writer I/O error.
+    Io(IoError),
+    /// The reader reached end of file.
+    Eof,
+    /// Received frame with an invalid CRC or length. The frame was not answered.
+    Frame(LengthPrefixedDecodeError),
+    /// Erasing or writing the flash failed. The frame was answered with
+    /// [UploadStatus::FlashError].
+    Flash(FlashError),
+}
+
+impl<IoError, FlashError> From<FrameReadError<IoError, LengthPrefixedDecodeError>>
+    for UploadError<IoError, FlashError>
+{
+    fn from(error: FrameReadError<IoError, LengthPrefixedDecodeError>) -> Self {
+        match error {
+            FrameReadError::Io(e) => Self::Io(e),
+            FrameReadError::Eof => Self::Eof,
+            FrameReadError::Decode(e) => Self::Frame(e),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug, FlashError: core::fmt::Debug> core::fmt::Display
+    for UploadError<IoError, FlashError>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+            Self::Eof => write!(f, "unexpected end of file"),
+            Self::Frame(e) => write!(f, "frame error: {e}"),
+            Self::Flash(e) => write!(f, "flash error: {e:?}"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug, FlashError: core::fmt::Debug> core::error::Error
+    for UploadError<IoError, FlashError>
+{
+}
+
+/// Receive-side upload service which writes the received image into a flash region.
+///
+/// The frame decoder has a buffer of `N` bytes, which limits the chunk length to
+/// `N - CHUNK_HEADER_LEN`.
+pub struct UploadService<F, const N: usize> {
+    target: Target<F>,
+    decoder: LengthPrefixedDecoder<N>,
+}
+
+/// Flash region and upload state, separate from the decoder which holds the received frame.
+struct Target<F> {
+    flash: F,
+    base: u32,
+    capacity: u32,
+    state: UploadState,
+}
+
+impl<F: NorFlash, const N: usize> UploadService<F, N> {
+    /// Create a new upload service which writes into the flash region starting at `base` with
+    /// a length of `capacity` bytes.
+    ///
+    /// The `base` offset and the `capacity` should be multiples of the [NorFlash::ERASE_SIZE]
+    /// of the flash, otherwise every start command fails with [UploadStatus::Misaligned].
+    pub const fn new(flash: F, base: u32, capacity: u32) -> Self {
+        Self {
+            target: Target {
+                flash,
+                base,
+                capacity,
+                state: UploadState::Idle,
+            },
+            decoder: LengthPrefixedDecoder::new(),
+        }
+    }
+
+    /// Current upload state.
+    #[inline]
+    pub const fn state(&self) -> UploadState {
+        self.target.state
+    }
+
+    /// Abort the current upload.
+    pub fn abort(&mut self) {
+        self.target.state = UploadState::Idle;
+        self.decoder.reset();
+    }
+
+    /// Release the flash.
+    pub fn release(self) -> F {
+        self.target.flash
+    }
+
+    /// Read the next command frame from the I/O driver, execute it and send the response.
+    ///
+    /// Returns the sent response.
+    pub fn process<T: embedded_io::Read + embedded_io::Write>(
+        &mut self,
+        io: &mut T,
+    ) -> Result<UploadResponse, UploadError<T::Error, F::Error>> {
+        let payload = self.decoder.read_frame(io)?;
+        let (response, flash_error) = match UploadCommand::parse(payload) {
+            Some(cmd) => match self.target.handle(cmd) {
+                Ok(response) => (response, None),
+                Err(e) => (self.target.response(UploadStatus::FlashError), Some(e)),
+            },
+            None => (self.target.response(UploadStatus::Malformed), None),
+        };
+        match length_prefixed::send(io, &response.to_bytes()) {
+            Ok(()) => (),
+            Err(length_prefixed::SendError::Io(e)) => return Err(UploadError::Io(e)),
+            Err(length_prefixed::SendError::PayloadTooLarge) => unreachable!(),
+        }
+        match flash_error {
+            Some(e) => Err(UploadError::Flash(e)),
+            None => Ok(response),
+        }
+    }
+
+    /// Execute a command, for example one which was received outside of this service.
+    ///
+    /// A flash error aborts the current upload.
+    pub fn handle_command(&mut self, cmd: UploadCommand<'_>) -> Result<UploadResponse, F::Error> {
+        self.target.handle(cmd)
+    }
+}
+
+impl<F: NorFlash> Target<F> {
+    fn handle(&mut self, cmd: UploadCommand<'_>) -> Result<UploadResponse, F::Error> {
+        let status = match cmd {
+            UploadCommand::Start { len } => self.start(len)?,
+            UploadCommand::Chunk { offset, data } => self.write_chunk(offset, data)?,
+            UploadCommand::Finish => self.finish(),
+        };
+        Ok(self.response(status))
+    }
+
+    fn start(&mut self, len: u32) -> Result<UploadStatus, F::Error> {
+        self.state = UploadState::Idle;
+        if len > self.capacity {
+            return Ok(UploadStatus::TooLarge);
+        }
+        let erase_size = F::ERASE_SIZE as u32;
+        if self.base % erase_size != 0 || self.capacity % erase_size != 0 {
+            return Ok(UploadStatus::Misaligned);
+        }
+        let erase_len = len.div_ceil(erase_size) * erase_size;
+        self.flash
+            .erase(self.base, self.base + erase_len)
+            .inspect_err(|_| self.state = UploadState::Idle)?;
+        self.state = UploadState::Receiving { len, received: 0 };
+        Ok(UploadStatus::Ok)
+    }
+
+    fn write_chunk(&mut self, offset: u32, data: &[u8]) -> Result<UploadStatus, F::Error> {
+        let UploadState::Receiving { len, received } = self.state else {
+            return Ok(UploadStatus::NotStarted);
+        };
+        let end = offset as u64 + data.len() as u64;
+        if end > len as u64 {
+            return Ok(UploadStatus::TooLarge);
+        }
+        if end <= received as u64 && !data.is_empty() {
+            // Retransmission of a chunk whose response was lost.
+            return Ok(UploadStatus::Ok);
+        }
+        if offset != received {
+            return Ok(UploadStatus::OutOfSequence);
+        }
+        let write_size = F::WRITE_SIZE;
+        if offset as usize % write_size != 0 || data.len() % write_size != 0 {
+            return Ok(UploadStatus::Misaligned);
+        }
+        self.flash
+            .write(self.base + offset, data)
+            .inspect_err(|_| self.state = UploadState::Idle)?;
+        self.state = UploadState::Receiving {
+            len,
+            received: end as u32,
+        };
+        Ok(UploadStatus::Ok)
+    }
+
+    fn finish(&mut self) -> UploadStatus {
+        match self.state {
+            UploadState::Idle => UploadStatus::NotStarted,
+            UploadState::Receiving { len, received } if received == len => {
+                self.state = UploadState::Complete { len };
+                UploadStatus::Ok
+            }
+            UploadState::Receiving { .. } => UploadStatus::Incomplete,
+            UploadState::Complete { .. } => UploadStatus::Ok,
+        }
+    }
+
+    fn response(&self, status: UploadStatus) -> UploadResponse {
+        let next_offset = match self.state {
+            UploadState::Idle => 0,
+            UploadState::Receiving { received, .. } => received,
+            UploadState::Complete { len } => len,
+        };
+        UploadResponse {
+            status,
+            next_offset,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! # Linux userspace support
+//!
+//! This module allows using the driver from Linux userspace, for example from test tools
+//! running on a Zynq or Versal SoC. The register block has to be mapped into the address space
+//! of the process by the user, for example by using `mmap` on a `/dev/uioX` device or on
+//! `/dev/mem`.
+//!
+//! The [std::io::Read] and [std::io::Write] traits are implemented for [Tx], [Rx] and
+//! [AxiUartlite] as well, which allows using the driver with the standard library I/O
+//! ecosystem, for example with [std::io::BufReader] or [std::io::copy].
+use crate::{AxiUartlite, Rx, Tx, registers::RegisterAccess};
+
+impl AxiUartlite {
+    /// Create a new AXI UART Lite peripheral driver from the pointer to a register block which
+    /// was mapped into the address space of the process.
+    ///
+    /// # Safety
+    ///
+    /// - The pointer must point to the start of a mapped AXI UART Lite register block and
+    ///   must be aligned to 4 bytes.
+    /// - The mapping must remain valid for the whole lifetime of the driver.
+    /// - The same safety rules specified in [AxiUartlite::new] apply.
+    pub unsafe fn new_from_mapped_ptr(ptr: *mut u8) -> Self {
+        let regs = unsafe { crate::registers::Registers::new_mmio(ptr.cast()) };
+        Self::new_with_regs(unsafe { regs.clone() }, regs)
+    }
+}
+
+impl<R: RegisterAccess> std::io::Read for Rx<R> {
+    /// Blocks until at least one byte was read.
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Ok(read) = embedded_io::Read::read(self, buf);
+        Ok(read)
+    }
+}
+
+impl<R: RegisterAccess> std::io::Write for Tx<R> {
+    /// Blocks until at least one byte was written.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let Ok(written) = embedded_io::Write::write(self, buf);
+        Ok(written)
+    }
+
+    /// Blocks until the TX FIFO is empty.
+    fn flush(&mut self) -> std::io::Result<()> {
+        let Ok(()) = embedded_io::Write::flush(self);
+        Ok(())
+    }
+}
+
+impl<R: RegisterAccess> std::io::Read for AxiUartlite<R> {
+    /// Blocks until at least one byte was read.
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Ok(read) = embedded_io::Read::read(self, buf);
+        Ok(read)
+    }
+}
+
+impl<R: RegisterAccess> std::io::Write for AxiUartlite<R> {
+    /// Blocks until at least one byte was written.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let Ok(written) = embedded_io::Write::write(self, buf);
+        Ok(written)
+    }
+
+    /// Blocks until the TX FIFO is empty.
+    fn flush(&mut self) -> std::io::Result<()> {
+        let Ok(()) = embedded_io::Write::flush(self);
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! # UTF-8 decoding
+//!
+//! The [Utf8Decoder] is an incremental UTF-8 decoder which is fed with received bytes one at a
+//! time, so console and text protocol code does not have to hand-roll the decoding. Invalid
+//! sequences are either replaced with [char::REPLACEMENT_CHARACTER] or reported as an error,
+//! see [InvalidSequence].
+//!
+//! The [Utf8Reader] wraps any [embedded_io::Read] or [embedded_io_async::Read] implementation,
+//! for example [crate::Rx], and yields decoded [char]s.
+
+/// Handling of invalid UTF-8 sequences.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum InvalidSequence {
+    /// Replace each invalid sequence with [char::REPLACEMENT_CHARACTER].
+    #[default]
+    Replace,
+    /// Report each invalid sequence as an [InvalidUtf8] error.
+    Error,
+}
+
+/// Invalid UTF-8 sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidUtf8;
+
+impl core::fmt::Display for InvalidUtf8 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid UTF-8 sequence")
+    }
+}
+
+impl core::error::Error for InvalidUtf8 {}
+
+/// Incremental UTF-8 decoder.
+#[derive(Debug)]
+pub struct Utf8Decoder {
+    policy: InvalidSequence,
+    code_point: u32,
+    needed: u8,
+    lower: u8,
+    upper: u8,
+}
+
+impl Utf8Decoder {
+    /// Create a new decoder with the given handling of invalid sequences.
+    pub const fn new(policy: InvalidSequence) -> Self {
+        Self {
+            policy,
+            code_point: 0,
+            needed: 0,
+            lower: 0x80,
+            upper: 0xBF,
+        }
+    }
+
+    /// Discard a partially received sequence.
+    pub fn reset(&mut self) {
+        self.code_point = 0;
+        self.needed = 0;
+        self.lower = 0x80;
+        self.upper = 0xBF;
+    }
+
+    /// A sequence was started but not completed yet.
+    #[inline]
+    pub const fn is_incomplete(&self) -> bool {
+        self.needed > 0
+    }
+
+    /// Feed a received byte to the decoder.
+    ///
+    /// `emit` is called for every decoded character or invalid sequence. A byte which
+    /// interrupts a sequence terminates the invalid sequence and is decoded on its own, so up to
+    /// two results are emitted per byte.
+    pub fn feed(&mut self, byte: u8, mut emit: impl FnMut(Result<char, InvalidUtf8>)) {
+        if self.needed > 0 {
+            if (self.lower..=self.upper).contains(&byte) {
+                self.lower = 0x80;
+                self.upper = 0xBF;
+                self.code_point = (self.code_point << 6) | (byte & 0x3F) as u32;
+                self.needed -= 1;
+                if self.needed == 0 {
+                    // The accepted ranges exclude surrogates and values above U+10FFFF.
+                    let result = char::from_u32(self.code_point).ok_or(InvalidUtf8);
+                    self.code_point = 0;
+                    emit(self.apply_policy(result));
+                }
+                return;
+            }
+            self.reset();
+            emit(self.apply_policy(Err(InvalidUtf8)));
+        }
+        match byte {
+            0x00..=0x7F => emit(Ok(byte as char)),
+            0xC2..=0xDF => self.start(byte & 0x1F, 1),
+            0xE0..=0xEF => {
+                match byte {
+                    0xE0 => self.lower = 0xA0,
+                    0xED => self.upper = 0x9F,
+                    _ => (),
+                }
+                self.start(byte & 0x0F, 2);
+            }
+            0xF0..=0xF4 => {
+                match byte {
+                    0xF0 => self.lower = 0x90,
+                    0xF4 => self.upper = 0x8F,
+                    _ => (),
+                }
+                self.start(byte & 0x07, 3);
+            }
+            _ => emit(self.apply_policy(Err(InvalidUtf8))),
+        }
+    }
+
+    fn start(&mut self, bits: u8, needed: u8) {
+        self.code_point = bits as u32;
+        self.needed = needed;
+    }
+
+    fn apply_policy(&self, result: Result<char, InvalidUtf8>) -> Result<char, InvalidUtf8> {
+        match (result, self.policy) {
+            (Err(_), InvalidSequence::Replace) => Ok(char::REPLACEMENT_CHARACTER),
+            (result, _) => result,
+        }
+    }
+}
+
+impl Default for Utf8Decoder {
+    fn default() -> Self {
+        Self::new(InvalidSequence::default())
+    }
+}
+
+/// Error when reading characters from a reader.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Utf8ReadError<IoError> {
+    /// Reader I/O error.
+    Io(IoError),
+    /// The reader reached end of file. A partially received sequence is discarded.
+    Eof,
+    /// Invalid UTF-8 sequence, only reported with [InvalidSequence::Error].
+    InvalidUtf8,
+}
+
+impl<IoError: core::fmt::Debug> core::fmt::Display for Utf8ReadError<IoError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e:?}"),
+            Self::Eof => write!(f, "unexpected end of file"),
+            Self::InvalidUtf8 => write!(f, "invalid UTF-8 sequence"),
+        }
+    }
+}
+
+impl<IoError: core::fmt::Debug> core::error::Error for Utf8ReadError<IoError> {}
+
+/// Reader adapter which yields decoded [char]s.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+#[derive(Debug)]
+pub struct Utf8Reader<R> {
+    reader: R,
+    decoder: Utf8Decoder,
+    pending: Option<Result<char, InvalidUtf8>>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R> Utf8Reader<R> {
+    /// Create a new reader adapter with the given handling of invalid sequences.
+    pub const fn new(reader: R, policy: InvalidSequence) -> Self {
+        Self {
+            reader,
+            decoder: Utf8Decoder::new(policy),
+            pending: None,
+        }
+    }
+
+    /// Access the wrapped reader.
+    #[inline]
+    pub fn reader(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Release the wrapped reader. A partially received sequence is discarded.
+    pub fn release(self) -> R {
+        self.reader
+    }
+
+    /// Feed a byte to the decoder. Returns the first decoded result and keeps a second one for
+    /// the next call.
+    fn decode(&mut self, byte: u8) -> Option<Result<char, InvalidUtf8>> {
+        let mut first = None;
+        let pending = &mut self.pending;
+        self.decoder.feed(byte, |result| {
+            if first.is_none() {
+                first = Some(result);
+            } else {
+                *pending = Some(result);
+            }
+        });
+        first
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: embedded_io::Read> Utf8Reader<R> {
+    /// Read the next character, blocking until it was received completely.
+    pub fn read_char(&mut self) -> Result<char, Utf8ReadError<R::Error>> {
+        if let Some(result) = self.pending.take() {
+            return result.map_err(|_| Utf8ReadError::InvalidUtf8);
+        }
+        let mut byte = [0; 1];
+        loop {
+            if self.reader.read(&mut byte).map_err(Utf8ReadError::Io)? == 0 {
+                self.decoder.reset();
+                return Err(Utf8ReadError::Eof);
+            }
+            if let Some(result) = self.decode(byte[0]) {
+                return result.map_err(|_| Utf8ReadError::InvalidUtf8);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+impl<R: embedded_io_async::Read> Utf8Reader<R> {
+    /// Asynchronously read the next character.
+    pub async fn read_char_async(&mut self) -> Result<char, Utf8ReadError<R::Error>> {
+        if let Some(result) = self.pending.take() {
+            return result.map_err(|_| Utf8ReadError::InvalidUtf8);
+        }
+        let mut byte = [0; 1];
+        loop {
+            if self
+                .reader
+                .read(&mut byte)
+                .await
+                .map_err(Utf8ReadError::Io)?
+                == 0
+            {
+                self.decoder.reset();
+                return Err(Utf8ReadError::Eof);
+            }
+            if let Some(result) = self.decode(byte[0]) {
+                return result.map_err(|_| Utf8ReadError::InvalidUtf8);
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! # VT100 terminal helpers
+//!
+//! The [Vt100] extension trait adds cursor movement, screen clearing and color escape sequences
+//! to every [core::fmt::Write] implementation, for example [crate::Tx] or [crate::AxiUartlite].
+//! This allows drawing simple status dashboards on serial terminals without embedding the
+//! escape sequences in every project.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use axi_uartlite::vt100::{Color, Vt100};
+//!
+//! tx.clear_screen()?;
+//! tx.set_color(Color::Green)?;
+//! write!(tx, "link up")?;
+//! tx.reset_attributes()?;
+//! tx.move_cursor(0, 2)?;
+//! write!(tx, "rx errors: {}", errors.frame())?;
+//! ```
+use core::fmt::{Result, Write};
+
+/// Terminal color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Color {
+    /// Black.
+    Black,
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Cyan.
+    Cyan,
+    /// White.
+    White,
+    /// Bright black.
+    BrightBlack,
+    /// Bright red.
+    BrightRed,
+    /// Bright green.
+    BrightGreen,
+    /// Bright yellow.
+    BrightYellow,
+    /// Bright blue.
+    BrightBlue,
+    /// Bright magenta.
+    BrightMagenta,
+    /// Bright cyan.
+    BrightCyan,
+    /// Bright white.
+    BrightWhite,
+}
+
+impl Color {
+    /// SGR parameter of the color as foreground color.
+    pub const fn foreground_code(&self) -> u8 {
+        let idx = *self as u8;
+        if idx < 8 { 30 + idx } else { 90 + idx - 8 }
+    }
+
+    /// SGR parameter of the color as background color.
+    #[inline]
+    pub const fn background_code(&self) -> u8 {
+        self.foreground_code() + 10
+    }
+}
+
+/// VT100 escape sequences for [core::fmt::Write] implementations.
+///
+/// The trait is implemented for all writers.
+pub trait Vt100: Write {
+    /// Clear the screen and move the cursor to the top left corner.
+    fn clear_screen(&mut self) -> Result {
+        self.write_str("\x1b[2J\x1b[H")
+    }
+
+    /// Clear the line of the cursor.
+    fn clear_line(&mut self) -> Result {
+        self.write_str("\x1b[2K")
+    }
+
+    /// Clear the line from the cursor to the end of the line.
+    fn clear_to_end_of_line(&mut self) -> Result {
+        self.write_str("\x1b[K")
+    }
+
+    /// Move the cursor to the zero-based column `x` and row `y`.
+    fn move_cursor(&mut self, x: u16, y: u16) -> Result {
+        write!(self, "\x1b[{};{}H", u32::from(y) + 1, u32::from(x) + 1)
+    }
+
+    /// Move the cursor to the top left corner.
+    fn cursor_home(&mut self) -> Result {
+        self.write_str("\x1b[H")
+    }
+
+    /// Save the cursor position.
+    fn save_cursor(&mut self) -> Result {
+        self.write_str("\x1b7")
+    }
+
+    /// Restore the cursor position saved with [Self::save_cursor].
+    fn restore_cursor(&mut self) -> Result {
+        self.write_str("\x1b8")
+    }
+
+    /// Hide the cursor.
+    fn hide_cursor(&mut self) -> Result {
+        self.write_str("\x1b[?25l")
+    }
+
+    /// Show the cursor.
+    fn show_cursor(&mut self) -> Result {
+        self.write_str("\x1b[?25h")
+    }
+
+    /// Set the foreground color.
+    fn set_color(&mut self, color: Color) -> Result {
+        write!(self, "\x1b[{}m", color.foreground_code())
+    }
+
+    /// Set the background color.
+    fn set_background(&mut self, color: Color) -> Result {
+        write!(self, "\x1b[{}m", color.background_code())
+    }
+
+    /// Enable bold text.
+    fn set_bold(&mut self) -> Result {
+        self.write_str("\x1b[1m")
+    }
+
+    /// Reset the colors and text attributes.
+    fn reset_attributes(&mut self) -> Result {
+        self.write_str("\x1b[0m")
+    }
+}
+
+impl<W: Write + ?Sized> Vt100 for W {}
@@ -0,0 +1,407 @@
+//! # XMODEM support
+//!
+//! This module provides an XMODEM-CRC receiver which can be used by bootloaders to accept
+//! firmware images sent by standard terminal tools like `sz`, Tera Term or minicom.
+//! Both 128 byte and 1K blocks (XMODEM-1K) are accepted. If the sender does not answer the
+//! CRC mode requests, the receiver falls back to checksum mode.
+//!
+//! The [XmodemSender] can be used to send data to a host terminal with 128 byte or 1K blocks.
+//!
+//! The UART Lite has no timer, so all timeouts are specified as a number of RX status register
+//! polls. The required value depends on the CPU clock and should correspond to a few seconds
+//! for the initial handshake with most terminal programs.
+use crate::{Rx, Tx, crc::crc16_xmodem, registers::RegisterAccess};
+
+/// Start of 128 byte block.
+pub const SOH: u8 = 0x01;
+/// Start of 1024 byte block.
+pub const STX: u8 = 0x02;
+/// End of transmission.
+pub const EOT: u8 = 0x04;
+/// Acknowledge.
+pub const ACK: u8 = 0x06;
+/// Negative acknowledge.
+pub const NAK: u8 = 0x15;
+/// Cancel.
+pub const CAN: u8 = 0x18;
+/// Padding character used to fill the last block.
+pub const SUB: u8 = 0x1A;
+/// Character sent by the receiver to request CRC mode.
+pub const CRC_MODE: u8 = b'C';
+
+/// Number of [CRC_MODE] requests sent by the receiver before it falls back to checksum mode.
+const CRC_MODE_ATTEMPTS: u32 = 3;
+
+/// XMODEM configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct XmodemConfig {
+    /// Number of RX status polls before a read times out.
+    pub timeout_polls: u32,
+    /// Maximum number of consecutive retries before the transfer is aborted. Timeouts, invalid
+    /// blocks and unexpected characters count as retries.
+    pub max_retries: u8,
+}
+
+impl Default for XmodemConfig {
+    fn default() -> Self {
+        Self {
+            timeout_polls: 1_000_000,
+            max_retries: 10,
+        }
+    }
+}
+
+/// XMODEM transfer error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum XmodemError<UserError> {
+    /// The maximum number of retries was exceeded.
+    TooManyRetries,
+    /// The remote end cancelled the transfer.
+    Cancelled,
+    /// An unexpected block number was received.
+    UnexpectedBlock(u8),
+    /// Error returned by the user sink or source callback.
+    User(UserError),
+}
+
+impl<UserError: core::fmt::Debug> core::fmt::Display for XmodemError<UserError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooManyRetries => write!(f, "maximum number of retries exceeded"),
+            Self::Cancelled => write!(f, "transfer cancelled by remote"),
+            Self::UnexpectedBlock(block) => write!(f, "unexpected block number {block}"),
+            Self::User(e) => write!(f, "user callback error: {e:?}"),
+        }
+    }
+}
+
+impl<UserError: core::fmt::Debug> core::error::Error for XmodemError<UserError> {}
+
+pub(crate) fn send_byte<R: RegisterAccess>(tx: &mut Tx<R>, byte: u8) {
+    nb::block!(tx.write_fifo(byte)).unwrap();
+}
+
+pub(crate) fn read_byte_timeout<R: RegisterAccess>(
+    rx: &mut Rx<R>,
+    timeout_polls: u32,
+) -> Option<u8> {
+    let mut byte = [0; 1];
+    (rx.read_bounded(&mut byte, timeout_polls) == 1).then_some(byte[0])
+}
+
+pub(crate) fn send_cancel<R: RegisterAccess>(tx: &mut Tx<R>) {
+    send_byte(tx, CAN);
+    send_byte(tx, CAN);
+}
+
+/// Check whether a received [CAN] is followed by a second one. A single [CAN] may be line
+/// noise, so a transfer is only cancelled by two consecutive [CAN] characters.
+fn cancel_confirmed<R: RegisterAccess>(rx: &mut Rx<R>, timeout_polls: u32) -> bool {
+    read_byte_timeout(rx, timeout_polls) == Some(CAN)
+}
+
+/// Count a retry and cancel the transfer once the retry budget is exhausted.
+fn retry<E, R: RegisterAccess>(
+    tx: &mut Tx<R>,
+    retries: &mut u32,
+    max_retries: u8,
+) -> Result<(), XmodemError<E>> {
+    *retries += 1;
+    if *retries > u32::from(max_retries) {
+        send_cancel(tx);
+        return Err(XmodemError::TooManyRetries);
+    }
+    Ok(())
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+/// XMODEM-CRC receiver.
+///
+/// The receiver requests CRC mode three times and falls back to checksum mode if the sender
+/// did not start the transfer.
+pub struct XmodemReceiver {
+    config: XmodemConfig,
+    block: [u8; 1024],
+}
+
+impl XmodemReceiver {
+    /// Create a new receiver.
+    pub const fn new(config: XmodemConfig) -> Self {
+        Self {
+            config,
+            block: [0; 1024],
+        }
+    }
+
+    /// Receive a file and pass each received block to the user sink.
+    ///
+    /// The data blocks are passed in order and duplicates are filtered. The last block is
+    /// padded with [SUB] characters by the sender, which are not removed. If the sink returns
+    /// an error, the transfer is cancelled and the error is returned.
+    ///
+    /// Returns the total number of received bytes, including padding.
+    pub fn receive<E, R: RegisterAccess>(
+        &mut self,
+        tx: &mut Tx<R>,
+        rx: &mut Rx<R>,
+        mut sink: impl FnMut(&[u8]) -> Result<(), E>,
+    ) -> Result<usize, XmodemError<E>> {
+        let mut expected_block: u8 = 1;
+        let mut total = 0;
+        let mut retries = 0;
+        let mut started = false;
+        let mut crc_mode = true;
+        let mut cancel_received = false;
+        send_byte(tx, CRC_MODE);
+        loop {
+            let byte = read_byte_timeout(rx, self.config.timeout_polls);
+            // Only two consecutive CAN characters cancel the transfer.
+            if byte == Some(CAN) && cancel_received {
+                return Err(XmodemError::Cancelled);
+            }
+            cancel_received = byte == Some(CAN);
+            let block_size = match byte {
+                Some(SOH) => 128,
+                Some(STX) => 1024,
+                Some(EOT) => {
+                    send_byte(tx, ACK);
+                    return Ok(total);
+                }
+                Some(CAN) => continue,
+                // Line noise.
+                Some(_) => {
+                    retry(tx, &mut retries, self.config.max_retries)?;
+                    continue;
+                }
+                None => {
+                    retry(tx, &mut retries, self.config.max_retries)?;
+                    if !started && retries >= CRC_MODE_ATTEMPTS {
+                        crc_mode = false;
+                    }
+                    send_byte(tx, if crc_mode && !started { CRC_MODE } else { NAK });
+                    continue;
+                }
+            };
+            match self.read_block(rx, block_size, crc_mode) {
+                Some(block_num) if block_num == expected_block => {
+                    if let Err(e) = sink(&self.block[0..block_size]) {
+                        send_cancel(tx);
+                        return Err(XmodemError::User(e));
+                    }
+                    total += block_size;
+                    expected_block = expected_block.wrapping_add(1);
+                    started = true;
+                    retries = 0;
+                    send_byte(tx, ACK);
+                }
+                // Our ACK was lost, the sender repeated the last block.
+                Some(block_num) if block_num == expected_block.wrapping_sub(1) => {
+                    send_byte(tx, ACK);
+                }
+                Some(block_num) => {
+                    send_cancel(tx);
+                    return Err(XmodemError::UnexpectedBlock(block_num));
+                }
+                None => {
+                    self.purge(rx);
+                    retry(tx, &mut retries, self.config.max_retries)?;
+                    send_byte(tx, NAK);
+                }
+            }
+        }
+    }
+
+    /// Read the rest of a block after the start character. Returns the block number if the
+    /// block was received completely and is valid.
+    fn read_block<R: RegisterAccess>(
+        &mut self,
+        rx: &mut Rx<R>,
+        block_size: usize,
+        crc_mode: bool,
+    ) -> Option<u8> {
+        let timeout = self.config.timeout_polls;
+        let block_num = read_byte_timeout(rx, timeout)?;
+        let block_num_inv = read_byte_timeout(rx, timeout)?;
+        let data = &mut self.block[0..block_size];
+        for byte in data.iter_mut() {
+            *byte = read_byte_timeout(rx, timeout)?;
+        }
+        let valid = if crc_mode {
+            let crc = u16::from_be_bytes([
+                read_byte_timeout(rx, timeout)?,
+                read_byte_timeout(rx, timeout)?,
+            ]);
+            crc == crc16_xmodem(data)
+        } else {
+            read_byte_timeout(rx, timeout)? == checksum(data)
+        };
+        if block_num ^ block_num_inv != 0xFF || !valid {
+            return None;
+        }
+        Some(block_num)
+    }
+
+    /// Discard all received data until the line is idle.
+    fn purge<R: RegisterAccess>(&mut self, rx: &mut Rx<R>) {
+        while read_byte_timeout(rx, self.config.timeout_polls).is_some() {}
+    }
+}
+
+/// XMODEM block size used by the [XmodemSender].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlockSize {
+    /// 128 byte blocks.
+    Bytes128,
+    /// 1024 byte blocks (XMODEM-1K). Only used if the receiver requests CRC mode, 128 byte
+    /// blocks are used otherwise.
+    Bytes1024,
+}
+
+/// XMODEM sender.
+///
+/// Supports both CRC and checksum mode, depending on what the receiver requests.
+pub struct XmodemSender {
+    config: XmodemConfig,
+    block_size: BlockSize,
+    block: [u8; 1024],
+}
+
+impl XmodemSender {
+    /// Create a new sender.
+    pub const fn new(config: XmodemConfig, block_size: BlockSize) -> Self {
+        Self {
+            config,
+            block_size,
+            block: [0; 1024],
+        }
+    }
+
+    /// Send the passed data. The last block is padded with [SUB] characters.
+    pub fn send_slice<R: RegisterAccess>(
+        &mut self,
+        tx: &mut Tx<R>,
+        rx: &mut Rx<R>,
+        data: &[u8],
+    ) -> Result<usize, XmodemError<core::convert::Infallible>> {
+        let mut rest = data;
+        self.send(tx, rx, |buf| {
+            let len = core::cmp::min(buf.len(), rest.len());
+            buf[0..len].copy_from_slice(&rest[0..len]);
+            rest = &rest[len..];
+            Ok(len)
+        })
+    }
+
+    /// Send data provided by the user source.
+    ///
+    /// The source callback fills the passed buffer and returns the number of bytes filled.
+    /// Returning 0 signals the end of the data. The last block is padded with [SUB]
+    /// characters. If the source returns an error, the transfer is cancelled and the error
+    /// is returned.
+    ///
+    /// Returns the total number of sent data bytes, excluding padding.
+    pub fn send<E, R: RegisterAccess>(
+        &mut self,
+        tx: &mut Tx<R>,
+        rx: &mut Rx<R>,
+        mut source: impl FnMut(&mut [u8]) -> Result<usize, E>,
+    ) -> Result<usize, XmodemError<E>> {
+        let crc_mode = self.wait_for_receiver(tx, rx)?;
+        let block_size = match self.block_size {
+            BlockSize::Bytes1024 if crc_mode => 1024,
+            _ => 128,
+        };
+        let mut block_num: u8 = 1;
+        let mut total = 0;
+        loop {
+            let mut filled = 0;
+            while filled < block_size {
+                match source(&mut self.block[filled..block_size]) {
+                    Ok(0) => break,
+                    Ok(len) => filled += len,
+                    Err(e) => {
+                        send_cancel(tx);
+                        return Err(XmodemError::User(e));
+                    }
+                }
+            }
+            if filled == 0 {
+                break;
+            }
+            self.block[filled..block_size].fill(SUB);
+            self.send_block(tx, rx, block_num, block_size, crc_mode)?;
+            total += filled;
+            block_num = block_num.wrapping_add(1);
+            if filled < block_size {
+                break;
+            }
+        }
+        let mut retries = 0;
+        loop {
+            send_byte(tx, EOT);
+            match read_byte_timeout(rx, self.config.timeout_polls) {
+                Some(ACK) => return Ok(total),
+                Some(CAN) if cancel_confirmed(rx, self.config.timeout_polls) => {
+                    return Err(XmodemError::Cancelled);
+                }
+                _ => retry(tx, &mut retries, self.config.max_retries)?,
+            }
+        }
+    }
+
+    /// Wait for the receiver to start the transfer. Returns whether CRC mode was requested.
+    fn wait_for_receiver<E, R: RegisterAccess>(
+        &mut self,
+        tx: &mut Tx<R>,
+        rx: &mut Rx<R>,
+    ) -> Result<bool, XmodemError<E>> {
+        let mut retries = 0;
+        loop {
+            match read_byte_timeout(rx, self.config.timeout_polls) {
+                Some(CRC_MODE) => return Ok(true),
+                Some(NAK) => return Ok(false),
+                Some(CAN) if cancel_confirmed(rx, self.config.timeout_polls) => {
+                    return Err(XmodemError::Cancelled);
+                }
+                _ => retry(tx, &mut retries, self.config.max_retries)?,
+            }
+        }
+    }
+
+    fn send_block<E, R: RegisterAccess>(
+        &mut self,
+        tx: &mut Tx<R>,
+        rx: &mut Rx<R>,
+        block_num: u8,
+        block_size: usize,
+        crc_mode: bool,
+    ) -> Result<(), XmodemError<E>> {
+        let data = &self.block[0..block_size];
+        let mut retries = 0;
+        loop {
+            send_byte(tx, if block_size == 1024 { STX } else { SOH });
+            send_byte(tx, block_num);
+            send_byte(tx, !block_num);
+            for &byte in data {
+                send_byte(tx, byte);
+            }
+            if crc_mode {
+                for byte in crc16_xmodem(data).to_be_bytes() {
+                    send_byte(tx, byte);
+                }
+            } else {
+                send_byte(tx, checksum(data));
+            }
+            match read_byte_timeout(rx, self.config.timeout_polls) {
+                Some(ACK) => return Ok(()),
+                Some(CAN) if cancel_confirmed(rx, self.config.timeout_polls) => {
+                    return Err(XmodemError::Cancelled);
+                }
+                _ => retry(tx, &mut retries, self.config.max_retries)?,
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Tests of the asynchronous RX structure which shares the waker slot of the asynchronous TX
+//! structure of the same instance, using the mock register backend.
+use std::{
+    pin::pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite, FIFO_DEPTH, RxAsync, Tx, TxAsync,
+    mock::{ErrorInjection, MockUartlite},
+    on_interrupt_tx_rx,
+};
+
+#[derive(Default)]
+struct CountingWaker {
+    wakes: AtomicUsize,
+}
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wakes.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn full_duplex_pair_shares_one_waker_slot() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.enable_interrupt();
+    let (tx, rx) = uart.split();
+    let mut tx_async = TxAsync::new(tx, 0).unwrap();
+    let mut rx_async = RxAsync::new(rx, 0).unwrap();
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let rx_waker = Arc::new(CountingWaker::default());
+    let rx_waker_ref = Waker::from(rx_waker.clone());
+    let mut rx_cx = Context::from_waker(&rx_waker_ref);
+    let mut tx_cx = Context::from_waker(Waker::noop());
+    let data: Vec<u8> = (0..3 * FIFO_DEPTH as u8).collect();
+    let mut buf = [0; FIFO_DEPTH];
+    let mut wire = Vec::new();
+    {
+        let mut rx_fut = pin!(rx_async.read(&mut buf));
+        assert_eq!(rx_fut.as_mut().poll(&mut rx_cx), Poll::Pending);
+        let mut tx_fut = pin!(tx_async.write(&data));
+        assert_eq!(tx_fut.as_mut().poll(&mut tx_cx), Poll::Pending);
+
+        // A single handler call for the shared slot services the TX transfer and wakes the RX
+        // task.
+        mock.push_rx_slice(b"hello");
+        on_interrupt_tx_rx(&mut isr_tx, 0);
+        assert_eq!(rx_waker.wakes.load(Ordering::SeqCst), 1);
+        assert_eq!(rx_fut.as_mut().poll(&mut rx_cx), Poll::Ready(5));
+
+        let written = loop {
+            if let Poll::Ready(written) = tx_fut.as_mut().poll(&mut tx_cx) {
+                break written;
+            }
+            let mut chunk = [0; FIFO_DEPTH];
+            let sent = mock.pop_tx_slice(&mut chunk);
+            wire.extend_from_slice(&chunk[0..sent]);
+            on_interrupt_tx_rx(&mut isr_tx, 0);
+        };
+        assert_eq!(written, data.len());
+    }
+    assert_eq!(wire, data);
+    assert_eq!(&buf[0..5], b"hello");
+    // The TX interrupts did not wake the RX task without received data.
+    assert_eq!(rx_waker.wakes.load(Ordering::SeqCst), 1);
+
+    // RX errors cleared by the status register read of the handler are passed on.
+    mock.inject_errors(ErrorInjection {
+        parity: false,
+        frame: true,
+        overrun: false,
+    });
+    on_interrupt_tx_rx(&mut isr_tx, 0);
+    let errors = rx_async.read_and_clear_last_error().unwrap();
+    assert!(errors.frame());
+    assert!(!errors.parity());
+    assert_eq!(rx_async.read_and_clear_last_error(), None);
+}
+
+#[test]
+fn empty_read_and_invalid_slot() {
+    let mock = MockUartlite::new();
+    let uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let (_tx, rx) = uart.split();
+    let mut rx_async = RxAsync::new(rx, 0).unwrap();
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(pin!(rx_async.read(&mut [])).poll(&mut cx), Poll::Ready(0));
+    // Data which is already in the RX FIFO is returned without waiting for an interrupt.
+    mock.push_rx(0x42);
+    let mut buf = [0; 4];
+    assert_eq!(pin!(rx_async.read(&mut buf)).poll(&mut cx), Poll::Ready(1));
+    assert_eq!(buf[0], 0x42);
+    let rx = rx_async.release();
+    assert!(RxAsync::new(rx, axi_uartlite::NUM_WAKERS).is_err());
+}
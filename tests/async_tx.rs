@@ -0,0 +1,348 @@
+//! Randomized loopback tests of the asynchronous TX engine using the mock register backend.
+//!
+//! The tests script the transmission of bytes out of the TX FIFO and the interrupt timing, and
+//! verify that every byte is transmitted exactly once and in order, that the future completes
+//! with the correct length and that no wakeups happen after completion.
+use std::{
+    pin::pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite, BufferedTxAsync, FIFO_DEPTH, Tx, TxAsync, TxDropPolicy, WriteFmtError,
+    mock::MockUartlite, on_interrupt_tx,
+};
+
+// The wakers and transfer contexts are static, so the tests must not run in parallel.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+// Miri is orders of magnitude slower, so fewer cases are checked.
+const NUM_CASES: usize = if cfg!(miri) { 16 } else { 256 };
+
+/// Small xorshift PRNG to keep the tests deterministic without additional dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+}
+
+#[derive(Default)]
+struct CountingWaker {
+    wakes: AtomicUsize,
+}
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wakes.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Emulate the transmission of up to `max` bytes and call the interrupt handler if the TX
+/// FIFO became empty or randomly to emulate spurious interrupts.
+fn step(rng: &mut Rng, mock: &MockUartlite, isr_tx: &mut Tx<&MockUartlite>, wire: &mut Vec<u8>) {
+    let was_empty = mock.tx_len() == 0;
+    let mut buf = [0; FIFO_DEPTH];
+    let max = rng.below(5);
+    let sent = mock.pop_tx_slice(&mut buf[0..max]);
+    wire.extend_from_slice(&buf[0..sent]);
+    let fifo_empty_edge = !was_empty && mock.tx_len() == 0;
+    if fifo_empty_edge || rng.chance(10) {
+        on_interrupt_tx(isr_tx, 0);
+    }
+}
+
+fn test_data(rng: &mut Rng, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.next() as u8).collect()
+}
+
+fn setup(mock: &MockUartlite) -> (TxAsync<&MockUartlite>, Tx<&MockUartlite>) {
+    let mut uart = AxiUartlite::new_with_regs(mock, mock);
+    uart.enable_interrupt();
+    let (tx, _rx) = uart.split();
+    (TxAsync::new(tx, 0).unwrap(), Tx::new_with_regs(mock))
+}
+
+#[test]
+fn random_sizes_and_interrupt_timings() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    for _ in 0..NUM_CASES {
+        let len = rng.below(4 * FIFO_DEPTH + 1);
+        let data = test_data(&mut rng, len);
+        let waker_state = Arc::new(CountingWaker::default());
+        let waker = Waker::from(waker_state.clone());
+        let mut cx = Context::from_waker(&waker);
+        let mut wire = Vec::new();
+        let mut fut = pin!(tx_async.write(&data));
+        let written = loop {
+            if rng.chance(50) {
+                if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+                    break written;
+                }
+            }
+            step(&mut rng, &mock, &mut isr_tx, &mut wire);
+        };
+        let wakes = waker_state.wakes.load(Ordering::SeqCst);
+        // Further interrupts after completion must not wake the task again.
+        for _ in 0..4 {
+            on_interrupt_tx(&mut isr_tx, 0);
+        }
+        assert_eq!(waker_state.wakes.load(Ordering::SeqCst), wakes);
+        assert_eq!(written, data.len());
+        assert_eq!(mock.tx_len(), 0);
+        assert_eq!(wire, data);
+    }
+}
+
+#[test]
+fn random_cancellations() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    let waker = Waker::from(Arc::new(CountingWaker::default()));
+    let mut cx = Context::from_waker(&waker);
+    for _ in 0..NUM_CASES {
+        let len = 1 + rng.below(4 * FIFO_DEPTH);
+        let data = test_data(&mut rng, len);
+        let mut wire = Vec::new();
+        let cancel_after = rng.below(16);
+        {
+            let mut fut = pin!(tx_async.write(&data));
+            for _ in 0..cancel_after {
+                if fut.as_mut().poll(&mut cx).is_ready() {
+                    break;
+                }
+                step(&mut rng, &mock, &mut isr_tx, &mut wire);
+            }
+        }
+        // Dropping the future resets the TX FIFO, so only a prefix of the data was sent.
+        assert_eq!(mock.tx_len(), 0);
+        assert!(data.starts_with(&wire));
+        // Interrupts after cancellation must not write stale data.
+        on_interrupt_tx(&mut isr_tx, 0);
+        assert_eq!(mock.tx_len(), 0);
+
+        // The next transfer must not be affected by the cancelled one.
+        let len = rng.below(2 * FIFO_DEPTH + 1);
+        let data = test_data(&mut rng, len);
+        let mut wire = Vec::new();
+        let mut fut = pin!(tx_async.write(&data));
+        let written = loop {
+            if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+                break written;
+            }
+            step(&mut rng, &mock, &mut isr_tx, &mut wire);
+        };
+        assert_eq!(written, data.len());
+        assert_eq!(wire, data);
+    }
+}
+
+#[test]
+fn cancellation_leaving_fifo() {
+    let _guard = SERIAL.lock().unwrap();
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    assert_eq!(tx_async.drop_policy(), TxDropPolicy::ResetFifo);
+    tx_async.set_drop_policy(TxDropPolicy::LeaveAsIs);
+    let mut cx = Context::from_waker(Waker::noop());
+    let data: Vec<u8> = (0..2 * FIFO_DEPTH as u8).collect();
+    {
+        let mut fut = pin!(tx_async.write(&data));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+    }
+    // The initial FIFO fill stays in the FIFO, but no further data is written.
+    assert_eq!(mock.tx_len(), FIFO_DEPTH);
+    let mut buf = [0; FIFO_DEPTH];
+    assert_eq!(mock.pop_tx_slice(&mut buf), FIFO_DEPTH);
+    assert_eq!(buf, data[0..FIFO_DEPTH]);
+    on_interrupt_tx(&mut isr_tx, 0);
+    assert_eq!(mock.tx_len(), 0);
+}
+
+#[test]
+fn formatted_write() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    let values: Vec<u32> = (0..20).map(|_| rng.next() as u32).collect();
+    let text: String = values.iter().map(|v| format!("{v:#x},")).collect();
+    let expected = format!("{text} end=20");
+    assert!(expected.len() > 4 * FIFO_DEPTH);
+    assert!(expected.len() <= axi_uartlite::FMT_BUFFER_SIZE);
+    let mut wire = Vec::new();
+    let mut fut = pin!(async { write!(tx_async, "{text} end={}", values.len()).await });
+    let result = loop {
+        if let Poll::Ready(result) = fut.as_mut().poll(&mut cx) {
+            break result;
+        }
+        step(&mut rng, &mock, &mut isr_tx, &mut wire);
+    };
+    assert_eq!(result, Ok(()));
+    assert_eq!(wire, expected.as_bytes());
+}
+
+#[test]
+fn formatted_write_too_long() {
+    let _guard = SERIAL.lock().unwrap();
+    let mock = MockUartlite::new();
+    let (mut tx_async, _isr_tx) = setup(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    let text = "x".repeat(axi_uartlite::FMT_BUFFER_SIZE);
+    let mut fut = pin!(async { write!(tx_async, "{text}!").await });
+    assert_eq!(
+        fut.as_mut().poll(&mut cx),
+        Poll::Ready(Err(WriteFmtError::TooLong))
+    );
+    assert_eq!(mock.tx_len(), 0);
+}
+
+#[test]
+fn vectored_write() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rng = Rng(0xA076_1D64_78BD_642F);
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    let payload = test_data(&mut rng, 50);
+    let bufs: [&[u8]; 4] = [b"HDR:", &payload, b"", b"\r\n"];
+    let mut wire = Vec::new();
+    let mut fut = pin!(tx_async.write_vectored(&bufs));
+    let written = loop {
+        if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+            break written;
+        }
+        step(&mut rng, &mock, &mut isr_tx, &mut wire);
+    };
+    assert_eq!(written, 56);
+    while mock.tx_len() > 0 {
+        step(&mut rng, &mock, &mut isr_tx, &mut wire);
+    }
+    assert_eq!(wire, bufs.concat());
+}
+
+#[test]
+fn buffered_writer_flushes_on_newline_and_full_buffer() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rng = Rng(0x94D0_49BB_1331_11EB);
+    let mock = MockUartlite::new();
+    let (tx_async, mut isr_tx) = setup(&mock);
+    let mut writer = BufferedTxAsync::<24, _>::new(tx_async);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut wire = Vec::new();
+
+    // Small writes without a newline stay in the buffer.
+    for part in [b"temp=".as_slice(), b"21", b" C"] {
+        let mut fut = pin!(writer.write(part));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(part.len()));
+    }
+    assert_eq!(writer.buffered(), 9);
+    assert_eq!(mock.tx_len(), 0);
+
+    // A newline flushes the line.
+    {
+        let mut fut = pin!(writer.write(b"\n"));
+        let written = loop {
+            if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+                break written;
+            }
+            step(&mut rng, &mock, &mut isr_tx, &mut wire);
+        };
+        assert_eq!(written, 1);
+    }
+    assert_eq!(writer.buffered(), 0);
+    assert_eq!(wire, b"temp=21 C\n");
+
+    // Data exceeding the buffer is written in buffer sized transfers, the rest is kept.
+    wire.clear();
+    let data: Vec<u8> = (b'a'..=b'z').cycle().take(60).collect();
+    {
+        let mut fut = pin!(writer.write(&data));
+        let written = loop {
+            if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+                break written;
+            }
+            step(&mut rng, &mock, &mut isr_tx, &mut wire);
+        };
+        assert_eq!(written, data.len());
+    }
+    assert_eq!(wire, data[0..48]);
+    assert_eq!(writer.buffered(), 12);
+
+    // An explicit flush writes the rest.
+    {
+        let mut fut = pin!(writer.flush());
+        while fut.as_mut().poll(&mut cx).is_pending() {
+            step(&mut rng, &mock, &mut isr_tx, &mut wire);
+        }
+    }
+    assert_eq!(wire, data);
+    assert_eq!(writer.buffered(), 0);
+}
+
+#[test]
+fn resume_cancelled_write() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rng = Rng(0xBF58_476D_1CE4_E5B9);
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    tx_async.set_drop_policy(TxDropPolicy::LeaveAsIs);
+    let mut cx = Context::from_waker(Waker::noop());
+    let data: Vec<u8> = (0..5 * FIFO_DEPTH).map(|i| i as u8).collect();
+    let mut wire = Vec::new();
+    let mut rounds = 0;
+    loop {
+        let mut done = false;
+        {
+            let mut fut = pin!(tx_async.resume(&data));
+            // Lose the race after a few interrupts.
+            for _ in 0..3 {
+                if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+                    assert_eq!(written, data.len());
+                    done = true;
+                    break;
+                }
+                step(&mut rng, &mock, &mut isr_tx, &mut wire);
+            }
+        }
+        if done {
+            break;
+        }
+        let paused = tx_async.paused_transfer().unwrap();
+        assert_eq!(paused.len, data.len());
+        rounds += 1;
+    }
+    assert!(rounds > 0);
+    // Drain the bytes left in the TX FIFO by the last round.
+    while mock.tx_len() > 0 {
+        step(&mut rng, &mock, &mut isr_tx, &mut wire);
+    }
+    assert_eq!(wire, data);
+    assert_eq!(tx_async.paused_transfer(), None);
+}
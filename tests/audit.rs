@@ -0,0 +1,65 @@
+//! Tests for the control register write audit.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axi_uartlite::{AxiUartlite, CONTROL_AUDIT_DEPTH, ControlWrite, mock::MockUartlite};
+
+const ENABLE_INTR: u32 = 1 << 4;
+const RESET_RX: u32 = 1 << 1;
+
+static CLOCK: AtomicU64 = AtomicU64::new(0);
+
+fn now() -> u64 {
+    CLOCK.fetch_add(1, Ordering::Relaxed)
+}
+
+#[test]
+fn recorded_writes() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    assert!(uart.control_audit().is_empty());
+    uart.enable_interrupt();
+    uart.set_audit_clock(now);
+    uart.reset_rx_fifo();
+    uart.disable_interrupt();
+
+    let writes: Vec<_> = uart.control_audit().iter().collect();
+    assert_eq!(
+        writes,
+        [
+            ControlWrite {
+                value: ENABLE_INTR,
+                timestamp: None,
+            },
+            ControlWrite {
+                value: ENABLE_INTR | RESET_RX,
+                timestamp: Some(0),
+            },
+            ControlWrite {
+                value: 0,
+                timestamp: Some(1),
+            },
+        ]
+    );
+    assert_eq!(uart.diagnostics().control_writes, *uart.control_audit());
+}
+
+#[test]
+fn ring_overwrites_oldest() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    for _ in 0..CONTROL_AUDIT_DEPTH {
+        uart.enable_interrupt();
+    }
+    uart.disable_interrupt();
+    let audit = uart.control_audit();
+    assert_eq!(audit.len(), CONTROL_AUDIT_DEPTH);
+    assert_eq!(audit.iter().next().unwrap().value, ENABLE_INTR);
+    assert_eq!(audit.last().unwrap().value, 0);
+    assert_eq!(
+        audit
+            .iter()
+            .filter(|write| write.value == ENABLE_INTR)
+            .count(),
+        CONTROL_AUDIT_DEPTH - 1
+    );
+}
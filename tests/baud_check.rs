@@ -0,0 +1,68 @@
+//! Tests for the baud rate mismatch heuristic.
+use axi_uartlite::{
+    AxiUartlite,
+    baud_check::{BaudCheck, BaudVerdict, is_stretched_pattern},
+    mock::{ErrorInjection, MockUartlite},
+};
+
+const FRAME: ErrorInjection = ErrorInjection {
+    parity: false,
+    frame: true,
+    overrun: false,
+};
+
+/// Receive the bytes through the mock, with a frame error for every byte where `frame_error`
+/// returns true, and feed them into a new analyzer.
+fn check(bytes: &[u8], frame_error: impl Fn(usize) -> bool) -> BaudCheck {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let mut check = BaudCheck::new();
+    for (idx, &byte) in bytes.iter().enumerate() {
+        if frame_error(idx) {
+            mock.push_rx_with_errors(byte, FRAME);
+        } else {
+            mock.push_rx(byte);
+        }
+        check.record_byte(uart.read_fifo().unwrap());
+    }
+    check.record_errors(&uart.read_and_clear_errors());
+    check
+}
+
+#[test]
+fn stretched_patterns() {
+    for byte in [0x00, 0x80, 0xC0, 0xF0, 0xFE, 0xFF] {
+        assert!(is_stretched_pattern(byte), "{byte:#04x}");
+    }
+    for byte in [0x01, 0x0F, 0x41, 0x7F, 0xA5] {
+        assert!(!is_stretched_pattern(byte), "{byte:#04x}");
+    }
+}
+
+#[test]
+fn verdicts() {
+    let text = b"hello world, baud rate fine";
+    assert_eq!(
+        check(&text[0..8], |_| false).verdict(),
+        BaudVerdict::Insufficient
+    );
+    let clean = check(text, |_| false);
+    assert_eq!(clean.bytes(), text.len() as u32);
+    assert_eq!(clean.verdict(), BaudVerdict::Plausible);
+    // A single frame error on text traffic is noise.
+    assert_eq!(
+        check(text, |idx| idx == 3).verdict(),
+        BaudVerdict::Plausible
+    );
+    // High frame error ratio.
+    let garbage = check(text, |idx| idx % 3 == 0);
+    assert_eq!(garbage.frame_errors(), 9);
+    assert_eq!(garbage.verdict(), BaudVerdict::ProbableMismatch);
+    // Elevated frame error ratio with stretched patterns of a slower remote end.
+    let slow = [0x00, 0x80, 0xF0, 0xFE, 0xC0, 0x41, 0x00, 0xE0].repeat(4);
+    let mut slow_check = check(&slow, |idx| idx % 10 == 0);
+    assert_eq!(slow_check.stretched_bytes(), 28);
+    assert_eq!(slow_check.verdict(), BaudVerdict::ProbableMismatch);
+    slow_check.reset();
+    assert_eq!(slow_check, BaudCheck::new());
+}
@@ -0,0 +1,80 @@
+//! Tests for the `bbqueue` RX integration.
+use axi_uartlite::{
+    Rx,
+    bbq::{on_interrupt_rx_bbqueue, on_interrupt_rx_bbqueue_framed},
+    mock::MockUartlite,
+};
+use bbqueue::BBBuffer;
+
+#[test]
+fn stream() {
+    let queue = BBBuffer::<32>::new();
+    let (mut producer, mut consumer) = queue.try_split().unwrap();
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(b"hello");
+    assert_eq!(on_interrupt_rx_bbqueue(&mut rx, &mut producer).unwrap(), 5);
+    mock.push_rx_slice(b" world");
+    assert_eq!(on_interrupt_rx_bbqueue(&mut rx, &mut producer).unwrap(), 6);
+    // An empty RX FIFO commits nothing.
+    assert_eq!(on_interrupt_rx_bbqueue(&mut rx, &mut producer).unwrap(), 0);
+
+    let grant = consumer.read().unwrap();
+    assert_eq!(grant.buf(), b"hello world");
+    grant.release(11);
+    assert!(consumer.read().is_err());
+}
+
+#[test]
+fn full_queue_drains_rx_fifo() {
+    let queue = BBBuffer::<8>::new();
+    let (mut producer, mut consumer) = queue.try_split().unwrap();
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(b"0123456789abcdef");
+    assert_eq!(on_interrupt_rx_bbqueue(&mut rx, &mut producer).unwrap(), 8);
+    assert_eq!(mock.rx_len(), 8);
+    let dropped = on_interrupt_rx_bbqueue(&mut rx, &mut producer).unwrap_err();
+    assert_eq!(dropped.dropped, 8);
+    assert!(matches!(dropped.cause, bbqueue::Error::InsufficientSize));
+    assert_eq!(
+        dropped.to_string(),
+        "dropped 8 RX bytes, grant error: InsufficientSize"
+    );
+    // The FIFO was drained to avoid a hardware overrun.
+    assert_eq!(mock.rx_len(), 0);
+
+    let grant = consumer.read().unwrap();
+    assert_eq!(grant.buf(), b"01234567");
+}
+
+#[test]
+fn frames() {
+    let queue = BBBuffer::<64>::new();
+    let (mut producer, mut consumer) = queue.try_split_framed().unwrap();
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(b"abc");
+    assert_eq!(
+        on_interrupt_rx_bbqueue_framed(&mut rx, &mut producer).unwrap(),
+        3
+    );
+    mock.push_rx_slice(b"de");
+    assert_eq!(
+        on_interrupt_rx_bbqueue_framed(&mut rx, &mut producer).unwrap(),
+        2
+    );
+    // No empty frame is committed.
+    assert_eq!(
+        on_interrupt_rx_bbqueue_framed(&mut rx, &mut producer).unwrap(),
+        0
+    );
+
+    let frame = consumer.read().unwrap();
+    assert_eq!(&frame[..], b"abc");
+    frame.release();
+    let frame = consumer.read().unwrap();
+    assert_eq!(&frame[..], b"de");
+    frame.release();
+    assert!(consumer.read().is_none());
+}
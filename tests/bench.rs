@@ -0,0 +1,161 @@
+//! Tests for the benchmark routines.
+use core::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use axi_uartlite::{
+    AxiUartlite, Tx,
+    bench::{
+        BenchReport, InterruptBench, LatencyStats, RoundTrip, Throughput, measure_round_trip,
+        measure_tx_throughput,
+    },
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+/// Backend which sends every byte immediately.
+#[derive(Default)]
+struct Sink {
+    sent: Cell<u32>,
+}
+
+impl RegisterAccess for &Sink {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(0)
+    }
+
+    fn write_tx_fifo(&mut self, _value: TxFifo) {
+        self.sent.set(self.sent.get() + 1);
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        // TX FIFO empty.
+        Status::new_with_raw_value(1 << 2)
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+/// Backend which echoes every written byte, except for the byte at `drop`, which is replaced
+/// by a stray byte.
+#[derive(Default)]
+struct Echo {
+    fifo: RefCell<VecDeque<u8>>,
+    written: Cell<usize>,
+    drop: Option<usize>,
+}
+
+impl RegisterAccess for &Echo {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(self.fifo.borrow_mut().pop_front().unwrap_or(0) as u32)
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        let idx = self.written.get();
+        self.written.set(idx + 1);
+        let byte = if self.drop == Some(idx) {
+            0xA5
+        } else {
+            value.raw_value() as u8
+        };
+        self.fifo.borrow_mut().push_back(byte);
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        Status::new_with_raw_value(u32::from(!self.fifo.borrow().is_empty()) | (1 << 2))
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+#[test]
+fn tx_throughput() {
+    let sink = Sink::default();
+    let mut tx = Tx::new_with_regs(&sink);
+    // Counter which advances 100 cycles per read and wraps during the measurement.
+    let mut now = u32::MAX - 50;
+    let mut counter = || {
+        now = now.wrapping_add(100);
+        now
+    };
+    let throughput = measure_tx_throughput(&mut tx, &mut counter, 64);
+    assert_eq!(sink.sent.get(), 64);
+    assert_eq!(
+        throughput,
+        Throughput {
+            bytes: 64,
+            cycles: 100
+        }
+    );
+    assert_eq!(throughput.bytes_per_sec(1000), 640);
+    assert_eq!(Throughput::default().bytes_per_sec(1000), 0);
+}
+
+#[test]
+fn latency_stats() {
+    let mut stats = LatencyStats::new();
+    assert_eq!(stats.avg(), None);
+    stats.record(10);
+    stats.record(30);
+    stats.record(20);
+    assert_eq!(stats.count(), 3);
+    assert_eq!(stats.min(), Some(10));
+    assert_eq!(stats.max(), Some(30));
+    assert_eq!(stats.avg(), Some(20));
+}
+
+#[test]
+fn interrupt_bench_and_report() {
+    let mut bench = InterruptBench::new();
+    assert_eq!(bench.bytes_per_interrupt(), None);
+    bench.arm(u32::MAX - 4);
+    bench.on_interrupt(5, 16);
+    // Not armed, only counts the bytes.
+    bench.on_interrupt(100, 8);
+    assert_eq!(bench.latency().count(), 1);
+    assert_eq!(bench.latency().max(), Some(10));
+    assert_eq!(bench.interrupts(), 2);
+    assert_eq!(bench.bytes_per_interrupt(), Some(12));
+
+    let mut report = BenchReport::new(1000);
+    assert_eq!(report.to_string(), "");
+    report.tx_throughput = Some(Throughput {
+        bytes: 64,
+        cycles: 100,
+    });
+    report.interrupts = Some(bench);
+    assert_eq!(
+        report.to_string(),
+        "bench tx_throughput bytes=64 cycles=100 bytes_per_sec=640\n\
+         bench isr_latency count=1 min=10 max=10 avg=10\n\
+         bench interrupts count=2 bytes=24 bytes_per_interrupt=12\n"
+    );
+}
+
+#[test]
+fn round_trip() {
+    let echo = Echo {
+        drop: Some(2),
+        ..Default::default()
+    };
+    echo.fifo.borrow_mut().push_back(0xFF);
+    let mut uart = AxiUartlite::new_with_regs(&echo, &echo);
+    let mut now = u32::MAX - 10;
+    let mut counter = || {
+        now = now.wrapping_add(10);
+        now
+    };
+    let round_trip = measure_round_trip(&mut uart, &mut counter, 4, 50);
+    assert_eq!(echo.written.get(), 4);
+    assert_eq!(round_trip.lost, 1);
+    assert_eq!(round_trip.unexpected, 1);
+    assert_eq!(round_trip.latency.count(), 3);
+    assert_eq!(round_trip.latency.min(), Some(10));
+    assert_eq!(round_trip.latency.max(), Some(10));
+
+    let mut report = BenchReport::new(1000);
+    report.round_trip = Some(round_trip);
+    assert_eq!(
+        report.to_string(),
+        "bench round_trip count=3 min=10 max=10 avg=10 lost=1\n"
+    );
+    assert_eq!(RoundTrip::default().latency.count(), 0);
+}
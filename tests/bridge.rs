@@ -0,0 +1,49 @@
+//! Tests for the software bridge.
+use axi_uartlite::{AxiUartlite, Bridge, FIFO_DEPTH, bridge, mock::MockUartlite};
+
+#[test]
+fn forwards_both_directions() {
+    let mock_a = MockUartlite::new();
+    let mock_b = MockUartlite::new();
+    let mut a = AxiUartlite::new_with_regs(&mock_a, &mock_a);
+    let mut b = AxiUartlite::new_with_regs(&mock_b, &mock_b);
+    let mut bridge = Bridge::<32>::new();
+    bridge.enable_interrupts(&mut a, &mut b);
+    assert!(mock_a.interrupt_enabled() && mock_b.interrupt_enabled());
+
+    mock_a.push_rx_slice(b"ping");
+    mock_b.push_rx_slice(b"pong!");
+    assert_eq!(bridge.service(&mut a, &mut b), 9);
+    let mut buf = [0; 8];
+    assert_eq!(mock_b.pop_tx_slice(&mut buf), 4);
+    assert_eq!(&buf[0..4], b"ping");
+    assert_eq!(mock_a.pop_tx_slice(&mut buf), 5);
+    assert_eq!(&buf[0..5], b"pong!");
+
+    // A full TX FIFO on B keeps the data buffered.
+    mock_b.force_tx_fifo_full(true);
+    mock_a.push_rx_slice(&[0x11; FIFO_DEPTH]);
+    bridge.service(&mut a, &mut b);
+    assert_eq!(bridge.pending_a_to_b(), FIFO_DEPTH);
+    mock_b.force_tx_fifo_full(false);
+    bridge.service(&mut a, &mut b);
+    assert_eq!(bridge.pending_a_to_b(), 0);
+
+    let stats = bridge.stats();
+    assert_eq!(stats.a_to_b, 4 + FIFO_DEPTH as u32);
+    assert_eq!(stats.b_to_a, 5);
+    assert_eq!(stats.a_dropped, 0);
+    assert_eq!(stats.b_dropped, 0);
+}
+
+#[test]
+fn blocking_bridge() {
+    let mock_a = MockUartlite::new();
+    let mock_b = MockUartlite::new();
+    let mut a = AxiUartlite::new_with_regs(&mock_a, &mock_a);
+    let mut b = AxiUartlite::new_with_regs(&mock_b, &mock_b);
+    mock_b.push_rx_slice(b"console");
+    let stats = bridge(&mut a, &mut b, |stats| stats.b_to_a == 7);
+    assert_eq!(stats.b_to_a, 7);
+    assert_eq!(mock_a.tx_len(), 7);
+}
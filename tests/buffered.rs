@@ -0,0 +1,407 @@
+//! Tests for the buffered driver.
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite, BufferedState, BufferedUartlite, FIFO_DEPTH, Rx, RxOverflowConfig,
+    RxOverflowPolicy, Tx, UrgentFrameTooLarge,
+    mock::{ErrorInjection, MockUartlite},
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+/// Mock backend which transmits the whole TX FIFO on every status register read, like a line
+/// which is much faster than the CPU.
+struct Wire<'mock> {
+    mock: &'mock MockUartlite,
+    sent: RefCell<Vec<u8>>,
+}
+
+impl RegisterAccess for &Wire<'_> {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        let mut mock = self.mock;
+        mock.read_rx_fifo()
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        let mut mock = self.mock;
+        mock.write_tx_fifo(value)
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        while let Some(byte) = self.mock.pop_tx() {
+            self.sent.borrow_mut().push(byte);
+        }
+        self.mock.read_stat_reg()
+    }
+
+    fn write_ctrl_reg(&mut self, value: Control) {
+        let mut mock = self.mock;
+        mock.write_ctrl_reg(value)
+    }
+}
+
+#[test]
+fn overflow_policies() {
+    for policy in [RxOverflowPolicy::DropNewest, RxOverflowPolicy::DropOldest] {
+        let state = BufferedState::<8, 4>::new();
+        assert_eq!(state.rx_overflow_config(), RxOverflowConfig::default());
+        state.set_rx_overflow_config(RxOverflowConfig {
+            policy,
+            reset_rx_fifo: false,
+        });
+        let mock = MockUartlite::new();
+        let (mut tx, mut rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+        mock.push_rx_slice(b"abcdef");
+        state.on_interrupt(&mut tx, &mut rx);
+        assert_eq!(state.rx_overflows(), 2);
+        mock.push_rx_slice(b"gh");
+        state.on_interrupt(&mut tx, &mut rx);
+        assert_eq!(state.rx_overflows(), 4);
+    }
+}
+
+#[test]
+fn overflow_resets_rx_fifo() {
+    let state = BufferedState::<8, 4>::new();
+    let config = RxOverflowConfig {
+        policy: RxOverflowPolicy::DropOldest,
+        reset_rx_fifo: true,
+    };
+    state.set_rx_overflow_config(config);
+    assert_eq!(state.rx_overflow_config(), config);
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.enable_interrupt();
+    let (mut tx, mut rx) = uart.split();
+    mock.push_rx_slice(b"abcdef");
+    state.on_interrupt(&mut tx, &mut rx);
+    assert_eq!(state.rx_overflows(), 2);
+    // The interrupt stays enabled after the RX FIFO reset.
+    assert!(mock.interrupt_enabled());
+    assert_eq!(mock.rx_len(), 0);
+}
+
+#[test]
+fn rx_watermark() {
+    let state = BufferedState::<8, 8>::new();
+    let mock = MockUartlite::new();
+    let (mut tx, mut rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut watermark = pin!(state.wait_rx_watermark(4));
+    assert!(watermark.as_mut().poll(&mut cx).is_pending());
+    mock.push_rx_slice(b"abc");
+    state.on_interrupt(&mut tx, &mut rx);
+    assert!(watermark.as_mut().poll(&mut cx).is_pending());
+    mock.push_rx(b'd');
+    state.on_interrupt(&mut tx, &mut rx);
+    assert!(watermark.as_mut().poll(&mut cx).is_ready());
+
+    // The watermark is clamped to the RX ring buffer size.
+    mock.push_rx_slice(b"efgh");
+    state.on_interrupt(&mut tx, &mut rx);
+    assert!(pin!(state.wait_rx_watermark(100)).poll(&mut cx).is_ready());
+}
+
+static HIGH_WATERMARK_EVENTS: AtomicUsize = AtomicUsize::new(0);
+static HIGH_WATERMARK_FILL: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn rx_high_watermark_handler() {
+    let state = BufferedState::<8, 8>::new();
+    let mock = MockUartlite::new();
+    let (mut tx, mut rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    state.set_rx_high_watermark(6, |fill| {
+        HIGH_WATERMARK_EVENTS.fetch_add(1, Ordering::Relaxed);
+        HIGH_WATERMARK_FILL.store(fill, Ordering::Relaxed);
+    });
+    mock.push_rx_slice(b"abcde");
+    state.on_interrupt(&mut tx, &mut rx);
+    assert_eq!(state.rx_len(), 5);
+    assert!(!state.rx_above_high_watermark());
+    assert_eq!(HIGH_WATERMARK_EVENTS.load(Ordering::Relaxed), 0);
+
+    mock.push_rx_slice(b"fg");
+    state.on_interrupt(&mut tx, &mut rx);
+    assert!(state.rx_above_high_watermark());
+    assert_eq!(HIGH_WATERMARK_EVENTS.load(Ordering::Relaxed), 1);
+    assert_eq!(HIGH_WATERMARK_FILL.load(Ordering::Relaxed), 7);
+
+    // The handler is only called once per crossing.
+    mock.push_rx(b'h');
+    state.on_interrupt(&mut tx, &mut rx);
+    assert_eq!(HIGH_WATERMARK_EVENTS.load(Ordering::Relaxed), 1);
+
+    state.clear_rx_high_watermark();
+    assert_eq!(state.rx_len(), 8);
+}
+
+#[test]
+fn concurrent_tx_producers() {
+    const PRODUCERS: usize = 4;
+    const LINES: usize = 50;
+    let state = BufferedState::<64, 8>::new();
+    let mock = MockUartlite::new();
+    let (mut tx, mut rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut wire = Vec::new();
+    let done = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for id in 0..PRODUCERS {
+            let producer = state.producer();
+            let done = &done;
+            scope.spawn(move || {
+                for line in 0..LINES {
+                    let text = format!("p{id} line {line}\n");
+                    while !producer.try_enqueue(text.as_bytes()) {
+                        std::thread::yield_now();
+                    }
+                }
+                done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        loop {
+            let finished = done.load(Ordering::SeqCst) == PRODUCERS;
+            let mut buf = [0; 16];
+            let sent = mock.pop_tx_slice(&mut buf);
+            wire.extend_from_slice(&buf[0..sent]);
+            state.on_interrupt(&mut tx, &mut rx);
+            if finished && sent == 0 && mock.tx_len() == 0 {
+                break;
+            }
+        }
+    });
+    let text = String::from_utf8(wire).unwrap();
+    let mut lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), PRODUCERS * LINES);
+    lines.sort();
+    let mut expected: Vec<String> = (0..PRODUCERS)
+        .flat_map(|id| (0..LINES).map(move |line| format!("p{id} line {line}")))
+        .collect();
+    expected.sort();
+    assert_eq!(lines, expected);
+
+    // Chunks which can never fit are dropped and counted.
+    let dropped = state.tx_dropped_chunks();
+    assert!(!state.producer().try_enqueue(&[0; 65]));
+    assert_eq!(state.tx_dropped_chunks(), dropped + 1);
+}
+
+#[test]
+fn rx_error_events() {
+    let state = BufferedState::<8, 8>::new();
+    let mock = MockUartlite::new();
+    let (mut tx, mut rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut wait = pin!(state.wait_rx_error());
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+    mock.push_rx(b'a');
+    state.on_interrupt(&mut tx, &mut rx);
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+
+    mock.push_rx_with_errors(
+        b'b',
+        ErrorInjection {
+            frame: true,
+            ..Default::default()
+        },
+    );
+    state.on_interrupt(&mut tx, &mut rx);
+    let Poll::Ready(event) = wait.as_mut().poll(&mut cx) else {
+        panic!("no error event");
+    };
+    assert!(event.errors.frame());
+    assert!(!event.errors.parity());
+    assert_eq!(event.count, 1);
+
+    // Only the latest event is kept.
+    for _ in 0..2 {
+        mock.push_rx_with_errors(
+            b'c',
+            ErrorInjection {
+                parity: true,
+                ..Default::default()
+            },
+        );
+        state.on_interrupt(&mut tx, &mut rx);
+    }
+    let event = state.try_take_rx_error().unwrap();
+    assert!(event.errors.parity());
+    assert_eq!(event.count, 3);
+    assert_eq!(state.try_take_rx_error(), None);
+}
+
+static CONST_STATE: BufferedState<8, 8> = BufferedState::new();
+// The register block is never accessed because the driver is not initialized.
+static CONST_UART: critical_section::Mutex<RefCell<BufferedUartlite<'static, 8, 8>>> =
+    critical_section::Mutex::new(RefCell::new(BufferedUartlite::new_const(
+        unsafe { AxiUartlite::new(0x4060_0000) },
+        &CONST_STATE,
+    )));
+
+#[test]
+fn const_constructible_driver() {
+    critical_section::with(|cs| {
+        let mut uart = CONST_UART.borrow(cs).borrow_mut();
+        let mut buf = [0; 4];
+        assert_eq!(uart.try_read(&mut buf), 0);
+    });
+}
+
+#[test]
+fn urgent_frames_wrap_around_the_urgent_lane() {
+    let state = BufferedState::<32, 32, 8>::new();
+    let mock = MockUartlite::new();
+    let mut uart = BufferedUartlite::new(AxiUartlite::new_with_regs(&mock, &mock), &state);
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let mut isr_rx = Rx::new_with_regs(&mock);
+    let mut wire = Vec::new();
+    let mut drain = |wire: &mut Vec<u8>| {
+        let mut buf = [0; FIFO_DEPTH];
+        let len = mock.pop_tx_slice(&mut buf);
+        wire.extend_from_slice(&buf[0..len]);
+    };
+
+    // The transfer is active, so the urgent frames stay inside the urgent lane.
+    assert_eq!(uart.try_write(b"0123456789abcdefXYZ"), 19);
+    assert!(uart.try_write_urgent(b"uuuuuu"));
+    drain(&mut wire);
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    // This frame wraps around the end of the urgent lane.
+    assert!(uart.try_write_urgent(b"ABCDEF"));
+    assert!(!uart.try_write_urgent(b"toolong"));
+    drain(&mut wire);
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    drain(&mut wire);
+    assert_eq!(wire, b"0123456789abcdefuuuuuuXYZABCDEF");
+
+    // Frames which can never fit are rejected instead of waiting forever.
+    let mut cx = Context::from_waker(Waker::noop());
+    assert_eq!(
+        pin!(uart.write_urgent(&[0; 9])).poll(&mut cx),
+        Poll::Ready(Err(UrgentFrameTooLarge {
+            len: 9,
+            capacity: 8
+        }))
+    );
+    assert_eq!(
+        pin!(uart.write_urgent(b"ok")).poll(&mut cx),
+        Poll::Ready(Ok(()))
+    );
+}
+
+#[test]
+fn tx_refill_from_the_interrupt() {
+    let state = BufferedState::<64, 8>::new();
+    let mock = MockUartlite::new();
+    let mut uart = BufferedUartlite::new(AxiUartlite::new_with_regs(&mock, &mock), &state);
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let mut isr_rx = Rx::new_with_regs(&mock);
+    let data: Vec<u8> = (0..40).collect();
+    assert_eq!(uart.try_write(&data), 40);
+    // The write started the transfer with a full TX FIFO.
+    assert_eq!(mock.tx_len(), FIFO_DEPTH);
+    // The TX FIFO is not empty yet, so the interrupt does not write anything.
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert_eq!(mock.tx_len(), FIFO_DEPTH);
+
+    let mut wire = Vec::new();
+    while mock.tx_len() > 0 {
+        let mut buf = [0; FIFO_DEPTH];
+        let len = mock.pop_tx_slice(&mut buf);
+        wire.extend_from_slice(&buf[0..len]);
+        state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    }
+    assert_eq!(wire, data);
+    assert_eq!(uart.tx_free_capacity(), 64);
+}
+
+#[test]
+fn rx_errors_of_tx_status_reads() {
+    let state = BufferedState::<8, 8>::new();
+    let mock = MockUartlite::new();
+    let mut uart = BufferedUartlite::new(AxiUartlite::new_with_regs(&mock, &mock), &state);
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let mut isr_rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(b"ab");
+    // Two status reads for the received bytes and one which finds the RX FIFO empty, so the
+    // error is reported by the status read for the TX refill.
+    mock.schedule_errors(
+        3,
+        ErrorInjection {
+            overrun: true,
+            ..Default::default()
+        },
+    );
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    let event = state.try_take_rx_error().unwrap();
+    assert!(event.errors.overrun());
+    assert_eq!(event.count, 1);
+
+    // Status reads in thread context record the errors as well.
+    assert_eq!(uart.try_write(b"x"), 1);
+    assert_eq!(mock.pop_tx(), Some(b'x'));
+    mock.schedule_errors(
+        0,
+        ErrorInjection {
+            frame: true,
+            ..Default::default()
+        },
+    );
+    uart.flush_blocking();
+    let event = state.try_take_rx_error().unwrap();
+    assert!(event.errors.frame());
+    assert_eq!(event.count, 2);
+    let errors = uart.read_and_clear_errors();
+    assert_eq!((errors.overrun(), errors.frame()), (1, 1));
+}
+
+/// Buffered driver whose interrupt handler is never called, so the writers have to refill the
+/// TX FIFO themselves.
+fn without_interrupt<'wire>(
+    wire: &'wire Wire<'wire>,
+    state: &'wire BufferedState<8, 8>,
+) -> BufferedUartlite<'wire, 8, 8, 0, &'wire Wire<'wire>> {
+    BufferedUartlite::new(AxiUartlite::new_with_regs(wire, wire), state)
+}
+
+const LONG_TEXT: &str = "output which is much longer than the TX ring buffer";
+
+#[test]
+fn fmt_write_without_interrupt() {
+    use core::fmt::Write as _;
+
+    let state = BufferedState::<8, 8>::new();
+    let mock = MockUartlite::new();
+    let wire = Wire {
+        mock: &mock,
+        sent: RefCell::new(Vec::new()),
+    };
+    let mut uart = without_interrupt(&wire, &state);
+    write!(uart, "formatted {LONG_TEXT}").unwrap();
+    uart.flush_blocking();
+    assert_eq!(
+        *wire.sent.borrow(),
+        format!("formatted {LONG_TEXT}").as_bytes()
+    );
+}
+
+#[cfg(feature = "embedded-io")]
+#[test]
+fn io_write_without_interrupt() {
+    use embedded_io::Write as _;
+
+    let state = BufferedState::<8, 8>::new();
+    let mock = MockUartlite::new();
+    let wire = Wire {
+        mock: &mock,
+        sent: RefCell::new(Vec::new()),
+    };
+    let mut uart = without_interrupt(&wire, &state);
+    uart.write_all(LONG_TEXT.as_bytes()).unwrap();
+    uart.flush().unwrap();
+    assert_eq!(*wire.sent.borrow(), LONG_TEXT.as_bytes());
+}
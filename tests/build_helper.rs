@@ -0,0 +1,82 @@
+//! Tests for the `xparameters.h` build script helper.
+use axi_uartlite::{
+    DataBits, HwConfig, Parity,
+    build_helper::{UartliteInstance, XparametersError, generate, parse_xparameters},
+};
+
+const XPARAMETERS: &str = "\
+#ifndef XPARAMETERS_H
+#define XPARAMETERS_H
+
+/* Definitions for driver UARTLITE */
+#define XPAR_XUARTLITE_NUM_INSTANCES 2U
+
+/* Definitions for peripheral AXI_UARTLITE_0 */
+#define XPAR_AXI_UARTLITE_0_BASEADDR 0x40600000U
+#define XPAR_AXI_UARTLITE_0_HIGHADDR 0x4060FFFFU
+#define XPAR_AXI_UARTLITE_0_BAUDRATE 115200U
+#define XPAR_AXI_UARTLITE_0_USE_PARITY 0U
+#define XPAR_AXI_UARTLITE_0_ODD_PARITY 0U
+#define XPAR_AXI_UARTLITE_0_DATA_BITS 8U
+
+/* Definitions for peripheral AXI_UARTLITE_1 */
+#define XPAR_AXI_UARTLITE_1_BASEADDR 0x40610000U
+#define XPAR_AXI_UARTLITE_1_BAUDRATE 9600U
+#define XPAR_AXI_UARTLITE_1_USE_PARITY 1U
+#define XPAR_AXI_UARTLITE_1_ODD_PARITY 1U
+#define XPAR_AXI_UARTLITE_1_DATA_BITS 7U
+
+/* Canonical definitions for peripheral AXI_UARTLITE_0 */
+#define XPAR_UARTLITE_0_BASEADDR 0x40600000U
+
+/* Definitions for peripheral AXI_TIMER_0 */
+#define XPAR_AXI_TIMER_0_BASEADDR 0x41C00000U
+
+#endif
+";
+
+#[test]
+fn parse() {
+    let instances = parse_xparameters(XPARAMETERS).unwrap();
+    assert_eq!(
+        instances,
+        [
+            UartliteInstance {
+                name: "AXI_UARTLITE_0".into(),
+                base_addr: 0x4060_0000,
+                hw_config: Some(HwConfig::new(115200, DataBits::Eight, Parity::None)),
+            },
+            UartliteInstance {
+                name: "AXI_UARTLITE_1".into(),
+                base_addr: 0x4061_0000,
+                hw_config: Some(HwConfig::new(9600, DataBits::Seven, Parity::Odd)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_invalid_value() {
+    let error = parse_xparameters("#define XPAR_AXI_UARTLITE_0_DATA_BITS 9U\n").unwrap_err();
+    assert_eq!(
+        error,
+        XparametersError {
+            line: 1,
+            define: "XPAR_AXI_UARTLITE_0_DATA_BITS".into(),
+        }
+    );
+}
+
+#[test]
+fn generate_source() {
+    let source = generate(&parse_xparameters(XPARAMETERS).unwrap());
+    assert!(source.contains("pub const AXI_UARTLITE_0_BASEADDR: usize = 0x40600000;"));
+    assert!(source.contains(
+        "pub const AXI_UARTLITE_1_HW_CONFIG: axi_uartlite::HwConfig = \
+         axi_uartlite::HwConfig::new(9600, axi_uartlite::DataBits::Seven, \
+         axi_uartlite::Parity::Odd);"
+    ));
+    assert!(source.contains("        AXI_UARTLITE_1: AXI_UARTLITE_1_BASEADDR,\n"));
+    assert!(!source.contains("TIMER"));
+    assert!(!generate(&[]).contains("Peripherals"));
+}
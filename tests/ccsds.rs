@@ -0,0 +1,110 @@
+//! Tests for the CCSDS space packet reception.
+use axi_uartlite::{
+    FIFO_DEPTH, Rx,
+    ccsds::{PacketType, PrimaryHeader, SpacePacketError, SpacePacketReceiver},
+    mock::MockUartlite,
+};
+
+/// Unsegmented space packet with a secondary header flag set for telecommands.
+fn packet(packet_type: PacketType, apid: u16, seq_count: u16, data: &[u8]) -> Vec<u8> {
+    let tc = packet_type == PacketType::Tc;
+    let mut packet = vec![
+        ((tc as u8) << 4) | ((tc as u8) << 3) | (apid >> 8) as u8,
+        apid as u8,
+        0b1100_0000 | (seq_count >> 8) as u8,
+        seq_count as u8,
+    ];
+    packet.extend_from_slice(&(data.len() as u16 - 1).to_be_bytes());
+    packet.extend_from_slice(data);
+    packet
+}
+
+/// Receive the stream through the RX FIFO, one interrupt per FIFO fill.
+fn receive<const N: usize>(
+    receiver: &mut SpacePacketReceiver<N>,
+    stream: &[u8],
+) -> Vec<Result<Vec<u8>, SpacePacketError>> {
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    let mut packets = Vec::new();
+    for chunk in stream.chunks(FIFO_DEPTH) {
+        mock.push_rx_slice(chunk);
+        let mut buf = [0; FIFO_DEPTH];
+        let read = rx.on_interrupt_rx(&mut buf);
+        receiver.feed_all(&buf[0..read], |packet| {
+            packets.push(packet.map(<[u8]>::to_vec))
+        });
+    }
+    packets
+}
+
+#[test]
+fn primary_header() {
+    let raw = packet(PacketType::Tc, 0x7FF, 0x3FFF, &[0; 3]);
+    let header = PrimaryHeader::from_bytes(&raw).unwrap();
+    assert_eq!(header.packet_type(), PacketType::Tc);
+    assert!(header.sec_header_flag());
+    assert_eq!(header.apid(), 0x7FF);
+    assert_eq!(header.seq_flags(), 0b11);
+    assert_eq!(header.seq_count(), 0x3FFF);
+    assert_eq!(header.data_len(), 2);
+    assert_eq!(header.packet_len(), 9);
+
+    assert_eq!(PrimaryHeader::from_bytes(&raw[0..5]), None);
+    // Packet version number 1.
+    let mut invalid = raw.clone();
+    invalid[0] |= 0b0010_0000;
+    assert_eq!(PrimaryHeader::from_bytes(&invalid), None);
+}
+
+#[test]
+fn packets_across_interrupts() {
+    let telecommand = packet(PacketType::Tc, 0x123, 5, &[0xAA; 20]);
+    let telemetry = packet(PacketType::Tm, 0x42, 6, b"hk");
+    let mut receiver = SpacePacketReceiver::<64>::new();
+    let packets = receive(
+        &mut receiver,
+        &[telecommand.clone(), telemetry.clone()].concat(),
+    );
+    assert_eq!(packets, [Ok(telecommand), Ok(telemetry)]);
+    assert_eq!(receiver.discarded(), 0);
+}
+
+#[test]
+fn resynchronization() {
+    let telecommand = packet(PacketType::Tc, 0x10, 0, b"ping");
+    // Line noise with a non-zero packet version number.
+    let stream = [&[0xFF, 0xE0, 0x20][..], &telecommand].concat();
+    let mut receiver = SpacePacketReceiver::<64>::new();
+    assert_eq!(receive(&mut receiver, &stream), [Ok(telecommand)]);
+    assert_eq!(receiver.discarded(), 3);
+}
+
+#[test]
+fn oversized_packet_is_skipped() {
+    let large = packet(PacketType::Tc, 0x10, 0, &[0x55; 40]);
+    let small = packet(PacketType::Tc, 0x10, 1, b"ok");
+    let mut receiver = SpacePacketReceiver::<32>::new();
+    assert_eq!(
+        receive(&mut receiver, &[large.clone(), small.clone()].concat()),
+        [Err(SpacePacketError::TooLarge(large.len())), Ok(small)]
+    );
+    assert_eq!(receiver.discarded(), 0);
+}
+
+#[test]
+fn reset_discards_partial_packet() {
+    let telecommand = packet(PacketType::Tc, 0x10, 0, b"data");
+    let mut receiver = SpacePacketReceiver::<32>::new();
+    for &byte in &telecommand[0..8] {
+        assert_eq!(receiver.feed(byte), Ok(None));
+    }
+    receiver.reset();
+    let mut received = None;
+    for &byte in &telecommand {
+        if let Some(packet) = receiver.feed(byte).unwrap() {
+            received = Some(packet.to_vec());
+        }
+    }
+    assert_eq!(received, Some(telecommand));
+}
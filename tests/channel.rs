@@ -0,0 +1,47 @@
+//! Tests for the typed message channel.
+use axi_uartlite::{AxiUartlite, ChannelError, MessageChannel, mock::MockUartlite};
+
+#[test]
+fn send_and_recv() {
+    let mock = MockUartlite::new();
+    let (tx, rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut channel = MessageChannel::<_, _, 32>::new(tx, rx);
+    channel.send(&(300u16, [1u8, 2, 3])).unwrap();
+    let mut frame = [0; 16];
+    let len = mock.pop_tx_slice(&mut frame);
+    // Varint encoded u16 followed by the array, COBS framed.
+    assert_eq!(&frame[0..len], [6, 0xAC, 0x02, 1, 2, 3, 0]);
+
+    // Loop the frame back.
+    mock.push_rx_slice(&frame[0..len]);
+    let msg: (u16, [u8; 3]) = channel.recv().unwrap();
+    assert_eq!(msg, (300, [1, 2, 3]));
+}
+
+#[test]
+fn message_too_large() {
+    let mock = MockUartlite::new();
+    let (tx, rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut channel = MessageChannel::<_, _, 4>::new(tx, rx);
+    assert!(matches!(
+        channel.send(&[0u8; 8]),
+        Err(ChannelError::Postcard(_))
+    ));
+    assert_eq!(mock.tx_len(), 0);
+}
+
+#[test]
+fn resync_after_corrupted_frame() {
+    let mock = MockUartlite::new();
+    let (tx, rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut channel = MessageChannel::<_, _, 16>::new(tx, rx);
+    // Truncated COBS block followed by a valid frame with the u8 value 7.
+    mock.push_rx_slice(&[5, 1, 0, 2, 7, 0]);
+    assert_eq!(
+        channel.recv::<u8>(),
+        Err(ChannelError::Frame(
+            axi_uartlite::framing::cobs::CobsDecodeError::Truncated
+        ))
+    );
+    assert_eq!(channel.recv::<u8>(), Ok(7));
+}
@@ -0,0 +1,41 @@
+//! Tests for the `embedded-cli` adapters.
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{AxiUartlite, CliReader, mock::MockUartlite};
+use embedded_io_06::Write;
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is pending"),
+    }
+}
+
+#[test]
+fn writer_and_reader() {
+    let mock = MockUartlite::new();
+    let (mut writer, mut reader) = AxiUartlite::new_with_regs(&mock, &mock).into_cli_io();
+    writer.write_all(b"> help\r\n").unwrap();
+    assert_eq!(writer.write(&[]), Ok(0));
+    let mut buf = [0; 16];
+    let len = mock.pop_tx_slice(&mut buf);
+    assert_eq!(&buf[0..len], b"> help\r\n");
+    writer.flush().unwrap();
+
+    assert_eq!(reader.try_read_byte(), None);
+    mock.push_rx_slice(b"ab");
+    assert_eq!(reader.read_byte(), b'a');
+    assert_eq!(reader.try_read_byte(), Some(b'b'));
+}
+
+#[test]
+fn async_reader() {
+    let mut reader = CliReader::new(&b"x"[..]);
+    assert_eq!(poll_ready(reader.read_byte_async()), Ok(b'x'));
+    assert!(poll_ready(reader.read_byte_async()).is_err());
+}
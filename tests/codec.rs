@@ -0,0 +1,155 @@
+//! Tests for the pluggable frame codecs.
+use core::{
+    convert::Infallible,
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite,
+    framing::{
+        FrameCodec, FrameReadError, FrameWriteError, Framed,
+        cobs::CobsDecoder,
+        delimited::{Framer, FramerConfig, FramerError},
+        length_prefixed::{EncodeError, LengthPrefixedDecoder, MAX_PAYLOAD_LEN},
+        slip::SlipDecoder,
+    },
+    mock::MockUartlite,
+};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is pending"),
+    }
+}
+
+/// Custom codec with a length byte in front of the payload.
+#[derive(Default)]
+struct LengthByte {
+    buf: [u8; 8],
+    len: Option<usize>,
+    received: usize,
+}
+
+impl FrameCodec for LengthByte {
+    type EncodeError = usize;
+    type DecodeError = Infallible;
+
+    fn encode_to_writer<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, usize>> {
+        if data.len() > self.buf.len() {
+            return Err(FrameWriteError::Encode(data.len()));
+        }
+        writer
+            .write_all(&[data.len() as u8])
+            .map_err(FrameWriteError::Io)?;
+        writer.write_all(data).map_err(FrameWriteError::Io)
+    }
+
+    async fn encode_to_writer_async<W: embedded_io_async::Write>(
+        &mut self,
+        writer: &mut W,
+        data: &[u8],
+    ) -> Result<(), FrameWriteError<W::Error, usize>> {
+        writer
+            .write_all(&[data.len() as u8])
+            .await
+            .map_err(FrameWriteError::Io)?;
+        writer.write_all(data).await.map_err(FrameWriteError::Io)
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<Option<usize>, Infallible> {
+        let Some(len) = self.len else {
+            self.len = Some(byte as usize);
+            self.received = 0;
+            return Ok(None);
+        };
+        self.buf[self.received] = byte;
+        self.received += 1;
+        if self.received == len {
+            self.len = None;
+            return Ok(Some(len));
+        }
+        Ok(None)
+    }
+
+    fn frame(&self, len: usize) -> &[u8] {
+        &self.buf[0..len]
+    }
+
+    fn reset(&mut self) {
+        self.len = None;
+    }
+}
+
+/// Send a frame through the mock TX FIFO, loop it back into the RX FIFO and receive it.
+fn loopback<C: FrameCodec>(codec: C, data: &[u8]) -> Vec<u8> {
+    let mock = MockUartlite::new();
+    let mut framed = Framed::new(AxiUartlite::new_with_regs(&mock, &mock), codec);
+    framed.send_frame(data).unwrap();
+    let mut wire = [0; 16];
+    let len = mock.pop_tx_slice(&mut wire);
+    assert_eq!(mock.push_rx_slice(&wire[0..len]), len);
+    framed.recv_frame().unwrap().to_vec()
+}
+
+#[test]
+fn builtin_codecs() {
+    let data = [0x00, 0xC0, 0xDB, 0xA5, 0x11];
+    assert_eq!(loopback(CobsDecoder::<8>::new(), &data), data);
+    assert_eq!(loopback(SlipDecoder::<8>::new(), &data), data);
+    assert_eq!(loopback(LengthPrefixedDecoder::<8>::new(), &data), data);
+    assert_eq!(
+        loopback(Framer::<8>::new(FramerConfig::nmea()), b"GPGGA"),
+        b"GPGGA"
+    );
+    assert_eq!(loopback(LengthByte::default(), &data), data);
+}
+
+#[test]
+fn encode_errors() {
+    let mut sink = [0; 8];
+    let mut framed = Framed::new(&mut sink[..], Framer::<8>::new(FramerConfig::lines(8)));
+    assert_eq!(
+        framed.send_frame(b"a\nb"),
+        Err(FrameWriteError::Encode(FramerError::Delimiter))
+    );
+    let mut framed = Framed::new(&mut sink[..], LengthPrefixedDecoder::<8>::new());
+    let payload = [0; MAX_PAYLOAD_LEN + 1];
+    assert_eq!(
+        framed.send_frame(&payload),
+        Err(FrameWriteError::Encode(EncodeError::PayloadTooLarge))
+    );
+    let mut framed = Framed::new(&mut sink[..], LengthByte::default());
+    assert_eq!(framed.send_frame(&[0; 9]), Err(FrameWriteError::Encode(9)));
+    // Nothing was written, so the whole sink is still available.
+    let (unused, _) = framed.release();
+    assert_eq!(unused.len(), 8);
+}
+
+#[test]
+fn feed_and_async() {
+    let mut wire = [0; 8];
+    let mut framed = Framed::new(&mut wire[..], LengthByte::default());
+    poll_ready(framed.send_frame_async(b"abc")).unwrap();
+    poll_ready(framed.send_frame_async(b"de")).unwrap();
+    let (rest, codec) = framed.release();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(&wire[0..7], b"\x03abc\x02de");
+
+    let mut framed = Framed::new(&wire[0..4], codec);
+    assert_eq!(poll_ready(framed.recv_frame_async()), Ok(&b"abc"[..]));
+    assert_eq!(
+        poll_ready(framed.recv_frame_async()),
+        Err(FrameReadError::Eof)
+    );
+    assert_eq!(framed.feed(2), Ok(None));
+    assert_eq!(framed.feed(b'd'), Ok(None));
+    assert_eq!(framed.feed(b'e'), Ok(Some(&b"de"[..])));
+}
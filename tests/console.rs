@@ -0,0 +1,57 @@
+//! Tests for the console input modes.
+use axi_uartlite::{AxiUartlite, Console, ConsoleInput, ConsoleMode, mock::MockUartlite};
+
+fn echoed(mock: &MockUartlite) -> Vec<u8> {
+    let mut buf = [0; 16];
+    let len = mock.pop_tx_slice(&mut buf);
+    buf[0..len].to_vec()
+}
+
+#[test]
+fn cooked_line_editing() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let mut console = Console::<4>::default();
+    assert_eq!(console.mode(), ConsoleMode::Cooked);
+
+    mock.push_rx_slice(b"lx\x7fs\r\n");
+    assert_eq!(console.read(&mut uart), ConsoleInput::Line(b"ls"));
+    assert_eq!(echoed(&mock), b"lx\x08 \x08s\r\n");
+    // The LF of the CRLF is skipped, a bare LF terminates an empty line.
+    mock.push_rx_slice(b"\n");
+    assert_eq!(console.poll(&mut uart), None);
+    assert_eq!(console.read(&mut uart), ConsoleInput::Line(b""));
+    assert_eq!(echoed(&mock), b"\r\n");
+
+    // Kill line, ignored control characters and line overflow.
+    mock.push_rx_slice(b"ab\x15\x01abcde");
+    for _ in 0..9 {
+        assert_eq!(console.poll(&mut uart), None);
+    }
+    assert_eq!(console.pending_line(), b"abcd");
+    assert_eq!(echoed(&mock), b"ab\x08 \x08\x08 \x08abcd\x07");
+    assert_eq!(
+        console.feed(b'\r', &mut uart),
+        Some(ConsoleInput::Line(b"abcd"))
+    );
+    echoed(&mock);
+}
+
+#[test]
+fn raw_mode_switch() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let mut console = Console::<8>::new(ConsoleMode::Cooked);
+    mock.push_rx_slice(b"x");
+    assert_eq!(console.poll(&mut uart), None);
+    echoed(&mock);
+
+    console.set_mode(ConsoleMode::Raw);
+    assert_eq!(console.pending_line(), b"");
+    mock.push_rx_slice(b"\r\x00\x7f");
+    assert_eq!(console.read(&mut uart), ConsoleInput::Byte(b'\r'));
+    assert_eq!(console.read(&mut uart), ConsoleInput::Byte(0x00));
+    assert_eq!(console.poll(&mut uart), Some(ConsoleInput::Byte(0x7f)));
+    assert_eq!(console.poll(&mut uart), None);
+    assert!(echoed(&mock).is_empty());
+}
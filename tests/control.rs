@@ -0,0 +1,199 @@
+//! Tests for the control register writes and the interrupt enable shadow.
+use core::cell::{Cell, RefCell};
+
+use axi_uartlite::{
+    AxiUartlite, Tx,
+    registers::{Control, ControlState, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+/// Backend which counts status register reads and records control register writes.
+#[derive(Default)]
+struct Recorder {
+    status: Cell<u32>,
+    status_reads: Cell<usize>,
+    ctrl_writes: RefCell<Vec<u32>>,
+}
+
+impl RegisterAccess for &Recorder {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(0)
+    }
+
+    fn write_tx_fifo(&mut self, _value: TxFifo) {}
+
+    fn read_stat_reg(&self) -> Status {
+        self.status_reads.set(self.status_reads.get() + 1);
+        Status::new_with_raw_value(self.status.get())
+    }
+
+    fn write_ctrl_reg(&mut self, value: Control) {
+        self.ctrl_writes.borrow_mut().push(value.raw_value());
+    }
+}
+
+const ENABLE_INTR: u32 = 1 << 4;
+const RESET_RX: u32 = 1 << 1;
+const RESET_TX: u32 = 1 << 0;
+
+#[test]
+fn shadowed_interrupt_state() {
+    let recorder = Recorder::default();
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    uart.reset_rx_fifo();
+    uart.enable_interrupt();
+    // FIFO resets keep the interrupt enabled.
+    uart.reset_rx_fifo();
+    let (mut tx, _rx) = uart.split();
+    tx.reset_fifo();
+    // The state is only read once by the first FIFO reset.
+    assert_eq!(recorder.status_reads.get(), 1);
+    assert_eq!(
+        *recorder.ctrl_writes.borrow(),
+        [
+            RESET_RX,
+            ENABLE_INTR,
+            ENABLE_INTR | RESET_RX,
+            ENABLE_INTR | RESET_TX
+        ]
+    );
+}
+
+#[test]
+fn unknown_interrupt_state() {
+    let recorder = Recorder::default();
+    recorder.status.set(ENABLE_INTR);
+    let mut tx = Tx::new_with_regs(&recorder);
+    tx.reset_fifo();
+    tx.reset_fifo();
+    // The state is only read once.
+    assert_eq!(recorder.status_reads.get(), 1);
+
+    // Interrupts were disabled through another handle.
+    recorder.status.set(0);
+    tx.invalidate_interrupt_shadow();
+    tx.reset_fifo();
+    assert_eq!(recorder.status_reads.get(), 2);
+    assert_eq!(
+        *recorder.ctrl_writes.borrow(),
+        [ENABLE_INTR | RESET_TX, ENABLE_INTR | RESET_TX, RESET_TX]
+    );
+}
+
+#[test]
+fn burst_write() {
+    const TX_EMPTY: u32 = 1 << 2;
+    const TX_FULL: u32 = 1 << 3;
+    let recorder = Recorder::default();
+    let mut tx = Tx::new_with_regs(&recorder);
+    recorder.status.set(TX_EMPTY);
+    assert_eq!(tx.write_burst(&[0; 40]), 16);
+    assert_eq!(tx.write_burst(&[0; 4]), 4);
+    recorder.status.set(0);
+    assert_eq!(tx.write_burst(&[0; 40]), 1);
+    recorder.status.set(TX_FULL);
+    assert_eq!(tx.write_burst(&[0; 40]), 0);
+    assert_eq!(tx.write_burst(&[]), 0);
+    assert_eq!(recorder.status_reads.get(), 4);
+}
+
+#[test]
+fn budgeted_write() {
+    const TX_EMPTY: u32 = 1 << 2;
+    const TX_FULL: u32 = 1 << 3;
+    let recorder = Recorder::default();
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    recorder.status.set(TX_EMPTY);
+    assert_eq!(uart.write_with_budget(&[0; 40], 2), 32);
+    assert_eq!(recorder.status_reads.get(), 2);
+    assert_eq!(uart.write_with_budget(&[0; 20], 8), 20);
+    assert_eq!(recorder.status_reads.get(), 4);
+    recorder.status.set(TX_FULL);
+    assert_eq!(uart.write_with_budget(&[0; 8], 3), 0);
+    assert_eq!(uart.write_with_budget(&[0; 8], 0), 0);
+    assert_eq!(recorder.status_reads.get(), 7);
+}
+
+#[test]
+fn unchecked_slice_write() {
+    let recorder = Recorder::default();
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    assert_eq!(uart.write_fifo_unchecked_slice(&[0; 40]), 16);
+    assert_eq!(uart.write_fifo_unchecked_slice(&[0; 3]), 3);
+    assert_eq!(recorder.status_reads.get(), 0);
+}
+
+#[test]
+fn interrupt_guard() {
+    let recorder = Recorder::default();
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    uart.enable_interrupt();
+    {
+        let mut guard = uart.interrupt_guard();
+        assert!(guard.was_enabled());
+        guard.reset_rx_fifo();
+    }
+    // The state of a new driver is unknown, so it is read from the status register once.
+    assert_eq!(recorder.status_reads.get(), 1);
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    drop(uart.interrupt_guard());
+    assert_eq!(recorder.status_reads.get(), 2);
+    assert_eq!(
+        *recorder.ctrl_writes.borrow(),
+        [ENABLE_INTR, 0, RESET_RX, ENABLE_INTR, 0, 0]
+    );
+}
+
+#[test]
+fn interrupt_state_toggling() {
+    let recorder = Recorder::default();
+    recorder.status.set(ENABLE_INTR);
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    assert!(uart.is_interrupt_enabled());
+    assert!(uart.disable_interrupt());
+    assert!(!uart.is_interrupt_enabled());
+    assert!(!uart.enable_interrupt());
+    assert!(uart.enable_interrupt());
+    // Only the initial query reads the status register.
+    assert_eq!(recorder.status_reads.get(), 1);
+}
+
+#[test]
+fn status_reader() {
+    const RX_VALID: u32 = 1 << 0;
+    const TX_EMPTY: u32 = 1 << 2;
+    let recorder = Recorder::default();
+    let uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    let reader = uart.status_reader();
+    let monitor = reader.clone();
+    recorder.status.set(RX_VALID | TX_EMPTY);
+    assert!(reader.rx_has_data());
+    assert!(monitor.tx_fifo_empty());
+    assert!(!monitor.tx_fifo_full());
+    assert_eq!(monitor.read().raw_value(), RX_VALID | TX_EMPTY);
+    assert_eq!(recorder.status_reads.get(), 4);
+    // No other register was accessed.
+    assert!(recorder.ctrl_writes.borrow().is_empty());
+}
+
+#[test]
+fn control_state_apply() {
+    let control = ControlState::new(true);
+    assert_eq!(control.apply(false, false).raw_value(), ENABLE_INTR);
+    assert_eq!(
+        control.apply(true, true).raw_value(),
+        ENABLE_INTR | RESET_RX | RESET_TX
+    );
+    let control = control.with_interrupt_enabled(false);
+    assert_eq!(control.apply(false, true).raw_value(), RESET_TX);
+    assert_eq!(
+        ControlState::from_status(&Status::new_with_raw_value(ENABLE_INTR)),
+        ControlState::new(true)
+    );
+
+    let recorder = Recorder::default();
+    let mut uart = AxiUartlite::new_with_regs(&recorder, &recorder);
+    uart.apply_control(ControlState::new(true));
+    assert_eq!(uart.control_state(), ControlState::new(true));
+    assert_eq!(recorder.status_reads.get(), 0);
+    assert_eq!(*recorder.ctrl_writes.borrow(), [ENABLE_INTR]);
+}
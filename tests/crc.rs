@@ -0,0 +1,46 @@
+//! Tests for the CRC calculators and the rolling CRC adapters.
+use axi_uartlite::{
+    AxiUartlite,
+    crc::{Crc16Ccitt, Crc32, CrcReader, CrcWriter, crc16_ccitt, crc32},
+    mock::MockUartlite,
+};
+use embedded_io::{Read, Write};
+
+#[test]
+fn check_values() {
+    assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
+
+#[test]
+fn crc_writer() {
+    let mock = MockUartlite::new();
+    let (tx, _rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut writer = CrcWriter::new(tx, Crc32::new());
+    writer.write_all(b"12345").unwrap();
+    writer.write_all(b"6789").unwrap();
+    let crc = writer.finalize();
+    assert_eq!(crc, 0xCBF4_3926);
+    // The checksum itself is written directly and is not part of the CRC.
+    writer.writer().write_all(&crc.to_le_bytes()).unwrap();
+    assert_eq!(writer.finalize(), crc);
+
+    let mut buf = [0; 16];
+    let len = mock.pop_tx_slice(&mut buf);
+    assert_eq!(&buf[0..9], b"123456789");
+    assert_eq!(&buf[9..len], crc.to_le_bytes());
+}
+
+#[test]
+fn crc_reader() {
+    let mock = MockUartlite::new();
+    let (_tx, rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    mock.push_rx_slice(b"123456789");
+    let mut reader = CrcReader::new(rx, Crc16Ccitt::new());
+    let mut buf = [0; 9];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(reader.finalize(), 0x29B1);
+
+    reader.reset(Crc16Ccitt::new());
+    assert_eq!(reader.finalize(), 0xFFFF);
+}
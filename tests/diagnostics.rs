@@ -0,0 +1,42 @@
+//! Tests for the diagnostics snapshot.
+#![cfg(not(feature = "tiny"))]
+use axi_uartlite::{
+    AxiUartlite, RxErrorsCounted, TxAsync,
+    mock::{ErrorInjection, MockUartlite},
+};
+
+#[test]
+fn driver_snapshot() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.enable_interrupt();
+    uart.write_fifo(0x01).unwrap();
+    mock.push_rx(0x02);
+    mock.inject_errors(ErrorInjection {
+        overrun: true,
+        ..Default::default()
+    });
+
+    let diagnostics = uart.diagnostics();
+    assert!(diagnostics.rx_valid_data);
+    assert!(!diagnostics.rx_full);
+    assert!(!diagnostics.tx_empty);
+    assert!(!diagnostics.tx_full);
+    assert!(diagnostics.intr_enabled);
+    assert_eq!(diagnostics.errors.overrun(), 1);
+    assert_eq!(diagnostics.tx_transfer, None);
+    // The error counters are not reset by the snapshot.
+    assert_eq!(uart.read_and_clear_errors(), diagnostics.errors);
+    assert_eq!(uart.diagnostics().errors, RxErrorsCounted::new());
+}
+
+#[test]
+fn async_snapshot() {
+    let mock = MockUartlite::new();
+    let (tx, _rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    let mut tx_async = TxAsync::new(tx, 0).unwrap();
+    let diagnostics = tx_async.diagnostics();
+    assert!(diagnostics.tx_empty);
+    assert_eq!(diagnostics.tx_transfer, None);
+    assert!(!format!("{diagnostics:?}").is_empty());
+}
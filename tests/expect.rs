@@ -0,0 +1,48 @@
+//! Tests of the expectation based register backend.
+use axi_uartlite::{
+    AxiUartlite,
+    mock::{
+        ErrorInjection,
+        expect::{ExpectUartlite, Transaction},
+    },
+};
+use embedded_io::{Read, Write};
+
+#[test]
+fn write_and_read() {
+    let transactions = [
+        Transaction::Write(b"AT\r\n"),
+        Transaction::Errors(ErrorInjection {
+            parity: true,
+            ..Default::default()
+        }),
+        Transaction::Read(b"OK"),
+    ];
+    let expect = ExpectUartlite::new(&transactions);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    uart.write_all(b"AT\r\n").unwrap();
+    let mut buf = [0; 2];
+    uart.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"OK");
+    assert_eq!(uart.read_and_clear_errors().parity(), 1);
+    expect.done();
+}
+
+#[test]
+#[should_panic(expected = "unexpected TX byte")]
+fn unexpected_write() {
+    let transactions = [Transaction::Write(b"AT")];
+    let expect = ExpectUartlite::new(&transactions);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    uart.write_all(b"AX").unwrap();
+}
+
+#[test]
+#[should_panic(expected = "not all transactions were consumed")]
+fn missing_transaction() {
+    let transactions = [Transaction::Write(b"AT"), Transaction::Read(b"OK")];
+    let expect = ExpectUartlite::new(&transactions);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    uart.write_all(b"AT").unwrap();
+    expect.done();
+}
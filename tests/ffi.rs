@@ -0,0 +1,70 @@
+//! Tests for the C FFI wrapper layer. A plain memory block is used as the register block.
+use core::mem::MaybeUninit;
+
+use axi_uartlite::ffi::*;
+
+#[repr(C, align(4))]
+struct RegisterBlock([u32; 4]);
+
+#[test]
+fn null_handles() {
+    unsafe {
+        assert_eq!(
+            axi_uartlite_init(core::ptr::null_mut(), 0),
+            AXI_UARTLITE_ERR_NULL
+        );
+        assert_eq!(
+            axi_uartlite_flush(core::ptr::null_mut()),
+            AXI_UARTLITE_ERR_NULL
+        );
+        assert_eq!(
+            axi_uartlite_write(core::ptr::null_mut(), b"a".as_ptr(), 1),
+            0
+        );
+    }
+}
+
+#[test]
+fn write_and_read() {
+    // RX FIFO valid data and TX FIFO empty.
+    let mut regs = RegisterBlock([0x5A, 0, 0b101, 0]);
+    let mut handle = MaybeUninit::<AxiUartliteHandle>::uninit();
+    unsafe {
+        assert_eq!(
+            axi_uartlite_init(handle.as_mut_ptr(), regs.0.as_mut_ptr() as usize),
+            AXI_UARTLITE_OK
+        );
+        assert_eq!(
+            axi_uartlite_write(handle.as_mut_ptr(), b"hi".as_ptr(), 2),
+            2
+        );
+        assert_eq!(core::ptr::read_volatile(&regs.0[1]), b'i' as u32);
+        let mut buf = [0; 2];
+        assert_eq!(
+            axi_uartlite_read(handle.as_mut_ptr(), buf.as_mut_ptr(), 2),
+            2
+        );
+        assert_eq!(buf, [0x5A; 2]);
+        assert_eq!(axi_uartlite_flush(handle.as_mut_ptr()), AXI_UARTLITE_OK);
+
+        // TX FIFO full.
+        core::ptr::write_volatile(&mut regs.0[2], 1 << 3);
+        assert_eq!(axi_uartlite_write(handle.as_mut_ptr(), b"x".as_ptr(), 1), 0);
+        // Parity error and TX FIFO empty.
+        core::ptr::write_volatile(&mut regs.0[2], (1 << 7) | (1 << 2));
+        assert_eq!(axi_uartlite_write(handle.as_mut_ptr(), b"x".as_ptr(), 1), 1);
+        let mut errors = AxiUartliteErrors::default();
+        assert_eq!(
+            axi_uartlite_read_and_clear_errors(handle.as_mut_ptr(), &mut errors),
+            AXI_UARTLITE_OK
+        );
+        assert_eq!(
+            errors,
+            AxiUartliteErrors {
+                parity: 1,
+                frame: 0,
+                overrun: 0
+            }
+        );
+    }
+}
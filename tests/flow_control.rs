@@ -0,0 +1,88 @@
+//! Tests for the XON/XOFF software flow control.
+use axi_uartlite::{
+    AxiUartlite, BufferedState, BufferedUartlite, Rx, Tx, XOFF, XON, XonXoff, XonXoffConfig,
+    mock::MockUartlite,
+};
+
+#[test]
+fn state_machine() {
+    let mut flow_control = XonXoff::new(XonXoffConfig::new(6, 2));
+    assert_eq!(flow_control.on_rx_byte(b'a'), Some(b'a'));
+    assert_eq!(flow_control.on_rx_byte(XOFF), None);
+    assert!(flow_control.tx_paused());
+    assert_eq!(flow_control.on_rx_byte(XON), None);
+    assert!(!flow_control.tx_paused());
+
+    assert_eq!(flow_control.on_rx_fill_level(5), None);
+    assert_eq!(flow_control.on_rx_fill_level(6), Some(XOFF));
+    assert!(flow_control.xoff_sent());
+    assert_eq!(flow_control.take_pending(), Some(XOFF));
+    assert_eq!(flow_control.take_pending(), None);
+    // XOFF is only sent once, and XON only once the fill level dropped to the low threshold.
+    assert_eq!(flow_control.on_rx_fill_level(8), None);
+    assert_eq!(flow_control.on_rx_fill_level(3), None);
+    assert_eq!(flow_control.on_rx_fill_level(2), Some(XON));
+    assert!(!flow_control.xoff_sent());
+    assert_eq!(flow_control.take_pending(), Some(XON));
+    assert_eq!(flow_control.on_rx_fill_level(0), None);
+}
+
+#[test]
+fn remote_pauses_tx() {
+    let state = BufferedState::<16, 16>::new();
+    state.enable_xon_xoff(XonXoffConfig::new(12, 4));
+    let mock = MockUartlite::new();
+    let mut uart = BufferedUartlite::new(AxiUartlite::new_with_regs(&mock, &mock), &state);
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let mut isr_rx = Rx::new_with_regs(&mock);
+
+    mock.push_rx_slice(&[b'a', XOFF, b'b']);
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert!(state.xon_xoff().unwrap().tx_paused());
+    // The flow control characters are not passed to the application.
+    let mut buf = [0; 4];
+    assert_eq!(uart.try_read(&mut buf), 2);
+    assert_eq!(&buf[0..2], b"ab");
+
+    assert_eq!(uart.try_write(b"data"), 4);
+    assert_eq!(mock.tx_len(), 0);
+    mock.push_rx(XON);
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert!(!state.xon_xoff().unwrap().tx_paused());
+    let mut sent = [0; 4];
+    assert_eq!(mock.pop_tx_slice(&mut sent), 4);
+    assert_eq!(&sent, b"data");
+}
+
+#[test]
+fn rx_fill_level_pauses_remote() {
+    let state = BufferedState::<16, 16>::new();
+    state.enable_xon_xoff(XonXoffConfig::new(8, 2));
+    let mock = MockUartlite::new();
+    let mut uart = BufferedUartlite::new(AxiUartlite::new_with_regs(&mock, &mock), &state);
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let mut isr_rx = Rx::new_with_regs(&mock);
+
+    mock.push_rx_slice(b"0123456");
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert_eq!(mock.tx_len(), 0);
+    mock.push_rx(b'7');
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert_eq!(mock.pop_tx(), Some(XOFF));
+    assert!(state.xon_xoff().unwrap().xoff_sent());
+
+    // Draining the RX ring buffer to the low threshold emits XON with the next TX FIFO refill.
+    let mut buf = [0; 6];
+    assert_eq!(uart.try_read(&mut buf), 6);
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert_eq!(mock.pop_tx(), Some(XON));
+    assert_eq!(mock.tx_len(), 0);
+    assert!(!state.xon_xoff().unwrap().xoff_sent());
+
+    // Flow control can be disabled at runtime.
+    state.disable_xon_xoff();
+    assert_eq!(state.xon_xoff(), None);
+    mock.push_rx_slice(&[XOFF, b'x']);
+    state.on_interrupt(&mut isr_tx, &mut isr_rx);
+    assert_eq!(state.rx_len(), 4);
+}
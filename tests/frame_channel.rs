@@ -0,0 +1,56 @@
+//! Tests for the interrupt-driven frame channel.
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{FrameChannel, Rx, framing::slip::SlipDecoder, mock::MockUartlite};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is pending"),
+    }
+}
+
+#[test]
+fn interrupt_handler_delivers_frames() {
+    let frames: FrameChannel<SlipDecoder<32>, 8, 2> = FrameChannel::new(SlipDecoder::new());
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    {
+        let mut receive = pin!(frames.receive());
+        assert!(receive.as_mut().poll(&mut cx).is_pending());
+        // A frame split across two interrupts.
+        mock.push_rx_slice(b"\xC0hel");
+        assert_eq!(frames.on_interrupt_rx(&mut rx), 0);
+        assert!(receive.as_mut().poll(&mut cx).is_pending());
+        mock.push_rx_slice(b"lo\xC0");
+        assert_eq!(frames.on_interrupt_rx(&mut rx), 1);
+        let Poll::Ready(frame) = receive.as_mut().poll(&mut cx) else {
+            panic!("frame not received");
+        };
+        assert_eq!(frame.as_slice(), b"hello");
+        assert_eq!(frame.len(), 5);
+    }
+    assert_eq!(frames.try_receive(), None);
+}
+
+#[test]
+fn dropped_frames_and_decode_errors_are_counted() {
+    let frames: FrameChannel<SlipDecoder<32>, 8, 2> = FrameChannel::new(SlipDecoder::new());
+    // Invalid escape sequence, the rest of the frame is discarded by the decoder.
+    assert_eq!(frames.feed(b"\xDB\x01ab\xC0"), 0);
+    assert_eq!(frames.decode_errors(), 1);
+    // Payload exceeding the frame size.
+    assert_eq!(frames.feed(b"too long!\xC0"), 1);
+    // The third frame does not fit into the channel.
+    assert_eq!(frames.feed(b"a\xC0b\xC0c\xC0"), 3);
+    assert_eq!(frames.dropped_frames(), 2);
+    assert_eq!(poll_ready(frames.receive()).as_slice(), b"a");
+    assert_eq!(&*frames.try_receive().unwrap(), b"b");
+    assert_eq!(frames.try_receive(), None);
+}
@@ -0,0 +1,60 @@
+//! Tests for the zero-copy frame buffer pool.
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{FramePool, Rx, mock::MockUartlite};
+
+#[test]
+fn frames_are_received_in_place_and_recycled() {
+    let pool: FramePool<8, 2> = FramePool::new(Some(b'\n'));
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut receive = pin!(pool.receive());
+    assert!(receive.as_mut().poll(&mut cx).is_pending());
+
+    mock.push_rx_slice(b"ping\npo");
+    assert_eq!(pool.on_interrupt_rx(&mut rx), 1);
+    let Poll::Ready(mut first) = receive.as_mut().poll(&mut cx) else {
+        panic!("frame not received");
+    };
+    assert_eq!(&*first, b"ping");
+    first[0] = b'P';
+    assert_eq!(&*first, b"Ping");
+
+    mock.push_rx_slice(b"ng\n");
+    assert_eq!(pool.on_interrupt_rx(&mut rx), 1);
+    assert_eq!(pool.free_buffers(), 0);
+    // Both buffers are in use, so the next frame is dropped.
+    assert_eq!(pool.feed(b"lost\n"), 1);
+    assert_eq!(pool.dropped_frames(), 1);
+
+    let second = pool.try_receive().unwrap();
+    assert_eq!(&*second, b"pong");
+    drop(first);
+    drop(second);
+    assert_eq!(pool.free_buffers(), 2);
+    assert!(pool.try_receive().is_none());
+}
+
+#[test]
+fn oversized_frames_are_dropped() {
+    let pool: FramePool<4, 2> = FramePool::new(Some(0));
+    assert_eq!(pool.feed(b"too long\0ok\0"), 2);
+    assert_eq!(pool.dropped_frames(), 1);
+    assert_eq!(pool.free_buffers(), 1);
+    assert_eq!(&*pool.try_receive().unwrap(), b"ok");
+    assert_eq!(pool.free_buffers(), 2);
+}
+
+#[test]
+fn frames_ended_explicitly() {
+    let pool: FramePool<8, 1> = FramePool::new(None);
+    assert!(!pool.end_frame());
+    assert_eq!(pool.feed(b"a\nb"), 0);
+    assert!(pool.end_frame());
+    assert_eq!(&*pool.try_receive().unwrap(), b"a\nb");
+}
@@ -0,0 +1,191 @@
+//! Tests for the S-record parser.
+use axi_uartlite::hex_records::{HexParseError, HexReadError, Record, SrecParser};
+
+/// Encode an S-record line with the given record type, address field and data.
+fn srec_line(record_type: u8, address: &[u8], data: &[u8]) -> String {
+    let mut bytes = vec![(address.len() + data.len() + 1) as u8];
+    bytes.extend_from_slice(address);
+    bytes.extend_from_slice(data);
+    let sum = bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    bytes.push(!sum);
+    let mut line = format!("S{record_type}");
+    for byte in bytes {
+        line.push_str(&format!("{byte:02X}"));
+    }
+    line.push_str("\r\n");
+    line
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Owned {
+    Data { address: u32, data: Vec<u8> },
+    StartAddress(u32),
+    EndOfFile,
+}
+
+impl From<Record<'_>> for Owned {
+    fn from(record: Record<'_>) -> Self {
+        match record {
+            Record::Data { address, data } => Self::Data {
+                address,
+                data: data.to_vec(),
+            },
+            Record::StartAddress(address) => Self::StartAddress(address),
+            Record::EndOfFile => Self::EndOfFile,
+        }
+    }
+}
+
+/// Feed all bytes and collect the records together with the finished flag after each record.
+fn feed_all(parser: &mut SrecParser, text: &str) -> Result<Vec<(Owned, bool)>, HexParseError> {
+    let mut records = Vec::new();
+    for &byte in text.as_bytes() {
+        if let Some(record) = parser.feed(byte)? {
+            let record = Owned::from(record);
+            records.push((record, parser.is_finished()));
+        }
+    }
+    Ok(records)
+}
+
+#[test]
+fn round_trip() {
+    let text = [
+        srec_line(0, &[0, 0], b"hdr"),
+        srec_line(1, &[0x12, 0x34], &[1, 2, 3]),
+        srec_line(2, &[0x12, 0x34, 0x56], &[4, 5]),
+        srec_line(3, &[0x12, 0x34, 0x56, 0x78], &[6]),
+        srec_line(5, &[0, 3], &[]),
+        srec_line(7, &[0x80, 0, 0, 0], &[]),
+    ]
+    .concat();
+    let mut parser = SrecParser::new();
+    assert_eq!(
+        feed_all(&mut parser, &text).unwrap(),
+        [
+            (
+                Owned::Data {
+                    address: 0x1234,
+                    data: vec![1, 2, 3]
+                },
+                false
+            ),
+            (
+                Owned::Data {
+                    address: 0x12_3456,
+                    data: vec![4, 5]
+                },
+                false
+            ),
+            (
+                Owned::Data {
+                    address: 0x1234_5678,
+                    data: vec![6]
+                },
+                false
+            ),
+            (Owned::StartAddress(0x8000_0000), true),
+        ]
+    );
+}
+
+#[test]
+fn termination_records() {
+    for (record_type, address, start) in [
+        (9, &[0x12, 0x34][..], 0x1234),
+        (8, &[0x12, 0x34, 0x56][..], 0x12_3456),
+        (7, &[0x12, 0x34, 0x56, 0x78][..], 0x1234_5678),
+    ] {
+        let mut parser = SrecParser::new();
+        // The stream ends right after the terminator.
+        let text = srec_line(record_type, address, &[]);
+        assert_eq!(
+            feed_all(&mut parser, &text).unwrap(),
+            [(Owned::StartAddress(start), true)]
+        );
+        assert!(parser.is_finished());
+    }
+}
+
+#[test]
+fn bad_checksum() {
+    let mut line = srec_line(1, &[0, 0x10], &[0xAA]);
+    // Corrupt the checksum digits.
+    line.replace_range(line.len() - 4..line.len() - 2, "00");
+    let mut parser = SrecParser::new();
+    assert_eq!(feed_all(&mut parser, &line), Err(HexParseError::Checksum));
+    // The parser recovers with the next record.
+    assert_eq!(
+        feed_all(&mut parser, &srec_line(1, &[0, 0x10], &[0xAA])).unwrap(),
+        [(
+            Owned::Data {
+                address: 0x10,
+                data: vec![0xAA]
+            },
+            false
+        )]
+    );
+}
+
+#[test]
+fn terminator_followed_by_more_data() {
+    let text = [
+        srec_line(9, &[0, 0], &[]),
+        srec_line(1, &[0, 0x20], &[0x55]),
+    ]
+    .concat();
+    let mut parser = SrecParser::new();
+    // No byte after the terminator is swallowed and the next record starts a new file.
+    assert_eq!(
+        feed_all(&mut parser, &text).unwrap(),
+        [
+            (Owned::StartAddress(0), true),
+            (
+                Owned::Data {
+                    address: 0x20,
+                    data: vec![0x55]
+                },
+                false
+            ),
+        ]
+    );
+    assert!(!parser.is_finished());
+}
+
+#[test]
+fn parse_from_reader() {
+    let text = [
+        srec_line(1, &[0, 0], &[1, 2]),
+        srec_line(9, &[0x01, 0x00], &[]),
+        srec_line(1, &[0, 0x40], &[3]),
+    ]
+    .concat();
+    let mut reader = text.as_bytes();
+    let mut parser = SrecParser::new();
+    let mut records = Vec::new();
+    parser
+        .parse_from_reader(&mut reader, |record| records.push(Owned::from(record)))
+        .unwrap();
+    assert_eq!(
+        records,
+        [
+            Owned::Data {
+                address: 0,
+                data: vec![1, 2]
+            },
+            Owned::StartAddress(0x100),
+            Owned::EndOfFile,
+        ]
+    );
+    // The reader stops at the line ending which completes the terminator.
+    let rest = format!("\n{}", srec_line(1, &[0, 0x40], &[3]));
+    assert_eq!(reader, rest.as_bytes());
+
+    // A truncated file without terminator.
+    let text = srec_line(1, &[0, 0], &[1]);
+    let mut parser = SrecParser::new();
+    assert_eq!(
+        parser.parse_from_reader(&mut text.as_bytes(), |_| ()),
+        Err(HexReadError::Eof)
+    );
+}
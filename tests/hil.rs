@@ -0,0 +1,124 @@
+//! Tests for the test hooks and the on-target test routines.
+use core::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use axi_uartlite::{
+    AxiUartlite, FIFO_DEPTH,
+    hil::{self, HilError},
+    mock::{ErrorInjection, MockUartlite},
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+/// Backend which models a UART Lite with the TX line wired to the RX line.
+#[derive(Default)]
+struct Loopback {
+    fifo: RefCell<VecDeque<u8>>,
+    intr_enabled: Cell<bool>,
+    corrupt: Option<u8>,
+}
+
+impl RegisterAccess for &Loopback {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(self.fifo.borrow_mut().pop_front().unwrap_or(0) as u32)
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        let byte = self.corrupt.unwrap_or(value.raw_value() as u8);
+        self.fifo.borrow_mut().push_back(byte);
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        let len = self.fifo.borrow().len();
+        let raw = u32::from(len > 0)
+            | (u32::from(len >= FIFO_DEPTH) << 1)
+            | (1 << 2)
+            | (u32::from(self.intr_enabled.get()) << 4);
+        Status::new_with_raw_value(raw)
+    }
+
+    fn write_ctrl_reg(&mut self, value: Control) {
+        if value.raw_value() & (1 << 1) != 0 {
+            self.fifo.borrow_mut().clear();
+        }
+        self.intr_enabled.set(value.raw_value() & (1 << 4) != 0);
+    }
+}
+
+#[test]
+fn fifo_state_and_force_reset() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.write_fifo(0x01).unwrap();
+    mock.push_rx(0x02);
+    let state = uart.fifo_state();
+    assert!(state.rx_valid_data);
+    assert!(!state.tx_empty);
+    assert_eq!(state.errors, None);
+
+    mock.inject_errors(ErrorInjection {
+        parity: true,
+        ..Default::default()
+    });
+    assert!(uart.fifo_state().errors.unwrap().parity());
+
+    uart.enable_interrupt();
+    uart.force_reset();
+    let state = uart.fifo_state();
+    assert!(state.tx_empty);
+    assert!(!state.rx_valid_data);
+    assert!(state.intr_enabled);
+    assert!(!uart.read_and_clear_errors().has_errors());
+}
+
+#[test]
+fn mock_routines() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    hil::check_fifo_reset(&mut uart).unwrap();
+    hil::check_interrupt_enable(&mut uart).unwrap();
+    assert!(!mock.interrupt_enabled());
+    // The model does not drain the TX FIFO on its own.
+    assert_eq!(hil::check_tx_drain(&mut uart, 10), Err(HilError::Timeout));
+}
+
+#[test]
+fn loopback() {
+    let backend = Loopback::default();
+    let mut uart = AxiUartlite::new_with_regs(&backend, &backend);
+    hil::check_tx_drain(&mut uart, 10).unwrap();
+    hil::check_loopback(&mut uart, b"hello world", 10).unwrap();
+}
+
+#[test]
+fn loopback_mismatch() {
+    let backend = Loopback {
+        corrupt: Some(b'x'),
+        ..Default::default()
+    };
+    let mut uart = AxiUartlite::new_with_regs(&backend, &backend);
+    assert_eq!(
+        hil::check_loopback(&mut uart, b"ab", 10),
+        Err(HilError::Mismatch {
+            idx: 0,
+            expected: b'a',
+            actual: b'x'
+        })
+    );
+}
+
+#[test]
+fn tx_interrupt() {
+    let backend = Loopback::default();
+    let mut uart = AxiUartlite::new_with_regs(&backend, &backend);
+    let mut polls = 0;
+    hil::check_tx_interrupt(&mut uart, 10, || {
+        polls += 1;
+        polls == 3
+    })
+    .unwrap();
+    assert!(!backend.intr_enabled.get());
+    assert_eq!(
+        hil::check_tx_interrupt(&mut uart, 10, || false),
+        Err(HilError::Timeout)
+    );
+}
@@ -0,0 +1,81 @@
+//! Tests for the trace hooks.
+//!
+//! The hooks can only be registered once per process, so all checks are done in one test.
+use std::sync::Mutex;
+
+use axi_uartlite::{
+    AxiUartlite, RxErrors, SetHooksError, UartHooks, hooks,
+    mock::{ErrorInjection, MockUartlite},
+    set_hooks,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Tx(u8),
+    Rx(u8),
+    Error(RxErrors),
+    Interrupt,
+}
+
+struct Recorder(Mutex<Vec<Event>>);
+
+impl UartHooks for Recorder {
+    fn on_tx_byte(&self, byte: u8) {
+        self.0.lock().unwrap().push(Event::Tx(byte));
+    }
+
+    fn on_rx_byte(&self, byte: u8) {
+        self.0.lock().unwrap().push(Event::Rx(byte));
+    }
+
+    fn on_error(&self, errors: RxErrors) {
+        self.0.lock().unwrap().push(Event::Error(errors));
+    }
+
+    fn on_interrupt(&self) {
+        self.0.lock().unwrap().push(Event::Interrupt);
+    }
+}
+
+static RECORDER: Recorder = Recorder(Mutex::new(Vec::new()));
+
+struct Nop;
+
+impl UartHooks for Nop {}
+
+static NOP: Nop = Nop;
+
+#[test]
+fn hooks_are_called() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    // Not registered yet.
+    uart.write_fifo(0x01).unwrap();
+    assert!(hooks().is_none());
+
+    set_hooks(&RECORDER).unwrap();
+    assert_eq!(set_hooks(&NOP), Err(SetHooksError));
+    assert!(hooks().is_some());
+
+    uart.write_fifo(0x02).unwrap();
+    mock.push_rx_with_errors(
+        0x03,
+        ErrorInjection {
+            frame: true,
+            ..Default::default()
+        },
+    );
+    let (_tx, mut rx) = uart.split();
+    let mut buf = [0; 16];
+    assert_eq!(rx.on_interrupt_rx(&mut buf), 1);
+
+    let events = RECORDER.0.lock().unwrap();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0], Event::Tx(0x02));
+    assert_eq!(events[1], Event::Interrupt);
+    assert_eq!(events[2], Event::Rx(0x03));
+    let Event::Error(errors) = events[3] else {
+        panic!("unexpected event {:?}", events[3]);
+    };
+    assert!(errors.frame());
+}
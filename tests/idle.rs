@@ -0,0 +1,66 @@
+//! Tests for the idle-line detection.
+use core::cell::Cell;
+
+use axi_uartlite::{
+    DataBits, HwConfig, IdleDetector, IdleFrameTooLarge, Parity, Rx, mock::MockUartlite,
+};
+
+#[test]
+fn reports_idle_once_per_burst() {
+    let hw_config = HwConfig::new(115200, DataBits::Eight, Parity::None);
+    let now = Cell::new(u64::MAX - 100);
+    let mut idle = IdleDetector::new(&hw_config, 3, 1_000_000, || now.get());
+    assert_eq!(idle.idle_ticks(), 261);
+    assert!(!idle.poll());
+
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(b"ab");
+    let mut received = Vec::new();
+    let mut idle_events = 0;
+    assert!(!idle.service(&mut rx, |byte| received.push(byte), || idle_events += 1));
+    assert_eq!(received, b"ab");
+
+    // The tick counter wraps around.
+    now.set(now.get().wrapping_add(260));
+    assert_eq!(idle.ticks_since_last_byte(), Some(260));
+    assert!(!idle.poll());
+    now.set(now.get().wrapping_add(1));
+    assert!(idle.service(&mut rx, |byte| received.push(byte), || idle_events += 1));
+    assert_eq!(idle_events, 1);
+    assert!(!idle.poll());
+    assert_eq!(idle.ticks_since_last_byte(), None);
+}
+
+#[test]
+fn read_frame_until_idle() {
+    let hw_config = HwConfig::new(115200, DataBits::Eight, Parity::None);
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    // Every clock read advances by 10 ticks, and the last byte of the frame arrives after a
+    // gap shorter than the idle time of 261 ticks.
+    let now = Cell::new(0u64);
+    let clock = || {
+        let tick = now.get() + 10;
+        now.set(tick);
+        if tick == 200 {
+            mock.push_rx(0x03);
+        }
+        tick
+    };
+    let mut idle = IdleDetector::new(&hw_config, 3, 1_000_000, clock);
+    let mut buf = [0; 8];
+    mock.push_rx_slice(&[0x01, 0x02]);
+    assert_eq!(idle.read_frame_until_idle(&mut rx, &mut buf), Ok(3));
+    assert_eq!(buf[0..3], [1, 2, 3]);
+    assert!(now.get() >= 200 + 261);
+
+    // A frame exceeding the buffer is reported with its full length.
+    let mut small = [0; 2];
+    mock.push_rx_slice(b"next");
+    assert_eq!(
+        idle.read_frame_until_idle(&mut rx, &mut small),
+        Err(IdleFrameTooLarge { len: 4 })
+    );
+    assert_eq!(&small, b"ne");
+}
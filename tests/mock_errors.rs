@@ -0,0 +1,270 @@
+//! Error handling tests using the error injection of the mock register backend.
+use axi_uartlite::{
+    AxiUartlite, DataBits, FIFO_DEPTH, HwConfig, Parity, Rx, RxErrors, TX_WATCHDOG_DRAIN_FACTOR,
+    Tx,
+    mock::{ErrorInjection, MockUartlite},
+};
+
+const PARITY: ErrorInjection = ErrorInjection {
+    parity: true,
+    frame: false,
+    overrun: false,
+};
+
+#[test]
+fn error_counters() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.push_rx_with_errors(0x01, PARITY);
+    mock.push_rx(0x02);
+    assert_eq!(uart.read_fifo(), Ok(0x01));
+    assert_eq!(uart.read_fifo(), Ok(0x02));
+    let errors = uart.read_and_clear_errors();
+    assert_eq!(errors.parity(), 1);
+    assert_eq!(errors.frame(), 0);
+    assert_eq!(errors.overrun(), 0);
+    assert!(!uart.read_and_clear_errors().has_errors());
+}
+
+#[test]
+fn overrun() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    for byte in 0..=FIFO_DEPTH as u8 {
+        mock.push_rx(byte);
+    }
+    let mut buf = [0; FIFO_DEPTH];
+    assert_eq!(embedded_io::Read::read(&mut uart, &mut buf), Ok(FIFO_DEPTH));
+    assert_eq!(uart.read_and_clear_errors().overrun(), 1);
+}
+
+#[test]
+fn per_byte_error_attribution() {
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(&[0, 1, 2, 3, 4]);
+    // Each byte read performs one status register read.
+    mock.schedule_errors(
+        3,
+        ErrorInjection {
+            frame: true,
+            ..Default::default()
+        },
+    );
+    let mut buf = [0; FIFO_DEPTH];
+    let mut errors = [RxErrors::new(); FIFO_DEPTH];
+    assert_eq!(rx.read_whole_fifo_with_errors(&mut buf, &mut errors), 5);
+    for (idx, errors) in errors.iter().take(5).enumerate() {
+        assert_eq!(errors.frame(), idx == 3);
+        assert!(!errors.parity());
+    }
+    assert!(rx.read_and_clear_last_error().unwrap().frame());
+    assert!(rx.read_and_clear_last_error().is_none());
+}
+
+#[test]
+fn tx_fifo_full() {
+    let mock = MockUartlite::new();
+    let mut tx = Tx::new_with_regs(&mock);
+    mock.force_tx_fifo_full(true);
+    assert_eq!(tx.write_fifo(0x01), Err(nb::Error::WouldBlock));
+    assert_eq!(tx.fill_fifo(&[0; 4]), 0);
+    mock.force_tx_fifo_full(false);
+    assert_eq!(tx.fill_fifo(&[0; 4]), 4);
+
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.force_tx_fifo_full(true);
+    assert_eq!(uart.write_fifo(0x01), Err(nb::Error::WouldBlock));
+    mock.force_tx_fifo_full(false);
+    assert_eq!(uart.write_fifo(0x01), Ok(()));
+}
+
+#[test]
+fn fast_write_skips_error_capture() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.inject_errors(PARITY);
+    assert_eq!(uart.write_fifo_fast(0x01), Ok(()));
+    assert_eq!(mock.pop_tx(), Some(0x01));
+    assert!(!uart.read_and_clear_errors().has_errors());
+
+    mock.force_tx_fifo_full(true);
+    assert_eq!(uart.write_fifo_fast(0x02), Err(nb::Error::WouldBlock));
+    assert_eq!(mock.pop_tx(), None);
+}
+
+#[test]
+fn burst_write_error_capture() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.inject_errors(PARITY);
+    assert_eq!(
+        embedded_io::Write::write(&mut uart, &[0; FIFO_DEPTH + 4]),
+        Ok(FIFO_DEPTH)
+    );
+    assert_eq!(mock.tx_len(), FIFO_DEPTH);
+    assert_eq!(uart.read_and_clear_errors().parity(), 1);
+}
+
+#[test]
+fn byte_iterators() {
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    mock.push_rx_slice(b"12,34\n");
+    let value: u32 = rx
+        .bytes()
+        .take_while(|&byte| byte != b',')
+        .fold(0, |acc, byte| acc * 10 + u32::from(byte - b'0'));
+    assert_eq!(value, 12);
+
+    mock.schedule_errors(1, PARITY);
+    for (idx, (byte, errors)) in rx.bytes_with_errors().take(3).enumerate() {
+        assert_eq!(byte, b"34\n"[idx]);
+        assert_eq!(errors.is_some_and(|errors| errors.parity()), idx == 1);
+    }
+    assert_eq!(mock.rx_len(), 0);
+}
+
+#[test]
+fn write_from_closure() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    // Hex encoder which produces two characters per input byte.
+    let data = [0xAB, 0x01];
+    let mut hex = data
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xF])
+        .map(|nibble| b"0123456789ABCDEF"[nibble as usize]);
+    assert_eq!(uart.write_from(|| hex.next()), 4);
+    let mut buf = [0; 4];
+    assert_eq!(mock.pop_tx_slice(&mut buf), 4);
+    assert_eq!(&buf, b"AB01");
+
+    // Bytes are only pulled if they can be written.
+    let mut counter = 0u8;
+    let mut pull = || {
+        counter += 1;
+        Some(counter)
+    };
+    assert_eq!(uart.write_from(&mut pull), FIFO_DEPTH);
+    assert_eq!(uart.write_from(&mut pull), 0);
+    assert_eq!(counter as usize, FIFO_DEPTH);
+}
+
+#[test]
+fn vectored_write() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let bufs: [&[u8]; 4] = [b"HDR:", b"", b"payload", b"\r\n"];
+    assert_eq!(uart.write_vectored(&bufs), 13);
+    let mut buf = [0; 16];
+    assert_eq!(mock.pop_tx_slice(&mut buf), 13);
+    assert_eq!(&buf[0..13], b"HDR:payload\r\n");
+    assert_eq!(uart.write_vectored(&[]), 0);
+}
+
+#[test]
+fn flush_watchdog_detects_stuck_transmitter() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let hw_config = HwConfig::new(115200, DataBits::Eight, Parity::None);
+    let mut clock = 0u64;
+    let mut now = || {
+        clock += 1_000;
+        clock
+    };
+    // The TX FIFO is empty, so the flush succeeds immediately.
+    assert_eq!(
+        uart.flush_with_watchdog(&hw_config, &mut now, 1_000_000),
+        Ok(())
+    );
+
+    // The mock never drains the TX FIFO on its own, like a transmitter held in reset.
+    uart.write_fifo(0x55).unwrap();
+    let limit = hw_config.chars_time_ticks(FIFO_DEPTH as u32 * TX_WATCHDOG_DRAIN_FACTOR, 1_000_000);
+    let stuck = uart
+        .flush_with_watchdog(&hw_config, &mut now, 1_000_000)
+        .unwrap_err();
+    assert!(stuck.waited_ticks > limit);
+}
+
+#[test]
+fn bounded_flush_gives_up_after_fifo_drain_time() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let hw_config = HwConfig::new(115200, DataBits::Eight, Parity::None);
+    let mut clock = 0u64;
+    let mut now = || {
+        clock += 10;
+        clock
+    };
+    assert_eq!(uart.flush_bounded(&hw_config, &mut now, 1_000_000), Ok(()));
+
+    uart.write_fifo(0x55).unwrap();
+    let limit = hw_config.chars_time_ticks(FIFO_DEPTH as u32 + 1, 1_000_000);
+    let stuck = uart
+        .flush_bounded(&hw_config, &mut now, 1_000_000)
+        .unwrap_err();
+    // The flush gives up with the first clock reading after the bound, long before the
+    // watchdog limit.
+    assert!(stuck.waited_ticks > limit);
+    assert!(stuck.waited_ticks <= limit + 10);
+    assert!(
+        stuck.waited_ticks
+            < hw_config.chars_time_ticks(FIFO_DEPTH as u32 * TX_WATCHDOG_DRAIN_FACTOR, 1_000_000)
+    );
+}
+
+#[test]
+fn ready_checks_record_status_errors() {
+    use embedded_io::{ReadReady, WriteReady};
+
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.inject_errors(PARITY);
+    assert_eq!(uart.read_ready(), Ok(false));
+    mock.inject_errors(PARITY);
+    assert_eq!(uart.write_ready(), Ok(true));
+    assert_eq!(uart.read_and_clear_errors().parity(), 2);
+
+    let (mut tx, mut rx) = uart.split();
+    mock.inject_errors(PARITY);
+    mock.push_rx(0x01);
+    assert_eq!(rx.read_ready(), Ok(true));
+    assert!(rx.read_and_clear_last_error().unwrap().parity());
+    mock.inject_errors(PARITY);
+    assert_eq!(tx.write_ready(), Ok(true));
+    assert!(tx.read_and_clear_last_error().unwrap().parity());
+}
+
+#[test]
+fn debug_output_keeps_status_errors() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.enable_interrupt();
+    mock.inject_errors(PARITY);
+    let output = format!("{uart:?}");
+    assert!(output.starts_with("AxiUartlite"));
+    assert!(output.contains("base_addr: None"));
+    assert!(output.contains("interrupt_enabled: true"));
+    assert!(output.contains("last_status: None"));
+    // The Debug output did not read the status register, so the error is still pending.
+    let status = uart.status_snapshot();
+    assert!(status.parity_error());
+    assert!(status.intr_enabled());
+    assert_eq!(uart.read_and_clear_errors().parity(), 1);
+    // The snapshot is cached and shown without another register read.
+    mock.inject_errors(PARITY);
+    let output = format!("{uart:?}");
+    assert!(output.contains("last_status: Some"));
+    assert!(output.contains("parity_error: true"));
+    assert!(uart.status_snapshot().parity_error());
+
+    let (mut tx, mut rx) = uart.split();
+    mock.inject_errors(PARITY);
+    assert!(format!("{tx:?}").contains("last_error: None"));
+    assert!(format!("{rx:?}").contains("last_error: None"));
+    assert!(rx.status_snapshot().parity_error());
+    assert!(!tx.status_snapshot().parity_error());
+    assert!(rx.read_and_clear_last_error().unwrap().parity());
+}
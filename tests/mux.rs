@@ -0,0 +1,88 @@
+//! Tests for the virtual channel multiplexing layer.
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::mux::{Demux, DemuxState, MAX_CHUNK, Mux, chunk_header};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut cx) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is pending"),
+    }
+}
+
+#[test]
+fn interleaved_channels() {
+    let mux = Mux::<_, 3>::new(Vec::new());
+    let mut console = mux.channel(0);
+    let mut telemetry = mux.channel(2);
+    embedded_io::Write::write_all(&mut console, b"hello").unwrap();
+    embedded_io::Write::write_all(&mut telemetry, &[1, 2, 3]).unwrap();
+    embedded_io::Write::write_all(&mut console, b"!").unwrap();
+    let link = mux.release();
+    assert_eq!(link, b"\x00\x05hello\x02\x03\x01\x02\x03\x00\x01!");
+
+    let demux = Demux::<_, 3, 16>::new(link.as_slice());
+    let mut telemetry_rx = demux.channel(2);
+    let mut buf = [0; 8];
+    assert_eq!(
+        embedded_io::Read::read(&mut telemetry_rx, &mut buf).unwrap(),
+        3
+    );
+    assert_eq!(&buf[0..3], [1, 2, 3]);
+    // The console data which was received in the meantime is buffered.
+    assert_eq!(demux.len(0), 6);
+    let mut console_rx = demux.channel(0);
+    embedded_io::Read::read_exact(&mut console_rx, &mut buf[0..6]).unwrap();
+    assert_eq!(&buf[0..6], b"hello!");
+}
+
+#[test]
+fn large_writes_are_chunked() {
+    let mux = Mux::<_, 1>::new(Vec::new());
+    let data = [0xAA; MAX_CHUNK + 10];
+    let mut channel = mux.channel(0);
+    assert_eq!(
+        embedded_io::Write::write(&mut channel, &data).unwrap(),
+        MAX_CHUNK
+    );
+    assert_eq!(embedded_io::Write::write(&mut channel, &[]).unwrap(), 0);
+    let link = mux.release();
+    assert_eq!(link.len(), MAX_CHUNK + 2);
+    assert_eq!(link[0..2], chunk_header(0, MAX_CHUNK));
+}
+
+#[test]
+fn async_handles() {
+    let mut link = [0; 8];
+    let mux = Mux::<_, 2>::new(link.as_mut_slice());
+    let mut logs = mux.channel(1);
+    poll_ready(embedded_io_async::Write::write_all(&mut logs, b"log")).unwrap();
+    let unused = mux.release().len();
+    assert_eq!(unused, 3);
+
+    let demux = Demux::<_, 2, 8>::new(&link[0..5]);
+    let mut logs_rx = demux.channel(1);
+    let mut buf = [0; 3];
+    poll_ready(embedded_io_async::Read::read_exact(&mut logs_rx, &mut buf)).unwrap();
+    assert_eq!(&buf, b"log");
+}
+
+#[test]
+fn unknown_channels_and_overflows() {
+    let mut state = DemuxState::<2, 4>::new();
+    state.feed(&[5, 2, 0xFF, 0xFF]);
+    state.feed(&[0, 0]);
+    state.feed(&[0, 6, 1, 2, 3, 4, 5, 6]);
+    let stats = state.take_stats();
+    assert_eq!(stats.unknown_channel, 2);
+    assert_eq!(stats.overflows, 2);
+    let mut buf = [0; 8];
+    assert_eq!(state.read(0, &mut buf), 4);
+    assert_eq!(&buf[0..4], [1, 2, 3, 4]);
+    assert!(state.is_empty(1));
+}
@@ -0,0 +1,46 @@
+//! Tests for the owned buffer conveniences of the `alloc` feature.
+use axi_uartlite::{
+    AxiUartlite,
+    framing::{cobs, length_prefixed, slip},
+    mock::MockUartlite,
+};
+
+#[test]
+fn read_to_vec_and_lines() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.push_rx_slice(b"abcdef");
+    assert_eq!(uart.read_to_vec(4), b"abcd");
+    assert_eq!(uart.read_to_vec(16), b"ef");
+    assert!(uart.read_to_vec(16).is_empty());
+
+    mock.push_rx_slice(b"help\r\nstatus\n\xFF\n");
+    let (_tx, mut rx) = uart.split();
+    assert_eq!(rx.read_line_string().unwrap(), "help");
+    assert_eq!(rx.read_line_string().unwrap(), "status");
+    assert_eq!(rx.read_line_string().unwrap_err().into_bytes(), [0xFF]);
+}
+
+#[test]
+fn frame_vectors() {
+    let data = [0x11, 0x00, 0xC0, 0x22];
+    let mut buf = [0; 16];
+
+    let encoded = cobs::encode_to_vec(&data);
+    let len = cobs::encode(&data, &mut buf).unwrap();
+    assert_eq!(encoded, buf[0..len]);
+    let mut decoder = cobs::CobsDecoder::<16>::new();
+    assert_eq!(decoder.read_frame_vec(&mut &encoded[..]).unwrap(), data);
+
+    let encoded = slip::encode_to_vec(&data);
+    let len = slip::encode(&data, &mut buf).unwrap();
+    assert_eq!(encoded, buf[0..len]);
+    let mut decoder = slip::SlipDecoder::<16>::new();
+    assert_eq!(decoder.read_frame_vec(&mut &encoded[..]).unwrap(), data);
+
+    let encoded = length_prefixed::encode_to_vec(&data).unwrap();
+    let len = length_prefixed::encode(&data, &mut buf).unwrap();
+    assert_eq!(encoded, buf[0..len]);
+    let mut decoder = length_prefixed::LengthPrefixedDecoder::<16>::new();
+    assert_eq!(decoder.read_frame_vec(&mut &encoded[..]).unwrap(), data);
+}
@@ -0,0 +1,79 @@
+//! Tests for the TX pacing wrapper.
+use core::cell::RefCell;
+
+use axi_uartlite::{
+    DataBits, HwConfig, PacedTx, PacingConfig, Parity, Tx,
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+use embedded_hal::delay::DelayNs;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Byte(u8),
+    Delay(u32),
+}
+
+/// Backend which moves every written byte into the shift register immediately.
+#[derive(Default)]
+struct Wire {
+    events: RefCell<Vec<Event>>,
+}
+
+impl RegisterAccess for &Wire {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(0)
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        self.events
+            .borrow_mut()
+            .push(Event::Byte(value.raw_value() as u8));
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        // TX FIFO empty.
+        Status::new_with_raw_value(1 << 2)
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+struct Delay<'wire>(&'wire Wire);
+
+impl DelayNs for Delay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.events.borrow_mut().push(Event::Delay(ns));
+    }
+}
+
+#[test]
+fn inserts_gaps() {
+    let wire = Wire::default();
+    let mut tx = PacedTx::new(
+        Tx::new_with_regs(&wire),
+        Delay(&wire),
+        PacingConfig::new(100, 1000),
+    );
+    tx.write_frame(b"ab");
+    tx.write_frame(&[]);
+    tx.write_byte(b'c');
+    assert_eq!(
+        *wire.events.borrow(),
+        [
+            Event::Byte(b'a'),
+            Event::Delay(100),
+            Event::Byte(b'b'),
+            Event::Delay(1000),
+            Event::Byte(b'c'),
+            Event::Delay(100),
+        ]
+    );
+}
+
+#[test]
+fn wire_gaps() {
+    // 10 bits per character at 1 MBd.
+    let hw_config = HwConfig::new(1_000_000, DataBits::Eight, Parity::None);
+    let config = PacingConfig::with_wire_gaps(&hw_config, 500, 5000);
+    assert_eq!(config, PacingConfig::new(10_500, 15_000));
+}
@@ -0,0 +1,32 @@
+//! Tests for the double-buffered RX.
+use axi_uartlite::{PingPong, Rx, mock::MockUartlite};
+
+#[test]
+fn swap_halves() {
+    static BUF: PingPong<8> = PingPong::new();
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    let mut consumer = BUF.consumer().unwrap();
+    assert!(BUF.consumer().is_none());
+    assert!(consumer.swap().is_empty());
+
+    mock.push_rx_slice(b"abc");
+    assert_eq!(BUF.on_interrupt_rx(&mut rx), 3);
+    mock.push_rx_slice(b"de");
+    BUF.on_interrupt_rx(&mut rx);
+    assert_eq!(consumer.pending(), 5);
+    assert_eq!(consumer.swap(), b"abcde");
+    assert_eq!(BUF.pending(), 0);
+
+    // The current half overflows while the other half is being consumed.
+    mock.push_rx_slice(b"0123456789");
+    assert_eq!(BUF.on_interrupt_rx(&mut rx), 10);
+    assert_eq!(BUF.overflows(), 2);
+    assert_eq!(consumer.swap(), b"01234567");
+    assert!(consumer.swap().is_empty());
+    assert_eq!(BUF.take_overflows(), 2);
+    assert_eq!(BUF.overflows(), 0);
+
+    drop(consumer);
+    assert!(BUF.consumer().is_some());
+}
@@ -0,0 +1,102 @@
+//! Tests for the PRBS bit error rate test utilities.
+use core::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use axi_uartlite::{
+    AxiUartlite, FIFO_DEPTH,
+    prbs::{PrbsChecker, PrbsGenerator, PrbsPattern, run_ber_test},
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+/// Backend with the TX line wired to the RX line, which flips bits of selected bytes and can
+/// drop bytes.
+#[derive(Default)]
+struct Loopback {
+    fifo: RefCell<VecDeque<u8>>,
+    written: Cell<usize>,
+    flip: Option<(usize, u8)>,
+    drop_from: Option<usize>,
+}
+
+impl RegisterAccess for &Loopback {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(self.fifo.borrow_mut().pop_front().unwrap_or(0) as u32)
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        let idx = self.written.get();
+        self.written.set(idx + 1);
+        if self.drop_from.is_some_and(|from| idx >= from) {
+            return;
+        }
+        let mut byte = value.raw_value() as u8;
+        if let Some((flip_idx, mask)) = self.flip {
+            if flip_idx == idx {
+                byte ^= mask;
+            }
+        }
+        self.fifo.borrow_mut().push_back(byte);
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        let len = self.fifo.borrow().len();
+        assert!(len <= FIFO_DEPTH, "RX FIFO overflow");
+        Status::new_with_raw_value(u32::from(len > 0) | (1 << 2))
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+#[test]
+fn sequence_periods() {
+    for pattern in [PrbsPattern::Prbs7, PrbsPattern::Prbs15] {
+        let period = pattern.period() as usize;
+        let mut generator = PrbsGenerator::new(pattern);
+        let bits: Vec<bool> = (0..2 * period).map(|_| generator.next_bit()).collect();
+        assert_eq!(bits[0..period], bits[period..]);
+        // Maximum length sequence: 2^(n-1) ones per period.
+        let ones = bits[0..period].iter().filter(|&&bit| bit).count();
+        assert_eq!(ones, 1 << (pattern.order() - 1));
+    }
+    // A period of 127 bits fits into 127 bytes.
+    let bytes: Vec<u8> = PrbsGenerator::new(PrbsPattern::Prbs7).take(254).collect();
+    assert_eq!(bytes[0..127], bytes[127..]);
+}
+
+#[test]
+fn checker() {
+    let mut checker = PrbsChecker::new(PrbsPattern::Prbs15);
+    let mut generator = PrbsGenerator::new(PrbsPattern::Prbs15);
+    assert_eq!(checker.check(generator.next_byte()), 0);
+    assert_eq!(checker.check(generator.next_byte() ^ 0b1001), 2);
+    let stats = checker.stats();
+    assert_eq!(
+        (stats.bytes, stats.byte_errors, stats.bit_errors),
+        (2, 1, 2)
+    );
+    assert_eq!(stats.bit_errors_ppm(), 125_000);
+    checker.reset();
+    assert!(checker.stats().is_error_free());
+}
+
+#[test]
+fn loopback_ber_test() {
+    let loopback = Loopback::default();
+    let mut uart = AxiUartlite::new_with_regs(&loopback, &loopback);
+    let stats = run_ber_test(&mut uart, PrbsPattern::Prbs7, 1000, 8);
+    assert_eq!(stats.bytes, 1000);
+    assert!(stats.is_error_free());
+
+    let loopback = Loopback {
+        flip: Some((100, 0x81)),
+        drop_from: Some(900),
+        ..Default::default()
+    };
+    let mut uart = AxiUartlite::new_with_regs(&loopback, &loopback);
+    let stats = run_ber_test(&mut uart, PrbsPattern::Prbs15, 1000, 8);
+    assert_eq!(stats.bytes, 900);
+    assert_eq!(stats.bit_errors, 2);
+    assert_eq!(stats.byte_errors, 1);
+    assert!(stats.lost > 0);
+    assert!(!stats.is_error_free());
+}
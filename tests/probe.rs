@@ -0,0 +1,87 @@
+//! Tests for the base address sanity check.
+use axi_uartlite::{
+    ProbeError,
+    mock::MockUartlite,
+    probe_regs,
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+
+/// Backend which models an unmapped address where every read returns all ones.
+struct Unmapped;
+
+impl RegisterAccess for Unmapped {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(0xFFFF_FFFF)
+    }
+
+    fn write_tx_fifo(&mut self, _value: TxFifo) {}
+
+    fn read_stat_reg(&self) -> Status {
+        Status::new_with_raw_value(0xFFFF_FFFF)
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+/// Backend which models plain memory where the status register never changes.
+struct Memory(u32);
+
+impl RegisterAccess for Memory {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(0)
+    }
+
+    fn write_tx_fifo(&mut self, _value: TxFifo) {}
+
+    fn read_stat_reg(&self) -> Status {
+        Status::new_with_raw_value(self.0)
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+#[test]
+fn probe_mock() {
+    let mock = MockUartlite::new();
+    mock.push_rx_slice(b"stale");
+    assert_eq!(probe_regs(&mut &mock), Ok(()));
+    assert_eq!(mock.rx_len(), 0);
+    assert!(!mock.interrupt_enabled());
+}
+
+#[test]
+fn probe_restores_interrupt_enable() {
+    let mock = MockUartlite::new();
+    let mut regs = &mock;
+    regs.write_ctrl_reg(
+        Control::builder()
+            .with_enable_interrupt(true)
+            .with_reset_rx_fifo(false)
+            .with_reset_tx_fifo(false)
+            .build(),
+    );
+    assert_eq!(probe_regs(&mut regs), Ok(()));
+    assert!(mock.interrupt_enabled());
+}
+
+#[test]
+fn probe_unmapped() {
+    assert_eq!(
+        probe_regs(&mut Unmapped),
+        Err(ProbeError::ReservedBitsSet(0xFFFF_FFFF))
+    );
+}
+
+#[test]
+fn probe_memory() {
+    // TX FIFO empty, but the interrupt enable bit can not be toggled.
+    assert_eq!(
+        probe_regs(&mut Memory(0b100)),
+        Err(ProbeError::ControlNotReflected)
+    );
+    // RX FIFO valid data bit stuck.
+    assert_eq!(
+        probe_regs(&mut Memory(0b101)),
+        Err(ProbeError::FifoResetFailed(0b101))
+    );
+}
@@ -0,0 +1,45 @@
+//! Tests for the QEMU runner. A shell script stands in for the QEMU process.
+#![cfg(unix)]
+use std::{process::Command, time::Duration};
+
+use axi_uartlite::qemu::{QemuMachine, QemuRunner};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[test]
+fn command() {
+    let command = QemuRunner::command(QemuMachine::PetalogixS3adsp1800, "selftest.elf");
+    assert_eq!(command.get_program(), "qemu-system-microblaze");
+    let args: Vec<_> = command.get_args().collect();
+    assert!(args.windows(2).any(|w| w == ["-M", "petalogix-s3adsp1800"]));
+    assert!(args.windows(2).any(|w| w == ["-kernel", "selftest.elf"]));
+}
+
+#[test]
+fn echo_test() {
+    // Emulates the target side of the echo test.
+    let mut command = Command::new("sh");
+    command.args([
+        "-c",
+        "printf 'AXI-UARTLITE SELFTEST READY\\n'; \
+         data=$(head -c 5); head -c 1 > /dev/null; \
+         printf '%sSELFTEST DONE echoed=5 parity=0 frame=0 overrun=0\\n' \"$data\"",
+    ]);
+    let mut runner = QemuRunner::from_command(command).unwrap();
+    runner.run_echo_test(b"hello", TIMEOUT).unwrap();
+}
+
+#[test]
+fn expect_timeout() {
+    let mut command = Command::new("sh");
+    command.args(["-c", "printf 'boot\\n'; sleep 10"]);
+    let mut runner = QemuRunner::from_command(command).unwrap();
+    assert_eq!(runner.expect(b"boot\n", TIMEOUT).unwrap(), b"boot\n");
+    assert_eq!(
+        runner
+            .expect(b"never", Duration::from_millis(50))
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::TimedOut
+    );
+}
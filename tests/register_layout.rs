@@ -0,0 +1,20 @@
+//! Host test of the register block layout. The registers are accessed through the generated
+//! MMIO handle on a register block in host memory, so every access hits a known word.
+use core::cell::UnsafeCell;
+
+use axi_uartlite::registers::{Control, Registers, TxFifo};
+
+#[test]
+fn register_layout() {
+    assert_eq!(core::mem::size_of::<Registers>(), 0x10);
+
+    // RX data valid and TX FIFO empty.
+    let block = UnsafeCell::new([0x5A, 0, 0b101, 0]);
+    let mut regs = unsafe { Registers::new_mmio(block.get().cast()) };
+    assert_eq!(regs.read_rx_fifo().data(), 0x5A);
+    assert_eq!(regs.read_stat_reg().raw_value(), 0b101);
+    regs.write_tx_fifo(TxFifo::new_with_raw_value(0xA5));
+    regs.write_ctrl_reg(Control::new_with_raw_value(0b10));
+    let words: [u32; 4] = unsafe { block.get().read_volatile() };
+    assert_eq!(words, [0x5A, 0xA5, 0b101, 0b10]);
+}
@@ -0,0 +1,187 @@
+//! Register layout tests. The bit positions are checked on raw register values, so the tests
+//! are independent of the endianness of the host.
+use core::cell::UnsafeCell;
+
+use axi_uartlite::registers::{
+    CTRL_REG_OFFSET, REGISTER_BLOCK_SIZE, RX_FIFO_OFFSET, RegisterAccess, RxFifo, STAT_REG_OFFSET,
+    Status, TX_FIFO_OFFSET, TxFifo, bus_to_cpu,
+};
+
+type StatusGetter = fn(&Status) -> bool;
+
+#[test]
+fn status_bits() {
+    let bits: [(u32, StatusGetter); 8] = [
+        (0, Status::rx_fifo_valid_data),
+        (1, Status::rx_fifo_full),
+        (2, Status::tx_fifo_empty),
+        (3, Status::tx_fifo_full),
+        (4, Status::intr_enabled),
+        (5, Status::overrun_error),
+        (6, Status::frame_error),
+        (7, Status::parity_error),
+    ];
+    for (bit, getter) in bits {
+        for (other_bit, other_getter) in bits {
+            let status = Status::new_with_raw_value(1 << bit);
+            assert_eq!(other_getter(&status), bit == other_bit);
+        }
+        assert!(getter(&Status::new_with_raw_value(0xFF)));
+        assert!(!getter(&Status::new_with_raw_value(0xFFFF_FF00)));
+    }
+}
+
+#[test]
+fn fifo_data() {
+    assert_eq!(RxFifo::new_with_raw_value(0xFFFF_FF5A).data(), 0x5A);
+    assert_eq!(TxFifo::new_with_raw_value(0xA5).raw_value(), 0xA5);
+}
+
+#[test]
+fn bus_conversion() {
+    let raw = 0x1234_5678;
+    if cfg!(feature = "swap-register-bytes") {
+        assert_eq!(bus_to_cpu(raw), 0x7856_3412);
+    } else {
+        assert_eq!(bus_to_cpu(raw), raw);
+    }
+    assert_eq!(bus_to_cpu(bus_to_cpu(raw)), raw);
+}
+
+/// Register block as modelled by a peripheral access crate. The registers are interior mutable
+/// like the `vcell::VolatileCell` registers of `svd2rust`.
+#[repr(C)]
+struct PacRegisterBlock {
+    rx_fifo: UnsafeCell<u32>,
+    tx_fifo: UnsafeCell<u32>,
+    stat_reg: UnsafeCell<u32>,
+    ctrl_reg: UnsafeCell<u32>,
+}
+
+/// Peripheral singleton of a peripheral access crate.
+struct PacUartlite<'a>(&'a PacRegisterBlock);
+
+impl core::ops::Deref for PacUartlite<'_> {
+    type Target = PacRegisterBlock;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+#[test]
+fn from_pac() {
+    // TX FIFO empty.
+    let block = PacRegisterBlock {
+        rx_fifo: UnsafeCell::new(0),
+        tx_fifo: UnsafeCell::new(0),
+        stat_reg: UnsafeCell::new(bus_to_cpu(1 << 2)),
+        ctrl_reg: UnsafeCell::new(0),
+    };
+    let mut uart = unsafe { axi_uartlite::AxiUartlite::from_pac(PacUartlite(&block)) };
+    uart.write_fifo(0x5A).unwrap();
+    uart.reset_rx_fifo();
+    unsafe {
+        assert_eq!(bus_to_cpu(block.tx_fifo.get().read_volatile()), 0x5A);
+        assert_eq!(bus_to_cpu(block.ctrl_reg.get().read_volatile()), 0b10);
+        assert_eq!(block.rx_fifo.get().read_volatile(), 0);
+    }
+}
+
+#[test]
+fn release_and_rebuild() {
+    let block = PacRegisterBlock {
+        rx_fifo: UnsafeCell::new(0),
+        tx_fifo: UnsafeCell::new(0),
+        stat_reg: UnsafeCell::new(bus_to_cpu(1 << 2)),
+        ctrl_reg: UnsafeCell::new(0),
+    };
+    let uart = unsafe { axi_uartlite::AxiUartlite::from_pac(PacUartlite(&block)) };
+    let (tx_regs, rx_regs) = uart.release();
+    let base_addr = core::ptr::from_ref(&block) as usize;
+    assert_eq!(tx_regs.base_addr(), Some(base_addr));
+    assert_eq!(rx_regs.base_addr(), Some(base_addr));
+
+    let mut uart = axi_uartlite::AxiUartlite::new_with_regs(tx_regs, rx_regs);
+    uart.write_fifo(0xA5).unwrap();
+    let (tx, rx) = uart.split();
+    assert_eq!(tx.release().base_addr(), Some(base_addr));
+    assert_eq!(rx.release().base_addr(), Some(base_addr));
+    unsafe {
+        assert_eq!(bus_to_cpu(block.tx_fifo.get().read_volatile()), 0xA5);
+    }
+}
+
+#[test]
+fn borrowed_mapping() {
+    let block = PacRegisterBlock {
+        rx_fifo: UnsafeCell::new(bus_to_cpu(0x5A)),
+        tx_fifo: UnsafeCell::new(0),
+        // RX data valid and TX FIFO empty.
+        stat_reg: UnsafeCell::new(bus_to_cpu(0b101)),
+        ctrl_reg: UnsafeCell::new(0),
+    };
+    let ptr = core::ptr::from_ref(&block).cast_mut().cast();
+    let uart = unsafe { axi_uartlite::AxiUartlite::new_borrowed(ptr, &block) };
+    let (mut tx, mut rx) = uart.split();
+    assert_eq!(rx.read_fifo().unwrap(), 0x5A);
+    tx.write_fifo(0xA5).unwrap();
+    unsafe {
+        assert_eq!(bus_to_cpu(block.tx_fifo.get().read_volatile()), 0xA5);
+    }
+}
+
+#[test]
+fn register_offsets_and_pointers() {
+    assert_eq!(
+        [
+            RX_FIFO_OFFSET,
+            TX_FIFO_OFFSET,
+            STAT_REG_OFFSET,
+            CTRL_REG_OFFSET
+        ],
+        [0x0, 0x4, 0x8, 0xC]
+    );
+    assert_eq!(
+        core::mem::size_of::<PacRegisterBlock>(),
+        REGISTER_BLOCK_SIZE
+    );
+
+    let block = PacRegisterBlock {
+        rx_fifo: UnsafeCell::new(0),
+        tx_fifo: UnsafeCell::new(0),
+        stat_reg: UnsafeCell::new(0),
+        ctrl_reg: UnsafeCell::new(0),
+    };
+    let ptr = core::ptr::from_ref(&block).cast_mut().cast();
+    let uart = unsafe { axi_uartlite::AxiUartlite::new_borrowed(ptr, &block) };
+    assert_eq!(uart.as_ptr(), ptr);
+    let (tx, rx) = uart.split();
+    assert_eq!(tx.as_ptr(), ptr);
+    assert_eq!(rx.as_ptr(), ptr);
+
+    let uart = unsafe { axi_uartlite::AxiUartliteAt::<0x4060_0000>::new_fixed() };
+    assert_eq!(uart.as_ptr() as usize, 0x4060_0000);
+}
+
+#[test]
+fn interrupt_reason() {
+    const RX_VALID: u32 = 1 << 0;
+    const TX_EMPTY: u32 = 1 << 2;
+    const INTR: u32 = 1 << 4;
+    let infer = |before: u32, after: u32| {
+        axi_uartlite::InterruptReason::infer(
+            &Status::new_with_raw_value(before),
+            &Status::new_with_raw_value(after),
+        )
+    };
+    let reason = infer(INTR, INTR | TX_EMPTY);
+    assert!(reason.tx_empty && !reason.rx_data);
+    let reason = infer(INTR | TX_EMPTY, INTR | TX_EMPTY | RX_VALID);
+    assert!(reason.rx_data && !reason.tx_empty);
+    let reason = infer(INTR, INTR | TX_EMPTY | RX_VALID);
+    assert!(reason.rx_data && reason.tx_empty);
+    assert!(infer(INTR | TX_EMPTY, INTR | TX_EMPTY).is_spurious());
+    // Interrupt of this instance is disabled.
+    assert!(infer(0, TX_EMPTY | RX_VALID).is_spurious());
+}
@@ -0,0 +1,40 @@
+//! Tests for the Renode test firmware helpers.
+use axi_uartlite::{
+    AxiUartlite,
+    mock::expect::{ExpectUartlite, Transaction},
+    renode::{REPL, report_result, run_echo_test},
+    selftest::{END_OF_TEST, READY_BANNER},
+};
+
+#[test]
+fn result_lines() {
+    let expectations = [
+        Transaction::Write(b"first: TEST PASSED\n"),
+        Transaction::Write(b"second: TEST FAILED: timeout\n"),
+    ];
+    let expect = ExpectUartlite::new(&expectations);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    report_result::<_, &str>(&mut uart, "first", Ok(()));
+    report_result(&mut uart, "second", Err("timeout"));
+    expect.done();
+}
+
+#[test]
+fn echo_without_data_fails() {
+    let expectations = [
+        Transaction::Write(READY_BANNER),
+        Transaction::Read(&[END_OF_TEST]),
+        Transaction::Write(b"SELFTEST DONE echoed=0 parity=0 frame=0 overrun=0\n"),
+        Transaction::Write(b"echo: TEST FAILED: no data\n"),
+    ];
+    let expect = ExpectUartlite::new(&expectations);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    run_echo_test(&mut uart);
+    expect.done();
+}
+
+#[test]
+fn platform_snippet() {
+    assert!(REPL.contains("UART.AxiUartLite @ sysbus 0x40600000"));
+    assert!(REPL.contains("IRQ -> intc@1"));
+}
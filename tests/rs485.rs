@@ -0,0 +1,108 @@
+//! Tests for the RS-485 direction control and the on-wire TX completion.
+use core::{cell::RefCell, convert::Infallible};
+
+use axi_uartlite::{
+    DataBits, HwConfig, Parity, Rs485Tx, Tx,
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+};
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{ErrorType, OutputPin},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Byte(u8),
+    Delay(u32),
+    De(bool),
+}
+
+/// Backend which moves every written byte into the shift register immediately.
+#[derive(Default)]
+struct Wire {
+    events: RefCell<Vec<Event>>,
+}
+
+impl RegisterAccess for &Wire {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(0)
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        self.events
+            .borrow_mut()
+            .push(Event::Byte(value.raw_value() as u8));
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        // TX FIFO empty.
+        Status::new_with_raw_value(1 << 2)
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+struct Pin<'wire>(&'wire Wire);
+
+impl ErrorType for Pin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for Pin<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.events.borrow_mut().push(Event::De(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.events.borrow_mut().push(Event::De(true));
+        Ok(())
+    }
+}
+
+struct Delay<'wire>(&'wire Wire);
+
+impl DelayNs for Delay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.events.borrow_mut().push(Event::Delay(ns));
+    }
+}
+
+#[test]
+fn direction_control() {
+    let wire = Wire::default();
+    // 10 bits per character at 1 MBd.
+    let hw_config = HwConfig::new(1_000_000, DataBits::Eight, Parity::None);
+    let mut tx = Rs485Tx::new(
+        Tx::new_with_regs(&wire),
+        Pin(&wire),
+        Delay(&wire),
+        &hw_config,
+    )
+    .unwrap();
+    tx.write(b"ab").unwrap();
+    tx.write(&[]).unwrap();
+    assert_eq!(
+        *wire.events.borrow(),
+        [
+            Event::De(false),
+            Event::De(true),
+            Event::Byte(b'a'),
+            Event::Byte(b'b'),
+            Event::Delay(10_000),
+            Event::De(false),
+        ]
+    );
+}
+
+#[test]
+fn write_all_and_drain() {
+    let wire = Wire::default();
+    let hw_config = HwConfig::new(115200, DataBits::Eight, Parity::None);
+    let mut tx = Tx::new_with_regs(&wire);
+    let data = [0x55; 40];
+    let mut delays = Vec::new();
+    tx.write_all_and_drain(&data, &hw_config, |ns| delays.push(ns));
+    assert_eq!(delays, [86_806]);
+    assert_eq!(wire.events.borrow().len(), data.len());
+}
@@ -0,0 +1,47 @@
+//! Tests for the cooperative RX pump.
+use axi_uartlite::{FIFO_DEPTH, Rx, RxPump, mock::MockUartlite};
+
+#[test]
+fn pump_and_read() {
+    let mock = MockUartlite::new();
+    let mut pump = RxPump::<_, 40>::new(Rx::new_with_regs(&mock));
+    assert_eq!(pump.pump(), 0);
+    assert!(pump.is_empty());
+
+    mock.push_rx_slice(b"hello");
+    assert_eq!(pump.pump(), 5);
+    assert_eq!(pump.len(), 5);
+    let mut buf = [0; 3];
+    assert_eq!(pump.read(&mut buf), 3);
+    assert_eq!(&buf, b"hel");
+
+    // Wrap around the end of the ring buffer.
+    for _ in 0..2 {
+        mock.push_rx_slice(&[0xAA; FIFO_DEPTH]);
+        pump.pump();
+    }
+    assert_eq!(pump.len(), 2 + 2 * FIFO_DEPTH);
+    let mut buf = [0; 64];
+    assert_eq!(pump.read(&mut buf), 2 + 2 * FIFO_DEPTH);
+    assert_eq!(&buf[0..2], b"lo");
+    assert!(buf[2..2 + 2 * FIFO_DEPTH].iter().all(|&byte| byte == 0xAA));
+    assert_eq!(pump.overflows(), 0);
+}
+
+#[test]
+fn overflow_accounting() {
+    let mock = MockUartlite::new();
+    let mut pump = RxPump::<_, 8>::new(Rx::new_with_regs(&mock));
+    mock.push_rx_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    // Dropped bytes are still read from the FIFO.
+    assert_eq!(pump.pump(), 12);
+    assert_eq!(mock.rx_len(), 0);
+    assert_eq!(pump.len(), 8);
+    assert_eq!(pump.overflows(), 4);
+    assert_eq!(pump.take_overflows(), 4);
+    assert_eq!(pump.overflows(), 0);
+    let mut buf = [0; 8];
+    assert_eq!(pump.read(&mut buf), 8);
+    assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7]);
+    let _rx = pump.release();
+}
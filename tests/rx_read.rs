@@ -0,0 +1,77 @@
+//! Tests for the non-blocking and bounded RX reads.
+use core::{cell::Cell, mem::MaybeUninit};
+
+use axi_uartlite::{AxiUartlite, Rx, mock::MockUartlite};
+
+#[test]
+fn read_with_deadline() {
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    let tick = Cell::new(u64::MAX - 2);
+    let now = || {
+        tick.set(tick.get().wrapping_add(1));
+        tick.get()
+    };
+    mock.push_rx_slice(b"abc");
+    let mut buf = [0; 8];
+    // The deadline lies after the wrap-around of the clock.
+    assert_eq!(rx.read_with_deadline(&mut buf, now, 2), 3);
+    assert_eq!(&buf[0..3], b"abc");
+    assert_eq!(tick.get(), 2);
+
+    // A full buffer returns without consulting the clock.
+    mock.push_rx_slice(b"de");
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let mut buf = [0; 2];
+    assert_eq!(uart.read_with_deadline(&mut buf, || unreachable!(), 0), 2);
+    assert_eq!(&buf, b"de");
+}
+
+#[test]
+fn read_bounded() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let mut buf = [0; 8];
+    assert_eq!(uart.read_bounded(&mut buf, 100), 0);
+    mock.push_rx_slice(b"abcd");
+    // The poll budget includes the polls of successful reads.
+    assert_eq!(uart.read_bounded(&mut buf, 3), 3);
+    assert_eq!(&buf[0..3], b"abc");
+    assert_eq!(uart.read_bounded(&mut buf[0..1], 100), 1);
+    assert_eq!(buf[0], b'd');
+    assert_eq!(uart.read_bounded(&mut buf, 0), 0);
+}
+
+#[test]
+fn read_nonblocking() {
+    let mock = MockUartlite::new();
+    let mut rx = Rx::new_with_regs(&mock);
+    let mut buf = [0; 4];
+    assert_eq!(rx.read_nonblocking(&mut buf), 0);
+    mock.push_rx_slice(b"abcdef");
+    assert_eq!(rx.read_nonblocking(&mut buf), 4);
+    assert_eq!(&buf, b"abcd");
+    assert_eq!(rx.read_nonblocking(&mut buf), 2);
+    assert_eq!(&buf[0..2], b"ef");
+}
+
+#[test]
+fn read_into_uninit_buffer() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let mut buf = [MaybeUninit::uninit(); 64];
+    assert_eq!(uart.read_uninit(&mut buf), b"");
+    mock.push_rx_slice(b"frame");
+    assert_eq!(uart.read_uninit(&mut buf), b"frame");
+    mock.push_rx_slice(b"abc");
+    assert_eq!(uart.read_uninit(&mut buf[0..2]), b"ab");
+
+    let tick = Cell::new(0);
+    let now = || {
+        tick.set(tick.get() + 1);
+        tick.get()
+    };
+    let (_tx, mut rx) = uart.split();
+    assert_eq!(rx.read_with_deadline_uninit(&mut buf, now, 5), b"c");
+    assert_eq!(tick.get(), 5);
+}
@@ -0,0 +1,45 @@
+//! Tests for the descriptor-list TX.
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Waker},
+};
+
+use axi_uartlite::{FIFO_DEPTH, SegmentQueueFull, SegmentTx, Tx, mock::MockUartlite};
+
+static HEADER: [u8; 4] = *b"HDR:";
+static PAYLOAD: [u8; 20] = *b"payload of 20 bytes.";
+static TRAILER: [u8; 2] = *b"\r\n";
+
+#[test]
+fn segments_are_sent_back_to_back() {
+    let segments: SegmentTx<4> = SegmentTx::new();
+    let mock = MockUartlite::new();
+    let mut tx = Tx::new_with_regs(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    segments
+        .try_enqueue(&[&HEADER, &PAYLOAD, &[], &TRAILER])
+        .unwrap();
+    assert_eq!(segments.pending_segments(), 3);
+    assert_eq!(
+        segments.try_enqueue(&[&HEADER, &TRAILER]),
+        Err(SegmentQueueFull {
+            segments: 2,
+            free: 1
+        })
+    );
+
+    let mut flush = pin!(segments.flush());
+    assert!(flush.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(segments.start(&mut tx), FIFO_DEPTH);
+    assert_eq!(segments.pending_segments(), 2);
+    let mut wire = [0; 32];
+    let mut len = mock.pop_tx_slice(&mut wire);
+    assert_eq!(segments.on_interrupt_tx(&mut tx), 10);
+    assert!(flush.as_mut().poll(&mut cx).is_ready());
+    len += mock.pop_tx_slice(&mut wire[len..]);
+    assert_eq!(&wire[0..len], b"HDR:payload of 20 bytes.\r\n");
+
+    // Nothing is written without queued segments.
+    assert_eq!(segments.on_interrupt_tx(&mut tx), 0);
+}
@@ -0,0 +1,49 @@
+//! Tests for the self-test firmware routines with the expectation based register backend.
+use axi_uartlite::{
+    AxiUartlite,
+    mock::{
+        ErrorInjection,
+        expect::{ExpectUartlite, Transaction},
+    },
+    selftest::{END_OF_TEST, READY_BANNER, run_echo},
+};
+
+#[test]
+fn echo() {
+    let expectations = [
+        Transaction::Write(READY_BANNER),
+        Transaction::Read(b"h"),
+        Transaction::Write(b"h"),
+        Transaction::Read(b"i"),
+        Transaction::Write(b"i"),
+        Transaction::Read(&[END_OF_TEST]),
+        Transaction::Write(b"SELFTEST DONE echoed=2 parity=0 frame=0 overrun=0\n"),
+    ];
+    let expect = ExpectUartlite::new(&expectations);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    let report = run_echo(&mut uart);
+    expect.done();
+    assert_eq!(report.echoed, 2);
+    assert!(!report.errors.has_errors());
+}
+
+#[test]
+fn echo_reports_errors() {
+    let expectations = [
+        Transaction::Write(READY_BANNER),
+        Transaction::Errors(ErrorInjection {
+            parity: true,
+            ..Default::default()
+        }),
+        Transaction::Read(b"a"),
+        Transaction::Write(b"a"),
+        Transaction::Read(&[END_OF_TEST]),
+        Transaction::Write(b"SELFTEST DONE echoed=1 parity=1 frame=0 overrun=0\n"),
+    ];
+    let expect = ExpectUartlite::new(&expectations);
+    let mut uart = AxiUartlite::new_with_regs(&expect, &expect);
+    let report = run_echo(&mut uart);
+    expect.done();
+    assert_eq!(report.echoed, 1);
+    assert_eq!(report.errors.parity(), 1);
+}
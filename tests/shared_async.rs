@@ -0,0 +1,56 @@
+//! Tests for the shared asynchronous TX handle.
+use core::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite, SharedUartAsync, Tx, TxAsync, mock::MockUartlite, on_interrupt_tx,
+};
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+#[test]
+fn writes_are_not_interleaved() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.enable_interrupt();
+    let (tx, _rx) = uart.split();
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    let shared = SharedUartAsync::<NoopRawMutex, _>::new(TxAsync::new(tx, 0).unwrap());
+    let logger = shared.writer();
+    let console = logger;
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let log_line = [b'l'; 20];
+    let mut log_write = pin!(logger.write(&log_line));
+    let mut console_write = pin!(console.write(b"prompt> "));
+    assert!(log_write.as_mut().poll(&mut cx).is_pending());
+    // The console task waits for the logger write to complete.
+    assert!(console_write.as_mut().poll(&mut cx).is_pending());
+
+    let mut wire = Vec::new();
+    let mut buf = [0; 16];
+    let written = loop {
+        let len = mock.pop_tx_slice(&mut buf);
+        wire.extend_from_slice(&buf[0..len]);
+        on_interrupt_tx(&mut isr_tx, 0);
+        assert!(console_write.as_mut().poll(&mut cx).is_pending() || !wire.is_empty());
+        if let Poll::Ready(written) = log_write.as_mut().poll(&mut cx) {
+            break written;
+        }
+    };
+    assert_eq!(written, log_line.len());
+    let written = loop {
+        if let Poll::Ready(written) = console_write.as_mut().poll(&mut cx) {
+            break written;
+        }
+        let len = mock.pop_tx_slice(&mut buf);
+        wire.extend_from_slice(&buf[0..len]);
+        on_interrupt_tx(&mut isr_tx, 0);
+    };
+    assert_eq!(written, 8);
+    let len = mock.pop_tx_slice(&mut buf);
+    wire.extend_from_slice(&buf[0..len]);
+    assert_eq!(&wire[0..20], log_line);
+    assert_eq!(&wire[20..], b"prompt> ");
+}
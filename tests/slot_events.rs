@@ -0,0 +1,46 @@
+//! Tests for the per waker slot event counters of the asynchronous TX engine.
+use core::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite, FIFO_DEPTH, Tx, TxAsync, WakerSlotEvents, mock::MockUartlite, on_interrupt_tx,
+    take_waker_slot_events, waker_slot_events,
+};
+
+#[test]
+fn counters() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.enable_interrupt();
+    let (tx, _rx) = uart.split();
+    let mut tx_async = TxAsync::new(tx, 0).unwrap();
+    let mut isr_tx = Tx::new_with_regs(&mock);
+    assert_eq!(waker_slot_events(0), Some(WakerSlotEvents::default()));
+    assert_eq!(waker_slot_events(usize::MAX), None);
+
+    let data = [0x5A; 2 * FIFO_DEPTH];
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut fut = pin!(tx_async.write(&data));
+    let mut buf = [0; FIFO_DEPTH];
+    let written = loop {
+        if let Poll::Ready(written) = fut.as_mut().poll(&mut cx) {
+            break written;
+        }
+        mock.pop_tx_slice(&mut buf);
+        on_interrupt_tx(&mut isr_tx, 0);
+    };
+    assert_eq!(written, data.len());
+
+    // One refill and one completion interrupt.
+    let expected = WakerSlotEvents {
+        interrupts: 2,
+        tx_bytes: 2 * FIFO_DEPTH as u32,
+        wakeups: 1,
+    };
+    assert_eq!(waker_slot_events(0), Some(expected));
+    assert_eq!(expected.bytes_per_interrupt(), FIFO_DEPTH as u32);
+    assert_eq!(take_waker_slot_events(0), Some(expected));
+    assert_eq!(waker_slot_events(0), Some(WakerSlotEvents::default()));
+}
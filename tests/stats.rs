@@ -0,0 +1,61 @@
+//! Tests for the driver statistics.
+use axi_uartlite::{AxiUartlite, Stats, mock::MockUartlite};
+
+#[test]
+fn counters() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    for byte in 0..3 {
+        uart.write_fifo(byte).unwrap();
+    }
+    mock.force_tx_fifo_full(true);
+    assert!(uart.write_fifo(0x03).is_err());
+    mock.force_tx_fifo_full(false);
+    mock.push_rx_slice(&[0x10, 0x11]);
+    uart.read_fifo().unwrap();
+    uart.read_fifo().unwrap();
+    assert!(uart.read_fifo().is_err());
+    uart.reset_rx_fifo();
+    uart.reset_tx_fifo();
+
+    let expected = Stats {
+        tx_bytes: 3,
+        rx_bytes: 2,
+        fifo_resets: 2,
+        interrupts: 0,
+        would_block: 2,
+    };
+    assert_eq!(uart.stats(), expected);
+    assert_eq!(uart.take_stats(), expected);
+    assert_eq!(uart.stats(), Stats::new());
+}
+
+#[test]
+fn split_halves() {
+    let mock = MockUartlite::new();
+    let uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let (mut tx, mut rx) = uart.split();
+    assert_eq!(tx.fill_fifo(b"abc"), 3);
+    mock.push_rx_slice(b"de");
+    let mut buf = [0; 16];
+    assert_eq!(rx.on_interrupt_rx(&mut buf), 2);
+
+    assert_eq!(tx.stats().tx_bytes, 3);
+    assert_eq!(tx.stats().rx_bytes, 0);
+    let rx_stats = rx.take_stats();
+    assert_eq!(rx_stats.rx_bytes, 2);
+    assert_eq!(rx_stats.interrupts, 1);
+    // The RX FIFO was drained until it was empty.
+    assert_eq!(rx_stats.would_block, 1);
+    assert_eq!(rx.stats(), Stats::default());
+    assert_eq!(
+        rx_stats.merge(&tx.stats()),
+        Stats {
+            tx_bytes: 3,
+            rx_bytes: 2,
+            fifo_resets: 0,
+            interrupts: 1,
+            would_block: 1,
+        }
+    );
+}
@@ -0,0 +1,30 @@
+//! Tests for the [std::io] trait implementations.
+use std::io::{BufRead, BufReader, Write};
+
+use axi_uartlite::{AxiUartlite, mock::MockUartlite};
+
+#[test]
+fn buf_reader_lines() {
+    let mock = MockUartlite::new();
+    let (_tx, rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    mock.push_rx_slice(b"help\nstatus\n");
+    let mut reader = BufReader::new(rx);
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "help\n");
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "status\n");
+}
+
+#[test]
+fn io_copy_and_write() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    let copied = std::io::copy(&mut &b"0123456789"[..], &mut uart).unwrap();
+    assert_eq!(copied, 10);
+    write!(uart, "ab").unwrap();
+    let mut buf = [0; 16];
+    let len = mock.pop_tx_slice(&mut buf);
+    assert_eq!(&buf[0..len], b"0123456789ab");
+}
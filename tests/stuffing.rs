@@ -0,0 +1,33 @@
+//! Tests for the generic byte stuffing layer.
+use axi_uartlite::{
+    Tx,
+    framing::stuffing::{ByteStuffer, HDLC, StuffingConfig},
+    mock::MockUartlite,
+};
+use embedded_io::Write;
+
+#[test]
+fn hdlc_escaping() {
+    let mock = MockUartlite::new();
+    let mut stuffer = ByteStuffer::new(Tx::new_with_regs(&mock), HDLC);
+    stuffer.write_raw(&[0x7E]).unwrap();
+    assert_eq!(stuffer.write(&[0x01, 0x7E, 0x02, 0x7D]).unwrap(), 4);
+    stuffer.write_raw(&[0x7E]).unwrap();
+    let mut buf = [0; 16];
+    let len = mock.pop_tx_slice(&mut buf);
+    assert_eq!(
+        &buf[0..len],
+        [0x7E, 0x01, 0x7D, 0x5E, 0x02, 0x7D, 0x5D, 0x7E]
+    );
+}
+
+#[test]
+fn custom_config() {
+    const CONFIG: StuffingConfig<'static> = StuffingConfig::new(b'\\', b"\r\n", 0x40);
+    assert!(CONFIG.needs_escape(b'\\'));
+    assert!(CONFIG.needs_escape(b'\n'));
+    assert!(!CONFIG.needs_escape(b'a'));
+    let mut stuffer = ByteStuffer::new(Vec::new(), CONFIG);
+    stuffer.write_all(b"a\nb").unwrap();
+    assert_eq!(stuffer.release(), b"a\\\x4ab");
+}
@@ -0,0 +1,64 @@
+//! Tests of the timeout extension trait with the mock register backend and the mock time driver.
+use std::{
+    pin::pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+use axi_uartlite::{
+    AxiUartlite, FIFO_DEPTH, Tx, TxAsync,
+    mock::MockUartlite,
+    on_interrupt_tx,
+    timeout::{Duration, TimeoutError, WithTimeout},
+    tx_transfer_progress,
+};
+use embassy_time::MockDriver;
+
+// The wakers, transfer contexts and the mock time are static, so the tests must not run in
+// parallel.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn setup(mock: &MockUartlite) -> (TxAsync<&MockUartlite>, Tx<&MockUartlite>) {
+    let mut uart = AxiUartlite::new_with_regs(mock, mock);
+    uart.enable_interrupt();
+    let (tx, _rx) = uart.split();
+    (TxAsync::new(tx, 0).unwrap(), Tx::new_with_regs(mock))
+}
+
+#[test]
+fn expired_write_is_cancelled() {
+    let _guard = SERIAL.lock().unwrap();
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    let data = [0x55; 2 * FIFO_DEPTH];
+    {
+        let mut fut = pin!(tx_async.write(&data).with_timeout(Duration::from_millis(5)));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(tx_transfer_progress(0).unwrap().len, data.len());
+        MockDriver::get().advance(Duration::from_millis(2));
+        assert!(fut.as_mut().poll(&mut cx).is_pending());
+        MockDriver::get().advance(Duration::from_millis(3));
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Err(TimeoutError)));
+        // The transfer was cleaned up before the timeout was reported.
+        assert_eq!(tx_transfer_progress(0), None);
+        assert_eq!(mock.tx_len(), 0);
+    }
+    on_interrupt_tx(&mut isr_tx, 0);
+    assert_eq!(mock.tx_len(), 0);
+}
+
+#[test]
+fn completed_write_returns_output() {
+    let _guard = SERIAL.lock().unwrap();
+    let mock = MockUartlite::new();
+    let (mut tx_async, mut isr_tx) = setup(&mock);
+    let mut cx = Context::from_waker(Waker::noop());
+    let data = [0xAA; 4];
+    let mut fut = pin!(tx_async.write(&data).with_timeout(Duration::from_millis(5)));
+    assert!(fut.as_mut().poll(&mut cx).is_pending());
+    let mut buf = [0; 4];
+    assert_eq!(mock.pop_tx_slice(&mut buf), 4);
+    on_interrupt_tx(&mut isr_tx, 0);
+    assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(Ok(4)));
+}
@@ -0,0 +1,79 @@
+//! Tests for the RX timestamping.
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use axi_uartlite::{
+    AxiUartlite, BufferedState, DataBits, HwConfig, Parity,
+    crc::crc16_modbus,
+    framing::{FrameTimestamp, slip::SlipDecoder},
+    mock::MockUartlite,
+    modbus::{RtuFrameReceiver, RtuTiming},
+};
+
+static NOW: AtomicU64 = AtomicU64::new(0);
+
+fn now() -> u64 {
+    NOW.load(Ordering::Relaxed)
+}
+
+#[test]
+fn buffered_rx_timestamp() {
+    let state = BufferedState::<8, 16>::new();
+    let mock = MockUartlite::new();
+    let (mut tx, mut rx) = AxiUartlite::new_with_regs(&mock, &mock).split();
+    mock.push_rx(0x01);
+    state.on_interrupt(&mut tx, &mut rx);
+    // No clock registered.
+    assert_eq!(state.take_rx_timestamp(), None);
+
+    state.set_rx_clock(now);
+    NOW.store(100, Ordering::Relaxed);
+    state.on_interrupt(&mut tx, &mut rx);
+    assert_eq!(state.take_rx_timestamp(), None);
+    // Data received into a non-empty ring buffer is not timestamped.
+    mock.push_rx(0x02);
+    state.on_interrupt(&mut tx, &mut rx);
+    assert_eq!(state.take_rx_timestamp(), None);
+
+    let state = BufferedState::<8, 16>::new();
+    state.set_rx_clock(now);
+    mock.push_rx_slice(b"ab");
+    state.on_interrupt(&mut tx, &mut rx);
+    assert_eq!(state.take_rx_timestamp(), Some(100));
+    assert_eq!(state.take_rx_timestamp(), None);
+}
+
+#[test]
+fn frame_timestamp() {
+    let mut tick = 10;
+    let mut timestamp = FrameTimestamp::new(|| {
+        tick += 1;
+        tick
+    });
+    let mut decoder = SlipDecoder::<16>::new();
+    assert_eq!(timestamp.current(), None);
+    for &byte in &[0x01, 0x02, 0xC0] {
+        assert_eq!(timestamp.on_byte(), 11);
+        if decoder.feed(byte).unwrap().is_some() {
+            assert_eq!(timestamp.take(), Some(11));
+        }
+    }
+    assert_eq!(timestamp.current(), None);
+    assert_eq!(timestamp.on_byte(), 12);
+}
+
+#[test]
+fn rtu_frame_start_tick() {
+    let timing = RtuTiming::new(
+        &HwConfig::new(115200, DataBits::Eight, Parity::None),
+        1_000_000,
+    );
+    let mut receiver = RtuFrameReceiver::<32>::new(timing);
+    let mut frame = [0x11, 0x03, 0, 0];
+    let crc = crc16_modbus(&frame[0..2]).to_le_bytes();
+    frame[2..4].copy_from_slice(&crc);
+    for (idx, &byte) in frame.iter().enumerate() {
+        receiver.on_byte(byte, 5000 + idx as u64 * 100);
+    }
+    assert_eq!(receiver.poll(10_000).unwrap(), Some(&frame[0..2]));
+    assert_eq!(receiver.frame_start_tick(), 5000);
+}
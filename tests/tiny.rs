@@ -0,0 +1,28 @@
+//! Tests for the size-optimized build.
+use axi_uartlite::{
+    AxiUartlite,
+    mock::{ErrorInjection, MockUartlite},
+};
+
+const ALL_ERRORS: ErrorInjection = ErrorInjection {
+    parity: true,
+    frame: true,
+    overrun: true,
+};
+
+#[test]
+fn errors_are_not_captured() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    mock.push_rx_with_errors(0x01, ALL_ERRORS);
+    assert_eq!(uart.read_fifo(), Ok(0x01));
+    mock.inject_errors(ALL_ERRORS);
+    assert_eq!(uart.write_fifo(0x02), Ok(()));
+    assert_eq!(mock.pop_tx(), Some(0x02));
+    assert!(!uart.read_and_clear_errors().has_errors());
+
+    let (_tx, mut rx) = uart.split();
+    mock.push_rx_with_errors(0x03, ALL_ERRORS);
+    assert_eq!(rx.read_fifo_with_errors(), Ok((0x03, None)));
+    assert_eq!(rx.read_and_clear_last_error(), None);
+}
@@ -0,0 +1,60 @@
+//! Tests for the driver instance tracking.
+use std::sync::Mutex;
+
+use axi_uartlite::{
+    AxiUartlite, InstanceTrackingError, MAX_TRACKED_INSTANCES, is_instance_tracked, track_instance,
+    untrack_instance,
+};
+
+// The instance table is global, so the tests must not run concurrently.
+static TABLE: Mutex<()> = Mutex::new(());
+
+#[test]
+fn double_instantiation() {
+    let _table = TABLE.lock().unwrap();
+    const BASE_ADDR: u32 = 0x4060_0000;
+    // The driver does not access its registers on creation.
+    let uart = unsafe { AxiUartlite::new_tracked(BASE_ADDR) }.unwrap();
+    assert!(is_instance_tracked(BASE_ADDR as usize));
+    let error = unsafe { AxiUartlite::new_tracked(BASE_ADDR) }.unwrap_err();
+    assert_eq!(
+        error,
+        InstanceTrackingError::AlreadyExists(BASE_ADDR as usize)
+    );
+    assert_eq!(
+        error.to_string(),
+        "driver for base address 0x40600000 already exists"
+    );
+
+    drop(uart);
+    assert!(unsafe { untrack_instance(BASE_ADDR as usize) });
+    assert!(!is_instance_tracked(BASE_ADDR as usize));
+    let _uart = unsafe { AxiUartlite::new_tracked(BASE_ADDR) }.unwrap();
+    assert!(unsafe { untrack_instance(BASE_ADDR as usize) });
+}
+
+#[test]
+fn untrack_unknown_instance() {
+    let _table = TABLE.lock().unwrap();
+    assert!(!unsafe { untrack_instance(0x4061_0000) });
+}
+
+#[test]
+fn table_full() {
+    let _table = TABLE.lock().unwrap();
+    let addrs = (0..MAX_TRACKED_INSTANCES).map(|idx| 0x5000_0000 + idx * 0x1_0000);
+    for addr in addrs.clone() {
+        track_instance(addr).unwrap();
+    }
+    assert_eq!(
+        track_instance(0x6000_0000),
+        Err(InstanceTrackingError::TableFull)
+    );
+    // A freed slot can be reused.
+    assert!(unsafe { untrack_instance(0x5000_0000) });
+    track_instance(0x6000_0000).unwrap();
+    assert!(unsafe { untrack_instance(0x6000_0000) });
+    for addr in addrs.skip(1) {
+        assert!(unsafe { untrack_instance(addr) });
+    }
+}
@@ -0,0 +1,38 @@
+//! Tests for the generic driver traits.
+use axi_uartlite::{AxiUartlite, UartLiteIo, UartLiteRx, UartLiteTx, mock::MockUartlite};
+
+fn send_all(tx: &mut impl UartLiteTx, data: &[u8]) {
+    let mut rest = data;
+    while !rest.is_empty() {
+        rest = &rest[tx.write_burst(rest)..];
+    }
+}
+
+fn echo(io: &mut impl UartLiteIo) -> usize {
+    let mut buf = [0; 16];
+    let read = io.read_nonblocking(&mut buf);
+    send_all(io, &buf[0..read]);
+    read
+}
+
+#[test]
+fn combined_driver_and_halves() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    send_all(&mut uart, b"ab");
+    assert!(!UartLiteTx::tx_fifo_empty(&uart));
+
+    mock.push_rx_slice(b"cd");
+    assert!(UartLiteRx::rx_has_data(&uart));
+    assert_eq!(echo(&mut uart), 2);
+
+    let (mut tx, mut rx) = uart.split();
+    send_all(&mut tx, b"e");
+    mock.push_rx(b'f');
+    assert_eq!(UartLiteRx::read_fifo(&mut rx), Ok(b'f'));
+    assert!(!rx.rx_has_data());
+
+    let mut buf = [0; 8];
+    let len = mock.pop_tx_slice(&mut buf);
+    assert_eq!(&buf[0..len], b"abcde");
+}
@@ -0,0 +1,51 @@
+//! Tests for the poll-driven TX transfers.
+use core::task::Poll;
+
+use axi_uartlite::{FIFO_DEPTH, Transfer, Tx, mock::MockUartlite};
+
+#[test]
+fn pump_until_done() {
+    let mock = MockUartlite::new();
+    let mut transfer = Transfer::new(Tx::new_with_regs(&mock));
+    assert_eq!(transfer.pump(), Poll::Ready(0));
+
+    let data: Vec<u8> = (0..40).collect();
+    transfer.start(&data);
+    assert!(transfer.is_active());
+    assert_eq!(transfer.written(), 0);
+    assert_eq!(transfer.pump(), Poll::Pending);
+    assert_eq!(transfer.written(), FIFO_DEPTH);
+    // The FIFO is full until the transmitter drains it.
+    assert_eq!(transfer.pump(), Poll::Pending);
+    assert_eq!(transfer.written(), FIFO_DEPTH);
+
+    let mut sent = Vec::new();
+    let mut buf = [0; FIFO_DEPTH];
+    loop {
+        let len = mock.pop_tx_slice(&mut buf);
+        sent.extend_from_slice(&buf[0..len]);
+        if let Poll::Ready(written) = transfer.pump() {
+            assert_eq!(written, data.len());
+            break;
+        }
+    }
+    assert!(!transfer.is_active());
+    assert_eq!(transfer.remaining(), 0);
+    let len = mock.pop_tx_slice(&mut buf);
+    sent.extend_from_slice(&buf[0..len]);
+    assert_eq!(sent, data);
+}
+
+#[test]
+fn restart_aborts() {
+    let mock = MockUartlite::new();
+    let mut transfer = Transfer::new(Tx::new_with_regs(&mock));
+    transfer.start(&[0; 20]);
+    assert_eq!(transfer.pump(), Poll::Pending);
+    assert_eq!(transfer.remaining(), 20 - FIFO_DEPTH);
+    transfer.start(b"ab");
+    assert_eq!(transfer.remaining(), 2);
+    mock.pop_tx_slice(&mut [0; FIFO_DEPTH]);
+    assert_eq!(transfer.pump(), Poll::Ready(2));
+    let _tx = transfer.release();
+}
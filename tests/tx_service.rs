@@ -0,0 +1,38 @@
+//! Tests for the timer-driven background TX service.
+use axi_uartlite::{FIFO_DEPTH, Tx, TxService, mock::MockUartlite};
+
+#[test]
+fn background_transmission() {
+    static SERVICE: TxService<40> = TxService::new();
+    let mock = MockUartlite::new();
+    let mut tx = Tx::new_with_regs(&mock);
+    assert_eq!(SERVICE.on_tick(&mut tx), 0);
+
+    let data: Vec<u8> = (0..50).collect();
+    assert_eq!(SERVICE.write(&data), 40);
+    assert_eq!(SERVICE.free(), 0);
+    assert_eq!(SERVICE.on_tick(&mut tx), FIFO_DEPTH);
+    // The FIFO was not drained yet.
+    assert_eq!(SERVICE.on_tick(&mut tx), 0);
+    assert_eq!(SERVICE.write(&data[40..]), 10);
+
+    let mut sent = Vec::new();
+    let mut buf = [0; FIFO_DEPTH];
+    while SERVICE.queued() > 0 {
+        let len = mock.pop_tx_slice(&mut buf);
+        sent.extend_from_slice(&buf[0..len]);
+        SERVICE.on_tick(&mut tx);
+    }
+    let len = mock.pop_tx_slice(&mut buf);
+    sent.extend_from_slice(&buf[0..len]);
+    assert_eq!(sent, data);
+}
+
+#[test]
+fn clear() {
+    let service = TxService::<8>::default();
+    assert_eq!(service.write(b"abc"), 3);
+    service.clear();
+    assert_eq!(service.queued(), 0);
+    assert_eq!(service.free(), 8);
+}
@@ -0,0 +1,58 @@
+//! Tests for the multi-UART pool.
+use axi_uartlite::{AxiUartlite, FIFO_DEPTH, UartPool, mock::MockUartlite};
+
+#[test]
+fn services_all_instances() {
+    let mocks = [MockUartlite::new(), MockUartlite::new()];
+    let mut pool = UartPool::<2, _, 32>::new(
+        mocks
+            .each_ref()
+            .map(|mock| AxiUartlite::new_with_regs(mock, mock).split()),
+    );
+    assert_eq!(pool.len(), 2);
+    pool.enable_interrupts();
+    assert!(mocks.iter().all(|mock| mock.interrupt_enabled()));
+
+    mocks[0].push_rx_slice(b"abc");
+    mocks[1].push_rx_slice(b"de");
+    assert_eq!(pool.on_interrupt(), 5);
+    let mut buf = [0; 8];
+    assert_eq!(pool.read(0, &mut buf), 3);
+    assert_eq!(&buf[0..3], b"abc");
+    assert_eq!(pool.read(1, &mut buf), 2);
+    assert_eq!(&buf[0..2], b"de");
+
+    // The first burst is written immediately, the rest from the interrupt.
+    let data = [0x55; FIFO_DEPTH + 4];
+    assert_eq!(pool.write(1, &data), data.len());
+    assert_eq!(mocks[1].tx_len(), FIFO_DEPTH);
+    assert_eq!(pool.tx_queued(1), 4);
+    while mocks[1].pop_tx().is_some() {}
+    pool.on_interrupt();
+    assert_eq!(mocks[1].tx_len(), 4);
+    assert_eq!(pool.tx_queued(1), 0);
+    assert_eq!(mocks[0].tx_len(), 0);
+
+    let stats = pool.take_stats(1);
+    assert_eq!(stats.rx_bytes, 2);
+    assert_eq!(stats.tx_bytes, data.len() as u32);
+    assert_eq!(stats.rx_overflows, 0);
+    assert_eq!(pool.stats(1).tx_bytes, 0);
+    assert_eq!(pool.stats(0).rx_bytes, 3);
+
+    pool.disable_interrupts();
+    assert!(mocks.iter().all(|mock| !mock.interrupt_enabled()));
+    let _uarts = pool.release();
+}
+
+#[test]
+fn rx_overflow_accounting() {
+    let mock = MockUartlite::new();
+    let mut pool = UartPool::<1, _, 8>::new([AxiUartlite::new_with_regs(&mock, &mock).split()]);
+    mock.push_rx_slice(&[1; 12]);
+    assert_eq!(pool.on_interrupt(), 12);
+    assert_eq!(pool.rx_len(0), 8);
+    assert_eq!(pool.stats(0).rx_overflows, 4);
+    pool.clear(0);
+    assert_eq!(pool.rx_len(0), 0);
+}
@@ -0,0 +1,275 @@
+//! Tests for the chunked upload service.
+use std::collections::VecDeque;
+
+use axi_uartlite::{
+    framing::length_prefixed::{self, LengthPrefixedDecoder},
+    upload::{
+        UploadCommand, UploadError, UploadResponse, UploadService, UploadState, UploadStatus,
+    },
+};
+use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash};
+
+/// RAM model of a NOR flash with 16 byte pages and 4 byte words.
+struct RamFlash {
+    mem: [u8; 64],
+    erases: usize,
+    fail_writes: bool,
+}
+
+impl RamFlash {
+    fn new() -> Self {
+        Self {
+            mem: [0; 64],
+            erases: 0,
+            fail_writes: false,
+        }
+    }
+}
+
+impl ErrorType for RamFlash {
+    type Error = NorFlashErrorKind;
+}
+
+impl ReadNorFlash for RamFlash {
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        bytes.copy_from_slice(&self.mem[offset..offset + bytes.len()]);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.mem.len()
+    }
+}
+
+impl NorFlash for RamFlash {
+    const WRITE_SIZE: usize = 4;
+    const ERASE_SIZE: usize = 16;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        self.mem[from as usize..to as usize].fill(0xFF);
+        self.erases += 1;
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        if self.fail_writes {
+            return Err(NorFlashErrorKind::Other);
+        }
+        let offset = offset as usize;
+        self.mem[offset..offset + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// In-memory duplex link. Reads return end of file if no data is left.
+#[derive(Default)]
+struct Link {
+    rx: VecDeque<u8>,
+    tx: Vec<u8>,
+}
+
+impl embedded_io::ErrorType for Link {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_io::Read for Link {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = buf.len().min(self.rx.len());
+        for (dst, src) in buf.iter_mut().zip(self.rx.drain(0..len)) {
+            *dst = src;
+        }
+        Ok(len)
+    }
+}
+
+impl embedded_io::Write for Link {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.tx.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl Link {
+    fn send(&mut self, cmd: UploadCommand<'_>) {
+        let mut payload = [0; 32];
+        let len = cmd.encode(&mut payload).unwrap();
+        let mut frame = [0; 40];
+        let frame_len = length_prefixed::encode(&payload[0..len], &mut frame).unwrap();
+        self.rx.extend(&frame[0..frame_len]);
+    }
+
+    fn response(&mut self) -> UploadResponse {
+        let mut decoder = LengthPrefixedDecoder::<8>::new();
+        let payload = decoder.read_frame(&mut self.tx.as_slice()).unwrap();
+        let response = UploadResponse::from_bytes(payload).unwrap();
+        self.tx.clear();
+        response
+    }
+}
+
+fn exchange(
+    service: &mut UploadService<RamFlash, 32>,
+    link: &mut Link,
+    cmd: UploadCommand<'_>,
+) -> UploadResponse {
+    link.send(cmd);
+    let response = service.process(link).unwrap();
+    assert_eq!(link.response(), response);
+    response
+}
+
+fn ok(next_offset: u32) -> UploadResponse {
+    UploadResponse {
+        status: UploadStatus::Ok,
+        next_offset,
+    }
+}
+
+#[test]
+fn command_encoding() {
+    let mut buf = [0; 16];
+    let cmd = UploadCommand::Chunk {
+        offset: 0x0102_0304,
+        data: b"abcd",
+    };
+    let len = cmd.encode(&mut buf).unwrap();
+    assert_eq!(&buf[0..len], b"\x02\x01\x02\x03\x04abcd");
+    assert_eq!(UploadCommand::parse(&buf[0..len]), Some(cmd));
+    assert!(cmd.encode(&mut buf[0..8]).is_err());
+    assert_eq!(UploadCommand::parse(b"\x01\x00"), None);
+    assert_eq!(UploadCommand::parse(b"\x03\x00"), None);
+    let response = UploadResponse {
+        status: UploadStatus::OutOfSequence,
+        next_offset: 8,
+    };
+    assert_eq!(
+        UploadResponse::from_bytes(&response.to_bytes()),
+        Some(response)
+    );
+    assert_eq!(UploadResponse::from_bytes(b"\x09\x00\x00\x00\x00"), None);
+}
+
+#[test]
+fn upload_image() {
+    let mut service = UploadService::<_, 32>::new(RamFlash::new(), 16, 32);
+    let mut link = Link::default();
+    let image = b"0123456789abcdefghijklmn";
+
+    assert_eq!(
+        exchange(
+            &mut service,
+            &mut link,
+            UploadCommand::Chunk {
+                offset: 0,
+                data: &image[0..8],
+            }
+        )
+        .status,
+        UploadStatus::NotStarted
+    );
+    assert_eq!(
+        exchange(&mut service, &mut link, UploadCommand::Start { len: 40 }).status,
+        UploadStatus::TooLarge
+    );
+    assert_eq!(
+        exchange(&mut service, &mut link, UploadCommand::Start { len: 24 }),
+        ok(0)
+    );
+    assert_eq!(
+        service.state(),
+        UploadState::Receiving {
+            len: 24,
+            received: 0
+        }
+    );
+    let chunk = |offset: usize, len: usize| UploadCommand::Chunk {
+        offset: offset as u32,
+        data: &image[offset..offset + len],
+    };
+    assert_eq!(exchange(&mut service, &mut link, chunk(0, 8)), ok(8));
+    // Retransmission after a lost response.
+    assert_eq!(exchange(&mut service, &mut link, chunk(0, 8)), ok(8));
+    assert_eq!(
+        exchange(&mut service, &mut link, chunk(16, 8)),
+        UploadResponse {
+            status: UploadStatus::OutOfSequence,
+            next_offset: 8
+        }
+    );
+    assert_eq!(
+        exchange(&mut service, &mut link, chunk(8, 6)).status,
+        UploadStatus::Misaligned
+    );
+    assert_eq!(
+        exchange(&mut service, &mut link, UploadCommand::Finish).status,
+        UploadStatus::Incomplete
+    );
+    assert_eq!(exchange(&mut service, &mut link, chunk(8, 16)), ok(24));
+    assert_eq!(
+        exchange(&mut service, &mut link, UploadCommand::Finish),
+        ok(24)
+    );
+    assert_eq!(service.state(), UploadState::Complete { len: 24 });
+
+    let flash = service.release();
+    assert_eq!(flash.erases, 1);
+    assert_eq!(&flash.mem[0..16], &[0; 16]);
+    assert_eq!(&flash.mem[16..40], image);
+    assert_eq!(&flash.mem[40..48], &[0xFF; 8]);
+}
+
+#[test]
+fn errors() {
+    let mut service = UploadService::<_, 32>::new(RamFlash::new(), 8, 32);
+    let mut link = Link::default();
+    assert_eq!(
+        exchange(&mut service, &mut link, UploadCommand::Start { len: 8 }).status,
+        UploadStatus::Misaligned
+    );
+
+    let mut flash = RamFlash::new();
+    flash.fail_writes = true;
+    let mut service = UploadService::<_, 32>::new(flash, 0, 32);
+    exchange(&mut service, &mut link, UploadCommand::Start { len: 8 });
+    link.send(UploadCommand::Chunk {
+        offset: 0,
+        data: &[1; 8],
+    });
+    assert_eq!(
+        service.process(&mut link),
+        Err(UploadError::Flash(NorFlashErrorKind::Other))
+    );
+    assert_eq!(
+        link.response(),
+        UploadResponse {
+            status: UploadStatus::FlashError,
+            next_offset: 0
+        }
+    );
+    assert_eq!(service.state(), UploadState::Idle);
+
+    // Malformed payload and corrupted frame.
+    let mut frame = [0; 8];
+    let len = length_prefixed::encode(b"\x07", &mut frame).unwrap();
+    link.rx.extend(&frame[0..len]);
+    assert_eq!(
+        service.process(&mut link).unwrap().status,
+        UploadStatus::Malformed
+    );
+    link.response();
+    frame[3] ^= 1;
+    link.rx.extend(&frame[0..len]);
+    assert!(matches!(
+        service.process(&mut link),
+        Err(UploadError::Frame(_))
+    ));
+    assert!(link.tx.is_empty());
+    assert_eq!(service.process(&mut link), Err(UploadError::Eof));
+}
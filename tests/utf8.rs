@@ -0,0 +1,56 @@
+//! Tests for the UTF-8 decoding.
+use axi_uartlite::{
+    Rx,
+    mock::MockUartlite,
+    utf8::{InvalidSequence, InvalidUtf8, Utf8Decoder, Utf8ReadError, Utf8Reader},
+};
+
+fn decode(policy: InvalidSequence, data: &[u8]) -> Vec<Result<char, InvalidUtf8>> {
+    let mut decoder = Utf8Decoder::new(policy);
+    let mut decoded = Vec::new();
+    for &byte in data {
+        decoder.feed(byte, |result| decoded.push(result));
+    }
+    decoded
+}
+
+#[test]
+fn valid_sequences() {
+    let text = "aä€😀";
+    let decoded = decode(InvalidSequence::Error, text.as_bytes());
+    assert_eq!(decoded, text.chars().map(Ok).collect::<Vec<_>>());
+}
+
+#[test]
+fn invalid_sequences() {
+    // Truncated sequence interrupted by ASCII, overlong encoding, surrogate and lone
+    // continuation byte.
+    let data = [0xE2, 0x82, b'a', 0xC0, 0xED, 0xA0, 0x80];
+    assert_eq!(
+        decode(InvalidSequence::Error, &data),
+        [
+            Err(InvalidUtf8),
+            Ok('a'),
+            Err(InvalidUtf8),
+            Err(InvalidUtf8),
+            Err(InvalidUtf8),
+            Err(InvalidUtf8),
+        ]
+    );
+    assert_eq!(
+        decode(InvalidSequence::Replace, &[0xE2, 0x82, b'a']),
+        [Ok(char::REPLACEMENT_CHARACTER), Ok('a')]
+    );
+}
+
+#[test]
+fn reader() {
+    let mock = MockUartlite::new();
+    let mut reader = Utf8Reader::new(Rx::new_with_regs(&mock), InvalidSequence::Error);
+    mock.push_rx_slice("ö".as_bytes());
+    mock.push_rx_slice(&[0xC3, b'x']);
+    assert_eq!(reader.read_char(), Ok('ö'));
+    assert_eq!(reader.read_char(), Err(Utf8ReadError::InvalidUtf8));
+    assert_eq!(reader.read_char(), Ok('x'));
+    let _rx = reader.release();
+}
@@ -0,0 +1,39 @@
+//! Tests for the VT100 terminal helpers.
+use core::fmt::Write;
+
+use axi_uartlite::{
+    AxiUartlite,
+    mock::MockUartlite,
+    vt100::{Color, Vt100},
+};
+
+#[test]
+fn escape_sequences() {
+    let mut out = String::new();
+    out.clear_screen().unwrap();
+    out.move_cursor(4, 1).unwrap();
+    out.set_color(Color::Red).unwrap();
+    out.set_background(Color::BrightBlue).unwrap();
+    out.set_bold().unwrap();
+    out.reset_attributes().unwrap();
+    out.clear_to_end_of_line().unwrap();
+    out.hide_cursor().unwrap();
+    assert_eq!(
+        out,
+        "\x1b[2J\x1b[H\x1b[2;5H\x1b[31m\x1b[104m\x1b[1m\x1b[0m\x1b[K\x1b[?25l"
+    );
+    assert_eq!(Color::White.foreground_code(), 37);
+    assert_eq!(Color::BrightBlack.foreground_code(), 90);
+    assert_eq!(Color::Black.background_code(), 40);
+}
+
+#[test]
+fn uart_writer() {
+    let mock = MockUartlite::new();
+    let mut uart = AxiUartlite::new_with_regs(&mock, &mock);
+    uart.set_color(Color::Green).unwrap();
+    write!(uart, "ok").unwrap();
+    let mut buf = [0; 16];
+    let len = mock.pop_tx_slice(&mut buf);
+    assert_eq!(&buf[0..len], b"\x1b[32mok");
+}
@@ -0,0 +1,310 @@
+//! Tests for the XMODEM receiver and sender.
+use core::cell::RefCell;
+use std::collections::VecDeque;
+
+use axi_uartlite::{
+    Rx, Tx,
+    crc::crc16_xmodem,
+    registers::{Control, RegisterAccess, RxFifo, Status, TxFifo},
+    xmodem::{
+        ACK, BlockSize, CAN, CRC_MODE, EOT, NAK, SOH, STX, SUB, XmodemConfig, XmodemError,
+        XmodemReceiver, XmodemSender,
+    },
+};
+
+/// Transport which plays the remote end of the transfer from a script. Each step lists the
+/// characters the remote expects from the driver and the characters it answers with once they
+/// were sent. The line is idle if all answers were read.
+struct Link {
+    rx: RefCell<VecDeque<u8>>,
+    sent: RefCell<Vec<u8>>,
+    steps: RefCell<VecDeque<(Vec<u8>, Vec<u8>)>>,
+}
+
+impl Link {
+    fn new(initial: &[u8], steps: Vec<(Vec<u8>, Vec<u8>)>) -> Self {
+        Self {
+            rx: RefCell::new(initial.iter().copied().collect()),
+            sent: RefCell::new(Vec::new()),
+            steps: RefCell::new(steps.into()),
+        }
+    }
+
+    /// Check that the driver went through all steps.
+    fn finish(&self) {
+        assert!(self.steps.borrow().is_empty(), "remaining steps");
+        assert!(self.sent.borrow().is_empty(), "unmatched characters");
+    }
+}
+
+impl RegisterAccess for &Link {
+    fn read_rx_fifo(&mut self) -> RxFifo {
+        RxFifo::new_with_raw_value(self.rx.borrow_mut().pop_front().unwrap().into())
+    }
+
+    fn write_tx_fifo(&mut self, value: TxFifo) {
+        let mut sent = self.sent.borrow_mut();
+        sent.push(value.raw_value() as u8);
+        let mut steps = self.steps.borrow_mut();
+        let (expected, answer) = steps.front().expect("no character expected");
+        assert!(expected.starts_with(&sent), "unexpected characters");
+        if *sent == *expected {
+            self.rx.borrow_mut().extend(answer);
+            sent.clear();
+            steps.pop_front();
+        }
+    }
+
+    fn read_stat_reg(&self) -> Status {
+        // TX FIFO empty and RX data valid if the remote answered.
+        Status::new_with_raw_value((1 << 2) | u32::from(!self.rx.borrow().is_empty()))
+    }
+
+    fn write_ctrl_reg(&mut self, _value: Control) {}
+}
+
+const CONFIG: XmodemConfig = XmodemConfig {
+    timeout_polls: 8,
+    max_retries: 4,
+};
+
+fn block(num: u8, data: &[u8], crc_mode: bool) -> Vec<u8> {
+    let mut block = vec![if data.len() == 1024 { STX } else { SOH }, num, !num];
+    block.extend_from_slice(data);
+    if crc_mode {
+        block.extend_from_slice(&crc16_xmodem(data).to_be_bytes());
+    } else {
+        block.push(data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)));
+    }
+    block
+}
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| i as u8).collect()
+}
+
+fn padded(data: &[u8], len: usize) -> Vec<u8> {
+    let mut padded = data.to_vec();
+    padded.resize(len, SUB);
+    padded
+}
+
+fn receive(link: &Link) -> (Result<usize, XmodemError<()>>, Vec<Vec<u8>>) {
+    let mut blocks = Vec::new();
+    let result = XmodemReceiver::new(CONFIG).receive(
+        &mut Tx::new_with_regs(link),
+        &mut Rx::new_with_regs(link),
+        |block| {
+            blocks.push(block.to_vec());
+            Ok(())
+        },
+    );
+    (result, blocks)
+}
+
+fn send(link: &Link, block_size: BlockSize, data: &[u8]) -> Result<usize, XmodemError<()>> {
+    let mut rest = data;
+    XmodemSender::new(CONFIG, block_size).send(
+        &mut Tx::new_with_regs(link),
+        &mut Rx::new_with_regs(link),
+        |buf| {
+            let len = buf.len().min(rest.len());
+            buf[0..len].copy_from_slice(&rest[0..len]);
+            rest = &rest[len..];
+            Ok(len)
+        },
+    )
+}
+
+#[test]
+fn receive_crc_mode() {
+    let first = data(128);
+    let second = data(1024);
+    let link = Link::new(
+        &[],
+        vec![
+            (vec![CRC_MODE], block(1, &first, true)),
+            (vec![ACK], block(2, &second, true)),
+            (vec![ACK], vec![EOT]),
+            (vec![ACK], vec![]),
+        ],
+    );
+    assert_eq!(receive(&link), (Ok(128 + 1024), vec![first, second]));
+    link.finish();
+}
+
+#[test]
+fn receive_checksum_mode_fallback() {
+    let first = data(128);
+    let link = Link::new(
+        &[],
+        vec![
+            (vec![CRC_MODE], vec![]),
+            (vec![CRC_MODE], vec![]),
+            (vec![CRC_MODE], vec![]),
+            (vec![NAK], block(1, &first, false)),
+            (vec![ACK], vec![EOT]),
+            (vec![ACK], vec![]),
+        ],
+    );
+    assert_eq!(receive(&link), (Ok(128), vec![first]));
+    link.finish();
+}
+
+#[test]
+fn receive_duplicate_and_invalid_block() {
+    let first = data(128);
+    let second = padded(b"end", 128);
+    let mut corrupted = block(2, &second, true);
+    corrupted[10] ^= 0xFF;
+    let link = Link::new(
+        &[],
+        vec![
+            (vec![CRC_MODE], block(1, &first, true)),
+            // The ACK got lost.
+            (vec![ACK], block(1, &first, true)),
+            (vec![ACK], corrupted),
+            (vec![NAK], block(2, &second, true)),
+            (vec![ACK], vec![EOT]),
+            (vec![ACK], vec![]),
+        ],
+    );
+    assert_eq!(receive(&link), (Ok(256), vec![first, second]));
+    link.finish();
+}
+
+#[test]
+fn receive_cancel() {
+    let first = data(128);
+    // A single CAN is line noise.
+    let mut answer = vec![CAN];
+    answer.extend(block(1, &first, true));
+    let link = Link::new(
+        &[],
+        vec![(vec![CRC_MODE], answer), (vec![ACK], vec![CAN, CAN])],
+    );
+    assert_eq!(receive(&link), (Err(XmodemError::Cancelled), vec![first]));
+    link.finish();
+}
+
+#[test]
+fn receive_retry_exhaustion() {
+    // Timeouts.
+    let link = Link::new(
+        &[],
+        vec![
+            (vec![CRC_MODE], vec![]),
+            (vec![CRC_MODE], vec![]),
+            (vec![CRC_MODE], vec![]),
+            (vec![NAK], vec![]),
+            (vec![NAK], vec![]),
+            (vec![CAN, CAN], vec![]),
+        ],
+    );
+    assert_eq!(receive(&link), (Err(XmodemError::TooManyRetries), vec![]));
+    link.finish();
+
+    // Line noise.
+    let link = Link::new(
+        &[],
+        vec![(vec![CRC_MODE], vec![b'x'; 16]), (vec![CAN, CAN], vec![])],
+    );
+    assert_eq!(receive(&link), (Err(XmodemError::TooManyRetries), vec![]));
+    link.finish();
+}
+
+#[test]
+fn send_crc_mode() {
+    let payload = data(200);
+    let link = Link::new(
+        &[CRC_MODE],
+        vec![
+            (block(1, &payload[0..128], true), vec![ACK]),
+            (block(2, &padded(&payload[128..], 128), true), vec![ACK]),
+            (vec![EOT], vec![ACK]),
+        ],
+    );
+    assert_eq!(send(&link, BlockSize::Bytes128, &payload), Ok(200));
+    link.finish();
+
+    let payload = data(1024);
+    let link = Link::new(
+        &[CRC_MODE],
+        vec![
+            (block(1, &payload, true), vec![ACK]),
+            (vec![EOT], vec![ACK]),
+        ],
+    );
+    assert_eq!(send(&link, BlockSize::Bytes1024, &payload), Ok(1024));
+    link.finish();
+}
+
+#[test]
+fn send_checksum_mode() {
+    // 1K blocks are only used in CRC mode.
+    let payload = data(128);
+    let link = Link::new(
+        &[NAK],
+        vec![
+            (block(1, &payload, false), vec![ACK]),
+            (vec![EOT], vec![ACK]),
+        ],
+    );
+    assert_eq!(send(&link, BlockSize::Bytes1024, &payload), Ok(128));
+    link.finish();
+}
+
+#[test]
+fn send_repeats_block() {
+    let payload = data(128);
+    let link = Link::new(
+        &[CRC_MODE],
+        vec![
+            (block(1, &payload, true), vec![NAK]),
+            (block(1, &payload, true), vec![]),
+            (block(1, &payload, true), vec![ACK]),
+            (vec![EOT], vec![ACK]),
+        ],
+    );
+    assert_eq!(send(&link, BlockSize::Bytes128, &payload), Ok(128));
+    link.finish();
+}
+
+#[test]
+fn send_cancel() {
+    let payload = data(256);
+    let link = Link::new(
+        &[CRC_MODE],
+        vec![
+            // A single CAN is line noise.
+            (block(1, &payload[0..128], true), vec![CAN, NAK]),
+            (block(1, &payload[0..128], true), vec![CAN, CAN]),
+        ],
+    );
+    assert_eq!(
+        send(&link, BlockSize::Bytes128, &payload),
+        Err(XmodemError::Cancelled)
+    );
+    link.finish();
+}
+
+#[test]
+fn send_retry_exhaustion() {
+    let payload = data(128);
+    let mut steps = vec![(block(1, &payload, true), vec![]); 5];
+    steps.push((vec![CAN, CAN], vec![]));
+    let link = Link::new(&[CRC_MODE], steps);
+    assert_eq!(
+        send(&link, BlockSize::Bytes128, &payload),
+        Err(XmodemError::TooManyRetries)
+    );
+    link.finish();
+
+    // Line noise while waiting for the receiver.
+    let link = Link::new(&[b'x'; 5], vec![(vec![CAN, CAN], vec![])]);
+    assert_eq!(
+        send(&link, BlockSize::Bytes128, &payload),
+        Err(XmodemError::TooManyRetries)
+    );
+    link.finish();
+}